@@ -308,6 +308,7 @@ class JobMatchingResult(BaseModel):
         default_factory=list, description="Ranked candidate matches"
     )
     total_evaluated: int = Field(0, description="Total candidates evaluated")
+    model: str | None = Field(None, description="Model that produced the ranking")
     timestamp: str = Field(..., description="Matching timestamp (ISO 8601)")
 
 
@@ -17,6 +17,7 @@ from grok_service.models import (
 
 logger = logging.getLogger(__name__)
 
+MATCHING_MODEL = "grok-4-1-fast"
 
 MATCHING_SYSTEM_PROMPT = """\
 You are a technical recruiter matching candidates to a job.
@@ -100,7 +101,7 @@ class JobMatchingService:
 
         # Create chat with cross-collection search
         chat = self.client.chat.create(
-            model="grok-4-1-fast",
+            model=MATCHING_MODEL,
             tools=[
                 collections_search(
                     collection_ids=collection_ids,
@@ -189,6 +190,7 @@ Return JSON array (top {top_n} only, sorted by score descending):
                 job_id=job_id,
                 matches=matches,
                 total_evaluated=len(candidates),
+                model=MATCHING_MODEL,
                 timestamp=datetime.now(timezone.utc).isoformat(),
             )
 
@@ -202,6 +204,7 @@ Return JSON array (top {top_n} only, sorted by score descending):
                 job_id=job_id,
                 matches=[],
                 total_evaluated=len(candidates),
+                model=MATCHING_MODEL,
                 timestamp=datetime.now(timezone.utc).isoformat(),
             )
         except Exception as e:
@@ -0,0 +1,6 @@
+-- Job posting lifecycle: draft -> published <-> paused -> closed | filled
+ALTER TABLE jobs ADD COLUMN published_at TEXT;
+ALTER TABLE jobs ADD COLUMN closed_at TEXT;
+
+-- Legacy 'active' jobs are published
+UPDATE jobs SET status = 'published', published_at = COALESCE(published_at, created_at) WHERE status = 'active';
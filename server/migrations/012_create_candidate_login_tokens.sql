@@ -0,0 +1,11 @@
+-- Single-use magic link tokens for the candidate self-service portal
+CREATE TABLE IF NOT EXISTS candidate_login_tokens (
+    token_hash TEXT PRIMARY KEY,  -- SHA-256 of the token sent by email
+    talent_id TEXT NOT NULL,
+    expires_at TEXT NOT NULL,
+    used_at TEXT,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (talent_id) REFERENCES talents(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_candidate_login_tokens_talent_id ON candidate_login_tokens(talent_id);
@@ -0,0 +1,16 @@
+-- Audit record for every data subject erasure (GDPR Art. 17 / CCPA deletion)
+-- Holds no personal data: only the erased ID, a hash of the email and counts
+CREATE TABLE IF NOT EXISTS erasure_tombstones (
+    id TEXT PRIMARY KEY,
+    talent_id TEXT NOT NULL,           -- ID of the erased talent (no longer resolvable)
+    email_hash TEXT NOT NULL,          -- SHA-256 of the normalized email, to honor repeat requests
+    reason TEXT,
+    requested_by TEXT,
+    deleted_counts TEXT NOT NULL,      -- JSON object of rows removed/anonymized per table
+    collection_id TEXT,                -- xAI collection that was deleted remotely
+    remote_status TEXT NOT NULL,       -- none | deleted | failed
+    erased_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_erasure_tombstones_talent_id ON erasure_tombstones(talent_id);
+CREATE INDEX IF NOT EXISTS idx_erasure_tombstones_email_hash ON erasure_tombstones(email_hash);
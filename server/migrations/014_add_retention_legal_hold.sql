@@ -0,0 +1,3 @@
+-- Legal hold exempts a talent or job (and everything under it) from retention purges and erasure
+ALTER TABLE talents ADD COLUMN legal_hold INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE jobs ADD COLUMN legal_hold INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,29 @@
+-- Append-only audit log of mutating API calls and sensitive reads
+CREATE TABLE IF NOT EXISTS audit_log (
+    id TEXT PRIMARY KEY,
+    occurred_at TEXT NOT NULL,
+    request_id TEXT,
+    actor TEXT NOT NULL,           -- X-Actor header, candidate:<talent_id>, or anonymous
+    method TEXT NOT NULL,
+    route TEXT NOT NULL,           -- matched route pattern, e.g. /api/v1/talents/{id}
+    path TEXT NOT NULL,
+    target_ids TEXT NOT NULL,      -- JSON array of IDs the request touched
+    status INTEGER NOT NULL,
+    outcome TEXT NOT NULL,         -- success | failure
+    error_code TEXT,
+    ip TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_audit_log_occurred_at ON audit_log(occurred_at);
+CREATE INDEX IF NOT EXISTS idx_audit_log_actor ON audit_log(actor);
+
+-- Entries can never be changed or removed
+CREATE TRIGGER IF NOT EXISTS audit_log_no_update BEFORE UPDATE ON audit_log
+BEGIN
+    SELECT RAISE(ABORT, 'audit_log is append-only');
+END;
+
+CREATE TRIGGER IF NOT EXISTS audit_log_no_delete BEFORE DELETE ON audit_log
+BEGIN
+    SELECT RAISE(ABORT, 'audit_log is append-only');
+END;
@@ -0,0 +1,7 @@
+-- Soft delete: rows with deleted_at set are in the trash and hidden from normal queries
+ALTER TABLE talents ADD COLUMN deleted_at TEXT;
+ALTER TABLE jobs ADD COLUMN deleted_at TEXT;
+ALTER TABLE applications ADD COLUMN deleted_at TEXT;
+CREATE INDEX IF NOT EXISTS idx_talents_deleted_at ON talents(deleted_at);
+CREATE INDEX IF NOT EXISTS idx_jobs_deleted_at ON jobs(deleted_at);
+CREATE INDEX IF NOT EXISTS idx_applications_deleted_at ON applications(deleted_at);
@@ -0,0 +1,29 @@
+-- Rebuild applications with explicit delete policies: applications follow
+-- their talent, and a job cannot be hard-deleted while it has applications.
+-- SQLite cannot alter constraints in place, so the table is copied.
+CREATE TABLE applications_new (
+    id TEXT PRIMARY KEY,
+    talent_id TEXT NOT NULL,
+    job_id TEXT NOT NULL,
+    resume_data TEXT,
+    resume_filename TEXT,
+    resume_content_type TEXT,
+    cover_letter TEXT,
+    status TEXT NOT NULL DEFAULT 'pending',
+    created_at TEXT NOT NULL,
+    deleted_at TEXT,
+    FOREIGN KEY (talent_id) REFERENCES talents(id) ON DELETE CASCADE,
+    FOREIGN KEY (job_id) REFERENCES jobs(id) ON DELETE RESTRICT
+);
+
+INSERT INTO applications_new
+    (id, talent_id, job_id, resume_data, resume_filename, resume_content_type, cover_letter, status, created_at, deleted_at)
+SELECT id, talent_id, job_id, resume_data, resume_filename, resume_content_type, cover_letter, status, created_at, deleted_at
+FROM applications;
+
+DROP TABLE applications;
+ALTER TABLE applications_new RENAME TO applications;
+
+CREATE INDEX IF NOT EXISTS idx_applications_talent_id ON applications(talent_id);
+CREATE INDEX IF NOT EXISTS idx_applications_job_id ON applications(job_id);
+CREATE INDEX IF NOT EXISTS idx_applications_deleted_at ON applications(deleted_at);
@@ -0,0 +1,31 @@
+-- Interviews scheduled for an application, and the scorecards submitted for them
+CREATE TABLE IF NOT EXISTS interviews (
+    id TEXT PRIMARY KEY,
+    application_id TEXT NOT NULL,
+    round INTEGER NOT NULL,
+    title TEXT,                  -- e.g. "Technical screen"
+    interviewers TEXT NOT NULL,  -- JSON array
+    scheduled_at TEXT NOT NULL,
+    duration_minutes INTEGER NOT NULL DEFAULT 60,
+    location TEXT,
+    video_link TEXT,
+    status TEXT NOT NULL DEFAULT 'scheduled',  -- scheduled, completed, cancelled, no_show
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (application_id) REFERENCES applications(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_interviews_application_id ON interviews(application_id);
+
+CREATE TABLE IF NOT EXISTS scorecards (
+    id TEXT PRIMARY KEY,
+    interview_id TEXT NOT NULL,
+    interviewer TEXT NOT NULL,
+    ratings TEXT NOT NULL,         -- JSON object: competency -> rating (1-4)
+    recommendation TEXT NOT NULL,  -- strong_no_hire, no_hire, hire, strong_hire
+    notes TEXT,
+    submitted_at TEXT NOT NULL,
+    FOREIGN KEY (interview_id) REFERENCES interviews(id) ON DELETE CASCADE,
+    UNIQUE(interview_id, interviewer)
+);
+
+CREATE INDEX IF NOT EXISTS idx_scorecards_interview_id ON scorecards(interview_id);
@@ -0,0 +1,45 @@
+-- Offers made on applications, their approval chain and their history
+CREATE TABLE IF NOT EXISTS offers (
+    id TEXT PRIMARY KEY,
+    application_id TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'draft',  -- draft, pending_approval, extended, accepted, declined, rescinded
+    base_salary INTEGER NOT NULL,
+    currency TEXT NOT NULL,
+    bonus INTEGER,
+    equity TEXT,
+    start_date TEXT NOT NULL,
+    expires_at TEXT,
+    notes TEXT,
+    created_by TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    FOREIGN KEY (application_id) REFERENCES applications(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_offers_application_id ON offers(application_id);
+
+-- One row per approver, recreated each time the offer is submitted
+CREATE TABLE IF NOT EXISTS offer_approvals (
+    offer_id TEXT NOT NULL,
+    step INTEGER NOT NULL,
+    approver TEXT NOT NULL,
+    decision TEXT,  -- approved or rejected, NULL while pending
+    comment TEXT,
+    decided_at TEXT,
+    PRIMARY KEY (offer_id, step),
+    FOREIGN KEY (offer_id) REFERENCES offers(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS offer_events (
+    id TEXT PRIMARY KEY,
+    offer_id TEXT NOT NULL,
+    action TEXT NOT NULL,  -- create, update, submit, approve, reject, accept, decline, rescind
+    actor TEXT NOT NULL,
+    from_status TEXT,
+    to_status TEXT NOT NULL,
+    comment TEXT,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (offer_id) REFERENCES offers(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_offer_events_offer_id ON offer_events(offer_id, created_at);
@@ -0,0 +1,14 @@
+-- Recently published events, kept briefly so event stream clients can
+-- resume from the last event they saw. seq is the stream's event ID
+CREATE TABLE IF NOT EXISTS event_history (
+    seq INTEGER PRIMARY KEY AUTOINCREMENT,
+    id TEXT NOT NULL UNIQUE,
+    event_type TEXT NOT NULL,
+    job_id TEXT,
+    talent_id TEXT,
+    payload TEXT NOT NULL,  -- JSON event envelope, as sent to webhooks
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_event_history_created_at ON event_history(created_at);
+CREATE INDEX IF NOT EXISTS idx_event_history_talent ON event_history(talent_id);
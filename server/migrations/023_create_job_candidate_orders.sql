@@ -0,0 +1,10 @@
+-- Current recruiter-curated candidate order per job. version increases with
+-- every accepted reorder, so reorders made against a stale order are caught
+CREATE TABLE IF NOT EXISTS job_candidate_orders (
+    job_id TEXT PRIMARY KEY,
+    talent_ids TEXT NOT NULL,  -- JSON array of talent IDs, first is highest ranked
+    version INTEGER NOT NULL,
+    reorder_event_id TEXT,     -- reorder that produced this order
+    updated_at TEXT NOT NULL,
+    FOREIGN KEY (job_id) REFERENCES jobs(id) ON DELETE CASCADE
+);
@@ -0,0 +1,2 @@
+-- Tie preferences record two candidates as equally good rather than winner over loser
+ALTER TABLE pairwise_preferences ADD COLUMN tie INTEGER NOT NULL DEFAULT 0
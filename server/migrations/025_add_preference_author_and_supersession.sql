@@ -0,0 +1,7 @@
+-- Who recorded each preference, and which newer preference replaced it when
+-- a recruiter reversed a judgement. Superseded preferences are kept for the
+-- record but no longer count
+ALTER TABLE pairwise_preferences ADD COLUMN author TEXT;
+ALTER TABLE pairwise_preferences ADD COLUMN superseded_by TEXT;
+ALTER TABLE pairwise_preferences ADD COLUMN superseded_at TEXT;
+CREATE INDEX IF NOT EXISTS idx_pairwise_preferences_superseded_by ON pairwise_preferences(superseded_by)
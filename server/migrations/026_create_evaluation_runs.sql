@@ -0,0 +1,18 @@
+-- How well a ranking source agreed with recruiter preferences at one point
+-- in time. label names what was evaluated, e.g. a grok-service prompt version
+CREATE TABLE IF NOT EXISTS evaluation_runs (
+    id TEXT PRIMARY KEY,
+    source TEXT NOT NULL,           -- job_matches or candidate_score
+    label TEXT,
+    split TEXT NOT NULL,            -- all, train or validation
+    k INTEGER NOT NULL,             -- cutoff for NDCG
+    jobs_evaluated INTEGER NOT NULL,
+    preferences_evaluated INTEGER NOT NULL,
+    pairwise_accuracy REAL,
+    kendall_tau REAL,
+    ndcg REAL,
+    job_results TEXT NOT NULL,      -- JSON array of per-job metrics
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_evaluation_runs_source_created_at ON evaluation_runs(source, created_at)
@@ -0,0 +1,50 @@
+-- Each job matching generation is a run with its own results, so
+-- regenerating keeps earlier rankings. job_matches holds a copy of the
+-- latest succeeded run, swapped in by the transaction that completes it
+CREATE TABLE IF NOT EXISTS match_runs (
+    id TEXT PRIMARY KEY,
+    job_id TEXT NOT NULL,
+    status TEXT NOT NULL,           -- running, succeeded or failed
+    top_n INTEGER NOT NULL,
+    candidate_count INTEGER NOT NULL,
+    total_evaluated INTEGER,
+    match_count INTEGER NOT NULL DEFAULT 0,
+    model_version TEXT,             -- as reported by grok-service
+    error TEXT,
+    created_at TEXT NOT NULL,
+    completed_at TEXT,
+    FOREIGN KEY (job_id) REFERENCES jobs(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_match_runs_job_id_created_at ON match_runs(job_id, created_at);
+
+CREATE TABLE IF NOT EXISTS match_run_results (
+    id TEXT PRIMARY KEY,
+    run_id TEXT NOT NULL,
+    job_id TEXT NOT NULL,
+    talent_id TEXT NOT NULL,
+    score REAL NOT NULL,
+    rank INTEGER NOT NULL,
+    match_reasons TEXT,  -- JSON array
+    concerns TEXT,       -- JSON array
+    summary TEXT,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (run_id) REFERENCES match_runs(id) ON DELETE CASCADE,
+    FOREIGN KEY (talent_id) REFERENCES talents(id) ON DELETE CASCADE,
+    UNIQUE(run_id, talent_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_match_run_results_run_id_rank ON match_run_results(run_id, rank);
+
+-- Matches generated before runs existed become one succeeded run per job
+INSERT OR IGNORE INTO match_runs (id, job_id, status, top_n, candidate_count, total_evaluated, match_count, created_at, completed_at)
+SELECT 'legacy-' || job_id, job_id, 'succeeded', COUNT(*), COUNT(*), COUNT(*), COUNT(*), MIN(created_at), MIN(created_at)
+FROM job_matches
+WHERE job_id NOT IN (SELECT job_id FROM match_runs)
+GROUP BY job_id;
+
+INSERT OR IGNORE INTO match_run_results (id, run_id, job_id, talent_id, score, rank, match_reasons, concerns, summary, created_at)
+SELECT m.id, 'legacy-' || m.job_id, m.job_id, m.talent_id, m.score, m.rank, m.match_reasons, m.concerns, m.summary, m.created_at
+FROM job_matches m
+WHERE EXISTS (SELECT 1 FROM match_runs r WHERE r.id = 'legacy-' || m.job_id)
+AND NOT EXISTS (SELECT 1 FROM match_run_results r WHERE r.run_id = 'legacy-' || m.job_id)
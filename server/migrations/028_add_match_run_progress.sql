@@ -0,0 +1,6 @@
+-- Progress of a matching run whose candidates are sent to grok-service in
+-- batches. status may now also be cancelled
+ALTER TABLE match_runs ADD COLUMN batch_size INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE match_runs ADD COLUMN batch_count INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE match_runs ADD COLUMN current_batch INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE match_runs ADD COLUMN evaluated INTEGER NOT NULL DEFAULT 0
@@ -0,0 +1,5 @@
+-- Last time a talent or an application was changed by its owner or a
+-- recruiter. Retention is measured from here, falling back to created_at
+-- for rows that have not been touched since this column was added
+ALTER TABLE talents ADD COLUMN updated_at TEXT;
+ALTER TABLE applications ADD COLUMN updated_at TEXT
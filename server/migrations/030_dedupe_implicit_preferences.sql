@@ -0,0 +1,32 @@
+-- Implicit signals (shortlist, reject, status change, tie) have no reorder
+-- event, and SQLite treats the NULL reorder_event_id in the original UNIQUE
+-- constraint as distinct, so repeating a signal stored the same preference
+-- again. Collapse existing duplicates onto the earliest copy and keep at
+-- most one active preference per pair, job and source from here on.
+-- Superseded preferences are excluded so a reversed judgement can be made
+-- again later
+UPDATE pairwise_preferences
+SET superseded_by = (
+    SELECT keep.id
+    FROM pairwise_preferences dup
+    JOIN pairwise_preferences keep
+      ON keep.winner_id = dup.winner_id AND keep.loser_id = dup.loser_id
+     AND keep.job_id = dup.job_id AND keep.source = dup.source
+     AND keep.reorder_event_id IS NULL AND keep.superseded_by IS NULL
+    WHERE dup.id = pairwise_preferences.superseded_by
+    ORDER BY keep.rowid
+    LIMIT 1
+)
+WHERE superseded_by IN (
+    SELECT id FROM pairwise_preferences WHERE reorder_event_id IS NULL AND superseded_by IS NULL
+);
+DELETE FROM pairwise_preferences
+WHERE reorder_event_id IS NULL AND superseded_by IS NULL
+  AND rowid NOT IN (
+    SELECT MIN(rowid) FROM pairwise_preferences
+    WHERE reorder_event_id IS NULL AND superseded_by IS NULL
+    GROUP BY winner_id, loser_id, job_id, source
+  );
+CREATE UNIQUE INDEX IF NOT EXISTS idx_pairwise_preferences_active_signal
+    ON pairwise_preferences(winner_id, loser_id, job_id, source, COALESCE(reorder_event_id, ''))
+    WHERE superseded_by IS NULL
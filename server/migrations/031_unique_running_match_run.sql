@@ -0,0 +1,16 @@
+-- At most one matching run per job may be running. Older duplicates left
+-- by the earlier check-then-insert are failed first so the index can be
+-- built
+UPDATE match_runs
+SET status = 'failed',
+    error = 'Superseded by a concurrent run',
+    completed_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+WHERE status = 'running'
+  AND EXISTS (
+    SELECT 1 FROM match_runs newer
+    WHERE newer.job_id = match_runs.job_id AND newer.status = 'running'
+      AND (newer.created_at > match_runs.created_at
+           OR (newer.created_at = match_runs.created_at AND newer.id > match_runs.id))
+  );
+CREATE UNIQUE INDEX IF NOT EXISTS idx_match_runs_one_running
+    ON match_runs(job_id) WHERE status = 'running'
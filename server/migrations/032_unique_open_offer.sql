@@ -0,0 +1,16 @@
+-- At most one open offer per application. Older open duplicates left by
+-- the earlier check-then-insert are rescinded first so the index can be
+-- built
+UPDATE offers
+SET status = 'rescinded',
+    updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+WHERE status IN ('draft', 'pending_approval', 'extended')
+  AND EXISTS (
+    SELECT 1 FROM offers newer
+    WHERE newer.application_id = offers.application_id
+      AND newer.status IN ('draft', 'pending_approval', 'extended')
+      AND (newer.created_at > offers.created_at
+           OR (newer.created_at = offers.created_at AND newer.id > offers.id))
+  );
+CREATE UNIQUE INDEX IF NOT EXISTS idx_offers_one_open
+    ON offers(application_id) WHERE status IN ('draft', 'pending_approval', 'extended')
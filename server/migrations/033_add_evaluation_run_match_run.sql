@@ -0,0 +1,5 @@
+-- Which matching run an evaluation of job_matches scored: the run that served
+-- the job's matches when one job was evaluated, and the model version all
+-- evaluated runs share
+ALTER TABLE evaluation_runs ADD COLUMN match_run_id TEXT;
+ALTER TABLE evaluation_runs ADD COLUMN model_version TEXT
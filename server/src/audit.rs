@@ -0,0 +1,115 @@
+//! Audit log
+//!
+//! Every mutating request and every sensitive read is recorded in the
+//! append-only `audit_log` table by the audit middleware. This module decides
+//! which requests are audited and renders entries for compliance exports.
+
+use actix_web::http::Method;
+
+use crate::models::AuditLogEntry;
+
+/// Header identifying the person or system acting on the API
+pub const ACTOR_HEADER: &str = "x-actor";
+
+/// Actor recorded when the caller did not identify themselves
+pub const ANONYMOUS_ACTOR: &str = "anonymous";
+
+/// Read-only routes that expose personal data and are audited anyway
+pub const SENSITIVE_READS: &[&str] = &[
+    "/api/v1/applications/{id}/resume",
+    "/api/v1/talents/{id}/export",
+    "/api/v1/talents/export",
+    "/api/v1/talents/{id}/jsonresume",
+    "/api/v1/jobs/{id}/export",
+    "/api/v1/preferences/export",
+    "/api/v1/erasures",
+    "/api/v1/trash",
+    "/api/v1/audit",
+    "/api/v1/notifications/outbox",
+];
+
+pub const DEFAULT_LIMIT: i64 = 100;
+pub const MAX_LIMIT: i64 = 1000;
+
+/// Whether a request to `route` with `method` must be audited
+pub fn is_audited(method: &Method, route: &str) -> bool {
+    let read_only = matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS);
+    !read_only || SENSITIVE_READS.contains(&route)
+}
+
+/// Outcome recorded for an HTTP status
+pub fn outcome(status: u16) -> &'static str {
+    if status < 400 { "success" } else { "failure" }
+}
+
+/// Render audit entries as CSV with a header row
+pub fn to_csv(entries: &[AuditLogEntry]) -> Result<Vec<u8>, csv::Error> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record([
+        "id", "occurred_at", "request_id", "actor", "method", "route", "path",
+        "target_ids", "status", "outcome", "error_code", "ip",
+    ])?;
+    for e in entries {
+        writer.write_record([
+            e.id.as_str(),
+            e.occurred_at.as_str(),
+            e.request_id.as_deref().unwrap_or(""),
+            e.actor.as_str(),
+            e.method.as_str(),
+            e.route.as_str(),
+            e.path.as_str(),
+            e.target_ids.as_str(),
+            &e.status.to_string(),
+            e.outcome.as_str(),
+            e.error_code.as_deref().unwrap_or(""),
+            e.ip.as_deref().unwrap_or(""),
+        ])?;
+    }
+    writer.into_inner().map_err(|e| e.into_error().into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mutations_and_sensitive_reads_are_audited() {
+        assert!(is_audited(&Method::POST, "/api/v1/talents/bulk-delete"));
+        assert!(is_audited(&Method::DELETE, "/api/v1/talents/{id}"));
+        assert!(is_audited(&Method::GET, "/api/v1/applications/{id}/resume"));
+        assert!(!is_audited(&Method::GET, "/api/v1/talents"));
+        assert!(!is_audited(&Method::OPTIONS, "/api/v1/talents/{id}"));
+    }
+
+    #[test]
+    fn test_outcome() {
+        assert_eq!(outcome(204), "success");
+        assert_eq!(outcome(404), "failure");
+        assert_eq!(outcome(500), "failure");
+    }
+
+    #[test]
+    fn test_csv_escapes_fields() {
+        let entry = AuditLogEntry {
+            id: "a1".to_string(),
+            occurred_at: "2026-01-01T00:00:00+00:00".to_string(),
+            request_id: None,
+            actor: "Doe, Jane".to_string(),
+            method: "POST".to_string(),
+            route: "/api/v1/talents/bulk-delete".to_string(),
+            path: "/api/v1/talents/bulk-delete".to_string(),
+            target_ids: r#"["t1","t2"]"#.to_string(),
+            status: 200,
+            outcome: "success".to_string(),
+            error_code: None,
+            ip: Some("127.0.0.1".to_string()),
+        };
+        let csv = String::from_utf8(to_csv(&[entry]).unwrap()).unwrap();
+        let mut lines = csv.lines();
+        assert!(lines.next().unwrap().starts_with("id,occurred_at,"));
+        assert_eq!(
+            lines.next().unwrap(),
+            r#"a1,2026-01-01T00:00:00+00:00,,"Doe, Jane",POST,/api/v1/talents/bulk-delete,/api/v1/talents/bulk-delete,"[""t1"",""t2""]",200,success,,127.0.0.1"#
+        );
+    }
+}
@@ -0,0 +1,65 @@
+//! Queued creation of talents' xAI collections
+//!
+//! New talents need a collection in grok-service. Requests are queued and
+//! worked through one at a time, so a bulk import of hundreds of talents does
+//! not fire hundreds of concurrent requests at grok-service.
+
+use log::{error, info};
+use tokio::sync::mpsc;
+
+use crate::database::Pool;
+use crate::grok_client::GrokClient;
+
+struct CollectionRequest {
+    talent_id: String,
+    talent_name: String,
+}
+
+#[derive(Clone)]
+pub struct CollectionQueue {
+    tx: mpsc::UnboundedSender<CollectionRequest>,
+}
+
+impl CollectionQueue {
+    /// Start the worker and return a handle for enqueueing talents
+    pub fn spawn(pool: Pool, grok_url: String) -> Self {
+        let (tx, mut rx) = mpsc::unbounded_channel::<CollectionRequest>();
+
+        tokio::spawn(async move {
+            let client = GrokClient::new(&grok_url);
+            while let Some(request) = rx.recv().await {
+                create_collection(&pool, &client, request).await;
+            }
+        });
+
+        CollectionQueue { tx }
+    }
+
+    pub fn enqueue(&self, talent_id: String, talent_name: String) {
+        if self.tx.send(CollectionRequest { talent_id, talent_name }).is_err() {
+            error!("[collections] Worker has stopped; collection not queued");
+        }
+    }
+}
+
+async fn create_collection(pool: &Pool, client: &GrokClient, request: CollectionRequest) {
+    let CollectionRequest { talent_id, talent_name } = request;
+    info!("COLLECTION CREATION: Starting for talent {}", talent_id);
+
+    match client.create_collection(&talent_id, &talent_name).await {
+        Ok(response) if response.success => {
+            let Some(collection) = response.collection else {
+                error!("COLLECTION CREATION: No collection in response for talent {}", talent_id);
+                return;
+            };
+            info!("COLLECTION CREATION: Created {} ({}) for talent {}", collection.collection_id, collection.collection_name, talent_id);
+
+            match crate::database::update_talent_collection_id(pool, talent_id.clone(), collection.collection_id.clone()).await {
+                Ok(_) => info!("SUCCESS: Updated talent {} with collection_id {}", talent_id, collection.collection_id),
+                Err(e) => error!("FAILED to update talent {} with collection_id: {}", talent_id, e),
+            }
+        }
+        Ok(response) => error!("COLLECTION CREATION: Failed for talent {} - {:?}", talent_id, response.error),
+        Err(e) => error!("COLLECTION CREATION: Error for talent {} - {}", talent_id, e),
+    }
+}
@@ -1,10 +1,26 @@
-use sqlx::SqlitePool;
-use crate::models::{Talent, UpdateTalentRequest, Job, UpdateJobRequest, Application, JobMatch, ReorderEvent, PairwisePreference};
+use std::str::FromStr;
+
+use futures_util::stream::{BoxStream, TryStreamExt};
+
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+use sqlx::{Connection, SqliteConnection, SqliteExecutor, SqlitePool};
+use crate::models::{
+    Talent, UpdateTalentRequest, Job, UpdateJobRequest, Application, JobMatch, ReorderEvent, PairwisePreference,
+    CandidateLoginTokenRecord, ErasureCounts, ErasureTombstone, AuditLogEntry, AuditLogQuery,
+    TalentListQuery, PipelineRow, Interview, Scorecard, Offer, OfferApproval, OfferEvent, Webhook, WebhookDelivery,
+    OutboxEmail, OutboxQuery, StoredEvent, JobCandidateOrder, EvaluationRun, EvaluationRunQuery, MatchRun,
+};
+use crate::job_lifecycle::JobStatus;
+use crate::import::{JobImport, TalentImport};
+use crate::integrity::Relationship;
+use crate::retention::DataClass;
 
 pub type Pool = SqlitePool;
 
 pub async fn init_pool(database_url: &str) -> Result<Pool, sqlx::Error> {
-    let pool = SqlitePool::connect(database_url).await?;
+    // Enforce foreign keys on every pooled connection
+    let options = SqliteConnectOptions::from_str(database_url)?.foreign_keys(true);
+    let pool = SqlitePoolOptions::new().connect_with(options).await?;
 
     // Run migrations
     let talents_schema = include_str!("../migrations/001_create_talents_table.sql");
@@ -63,10 +79,162 @@ pub async fn init_pool(database_url: &str) -> Result<Pool, sqlx::Error> {
         let _ = sqlx::query(statement).execute(&pool).await;
     }
 
+    // Add job lifecycle fields (ignore error if columns already exist)
+    let job_lifecycle_schema = include_str!("../migrations/011_add_job_lifecycle.sql");
+    for statement in job_lifecycle_schema.split(';').filter(|s| !s.trim().is_empty()) {
+        let _ = sqlx::query(statement).execute(&pool).await;
+    }
+
+    // Create candidate portal login tokens table
+    let login_tokens_schema = include_str!("../migrations/012_create_candidate_login_tokens.sql");
+    for statement in login_tokens_schema.split(';').filter(|s| !s.trim().is_empty()) {
+        sqlx::query(statement).execute(&pool).await?;
+    }
+
+    // Create erasure tombstones table
+    let tombstones_schema = include_str!("../migrations/013_create_erasure_tombstones.sql");
+    for statement in tombstones_schema.split(';').filter(|s| !s.trim().is_empty()) {
+        sqlx::query(statement).execute(&pool).await?;
+    }
+
+    // Add legal hold flags (ignore error if columns already exist)
+    let legal_hold_schema = include_str!("../migrations/014_add_retention_legal_hold.sql");
+    for statement in legal_hold_schema.split(';').filter(|s| !s.trim().is_empty()) {
+        let _ = sqlx::query(statement).execute(&pool).await;
+    }
+
+    // Create audit log table. Its triggers contain ';' so the file runs as one script
+    let audit_log_schema = include_str!("../migrations/015_create_audit_log.sql");
+    sqlx::raw_sql(audit_log_schema).execute(&pool).await?;
+
+    // Add soft delete columns (ignore error if columns already exist)
+    let soft_delete_schema = include_str!("../migrations/016_add_soft_delete.sql");
+    for statement in soft_delete_schema.split(';').filter(|s| !s.trim().is_empty()) {
+        let _ = sqlx::query(statement).execute(&pool).await;
+    }
+
+    // Rebuild applications with explicit delete policies (once; detected from
+    // the current foreign key list). Foreign keys must be off while the table
+    // is swapped, and that pragma cannot change inside a transaction.
+    let talent_policy: Option<String> = sqlx::query_scalar(
+        "SELECT on_delete FROM pragma_foreign_key_list('applications') WHERE \"from\" = 'talent_id'"
+    )
+        .fetch_optional(&pool)
+        .await?;
+    if talent_policy.as_deref() != Some("CASCADE") {
+        let mut conn = pool.acquire().await?;
+        sqlx::query("PRAGMA foreign_keys = OFF").execute(&mut *conn).await?;
+        let rebuilt = async {
+            let mut tx = conn.begin().await?;
+            sqlx::raw_sql(include_str!("../migrations/017_application_foreign_keys.sql"))
+                .execute(&mut *tx)
+                .await?;
+            tx.commit().await
+        }.await;
+        sqlx::query("PRAGMA foreign_keys = ON").execute(&mut *conn).await?;
+        rebuilt?;
+    }
+
+    // Create interview and scorecard tables
+    let interviews_schema = include_str!("../migrations/018_create_interviews.sql");
+    for statement in interviews_schema.split(';').filter(|s| !s.trim().is_empty()) {
+        sqlx::query(statement).execute(&pool).await?;
+    }
+
+    // Create offer, approval and offer history tables
+    let offers_schema = include_str!("../migrations/019_create_offers.sql");
+    for statement in offers_schema.split(';').filter(|s| !s.trim().is_empty()) {
+        sqlx::query(statement).execute(&pool).await?;
+    }
+
+    // Create webhook endpoint and delivery log tables
+    let webhooks_schema = include_str!("../migrations/020_create_webhooks.sql");
+    for statement in webhooks_schema.split(';').filter(|s| !s.trim().is_empty()) {
+        sqlx::query(statement).execute(&pool).await?;
+    }
+
+    // Create email outbox and notification preference tables
+    let email_schema = include_str!("../migrations/021_create_email_outbox.sql");
+    for statement in email_schema.split(';').filter(|s| !s.trim().is_empty()) {
+        sqlx::query(statement).execute(&pool).await?;
+    }
+
+    // Create the event history behind event stream resumption
+    let event_history_schema = include_str!("../migrations/022_create_event_history.sql");
+    for statement in event_history_schema.split(';').filter(|s| !s.trim().is_empty()) {
+        sqlx::query(statement).execute(&pool).await?;
+    }
+
+    // Create the canonical candidate order per job
+    let candidate_orders_schema = include_str!("../migrations/023_create_job_candidate_orders.sql");
+    for statement in candidate_orders_schema.split(';').filter(|s| !s.trim().is_empty()) {
+        sqlx::query(statement).execute(&pool).await?;
+    }
+
+    // Add the tie flag to pairwise preferences (ignore error if the column already exists)
+    let preference_ties_schema = include_str!("../migrations/024_add_preference_ties.sql");
+    for statement in preference_ties_schema.split(';').filter(|s| !s.trim().is_empty()) {
+        let _ = sqlx::query(statement).execute(&pool).await;
+    }
+
+    // Add preference authors and supersession (ignore error if the columns already exist)
+    let preference_authors_schema = include_str!("../migrations/025_add_preference_author_and_supersession.sql");
+    for statement in preference_authors_schema.split(';').filter(|s| !s.trim().is_empty()) {
+        let _ = sqlx::query(statement).execute(&pool).await;
+    }
+
+    // Create the evaluation history of ranking sources
+    let evaluation_runs_schema = include_str!("../migrations/026_create_evaluation_runs.sql");
+    for statement in evaluation_runs_schema.split(';').filter(|s| !s.trim().is_empty()) {
+        sqlx::query(statement).execute(&pool).await?;
+    }
+
+    // Create versioned job matching runs, backfilled from existing matches
+    let match_runs_schema = include_str!("../migrations/027_create_match_runs.sql");
+    for statement in match_runs_schema.split(';').filter(|s| !s.trim().is_empty()) {
+        sqlx::query(statement).execute(&pool).await?;
+    }
+
+    // Add batch progress to matching runs (ignore error if the columns already exist)
+    let match_run_progress_schema = include_str!("../migrations/028_add_match_run_progress.sql");
+    for statement in match_run_progress_schema.split(';').filter(|s| !s.trim().is_empty()) {
+        let _ = sqlx::query(statement).execute(&pool).await;
+    }
+
+    // Add activity timestamps to talents and applications (ignore error if the columns already exist)
+    let activity_schema = include_str!("../migrations/029_add_activity_timestamps.sql");
+    for statement in activity_schema.split(';').filter(|s| !s.trim().is_empty()) {
+        let _ = sqlx::query(statement).execute(&pool).await;
+    }
+
+    // Deduplicate implicit preferences and enforce their uniqueness
+    let preference_dedupe_schema = include_str!("../migrations/030_dedupe_implicit_preferences.sql");
+    for statement in preference_dedupe_schema.split(';').filter(|s| !s.trim().is_empty()) {
+        sqlx::query(statement).execute(&pool).await?;
+    }
+
+    // Allow at most one running matching run per job
+    let running_match_run_schema = include_str!("../migrations/031_unique_running_match_run.sql");
+    for statement in running_match_run_schema.split(';').filter(|s| !s.trim().is_empty()) {
+        sqlx::query(statement).execute(&pool).await?;
+    }
+
+    // Allow at most one open offer per application
+    let open_offer_schema = include_str!("../migrations/032_unique_open_offer.sql");
+    for statement in open_offer_schema.split(';').filter(|s| !s.trim().is_empty()) {
+        sqlx::query(statement).execute(&pool).await?;
+    }
+
+    // Record the matching run evaluated by an evaluation run (ignore error if the columns already exist)
+    let evaluation_run_match_run_schema = include_str!("../migrations/033_add_evaluation_run_match_run.sql");
+    for statement in evaluation_run_match_run_schema.split(';').filter(|s| !s.trim().is_empty()) {
+        let _ = sqlx::query(statement).execute(&pool).await;
+    }
+
     Ok(pool)
 }
 
-pub async fn create_talent(pool: &Pool, talent: &Talent) -> Result<Talent, sqlx::Error> {
+pub async fn create_talent<'e, E: SqliteExecutor<'e>>(executor: E, talent: &Talent) -> Result<Talent, sqlx::Error> {
     sqlx::query_as::<_, Talent>(include_str!("queries/insert_talent.sql"))
         .bind(&talent.id)
         .bind(&talent.name)
@@ -78,16 +246,79 @@ pub async fn create_talent(pool: &Pool, talent: &Talent) -> Result<Talent, sqlx:
         .bind(&talent.experience)
         .bind(sqlx::types::Json(&talent.skills))
         .bind(&talent.bio)
-        .bind(talent.verified as i32)
+        .bind(talent.verified)
         .bind(&talent.created_at)
-        .fetch_one(pool)
+        .fetch_one(executor)
         .await
 }
 
-pub async fn get_all_talents(pool: &Pool) -> Result<Vec<Talent>, sqlx::Error> {
-    sqlx::query_as::<_, Talent>(include_str!("queries/get_all_talents.sql"))
-    .fetch_all(pool)
-    .await
+/// Talent skills are stored JSON-encoded; recover the plain text
+pub fn decode_skills(skills: String) -> String {
+    serde_json::from_str::<String>(&skills).unwrap_or(skills)
+}
+
+pub async fn get_all_talents(pool: &Pool, filter: &TalentListQuery) -> Result<Vec<Talent>, sqlx::Error> {
+    TalentQuery::new(filter).fetch_all(pool).await
+}
+
+/// Filtered talent listing, shared by the list and export endpoints
+pub struct TalentQuery {
+    sql: String,
+    binds: Vec<String>,
+}
+
+impl TalentQuery {
+    pub fn new(filter: &TalentListQuery) -> Self {
+        let mut conditions: Vec<&str> = vec!["deleted_at IS NULL"];
+        let mut binds: Vec<String> = Vec::new();
+
+        if let Some(q) = filter.q.as_deref().map(str::trim).filter(|q| !q.is_empty()) {
+            conditions.push("(name LIKE ? OR email LIKE ? OR handle LIKE ? OR title LIKE ?)");
+            binds.extend(std::iter::repeat_n(format!("%{}%", q), 4));
+        }
+        if let Some(skill) = &filter.skill {
+            conditions.push("skills LIKE ?");
+            binds.push(format!("%{}%", skill));
+        }
+        if let Some(location) = &filter.location {
+            conditions.push("location LIKE ?");
+            binds.push(format!("%{}%", location));
+        }
+        if let Some(verified) = filter.verified {
+            conditions.push("verified = ?");
+            binds.push((verified as i32).to_string());
+        }
+        if let Some(min_score) = filter.min_score {
+            conditions.push("candidate_score >= ?");
+            binds.push(min_score.to_string());
+        }
+        if let Some(after) = &filter.created_after {
+            conditions.push("created_at >= ?");
+            binds.push(after.clone());
+        }
+        if let Some(before) = &filter.created_before {
+            conditions.push("created_at < ?");
+            binds.push(before.clone());
+        }
+
+        TalentQuery {
+            sql: format!("SELECT * FROM talents WHERE {} ORDER BY created_at DESC", conditions.join(" AND ")),
+            binds,
+        }
+    }
+
+    pub async fn fetch_all(&self, pool: &Pool) -> Result<Vec<Talent>, sqlx::Error> {
+        self.fetch(pool).try_collect().await
+    }
+
+    /// Stream matching talents without loading them all into memory
+    pub fn fetch<'a>(&'a self, pool: &'a Pool) -> BoxStream<'a, Result<Talent, sqlx::Error>> {
+        let mut query = sqlx::query_as::<_, Talent>(&self.sql);
+        for value in &self.binds {
+            query = query.bind(value);
+        }
+        query.fetch(pool)
+    }
 }
 
 pub async fn get_talent_by_id(pool: &Pool, id: String) -> Result<Option<Talent>, sqlx::Error> {
@@ -97,6 +328,15 @@ pub async fn get_talent_by_id(pool: &Pool, id: String) -> Result<Option<Talent>,
         .await
 }
 
+/// Look up a talent whether or not they are in the trash
+pub async fn get_talent_by_id_including_deleted(pool: &Pool, id: &str) -> Result<Option<Talent>, sqlx::Error> {
+    sqlx::query_as::<_, Talent>("SELECT * FROM talents WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Find a live talent by email, ignoring case
 pub async fn get_talent_by_email(pool: &Pool, email: String) -> Result<Option<Talent>, sqlx::Error> {
     sqlx::query_as::<_, Talent>(include_str!("queries/get_talent_by_email.sql"))
         .bind(&email)
@@ -104,7 +344,7 @@ pub async fn get_talent_by_email(pool: &Pool, email: String) -> Result<Option<Ta
         .await
 }
 
-pub async fn update_talent(pool: &Pool, id: String, updates: &UpdateTalentRequest) -> Result<Option<Talent>, sqlx::Error> {
+pub async fn update_talent<'e, E: SqliteExecutor<'e>>(executor: E, id: String, updates: &UpdateTalentRequest) -> Result<Option<Talent>, sqlx::Error> {
     let query_str = include_str!("queries/update_talent.sql");
     let name = updates.name.as_ref().map(|s| s as &str).unwrap_or("");
     let email = updates.email.as_ref().map(|s| s as &str).unwrap_or("");
@@ -113,7 +353,7 @@ pub async fn update_talent(pool: &Pool, id: String, updates: &UpdateTalentReques
     let title = updates.title.as_ref().map(|s| s as &str).unwrap_or("");
     let location = &updates.location;
     let experience = updates.experience.as_ref().map(|s| s as &str).unwrap_or("");
-    let skills_json = updates.skills.as_ref().map(|s| sqlx::types::Json(s));
+    let skills_json = updates.skills.as_ref().map(sqlx::types::Json);
     let bio = &updates.bio;
     let verified = updates.verified.unwrap_or(false) as i32;
     sqlx::query_as::<_, Talent>(query_str)
@@ -127,19 +367,16 @@ pub async fn update_talent(pool: &Pool, id: String, updates: &UpdateTalentReques
         .bind(skills_json)
         .bind(bio)
         .bind(verified)
+        .bind(chrono::Utc::now().to_rfc3339())
         .bind(&id)
-        .fetch_optional(pool)
+        .fetch_optional(executor)
         .await
 }
 
+/// Soft delete a talent. Their live applications are moved to the trash
+/// with the same timestamp so a restore can bring them back together.
 pub async fn delete_talent(pool: &Pool, id: String) -> Result<bool, sqlx::Error> {
-    let query_str = include_str!("queries/delete_talent.sql");
-    let rows = sqlx::query(query_str)
-        .bind(&id)
-        .execute(pool)
-        .await?
-        .rows_affected();
-    Ok(rows > 0)
+    Ok(delete_talents_bulk(pool, &[id]).await? > 0)
 }
 
 pub async fn delete_talents_bulk(pool: &Pool, ids: &[String]) -> Result<u64, sqlx::Error> {
@@ -147,21 +384,32 @@ pub async fn delete_talents_bulk(pool: &Pool, ids: &[String]) -> Result<u64, sql
         return Ok(0);
     }
 
-    let placeholders: Vec<String> = ids.iter().enumerate().map(|(i, _)| format!("?{}", i + 1)).collect();
-    let query_str = format!("DELETE FROM talents WHERE id IN ({})", placeholders.join(", "));
-
-    let mut query = sqlx::query(&query_str);
+    let now = chrono::Utc::now().to_rfc3339();
+    let mut tx = pool.begin().await?;
+    let mut rows = 0;
     for id in ids {
-        query = query.bind(id);
+        let deleted = sqlx::query(include_str!("queries/delete_talent.sql"))
+            .bind(&now)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        if deleted > 0 {
+            sqlx::query("UPDATE applications SET deleted_at = ? WHERE talent_id = ? AND deleted_at IS NULL")
+                .bind(&now)
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        rows += deleted;
     }
-
-    let rows = query.execute(pool).await?.rows_affected();
+    tx.commit().await?;
     Ok(rows)
 }
 
 // Job database functions
 
-pub async fn create_job(pool: &Pool, job: &Job) -> Result<Job, sqlx::Error> {
+pub async fn create_job<'e, E: SqliteExecutor<'e>>(executor: E, job: &Job) -> Result<Job, sqlx::Error> {
     sqlx::query_as::<_, Job>(include_str!("queries/insert_job.sql"))
         .bind(&job.id)
         .bind(&job.title)
@@ -171,15 +419,16 @@ pub async fn create_job(pool: &Pool, job: &Job) -> Result<Job, sqlx::Error> {
         .bind(&job.location)
         .bind(&job.location_type)
         .bind(&job.employment_type)
-        .bind(&job.salary_min)
-        .bind(&job.salary_max)
+        .bind(job.salary_min)
+        .bind(job.salary_max)
         .bind(&job.salary_currency)
         .bind(&job.skills_required)
         .bind(&job.experience_level)
         .bind(&job.status)
         .bind(&job.created_at)
         .bind(&job.expires_at)
-        .fetch_one(pool)
+        .bind(&job.published_at)
+        .fetch_one(executor)
         .await
 }
 
@@ -196,7 +445,7 @@ pub async fn get_job_by_id(pool: &Pool, id: String) -> Result<Option<Job>, sqlx:
         .await
 }
 
-pub async fn update_job(pool: &Pool, id: String, updates: &UpdateJobRequest) -> Result<Option<Job>, sqlx::Error> {
+pub async fn update_job<'e, E: SqliteExecutor<'e>>(executor: E, id: String, updates: &UpdateJobRequest) -> Result<Option<Job>, sqlx::Error> {
     let title = updates.title.as_ref().map(|s| s as &str).unwrap_or("");
     let description = updates.description.as_ref().map(|s| s as &str).unwrap_or("");
     let company_name = updates.company_name.as_ref().map(|s| s as &str).unwrap_or("");
@@ -214,29 +463,107 @@ pub async fn update_job(pool: &Pool, id: String, updates: &UpdateJobRequest) ->
         .bind(&updates.location)
         .bind(location_type)
         .bind(employment_type)
-        .bind(&updates.salary_min)
-        .bind(&updates.salary_max)
+        .bind(updates.salary_min)
+        .bind(updates.salary_max)
         .bind(&updates.salary_currency)
         .bind(skills_required)
         .bind(experience_level)
         .bind(status)
         .bind(&updates.expires_at)
         .bind(&id)
-        .fetch_optional(pool)
+        .fetch_optional(executor)
         .await
 }
 
+/// Soft delete a job along with its live applications (same timestamp)
 pub async fn delete_job(pool: &Pool, id: String) -> Result<bool, sqlx::Error> {
+    let now = chrono::Utc::now().to_rfc3339();
+    let mut tx = pool.begin().await?;
     let rows = sqlx::query(include_str!("queries/delete_job.sql"))
+        .bind(&now)
         .bind(&id)
-        .execute(pool)
+        .execute(&mut *tx)
         .await?
         .rows_affected();
+    if rows > 0 {
+        sqlx::query("UPDATE applications SET deleted_at = ? WHERE job_id = ? AND deleted_at IS NULL")
+            .bind(&now)
+            .bind(&id)
+            .execute(&mut *tx)
+            .await?;
+    }
+    tx.commit().await?;
     Ok(rows > 0)
 }
 
+/// Get published jobs, newest first (includes legacy 'active' rows)
+pub async fn get_published_jobs(pool: &Pool) -> Result<Vec<Job>, sqlx::Error> {
+    sqlx::query_as::<_, Job>(
+        "SELECT * FROM jobs WHERE status IN ('published', 'active') AND deleted_at IS NULL ORDER BY COALESCE(published_at, created_at) DESC"
+    )
+        .fetch_all(pool)
+        .await
+}
+
+/// Get jobs that have an expiry date set
+pub async fn get_jobs_with_expiry(pool: &Pool) -> Result<Vec<Job>, sqlx::Error> {
+    sqlx::query_as::<_, Job>(
+        "SELECT * FROM jobs WHERE expires_at IS NOT NULL AND expires_at != '' AND deleted_at IS NULL"
+    )
+        .fetch_all(pool)
+        .await
+}
+
+/// Move a job from the `from` status it was read with to a new lifecycle
+/// status, stamping published_at / closed_at. Returns `None` when the job is
+/// gone or its status has changed since it was read.
+pub async fn set_job_status(pool: &Pool, id: &str, from: &str, status: JobStatus) -> Result<Option<Job>, sqlx::Error> {
+    let now = chrono::Utc::now().to_rfc3339();
+    sqlx::query_as::<_, Job>(
+        r#"
+        UPDATE jobs
+        SET status = ?1,
+            published_at = CASE WHEN ?1 = 'published' AND published_at IS NULL THEN ?2 ELSE published_at END,
+            closed_at = CASE WHEN ?1 IN ('closed', 'filled') THEN ?2 ELSE closed_at END
+        WHERE id = ?3 AND status = ?4 AND deleted_at IS NULL
+        RETURNING *
+        "#,
+    )
+    .bind(status.as_str())
+    .bind(&now)
+    .bind(id)
+    .bind(from)
+    .fetch_optional(pool)
+    .await
+}
+
 // Application database functions
 
+/// Reject all still-open applications for a job, returning the rejected rows
+/// along with the status each had before
+pub async fn reject_open_applications_for_job(pool: &Pool, job_id: &str) -> Result<Vec<(Application, String)>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let open = sqlx::query_as::<_, Application>(
+        r#"
+        SELECT id, talent_id, job_id, resume_data, resume_filename, resume_content_type, cover_letter, status, created_at, deleted_at
+        FROM applications
+        WHERE job_id = ? AND status IN ('pending', 'reviewed') AND deleted_at IS NULL
+        "#,
+    )
+    .bind(job_id)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let mut rejected = Vec::with_capacity(open.len());
+    for application in open {
+        if let Some(updated) = update_application_status(&mut *tx, &application.id, "rejected").await? {
+            rejected.push((updated, application.status));
+        }
+    }
+    tx.commit().await?;
+    Ok(rejected)
+}
+
 pub async fn create_application(pool: &Pool, application: &Application) -> Result<Application, sqlx::Error> {
     sqlx::query_as::<_, Application>(include_str!("queries/insert_application.sql"))
         .bind(&application.id)
@@ -252,10 +579,10 @@ pub async fn create_application(pool: &Pool, application: &Application) -> Resul
         .await
 }
 
-pub async fn get_application_by_id(pool: &Pool, id: String) -> Result<Option<Application>, sqlx::Error> {
+pub async fn get_application_by_id<'e, E: SqliteExecutor<'e>>(executor: E, id: String) -> Result<Option<Application>, sqlx::Error> {
     sqlx::query_as::<_, Application>(include_str!("queries/get_application_by_id.sql"))
         .bind(&id)
-        .fetch_optional(pool)
+        .fetch_optional(executor)
         .await
 }
 
@@ -266,6 +593,17 @@ pub async fn get_applications_by_talent(pool: &Pool, talent_id: String) -> Resul
         .await
 }
 
+/// Applications of a talent, including those in the trash (data subject requests)
+pub async fn get_applications_by_talent_including_deleted(pool: &Pool, talent_id: &str) -> Result<Vec<Application>, sqlx::Error> {
+    sqlx::query_as::<_, Application>(&format!(
+        "SELECT {} FROM applications WHERE talent_id = ? ORDER BY created_at DESC",
+        APPLICATION_COLUMNS
+    ))
+        .bind(talent_id)
+        .fetch_all(pool)
+        .await
+}
+
 pub async fn get_applications_by_job(pool: &Pool, job_id: String) -> Result<Vec<Application>, sqlx::Error> {
     sqlx::query_as::<_, Application>(include_str!("queries/get_applications_by_job.sql"))
         .bind(&job_id)
@@ -273,8 +611,10 @@ pub async fn get_applications_by_job(pool: &Pool, job_id: String) -> Result<Vec<
         .await
 }
 
+/// Soft delete an application
 pub async fn delete_application(pool: &Pool, id: String) -> Result<bool, sqlx::Error> {
     let rows = sqlx::query(include_str!("queries/delete_application.sql"))
+        .bind(chrono::Utc::now().to_rfc3339())
         .bind(&id)
         .execute(pool)
         .await?
@@ -287,10 +627,13 @@ pub async fn delete_applications_bulk(pool: &Pool, ids: &[String]) -> Result<u64
         return Ok(0);
     }
 
-    let placeholders: Vec<String> = ids.iter().enumerate().map(|(i, _)| format!("?{}", i + 1)).collect();
-    let query_str = format!("DELETE FROM applications WHERE id IN ({})", placeholders.join(", "));
+    let placeholders: Vec<String> = ids.iter().enumerate().map(|(i, _)| format!("?{}", i + 2)).collect();
+    let query_str = format!(
+        "UPDATE applications SET deleted_at = ?1 WHERE deleted_at IS NULL AND id IN ({})",
+        placeholders.join(", ")
+    );
 
-    let mut query = sqlx::query(&query_str);
+    let mut query = sqlx::query(&query_str).bind(chrono::Utc::now().to_rfc3339());
     for id in ids {
         query = query.bind(id);
     }
@@ -299,6 +642,40 @@ pub async fn delete_applications_bulk(pool: &Pool, ids: &[String]) -> Result<u64
     Ok(rows)
 }
 
+/// Update an application's status
+pub async fn update_application_status<'e, E: SqliteExecutor<'e>>(executor: E, id: &str, status: &str) -> Result<Option<Application>, sqlx::Error> {
+    sqlx::query_as::<_, Application>(
+        "UPDATE applications SET status = ?, updated_at = ? WHERE id = ? AND deleted_at IS NULL
+         RETURNING id, talent_id, job_id, resume_data, resume_filename, resume_content_type, cover_letter, status, created_at, deleted_at"
+    )
+        .bind(status)
+        .bind(chrono::Utc::now().to_rfc3339())
+        .bind(id)
+        .fetch_optional(executor)
+        .await
+}
+
+/// Replace the resume attached to an application
+pub async fn update_application_resume(
+    pool: &Pool,
+    id: &str,
+    resume_data: &str,
+    resume_filename: Option<&str>,
+    resume_content_type: Option<&str>,
+) -> Result<Option<Application>, sqlx::Error> {
+    sqlx::query_as::<_, Application>(
+        "UPDATE applications SET resume_data = ?, resume_filename = ?, resume_content_type = ?, updated_at = ? WHERE id = ? AND deleted_at IS NULL
+         RETURNING id, talent_id, job_id, resume_data, resume_filename, resume_content_type, cover_letter, status, created_at, deleted_at"
+    )
+        .bind(resume_data)
+        .bind(resume_filename)
+        .bind(resume_content_type)
+        .bind(chrono::Utc::now().to_rfc3339())
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+}
+
 /// Update talent's resume_document_id
 pub async fn update_talent_resume_document_id(
     pool: &Pool,
@@ -330,8 +707,8 @@ pub async fn update_talent_collection_id(
 }
 
 /// Update talent's resume-extracted fields
-pub async fn update_talent_resume_fields(
-    pool: &Pool,
+pub async fn update_talent_resume_fields<'e, E: SqliteExecutor<'e>>(
+    executor: E,
     talent_id: String,
     resume_experiences: Option<String>,
     linkedin_url: Option<String>,
@@ -345,8 +722,9 @@ pub async fn update_talent_resume_fields(
         .bind(&x_url)
         .bind(&github_url)
         .bind(&gitlab_url)
+        .bind(chrono::Utc::now().to_rfc3339())
         .bind(&talent_id)
-        .fetch_optional(pool)
+        .fetch_optional(executor)
         .await
 }
 
@@ -397,7 +775,7 @@ pub async fn update_talent_candidate_score(
 // Job Match database functions
 
 /// Create a job match
-pub async fn create_job_match(pool: &Pool, job_match: &JobMatch) -> Result<JobMatch, sqlx::Error> {
+pub async fn create_job_match<'e, E: SqliteExecutor<'e>>(executor: E, job_match: &JobMatch) -> Result<JobMatch, sqlx::Error> {
     sqlx::query_as::<_, JobMatch>(
         r#"
         INSERT INTO job_matches (id, job_id, talent_id, score, rank, match_reasons, concerns, summary, created_at)
@@ -414,7 +792,7 @@ pub async fn create_job_match(pool: &Pool, job_match: &JobMatch) -> Result<JobMa
     .bind(&job_match.concerns)
     .bind(&job_match.summary)
     .bind(&job_match.created_at)
-    .fetch_one(pool)
+    .fetch_one(executor)
     .await
 }
 
@@ -424,6 +802,7 @@ pub async fn get_job_matches(pool: &Pool, job_id: &str) -> Result<Vec<JobMatch>,
         r#"
         SELECT * FROM job_matches
         WHERE job_id = ?
+        AND talent_id NOT IN (SELECT id FROM talents WHERE deleted_at IS NOT NULL)
         ORDER BY rank ASC
         "#,
     )
@@ -433,15 +812,223 @@ pub async fn get_job_matches(pool: &Pool, job_id: &str) -> Result<Vec<JobMatch>,
 }
 
 /// Delete all job matches for a job
-pub async fn delete_job_matches_by_job_id(pool: &Pool, job_id: &str) -> Result<u64, sqlx::Error> {
+pub async fn delete_job_matches_by_job_id<'e, E: SqliteExecutor<'e>>(executor: E, job_id: &str) -> Result<u64, sqlx::Error> {
     let rows = sqlx::query("DELETE FROM job_matches WHERE job_id = ?")
         .bind(job_id)
+        .execute(executor)
+        .await?
+        .rows_affected();
+    Ok(rows)
+}
+
+// Match run database functions
+
+/// Record the start of a job matching run. Returns `None`, storing nothing,
+/// when another run is already running for the job.
+pub async fn create_match_run(pool: &Pool, run: &MatchRun) -> Result<Option<MatchRun>, sqlx::Error> {
+    sqlx::query_as::<_, MatchRun>(
+        r#"
+        INSERT OR IGNORE INTO match_runs (id, job_id, status, top_n, candidate_count, batch_size, batch_count, current_batch, evaluated,
+                                total_evaluated, match_count, model_version, error, created_at, completed_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        RETURNING *
+        "#,
+    )
+    .bind(&run.id)
+    .bind(&run.job_id)
+    .bind(&run.status)
+    .bind(run.top_n)
+    .bind(run.candidate_count)
+    .bind(run.batch_size)
+    .bind(run.batch_count)
+    .bind(run.current_batch)
+    .bind(run.evaluated)
+    .bind(run.total_evaluated)
+    .bind(run.match_count)
+    .bind(&run.model_version)
+    .bind(&run.error)
+    .bind(&run.created_at)
+    .bind(&run.completed_at)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Record a running run's progress through its batches
+pub async fn update_match_run_progress(pool: &Pool, run_id: &str, current_batch: i32, evaluated: i32) -> Result<(), sqlx::Error> {
+    sqlx::query("UPDATE match_runs SET current_batch = ?, evaluated = ? WHERE id = ? AND status = 'running'")
+        .bind(current_batch)
+        .bind(evaluated)
+        .bind(run_id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Mark a running run failed. The matches being served are left as they are.
+pub async fn fail_match_run(pool: &Pool, run_id: &str, error: &str, completed_at: &str) -> Result<(), sqlx::Error> {
+    sqlx::query("UPDATE match_runs SET status = 'failed', error = ?, completed_at = ? WHERE id = ? AND status = 'running'")
+        .bind(error)
+        .bind(completed_at)
+        .bind(run_id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Cancel a running run, returning it, or `None` when it is not running
+pub async fn cancel_match_run(pool: &Pool, job_id: &str, run_id: &str, completed_at: &str) -> Result<Option<MatchRun>, sqlx::Error> {
+    sqlx::query_as::<_, MatchRun>(
+        r#"
+        UPDATE match_runs SET status = 'cancelled', completed_at = ?
+        WHERE id = ? AND job_id = ? AND status = 'running'
+        RETURNING *
+        "#,
+    )
+    .bind(completed_at)
+    .bind(run_id)
+    .bind(job_id)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Fail runs left running by a previous process; nothing is working on them
+pub async fn fail_interrupted_match_runs(pool: &Pool, completed_at: &str) -> Result<u64, sqlx::Error> {
+    let rows = sqlx::query(
+        "UPDATE match_runs SET status = 'failed', error = 'Interrupted by a server restart', completed_at = ? WHERE status = 'running'"
+    )
+        .bind(completed_at)
         .execute(pool)
         .await?
         .rows_affected();
     Ok(rows)
 }
 
+/// Store a succeeded run's matches and serve them from job_matches, all or
+/// nothing. `run` carries the completed status, counts and model version.
+/// Returns `None`, storing nothing, when the run is no longer running, e.g.
+/// because it was cancelled.
+pub async fn complete_match_run(pool: &Pool, run: &MatchRun, matches: &[JobMatch]) -> Result<Option<MatchRun>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    for m in matches {
+        sqlx::query(
+            r#"
+            INSERT INTO match_run_results (id, run_id, job_id, talent_id, score, rank, match_reasons, concerns, summary, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&m.id)
+        .bind(&run.id)
+        .bind(&m.job_id)
+        .bind(&m.talent_id)
+        .bind(m.score)
+        .bind(m.rank)
+        .bind(&m.match_reasons)
+        .bind(&m.concerns)
+        .bind(&m.summary)
+        .bind(&m.created_at)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    delete_job_matches_by_job_id(&mut *tx, &run.job_id).await?;
+    for m in matches {
+        create_job_match(&mut *tx, m).await?;
+    }
+
+    let completed = sqlx::query_as::<_, MatchRun>(
+        r#"
+        UPDATE match_runs
+        SET status = ?, current_batch = ?, evaluated = ?, total_evaluated = ?, match_count = ?, model_version = ?, completed_at = ?
+        WHERE id = ? AND status = 'running'
+        RETURNING *
+        "#,
+    )
+    .bind(&run.status)
+    .bind(run.current_batch)
+    .bind(run.evaluated)
+    .bind(run.total_evaluated)
+    .bind(run.match_count)
+    .bind(&run.model_version)
+    .bind(&run.completed_at)
+    .bind(&run.id)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    // Dropping the transaction uncommitted discards the results
+    let Some(completed) = completed else {
+        return Ok(None);
+    };
+    tx.commit().await?;
+    Ok(Some(completed))
+}
+
+/// Get a job's matching runs, newest first
+pub async fn get_match_runs(pool: &Pool, job_id: &str) -> Result<Vec<MatchRun>, sqlx::Error> {
+    sqlx::query_as::<_, MatchRun>("SELECT * FROM match_runs WHERE job_id = ? ORDER BY created_at DESC")
+        .bind(job_id)
+        .fetch_all(pool)
+        .await
+}
+
+/// The run currently in progress for a job, if any
+pub async fn get_running_match_run(pool: &Pool, job_id: &str) -> Result<Option<MatchRun>, sqlx::Error> {
+    sqlx::query_as::<_, MatchRun>("SELECT * FROM match_runs WHERE job_id = ? AND status = 'running' LIMIT 1")
+        .bind(job_id)
+        .fetch_optional(pool)
+        .await
+}
+
+pub async fn get_match_run(pool: &Pool, job_id: &str, run_id: &str) -> Result<Option<MatchRun>, sqlx::Error> {
+    sqlx::query_as::<_, MatchRun>("SELECT * FROM match_runs WHERE id = ? AND job_id = ?")
+        .bind(run_id)
+        .bind(job_id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// The succeeded run that came before `run`, if any
+pub async fn get_previous_match_run(pool: &Pool, run: &MatchRun) -> Result<Option<MatchRun>, sqlx::Error> {
+    sqlx::query_as::<_, MatchRun>(
+        r#"
+        SELECT * FROM match_runs
+        WHERE job_id = ? AND status = 'succeeded' AND created_at < ? AND id != ?
+        ORDER BY created_at DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(&run.job_id)
+    .bind(&run.created_at)
+    .bind(&run.id)
+    .fetch_optional(pool)
+    .await
+}
+
+/// The run whose matches the job currently serves: its latest succeeded run
+pub async fn get_served_match_run(pool: &Pool, job_id: &str) -> Result<Option<MatchRun>, sqlx::Error> {
+    sqlx::query_as::<_, MatchRun>(
+        "SELECT * FROM match_runs WHERE job_id = ? AND status = 'succeeded' ORDER BY completed_at DESC LIMIT 1"
+    )
+    .bind(job_id)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Get a run's matches, ordered by rank
+pub async fn get_match_run_results(pool: &Pool, run_id: &str) -> Result<Vec<JobMatch>, sqlx::Error> {
+    sqlx::query_as::<_, JobMatch>(
+        r#"
+        SELECT * FROM match_run_results
+        WHERE run_id = ?
+        AND talent_id NOT IN (SELECT id FROM talents WHERE deleted_at IS NOT NULL)
+        ORDER BY rank ASC
+        "#,
+    )
+    .bind(run_id)
+    .fetch_all(pool)
+    .await
+}
+
 /// Get talents with collections (for job matching)
 pub async fn get_talents_with_collections(pool: &Pool) -> Result<Vec<Talent>, sqlx::Error> {
     sqlx::query_as::<_, Talent>(
@@ -449,6 +1036,7 @@ pub async fn get_talents_with_collections(pool: &Pool) -> Result<Vec<Talent>, sq
         SELECT * FROM talents
         WHERE collection_id IS NOT NULL
         AND collection_id != ''
+        AND deleted_at IS NULL
         "#,
     )
     .fetch_all(pool)
@@ -458,7 +1046,7 @@ pub async fn get_talents_with_collections(pool: &Pool) -> Result<Vec<Talent>, sq
 // Reorder tracking functions
 
 /// Create a reorder event
-pub async fn create_reorder_event(pool: &Pool, event: &ReorderEvent) -> Result<ReorderEvent, sqlx::Error> {
+pub async fn create_reorder_event<'e, E: SqliteExecutor<'e>>(executor: E, event: &ReorderEvent) -> Result<ReorderEvent, sqlx::Error> {
     sqlx::query_as::<_, ReorderEvent>(
         "INSERT INTO reorder_events (id, job_id, before_order, after_order, moved_talent_id, event_timestamp, created_at)
          VALUES (?, ?, ?, ?, ?, ?, ?)
@@ -471,17 +1059,18 @@ pub async fn create_reorder_event(pool: &Pool, event: &ReorderEvent) -> Result<R
         .bind(&event.moved_talent_id)
         .bind(&event.event_timestamp)
         .bind(&event.created_at)
-        .fetch_one(pool)
+        .fetch_one(executor)
         .await
 }
 
-/// Create a pairwise preference (with UNIQUE constraint for idempotency)
-pub async fn create_pairwise_preference(pool: &Pool, pref: &PairwisePreference) -> Result<Option<PairwisePreference>, sqlx::Error> {
+/// Create a pairwise preference. Returns None when the same active
+/// preference already exists, so repeating a signal is idempotent
+pub async fn create_pairwise_preference<'e, E: SqliteExecutor<'e>>(executor: E, pref: &PairwisePreference) -> Result<Option<PairwisePreference>, sqlx::Error> {
     // Use INSERT OR IGNORE to handle duplicates gracefully
     let result = sqlx::query_as::<_, PairwisePreference>(
         "INSERT OR IGNORE INTO pairwise_preferences
-         (id, winner_id, loser_id, job_id, job_text, winner_text, loser_text, source, confidence, reorder_event_id, created_at)
-         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+         (id, winner_id, loser_id, job_id, job_text, winner_text, loser_text, source, confidence, reorder_event_id, created_at, tie, author)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
          RETURNING *"
     )
         .bind(&pref.id)
@@ -495,12 +1084,27 @@ pub async fn create_pairwise_preference(pool: &Pool, pref: &PairwisePreference)
         .bind(pref.confidence)
         .bind(&pref.reorder_event_id)
         .bind(&pref.created_at)
-        .fetch_optional(pool)
+        .bind(pref.tie)
+        .bind(&pref.author)
+        .fetch_optional(executor)
         .await;
 
     result
 }
 
+/// Store preferences derived from one signal together; returns how many were new
+pub async fn create_pairwise_preferences(pool: &Pool, preferences: &[PairwisePreference]) -> Result<i32, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let mut created = 0;
+    for pref in preferences {
+        if create_pairwise_preference(&mut *tx, pref).await?.is_some() {
+            created += 1;
+        }
+    }
+    tx.commit().await?;
+    Ok(created)
+}
+
 /// Get all pairwise preferences for a job
 pub async fn get_pairwise_preferences_for_job(pool: &Pool, job_id: String) -> Result<Vec<PairwisePreference>, sqlx::Error> {
     sqlx::query_as::<_, PairwisePreference>(
@@ -511,6 +1115,38 @@ pub async fn get_pairwise_preferences_for_job(pool: &Pool, job_id: String) -> Re
         .await
 }
 
+/// Mark preferences as replaced by newer ones; returns how many changed.
+/// Preferences already superseded are left alone.
+pub async fn supersede_pairwise_preferences(pool: &Pool, superseded: &[(String, String)], at: &str) -> Result<u64, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let mut changed = 0;
+    for (id, superseded_by) in superseded {
+        changed += sqlx::query(
+            "UPDATE pairwise_preferences SET superseded_by = ?, superseded_at = ? WHERE id = ? AND superseded_by IS NULL"
+        )
+            .bind(superseded_by)
+            .bind(at)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+    }
+    tx.commit().await?;
+    Ok(changed)
+}
+
+/// Live jobs with at least one strict, active preference
+pub async fn get_preference_job_ids(pool: &Pool) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar(
+        "SELECT DISTINCT p.job_id FROM pairwise_preferences p
+         JOIN jobs j ON j.id = p.job_id AND j.deleted_at IS NULL
+         WHERE p.tie = 0 AND p.superseded_by IS NULL
+         ORDER BY p.job_id"
+    )
+        .fetch_all(pool)
+        .await
+}
+
 /// Get all reorder events for a job
 pub async fn get_reorder_events_for_job(pool: &Pool, job_id: String) -> Result<Vec<ReorderEvent>, sqlx::Error> {
     sqlx::query_as::<_, ReorderEvent>(
@@ -519,4 +1155,1865 @@ pub async fn get_reorder_events_for_job(pool: &Pool, job_id: String) -> Result<V
         .bind(&job_id)
         .fetch_all(pool)
         .await
-}
\ No newline at end of file
+}
+
+pub async fn get_job_candidate_order(pool: &Pool, job_id: &str) -> Result<Option<JobCandidateOrder>, sqlx::Error> {
+    sqlx::query_as::<_, JobCandidateOrder>("SELECT * FROM job_candidate_orders WHERE job_id = ?")
+        .bind(job_id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Replace a job's candidate order if it is still at `expected_version`
+/// (0 when the job has no stored order yet). Returns None when another
+/// reorder got there first.
+pub async fn save_job_candidate_order<'e, E: SqliteExecutor<'e>>(
+    executor: E,
+    order: &JobCandidateOrder,
+    expected_version: i64,
+) -> Result<Option<JobCandidateOrder>, sqlx::Error> {
+    let query = if expected_version == 0 {
+        r#"
+        INSERT INTO job_candidate_orders (job_id, talent_ids, version, reorder_event_id, updated_at)
+        VALUES (?1, ?2, 1, ?3, ?4)
+        ON CONFLICT(job_id) DO NOTHING
+        RETURNING *
+        "#
+    } else {
+        r#"
+        UPDATE job_candidate_orders
+        SET talent_ids = ?2, version = version + 1, reorder_event_id = ?3, updated_at = ?4
+        WHERE job_id = ?1 AND version = ?5
+        RETURNING *
+        "#
+    };
+    sqlx::query_as::<_, JobCandidateOrder>(query)
+        .bind(&order.job_id)
+        .bind(&order.talent_ids)
+        .bind(&order.reorder_event_id)
+        .bind(&order.updated_at)
+        .bind(expected_version)
+        .fetch_optional(executor)
+        .await
+}
+
+/// Live talents who applied to or were matched with a job: applicants
+/// newest first, then matches by rank
+pub async fn get_job_candidate_ids(pool: &Pool, job_id: &str) -> Result<Vec<String>, sqlx::Error> {
+    let ids: Vec<String> = sqlx::query_scalar(
+        r#"
+        SELECT talent_id FROM (
+            SELECT a.talent_id, 0 AS source, a.created_at AS applied_at, 0 AS rank
+            FROM applications a JOIN talents t ON t.id = a.talent_id
+            WHERE a.job_id = ?1 AND a.deleted_at IS NULL AND t.deleted_at IS NULL
+            UNION ALL
+            SELECT m.talent_id, 1, '', m.rank
+            FROM job_matches m JOIN talents t ON t.id = m.talent_id
+            WHERE m.job_id = ?1 AND t.deleted_at IS NULL
+        )
+        ORDER BY source, applied_at DESC, rank
+        "#
+    )
+        .bind(job_id)
+        .fetch_all(pool)
+        .await?;
+    let mut seen = std::collections::HashSet::new();
+    Ok(ids.into_iter().filter(|id| seen.insert(id.clone())).collect())
+}
+
+/// Jobs whose candidate order may include the talent
+pub async fn get_job_ids_for_talent_orders(pool: &Pool, talent_id: &str) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar(
+        r#"
+        SELECT job_id FROM job_candidate_orders WHERE instr(talent_ids, ?1) > 0
+        UNION SELECT job_id FROM applications WHERE talent_id = ?1
+        UNION SELECT job_id FROM job_matches WHERE talent_id = ?1
+        "#
+    )
+        .bind(talent_id)
+        .fetch_all(pool)
+        .await
+}
+
+/// Live talents with the given IDs, in no particular order
+pub async fn get_talents_by_ids(pool: &Pool, ids: &[String]) -> Result<Vec<Talent>, sqlx::Error> {
+    sqlx::query_as::<_, Talent>(
+        "SELECT * FROM talents WHERE id IN (SELECT value FROM json_each(?)) AND deleted_at IS NULL"
+    )
+        .bind(serde_json::to_string(ids).unwrap_or_default())
+        .fetch_all(pool)
+        .await
+}
+
+// Candidate portal functions
+
+/// Store a magic link token (hashed) for a talent
+pub async fn create_candidate_login_token(
+    pool: &Pool,
+    token_hash: &str,
+    talent_id: &str,
+    expires_at: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO candidate_login_tokens (token_hash, talent_id, expires_at, created_at) VALUES (?, ?, ?, ?)"
+    )
+        .bind(token_hash)
+        .bind(talent_id)
+        .bind(expires_at)
+        .bind(chrono::Utc::now().to_rfc3339())
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Count the magic link tokens issued to a talent since `since`
+pub async fn count_candidate_login_tokens_since(pool: &Pool, talent_id: &str, since: &str) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar("SELECT COUNT(*) FROM candidate_login_tokens WHERE talent_id = ? AND created_at >= ?")
+        .bind(talent_id)
+        .bind(since)
+        .fetch_one(pool)
+        .await
+}
+
+/// Mark a magic link token as used, returning its talent ID if it was valid and unused
+pub async fn consume_candidate_login_token(pool: &Pool, token_hash: &str, now: &str) -> Result<Option<String>, sqlx::Error> {
+    sqlx::query_scalar::<_, String>(
+        "UPDATE candidate_login_tokens SET used_at = ?1
+         WHERE token_hash = ?2 AND used_at IS NULL AND expires_at > ?1
+         RETURNING talent_id"
+    )
+        .bind(now)
+        .bind(token_hash)
+        .fetch_optional(pool)
+        .await
+}
+
+// Data subject request functions
+
+/// Get all job matches for a talent
+pub async fn get_job_matches_by_talent(pool: &Pool, talent_id: &str) -> Result<Vec<JobMatch>, sqlx::Error> {
+    sqlx::query_as::<_, JobMatch>(
+        "SELECT * FROM job_matches WHERE talent_id = ? ORDER BY created_at DESC"
+    )
+        .bind(talent_id)
+        .fetch_all(pool)
+        .await
+}
+
+/// Get all pairwise preferences in which a talent is the winner or loser
+pub async fn get_pairwise_preferences_for_talent(pool: &Pool, talent_id: &str) -> Result<Vec<PairwisePreference>, sqlx::Error> {
+    sqlx::query_as::<_, PairwisePreference>(
+        "SELECT * FROM pairwise_preferences WHERE winner_id = ?1 OR loser_id = ?1 ORDER BY created_at DESC"
+    )
+        .bind(talent_id)
+        .fetch_all(pool)
+        .await
+}
+
+/// Get all reorder events whose snapshots include a talent
+pub async fn get_reorder_events_for_talent(pool: &Pool, talent_id: &str) -> Result<Vec<ReorderEvent>, sqlx::Error> {
+    let pattern = format!("%\"{}\"%", talent_id);
+    sqlx::query_as::<_, ReorderEvent>(
+        "SELECT * FROM reorder_events
+         WHERE moved_talent_id = ?1 OR before_order LIKE ?2 OR after_order LIKE ?2
+         ORDER BY event_timestamp DESC"
+    )
+        .bind(talent_id)
+        .bind(&pattern)
+        .fetch_all(pool)
+        .await
+}
+
+/// Get magic link token metadata for a talent
+pub async fn get_candidate_login_tokens_for_talent(pool: &Pool, talent_id: &str) -> Result<Vec<CandidateLoginTokenRecord>, sqlx::Error> {
+    sqlx::query_as::<_, CandidateLoginTokenRecord>(
+        "SELECT talent_id, expires_at, used_at, created_at FROM candidate_login_tokens
+         WHERE talent_id = ? ORDER BY created_at DESC"
+    )
+        .bind(talent_id)
+        .fetch_all(pool)
+        .await
+}
+
+/// Get a talent's matches from every matching run, newest first
+pub async fn get_match_run_results_for_talent(pool: &Pool, talent_id: &str) -> Result<Vec<JobMatch>, sqlx::Error> {
+    sqlx::query_as::<_, JobMatch>("SELECT * FROM match_run_results WHERE talent_id = ? ORDER BY created_at DESC")
+        .bind(talent_id)
+        .fetch_all(pool)
+        .await
+}
+
+/// Get every email queued for an address, matched the way erasure matches it
+pub async fn get_outbox_emails_for_recipient(pool: &Pool, email: &str) -> Result<Vec<OutboxEmail>, sqlx::Error> {
+    sqlx::query_as::<_, OutboxEmail>("SELECT * FROM email_outbox WHERE recipient = ? COLLATE NOCASE ORDER BY created_at DESC")
+        .bind(email)
+        .fetch_all(pool)
+        .await
+}
+
+/// Get stored events about a talent or mentioning them in their payload
+pub async fn get_events_for_talent(pool: &Pool, talent_id: &str) -> Result<Vec<StoredEvent>, sqlx::Error> {
+    sqlx::query_as::<_, StoredEvent>("SELECT * FROM event_history WHERE talent_id = ? OR instr(payload, ?) > 0 ORDER BY seq ASC")
+        .bind(talent_id)
+        .bind(talent_id)
+        .fetch_all(pool)
+        .await
+}
+
+/// Get webhook deliveries whose payload mentions a talent
+pub async fn get_webhook_deliveries_for_talent(pool: &Pool, talent_id: &str) -> Result<Vec<WebhookDelivery>, sqlx::Error> {
+    sqlx::query_as::<_, WebhookDelivery>("SELECT * FROM webhook_deliveries WHERE instr(payload, ?) > 0 ORDER BY created_at DESC")
+        .bind(talent_id)
+        .fetch_all(pool)
+        .await
+}
+
+/// Erase a talent and everything that references them in a single transaction.
+///
+/// Applications, job matches and login tokens are deleted. Pairwise
+/// preferences keep their (now unresolvable) IDs so rankings stay consistent,
+/// but the human-readable candidate text is replaced with `placeholder`.
+/// The tombstone is written in the same transaction.
+pub async fn erase_talent(
+    pool: &Pool,
+    talent_id: &str,
+    placeholder: &str,
+    tombstone: &ErasureTombstone,
+) -> Result<ErasureCounts, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let applications = sqlx::query("DELETE FROM applications WHERE talent_id = ?")
+        .bind(talent_id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+    let job_matches = sqlx::query("DELETE FROM job_matches WHERE talent_id = ?")
+        .bind(talent_id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected()
+        + sqlx::query("DELETE FROM match_run_results WHERE talent_id = ?")
+            .bind(talent_id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+
+    let login_tokens = sqlx::query("DELETE FROM candidate_login_tokens WHERE talent_id = ?")
+        .bind(talent_id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+    let winners = sqlx::query("UPDATE pairwise_preferences SET winner_text = ? WHERE winner_id = ?")
+        .bind(placeholder)
+        .bind(talent_id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+    let losers = sqlx::query("UPDATE pairwise_preferences SET loser_text = ? WHERE loser_id = ?")
+        .bind(placeholder)
+        .bind(talent_id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+    // Logged webhook payloads can carry the talent's scoring and application details
+    let webhook_deliveries = sqlx::query("DELETE FROM webhook_deliveries WHERE instr(payload, ?) > 0")
+        .bind(talent_id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+    let events = sqlx::query("DELETE FROM event_history WHERE talent_id = ? OR instr(payload, ?) > 0")
+        .bind(talent_id)
+        .bind(talent_id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+    // Emails sent to the talent, and their opt-outs, are keyed by address
+    let emails = sqlx::query(
+        "DELETE FROM email_outbox WHERE recipient = (SELECT email FROM talents WHERE id = ?) COLLATE NOCASE"
+    )
+        .bind(talent_id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+    sqlx::query("DELETE FROM notification_preferences WHERE email = (SELECT lower(email) FROM talents WHERE id = ?)")
+        .bind(talent_id)
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query("DELETE FROM talents WHERE id = ?")
+        .bind(talent_id)
+        .execute(&mut *tx)
+        .await?;
+
+    let counts = ErasureCounts {
+        applications,
+        job_matches,
+        login_tokens,
+        preferences_anonymized: winners + losers,
+        webhook_deliveries,
+        emails,
+        events,
+    };
+
+    let deleted_counts = serde_json::to_string(&counts)
+        .map_err(|e| sqlx::Error::Encode(Box::new(e)))?;
+    sqlx::query(
+        "INSERT INTO erasure_tombstones
+         (id, talent_id, email_hash, reason, requested_by, deleted_counts, collection_id, remote_status, erased_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)"
+    )
+        .bind(&tombstone.id)
+        .bind(&tombstone.talent_id)
+        .bind(&tombstone.email_hash)
+        .bind(&tombstone.reason)
+        .bind(&tombstone.requested_by)
+        .bind(&deleted_counts)
+        .bind(&tombstone.collection_id)
+        .bind(&tombstone.remote_status)
+        .bind(&tombstone.erased_at)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(counts)
+}
+
+/// Record the outcome of deleting a talent's remote xAI collection
+pub async fn update_erasure_remote_status(pool: &Pool, id: &str, remote_status: &str) -> Result<Option<ErasureTombstone>, sqlx::Error> {
+    sqlx::query_as::<_, ErasureTombstone>(
+        "UPDATE erasure_tombstones SET remote_status = ? WHERE id = ? RETURNING *"
+    )
+        .bind(remote_status)
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// List erasure tombstones, newest first
+pub async fn get_erasure_tombstones(pool: &Pool) -> Result<Vec<ErasureTombstone>, sqlx::Error> {
+    sqlx::query_as::<_, ErasureTombstone>(
+        "SELECT * FROM erasure_tombstones ORDER BY erased_at DESC"
+    )
+        .fetch_all(pool)
+        .await
+}
+
+// Data retention functions
+
+/// Place or lift a legal hold on a talent
+pub async fn set_talent_legal_hold(pool: &Pool, id: &str, legal_hold: bool) -> Result<Option<Talent>, sqlx::Error> {
+    sqlx::query_as::<_, Talent>("UPDATE talents SET legal_hold = ? WHERE id = ? RETURNING *")
+        .bind(legal_hold as i32)
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Place or lift a legal hold on a job
+pub async fn set_job_legal_hold(pool: &Pool, id: &str, legal_hold: bool) -> Result<Option<Job>, sqlx::Error> {
+    sqlx::query_as::<_, Job>("UPDATE jobs SET legal_hold = ? WHERE id = ? RETURNING *")
+        .bind(legal_hold as i32)
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Applications whose talent or job is under legal hold
+const HELD_APPLICATION: &str = "(talent_id IN (SELECT id FROM talents WHERE legal_hold = 1)
+     OR job_id IN (SELECT id FROM jobs WHERE legal_hold = 1))";
+
+/// Whether any of the talent's applications is on a job under legal hold
+pub async fn talent_has_held_applications(pool: &Pool, talent_id: &str) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar::<_, bool>(&format!(
+        "SELECT EXISTS(SELECT 1 FROM applications WHERE talent_id = ? AND {})",
+        HELD_APPLICATION
+    ))
+    .bind(talent_id)
+    .fetch_one(pool)
+    .await
+}
+
+/// Applications with no status or resume change since `?1`
+const INACTIVE_APPLICATION: &str = "COALESCE(updated_at, created_at) < ?1";
+
+/// Talents who have neither edited their profile nor had any application
+/// activity since `?1`
+const INACTIVE_TALENT: &str = "COALESCE(updated_at, created_at) < ?1
+     AND NOT EXISTS (SELECT 1 FROM applications a WHERE a.talent_id = talents.id
+                     AND COALESCE(a.updated_at, a.created_at) >= ?1)";
+
+/// Purge rows of `class` whose last activity is before `cutoff`, skipping
+/// anything under legal hold. With `dry_run` nothing is changed and the matching rows are
+/// only counted. Returns the number of affected rows.
+pub async fn purge_retention_class(pool: &Pool, class: DataClass, cutoff: &str, dry_run: bool) -> Result<u64, sqlx::Error> {
+    let (table, filter) = match class {
+        DataClass::Trash => return purge_trash(pool, cutoff, dry_run).await,
+        DataClass::Resumes => (
+            "applications",
+            format!(
+                "resume_data IS NOT NULL AND status IN ('rejected', 'withdrawn') AND {} AND NOT {}",
+                INACTIVE_APPLICATION, HELD_APPLICATION
+            ),
+        ),
+        DataClass::SocialAnalysis => (
+            "talents",
+            format!("social_analysis IS NOT NULL AND {} AND legal_hold = 0", INACTIVE_TALENT),
+        ),
+        DataClass::ReorderEvents => (
+            "reorder_events",
+            "event_timestamp < ?1 AND job_id NOT IN (SELECT id FROM jobs WHERE legal_hold = 1)".to_string(),
+        ),
+        DataClass::RejectedApplications => (
+            "applications",
+            format!("status = 'rejected' AND {} AND NOT {}", INACTIVE_APPLICATION, HELD_APPLICATION),
+        ),
+    };
+
+    if dry_run {
+        let count: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {} WHERE {}", table, filter))
+            .bind(cutoff)
+            .fetch_one(pool)
+            .await?;
+        return Ok(count as u64);
+    }
+
+    let statement = match class {
+        DataClass::Resumes => format!(
+            "UPDATE applications SET resume_data = NULL, resume_filename = NULL, resume_content_type = NULL WHERE {}",
+            filter
+        ),
+        DataClass::SocialAnalysis => format!(
+            "UPDATE talents SET social_analysis = NULL, x_handle_discovered = NULL WHERE {}",
+            filter
+        ),
+        DataClass::ReorderEvents => format!("DELETE FROM reorder_events WHERE {}", filter),
+        DataClass::RejectedApplications => format!("DELETE FROM applications WHERE {}", filter),
+        DataClass::Trash => unreachable!("trash is purged by purge_trash"),
+    };
+
+    let mut tx = pool.begin().await?;
+    if class == DataClass::ReorderEvents {
+        // Preferences cascade with their originating event
+        sqlx::query(&format!(
+            "DELETE FROM pairwise_preferences WHERE reorder_event_id IN (SELECT id FROM reorder_events WHERE {})",
+            filter
+        ))
+            .bind(cutoff)
+            .execute(&mut *tx)
+            .await?;
+    }
+    let affected = sqlx::query(&statement)
+        .bind(cutoff)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+    tx.commit().await?;
+
+    Ok(affected)
+}
+
+// Trash functions
+
+const APPLICATION_COLUMNS: &str =
+    "id, talent_id, job_id, resume_data, resume_filename, resume_content_type, cover_letter, status, created_at, deleted_at";
+
+pub async fn get_deleted_talents(pool: &Pool) -> Result<Vec<Talent>, sqlx::Error> {
+    sqlx::query_as::<_, Talent>("SELECT * FROM talents WHERE deleted_at IS NOT NULL ORDER BY deleted_at DESC")
+        .fetch_all(pool)
+        .await
+}
+
+pub async fn get_deleted_jobs(pool: &Pool) -> Result<Vec<Job>, sqlx::Error> {
+    sqlx::query_as::<_, Job>("SELECT * FROM jobs WHERE deleted_at IS NOT NULL ORDER BY deleted_at DESC")
+        .fetch_all(pool)
+        .await
+}
+
+pub async fn get_deleted_applications(pool: &Pool) -> Result<Vec<Application>, sqlx::Error> {
+    sqlx::query_as::<_, Application>(&format!(
+        "SELECT {} FROM applications WHERE deleted_at IS NOT NULL ORDER BY deleted_at DESC",
+        APPLICATION_COLUMNS
+    ))
+        .fetch_all(pool)
+        .await
+}
+
+pub async fn get_deleted_application(pool: &Pool, id: &str) -> Result<Option<Application>, sqlx::Error> {
+    sqlx::query_as::<_, Application>(&format!(
+        "SELECT {} FROM applications WHERE id = ? AND deleted_at IS NOT NULL",
+        APPLICATION_COLUMNS
+    ))
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Restore a talent from the trash, together with the applications that were
+/// deleted with it (same timestamp) whose job is not itself in the trash
+pub async fn restore_talent(pool: &Pool, id: &str) -> Result<Option<Talent>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let deleted_at: Option<String> = sqlx::query_scalar("SELECT deleted_at FROM talents WHERE id = ? AND deleted_at IS NOT NULL")
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?;
+    let Some(deleted_at) = deleted_at else {
+        return Ok(None);
+    };
+
+    sqlx::query(
+        "UPDATE applications SET deleted_at = NULL
+         WHERE talent_id = ? AND deleted_at = ?
+         AND job_id IN (SELECT id FROM jobs WHERE deleted_at IS NULL)"
+    )
+        .bind(id)
+        .bind(&deleted_at)
+        .execute(&mut *tx)
+        .await?;
+    let talent = sqlx::query_as::<_, Talent>("UPDATE talents SET deleted_at = NULL WHERE id = ? RETURNING *")
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    Ok(Some(talent))
+}
+
+/// Restore a job from the trash, together with the applications that were
+/// deleted with it (same timestamp) whose talent is not itself in the trash
+pub async fn restore_job(pool: &Pool, id: &str) -> Result<Option<Job>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let deleted_at: Option<String> = sqlx::query_scalar("SELECT deleted_at FROM jobs WHERE id = ? AND deleted_at IS NOT NULL")
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?;
+    let Some(deleted_at) = deleted_at else {
+        return Ok(None);
+    };
+
+    sqlx::query(
+        "UPDATE applications SET deleted_at = NULL
+         WHERE job_id = ? AND deleted_at = ?
+         AND talent_id IN (SELECT id FROM talents WHERE deleted_at IS NULL)"
+    )
+        .bind(id)
+        .bind(&deleted_at)
+        .execute(&mut *tx)
+        .await?;
+    let job = sqlx::query_as::<_, Job>("UPDATE jobs SET deleted_at = NULL WHERE id = ? RETURNING *")
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    Ok(Some(job))
+}
+
+/// Restore a single application; its talent and job must both be live
+pub async fn restore_application(pool: &Pool, id: &str) -> Result<Option<Application>, sqlx::Error> {
+    sqlx::query_as::<_, Application>(&format!(
+        "UPDATE applications SET deleted_at = NULL
+         WHERE id = ? AND deleted_at IS NOT NULL
+         AND talent_id IN (SELECT id FROM talents WHERE deleted_at IS NULL)
+         AND job_id IN (SELECT id FROM jobs WHERE deleted_at IS NULL)
+         RETURNING {}",
+        APPLICATION_COLUMNS
+    ))
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Applications in the trash past the cutoff, unless under legal hold
+fn purgeable_application() -> String {
+    format!("deleted_at IS NOT NULL AND deleted_at < ?1 AND NOT {}", HELD_APPLICATION)
+}
+
+/// Talents or jobs in the trash past the cutoff, unless under legal hold or
+/// still referenced by an application that is not being purged
+fn purgeable_parent(column: &str) -> String {
+    format!(
+        "deleted_at IS NOT NULL AND deleted_at < ?1 AND legal_hold = 0
+         AND id NOT IN (SELECT {} FROM applications WHERE NOT ({}))",
+        column,
+        purgeable_application()
+    )
+}
+
+/// Permanently delete everything that has been in the trash since before
+/// `cutoff`, along with the match results, login tokens and reorder history
+/// attached to purged talents and jobs. Returns the number of talents, jobs
+/// and applications removed (or, with `dry_run`, that would be removed).
+pub async fn purge_trash(pool: &Pool, cutoff: &str, dry_run: bool) -> Result<u64, sqlx::Error> {
+    let applications = purgeable_application();
+    let talents = purgeable_parent("talent_id");
+    let jobs = purgeable_parent("job_id");
+
+    if dry_run {
+        let count: i64 = sqlx::query_scalar(&format!(
+            "SELECT (SELECT COUNT(*) FROM applications WHERE {})
+                  + (SELECT COUNT(*) FROM talents WHERE {})
+                  + (SELECT COUNT(*) FROM jobs WHERE {})",
+            applications, talents, jobs
+        ))
+            .bind(cutoff)
+            .fetch_one(pool)
+            .await?;
+        return Ok(count as u64);
+    }
+
+    let mut tx = pool.begin().await?;
+    // Applications go first so the parent filters only see what remains
+    let mut affected = sqlx::query(&format!("DELETE FROM applications WHERE {}", applications))
+        .bind(cutoff)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+    let dependents = [
+        format!("DELETE FROM job_matches WHERE talent_id IN (SELECT id FROM talents WHERE {})", talents),
+        format!("DELETE FROM job_matches WHERE job_id IN (SELECT id FROM jobs WHERE {})", jobs),
+        format!("DELETE FROM match_run_results WHERE talent_id IN (SELECT id FROM talents WHERE {})", talents),
+        format!("DELETE FROM match_run_results WHERE job_id IN (SELECT id FROM jobs WHERE {})", jobs),
+        format!("DELETE FROM match_runs WHERE job_id IN (SELECT id FROM jobs WHERE {})", jobs),
+        format!("DELETE FROM candidate_login_tokens WHERE talent_id IN (SELECT id FROM talents WHERE {})", talents),
+        format!("DELETE FROM pairwise_preferences WHERE job_id IN (SELECT id FROM jobs WHERE {})", jobs),
+        format!("DELETE FROM reorder_events WHERE job_id IN (SELECT id FROM jobs WHERE {})", jobs),
+    ];
+    for statement in &dependents {
+        sqlx::query(statement).bind(cutoff).execute(&mut *tx).await?;
+    }
+
+    for (table, filter) in [("talents", &talents), ("jobs", &jobs)] {
+        affected += sqlx::query(&format!("DELETE FROM {} WHERE {}", table, filter))
+            .bind(cutoff)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+    }
+    tx.commit().await?;
+
+    Ok(affected)
+}
+
+// Interview functions
+
+pub async fn create_interview(pool: &Pool, interview: &Interview) -> Result<Interview, sqlx::Error> {
+    sqlx::query_as::<_, Interview>(
+        r#"
+        INSERT INTO interviews (id, application_id, round, title, interviewers, scheduled_at, duration_minutes, location, video_link, status, created_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        RETURNING *
+        "#
+    )
+        .bind(&interview.id)
+        .bind(&interview.application_id)
+        .bind(interview.round)
+        .bind(&interview.title)
+        .bind(&interview.interviewers)
+        .bind(&interview.scheduled_at)
+        .bind(interview.duration_minutes)
+        .bind(&interview.location)
+        .bind(&interview.video_link)
+        .bind(&interview.status)
+        .bind(&interview.created_at)
+        .fetch_one(pool)
+        .await
+}
+
+/// An interview, provided its application is not in the trash
+pub async fn get_interview(pool: &Pool, id: &str) -> Result<Option<Interview>, sqlx::Error> {
+    sqlx::query_as::<_, Interview>(
+        r#"
+        SELECT i.* FROM interviews i
+        JOIN applications a ON a.id = i.application_id AND a.deleted_at IS NULL
+        WHERE i.id = ?
+        "#
+    )
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+}
+
+pub async fn get_interviews_by_application(pool: &Pool, application_id: &str) -> Result<Vec<Interview>, sqlx::Error> {
+    sqlx::query_as::<_, Interview>(
+        "SELECT * FROM interviews WHERE application_id = ? ORDER BY round, scheduled_at"
+    )
+        .bind(application_id)
+        .fetch_all(pool)
+        .await
+}
+
+pub async fn next_interview_round(pool: &Pool, application_id: &str) -> Result<i32, sqlx::Error> {
+    sqlx::query_scalar("SELECT COALESCE(MAX(round), 0) + 1 FROM interviews WHERE application_id = ?")
+        .bind(application_id)
+        .fetch_one(pool)
+        .await
+}
+
+pub async fn update_interview(pool: &Pool, interview: &Interview) -> Result<Option<Interview>, sqlx::Error> {
+    sqlx::query_as::<_, Interview>(
+        r#"
+        UPDATE interviews
+        SET round = ?, title = ?, interviewers = ?, scheduled_at = ?, duration_minutes = ?,
+            location = ?, video_link = ?, status = ?
+        WHERE id = ?
+        RETURNING *
+        "#
+    )
+        .bind(interview.round)
+        .bind(&interview.title)
+        .bind(&interview.interviewers)
+        .bind(&interview.scheduled_at)
+        .bind(interview.duration_minutes)
+        .bind(&interview.location)
+        .bind(&interview.video_link)
+        .bind(&interview.status)
+        .bind(&interview.id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Delete an interview; its scorecards go with it
+pub async fn delete_interview(pool: &Pool, id: &str) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM interviews WHERE id = ?")
+        .bind(id)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Save a scorecard, replacing the interviewer's earlier one for the interview
+pub async fn upsert_scorecard(pool: &Pool, scorecard: &Scorecard) -> Result<Scorecard, sqlx::Error> {
+    sqlx::query_as::<_, Scorecard>(
+        r#"
+        INSERT INTO scorecards (id, interview_id, interviewer, ratings, recommendation, notes, submitted_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?)
+        ON CONFLICT(interview_id, interviewer) DO UPDATE SET
+            ratings = excluded.ratings,
+            recommendation = excluded.recommendation,
+            notes = excluded.notes,
+            submitted_at = excluded.submitted_at
+        RETURNING *
+        "#
+    )
+        .bind(&scorecard.id)
+        .bind(&scorecard.interview_id)
+        .bind(&scorecard.interviewer)
+        .bind(&scorecard.ratings)
+        .bind(&scorecard.recommendation)
+        .bind(&scorecard.notes)
+        .bind(&scorecard.submitted_at)
+        .fetch_one(pool)
+        .await
+}
+
+pub async fn get_scorecards_by_interview(pool: &Pool, interview_id: &str) -> Result<Vec<Scorecard>, sqlx::Error> {
+    sqlx::query_as::<_, Scorecard>("SELECT * FROM scorecards WHERE interview_id = ? ORDER BY submitted_at")
+        .bind(interview_id)
+        .fetch_all(pool)
+        .await
+}
+
+/// Interviews across all of a talent's applications, including trashed ones
+pub async fn get_interviews_for_talent(pool: &Pool, talent_id: &str) -> Result<Vec<Interview>, sqlx::Error> {
+    sqlx::query_as::<_, Interview>(
+        r#"
+        SELECT i.* FROM interviews i
+        JOIN applications a ON a.id = i.application_id
+        WHERE a.talent_id = ?
+        ORDER BY i.scheduled_at
+        "#
+    )
+        .bind(talent_id)
+        .fetch_all(pool)
+        .await
+}
+
+pub async fn get_scorecards_for_talent(pool: &Pool, talent_id: &str) -> Result<Vec<Scorecard>, sqlx::Error> {
+    sqlx::query_as::<_, Scorecard>(
+        r#"
+        SELECT s.* FROM scorecards s
+        JOIN interviews i ON i.id = s.interview_id
+        JOIN applications a ON a.id = i.application_id
+        WHERE a.talent_id = ?
+        ORDER BY s.submitted_at
+        "#
+    )
+        .bind(talent_id)
+        .fetch_all(pool)
+        .await
+}
+
+pub async fn get_scorecards_by_application(pool: &Pool, application_id: &str) -> Result<Vec<Scorecard>, sqlx::Error> {
+    sqlx::query_as::<_, Scorecard>(
+        r#"
+        SELECT s.* FROM scorecards s
+        JOIN interviews i ON i.id = s.interview_id
+        WHERE i.application_id = ?
+        ORDER BY i.round, s.submitted_at
+        "#
+    )
+        .bind(application_id)
+        .fetch_all(pool)
+        .await
+}
+
+// Offer functions
+
+/// Insert an offer. Returns `None`, storing nothing, when its application
+/// already has an open offer.
+pub async fn create_offer<'e, E: SqliteExecutor<'e>>(executor: E, offer: &Offer) -> Result<Option<Offer>, sqlx::Error> {
+    sqlx::query_as::<_, Offer>(
+        r#"
+        INSERT OR IGNORE INTO offers (id, application_id, status, base_salary, currency, bonus, equity, start_date, expires_at, notes, created_by, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        RETURNING *
+        "#
+    )
+        .bind(&offer.id)
+        .bind(&offer.application_id)
+        .bind(&offer.status)
+        .bind(offer.base_salary)
+        .bind(&offer.currency)
+        .bind(offer.bonus)
+        .bind(&offer.equity)
+        .bind(&offer.start_date)
+        .bind(&offer.expires_at)
+        .bind(&offer.notes)
+        .bind(&offer.created_by)
+        .bind(&offer.created_at)
+        .bind(&offer.updated_at)
+        .fetch_optional(executor)
+        .await
+}
+
+/// An offer, provided its application is not in the trash
+pub async fn get_offer(pool: &Pool, id: &str) -> Result<Option<Offer>, sqlx::Error> {
+    sqlx::query_as::<_, Offer>(
+        r#"
+        SELECT o.* FROM offers o
+        JOIN applications a ON a.id = o.application_id AND a.deleted_at IS NULL
+        WHERE o.id = ?
+        "#
+    )
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+}
+
+pub async fn get_offers_by_application(pool: &Pool, application_id: &str) -> Result<Vec<Offer>, sqlx::Error> {
+    sqlx::query_as::<_, Offer>("SELECT * FROM offers WHERE application_id = ? ORDER BY created_at")
+        .bind(application_id)
+        .fetch_all(pool)
+        .await
+}
+
+/// Offers across all of a talent's applications, including trashed ones
+pub async fn get_offers_for_talent(pool: &Pool, talent_id: &str) -> Result<Vec<Offer>, sqlx::Error> {
+    sqlx::query_as::<_, Offer>(
+        r#"
+        SELECT o.* FROM offers o
+        JOIN applications a ON a.id = o.application_id
+        WHERE a.talent_id = ?
+        ORDER BY o.created_at
+        "#
+    )
+        .bind(talent_id)
+        .fetch_all(pool)
+        .await
+}
+
+/// The application's offer that is still in play (draft, pending approval or extended)
+pub async fn get_open_offer(pool: &Pool, application_id: &str) -> Result<Option<Offer>, sqlx::Error> {
+    let statuses = crate::offers::OPEN_STATUSES.map(|s| format!("'{}'", s.as_str())).join(", ");
+    sqlx::query_as::<_, Offer>(&format!(
+        "SELECT * FROM offers WHERE application_id = ? AND status IN ({}) LIMIT 1",
+        statuses
+    ))
+        .bind(application_id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Save an offer's terms and status. `expected_status` guards against a
+/// concurrent transition: no row is returned if the status has moved on
+pub async fn update_offer<'e, E: SqliteExecutor<'e>>(executor: E, offer: &Offer, expected_status: &str) -> Result<Option<Offer>, sqlx::Error> {
+    sqlx::query_as::<_, Offer>(
+        r#"
+        UPDATE offers
+        SET status = ?, base_salary = ?, currency = ?, bonus = ?, equity = ?, start_date = ?,
+            expires_at = ?, notes = ?, updated_at = ?
+        WHERE id = ? AND status = ?
+        RETURNING *
+        "#
+    )
+        .bind(&offer.status)
+        .bind(offer.base_salary)
+        .bind(&offer.currency)
+        .bind(offer.bonus)
+        .bind(&offer.equity)
+        .bind(&offer.start_date)
+        .bind(&offer.expires_at)
+        .bind(&offer.notes)
+        .bind(&offer.updated_at)
+        .bind(&offer.id)
+        .bind(expected_status)
+        .fetch_optional(executor)
+        .await
+}
+
+/// Start a fresh approval chain for an offer
+pub async fn reset_offer_approvals(conn: &mut SqliteConnection, offer_id: &str, approvers: &[String]) -> Result<(), sqlx::Error> {
+    sqlx::query("DELETE FROM offer_approvals WHERE offer_id = ?")
+        .bind(offer_id)
+        .execute(&mut *conn)
+        .await?;
+    for (step, approver) in approvers.iter().enumerate() {
+        sqlx::query("INSERT INTO offer_approvals (offer_id, step, approver) VALUES (?, ?, ?)")
+            .bind(offer_id)
+            .bind(step as i64 + 1)
+            .bind(approver)
+            .execute(&mut *conn)
+            .await?;
+    }
+    Ok(())
+}
+
+pub async fn get_offer_approvals(pool: &Pool, offer_id: &str) -> Result<Vec<OfferApproval>, sqlx::Error> {
+    sqlx::query_as::<_, OfferApproval>("SELECT * FROM offer_approvals WHERE offer_id = ? ORDER BY step")
+        .bind(offer_id)
+        .fetch_all(pool)
+        .await
+}
+
+pub async fn decide_offer_approval<'e, E: SqliteExecutor<'e>>(
+    executor: E,
+    offer_id: &str,
+    step: i32,
+    decision: &str,
+    comment: Option<&str>,
+    decided_at: &str,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        "UPDATE offer_approvals SET decision = ?, comment = ?, decided_at = ?
+         WHERE offer_id = ? AND step = ? AND decision IS NULL"
+    )
+        .bind(decision)
+        .bind(comment)
+        .bind(decided_at)
+        .bind(offer_id)
+        .bind(step)
+        .execute(executor)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn insert_offer_event<'e, E: SqliteExecutor<'e>>(executor: E, event: &OfferEvent) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO offer_events (id, offer_id, action, actor, from_status, to_status, comment, created_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+        "#
+    )
+        .bind(&event.id)
+        .bind(&event.offer_id)
+        .bind(&event.action)
+        .bind(&event.actor)
+        .bind(&event.from_status)
+        .bind(&event.to_status)
+        .bind(&event.comment)
+        .bind(&event.created_at)
+        .execute(executor)
+        .await?;
+    Ok(())
+}
+
+pub async fn get_offer_events(pool: &Pool, offer_id: &str) -> Result<Vec<OfferEvent>, sqlx::Error> {
+    sqlx::query_as::<_, OfferEvent>("SELECT * FROM offer_events WHERE offer_id = ? ORDER BY created_at, rowid")
+        .bind(offer_id)
+        .fetch_all(pool)
+        .await
+}
+
+// Webhook functions
+
+pub async fn create_webhook(pool: &Pool, webhook: &Webhook) -> Result<Webhook, sqlx::Error> {
+    sqlx::query_as::<_, Webhook>(
+        r#"
+        INSERT INTO webhooks (id, url, secret, events, description, active, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+        RETURNING *
+        "#
+    )
+        .bind(&webhook.id)
+        .bind(&webhook.url)
+        .bind(&webhook.secret)
+        .bind(&webhook.events)
+        .bind(&webhook.description)
+        .bind(webhook.active)
+        .bind(&webhook.created_at)
+        .bind(&webhook.updated_at)
+        .fetch_one(pool)
+        .await
+}
+
+pub async fn get_webhooks(pool: &Pool) -> Result<Vec<Webhook>, sqlx::Error> {
+    sqlx::query_as::<_, Webhook>("SELECT * FROM webhooks ORDER BY created_at")
+        .fetch_all(pool)
+        .await
+}
+
+pub async fn get_active_webhooks(pool: &Pool) -> Result<Vec<Webhook>, sqlx::Error> {
+    sqlx::query_as::<_, Webhook>("SELECT * FROM webhooks WHERE active = 1")
+        .fetch_all(pool)
+        .await
+}
+
+pub async fn get_webhook(pool: &Pool, id: &str) -> Result<Option<Webhook>, sqlx::Error> {
+    sqlx::query_as::<_, Webhook>("SELECT * FROM webhooks WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+}
+
+pub async fn update_webhook(pool: &Pool, webhook: &Webhook) -> Result<Option<Webhook>, sqlx::Error> {
+    sqlx::query_as::<_, Webhook>(
+        r#"
+        UPDATE webhooks
+        SET url = ?, secret = ?, events = ?, description = ?, active = ?, updated_at = ?
+        WHERE id = ?
+        RETURNING *
+        "#
+    )
+        .bind(&webhook.url)
+        .bind(&webhook.secret)
+        .bind(&webhook.events)
+        .bind(&webhook.description)
+        .bind(webhook.active)
+        .bind(&webhook.updated_at)
+        .bind(&webhook.id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Delete a webhook along with its delivery log
+pub async fn delete_webhook(pool: &Pool, id: &str) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM webhooks WHERE id = ?")
+        .bind(id)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn insert_webhook_delivery<'e, E: SqliteExecutor<'e>>(executor: E, delivery: &WebhookDelivery) -> Result<WebhookDelivery, sqlx::Error> {
+    sqlx::query_as::<_, WebhookDelivery>(
+        r#"
+        INSERT INTO webhook_deliveries (id, webhook_id, event_id, event_type, payload, status, attempts, next_attempt_at, redelivery_of, created_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        RETURNING *
+        "#
+    )
+        .bind(&delivery.id)
+        .bind(&delivery.webhook_id)
+        .bind(&delivery.event_id)
+        .bind(&delivery.event_type)
+        .bind(&delivery.payload)
+        .bind(&delivery.status)
+        .bind(delivery.attempts)
+        .bind(&delivery.next_attempt_at)
+        .bind(&delivery.redelivery_of)
+        .bind(&delivery.created_at)
+        .fetch_one(executor)
+        .await
+}
+
+pub async fn get_webhook_delivery(pool: &Pool, id: &str) -> Result<Option<WebhookDelivery>, sqlx::Error> {
+    sqlx::query_as::<_, WebhookDelivery>("SELECT * FROM webhook_deliveries WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// A webhook's deliveries, newest first
+pub async fn get_webhook_deliveries(pool: &Pool, webhook_id: &str, status: Option<&str>, limit: i64) -> Result<Vec<WebhookDelivery>, sqlx::Error> {
+    sqlx::query_as::<_, WebhookDelivery>(
+        r#"
+        SELECT * FROM webhook_deliveries
+        WHERE webhook_id = ? AND (? IS NULL OR status = ?)
+        ORDER BY created_at DESC, rowid DESC
+        LIMIT ?
+        "#
+    )
+        .bind(webhook_id)
+        .bind(status)
+        .bind(status)
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+}
+
+/// Pending deliveries to active webhooks whose next attempt is due at `now`
+pub async fn get_due_webhook_deliveries(pool: &Pool, now: &str, limit: i64) -> Result<Vec<WebhookDelivery>, sqlx::Error> {
+    sqlx::query_as::<_, WebhookDelivery>(
+        r#"
+        SELECT d.* FROM webhook_deliveries d
+        JOIN webhooks w ON w.id = d.webhook_id AND w.active = 1
+        WHERE d.status = 'pending' AND d.next_attempt_at <= ?
+        ORDER BY d.next_attempt_at
+        LIMIT ?
+        "#
+    )
+        .bind(now)
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+}
+
+/// Record the outcome of a delivery attempt
+pub async fn update_webhook_delivery(pool: &Pool, delivery: &WebhookDelivery) -> Result<WebhookDelivery, sqlx::Error> {
+    sqlx::query_as::<_, WebhookDelivery>(
+        r#"
+        UPDATE webhook_deliveries
+        SET status = ?, attempts = ?, next_attempt_at = ?, last_attempt_at = ?, response_status = ?, response_body = ?, error = ?
+        WHERE id = ?
+        RETURNING *
+        "#
+    )
+        .bind(&delivery.status)
+        .bind(delivery.attempts)
+        .bind(&delivery.next_attempt_at)
+        .bind(&delivery.last_attempt_at)
+        .bind(delivery.response_status)
+        .bind(&delivery.response_body)
+        .bind(&delivery.error)
+        .bind(&delivery.id)
+        .fetch_one(pool)
+        .await
+}
+
+// Event history functions
+
+pub async fn insert_stored_event(pool: &Pool, event: &StoredEvent) -> Result<StoredEvent, sqlx::Error> {
+    sqlx::query_as::<_, StoredEvent>(
+        r#"
+        INSERT INTO event_history (id, event_type, job_id, talent_id, payload, created_at)
+        VALUES (?, ?, ?, ?, ?, ?)
+        RETURNING *
+        "#
+    )
+        .bind(&event.id)
+        .bind(&event.event_type)
+        .bind(&event.job_id)
+        .bind(&event.talent_id)
+        .bind(&event.payload)
+        .bind(&event.created_at)
+        .fetch_one(pool)
+        .await
+}
+
+/// Events published after `after_seq`, oldest first
+pub async fn get_stored_events_after(pool: &Pool, after_seq: i64, limit: i64) -> Result<Vec<StoredEvent>, sqlx::Error> {
+    sqlx::query_as::<_, StoredEvent>("SELECT * FROM event_history WHERE seq > ? ORDER BY seq LIMIT ?")
+        .bind(after_seq)
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+}
+
+/// Sequence numbers of the oldest event still in the history, if any, and
+/// of the last event ever recorded
+pub async fn get_event_history_bounds(pool: &Pool) -> Result<(Option<i64>, i64), sqlx::Error> {
+    sqlx::query_as(
+        r#"
+        SELECT (SELECT MIN(seq) FROM event_history),
+               COALESCE((SELECT seq FROM sqlite_sequence WHERE name = 'event_history'), 0)
+        "#
+    )
+        .fetch_one(pool)
+        .await
+}
+
+pub async fn prune_event_history(pool: &Pool, before: &str) -> Result<u64, sqlx::Error> {
+    Ok(sqlx::query("DELETE FROM event_history WHERE created_at < ?")
+        .bind(before)
+        .execute(pool)
+        .await?
+        .rows_affected())
+}
+
+// Email functions
+
+pub async fn insert_outbox_email(pool: &Pool, email: &OutboxEmail) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO email_outbox (id, template, recipient, subject, body, status, attempts, next_attempt_at, created_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#
+    )
+        .bind(&email.id)
+        .bind(&email.template)
+        .bind(&email.recipient)
+        .bind(&email.subject)
+        .bind(&email.body)
+        .bind(&email.status)
+        .bind(email.attempts)
+        .bind(&email.next_attempt_at)
+        .bind(&email.created_at)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Pending messages whose next attempt is due at `now`, oldest first
+pub async fn get_due_outbox_emails(pool: &Pool, now: &str, limit: i64) -> Result<Vec<OutboxEmail>, sqlx::Error> {
+    sqlx::query_as::<_, OutboxEmail>(
+        "SELECT * FROM email_outbox WHERE status = 'pending' AND next_attempt_at <= ? ORDER BY next_attempt_at, created_at LIMIT ?"
+    )
+        .bind(now)
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+}
+
+/// Record the outcome of a send attempt
+pub async fn update_outbox_email(pool: &Pool, email: &OutboxEmail) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE email_outbox
+        SET body = ?, status = ?, attempts = ?, next_attempt_at = ?, last_error = ?, sent_at = ?
+        WHERE id = ?
+        "#
+    )
+        .bind(&email.body)
+        .bind(&email.status)
+        .bind(email.attempts)
+        .bind(&email.next_attempt_at)
+        .bind(&email.last_error)
+        .bind(&email.sent_at)
+        .bind(&email.id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Outbox messages, newest first
+pub async fn get_outbox_emails(pool: &Pool, filter: &OutboxQuery, limit: i64) -> Result<Vec<OutboxEmail>, sqlx::Error> {
+    sqlx::query_as::<_, OutboxEmail>(
+        r#"
+        SELECT * FROM email_outbox
+        WHERE (? IS NULL OR status = ?) AND (? IS NULL OR recipient = ? COLLATE NOCASE)
+        ORDER BY created_at DESC, rowid DESC
+        LIMIT ?
+        "#
+    )
+        .bind(&filter.status)
+        .bind(&filter.status)
+        .bind(&filter.recipient)
+        .bind(&filter.recipient)
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+}
+
+/// Queue a failed message to be sent again, starting a fresh set of attempts
+pub async fn retry_outbox_email(pool: &Pool, id: &str, now: &str) -> Result<Option<OutboxEmail>, sqlx::Error> {
+    sqlx::query_as::<_, OutboxEmail>(
+        r#"
+        UPDATE email_outbox
+        SET status = 'pending', attempts = 0, next_attempt_at = ?, last_error = NULL
+        WHERE id = ? AND status = 'failed'
+        RETURNING *
+        "#
+    )
+        .bind(now)
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+}
+
+pub async fn get_outbox_email(pool: &Pool, id: &str) -> Result<Option<OutboxEmail>, sqlx::Error> {
+    sqlx::query_as::<_, OutboxEmail>("SELECT * FROM email_outbox WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Whether `email` receives `template` notifications; on unless turned off
+pub async fn notification_enabled(pool: &Pool, email: &str, template: &str) -> Result<bool, sqlx::Error> {
+    let enabled: Option<i32> = sqlx::query_scalar(
+        "SELECT enabled FROM notification_preferences WHERE email = ? COLLATE NOCASE AND template = ?"
+    )
+        .bind(email)
+        .bind(template)
+        .fetch_optional(pool)
+        .await?;
+    Ok(enabled.is_none_or(|enabled| enabled != 0))
+}
+
+/// Templates `email` has a stored preference for, with whether each is on
+pub async fn get_notification_preferences(pool: &Pool, email: &str) -> Result<Vec<(String, bool)>, sqlx::Error> {
+    let rows: Vec<(String, i32)> = sqlx::query_as(
+        "SELECT template, enabled FROM notification_preferences WHERE email = ? COLLATE NOCASE"
+    )
+        .bind(email)
+        .fetch_all(pool)
+        .await?;
+    Ok(rows.into_iter().map(|(template, enabled)| (template, enabled != 0)).collect())
+}
+
+pub async fn set_notification_preferences(pool: &Pool, email: &str, preferences: &[(String, bool)]) -> Result<(), sqlx::Error> {
+    let now = chrono::Utc::now().to_rfc3339();
+    let mut tx = pool.begin().await?;
+    for (template, enabled) in preferences {
+        sqlx::query(
+            r#"
+            INSERT INTO notification_preferences (email, template, enabled, updated_at)
+            VALUES (?, ?, ?, ?)
+            ON CONFLICT (email, template) DO UPDATE SET enabled = excluded.enabled, updated_at = excluded.updated_at
+            "#
+        )
+            .bind(email.to_lowercase())
+            .bind(template)
+            .bind(*enabled as i32)
+            .bind(&now)
+            .execute(&mut *tx)
+            .await?;
+    }
+    tx.commit().await
+}
+
+// Export functions
+
+/// Strict pairwise preferences (no ties, none superseded) for the training export, grouped
+/// by job and oldest first
+pub struct PreferenceQuery {
+    sql: String,
+    binds: Vec<String>,
+}
+
+impl PreferenceQuery {
+    pub fn new(sources: &[String], since: Option<&str>, until: Option<&str>) -> Self {
+        let mut conditions: Vec<String> = vec!["tie = 0".to_string(), "superseded_by IS NULL".to_string()];
+        let mut binds: Vec<String> = Vec::new();
+
+        if !sources.is_empty() {
+            conditions.push(format!("source IN ({})", vec!["?"; sources.len()].join(", ")));
+            binds.extend(sources.iter().cloned());
+        }
+        if let Some(since) = since {
+            conditions.push("created_at >= ?".to_string());
+            binds.push(since.to_string());
+        }
+        if let Some(until) = until {
+            conditions.push("created_at < ?".to_string());
+            binds.push(until.to_string());
+        }
+
+        PreferenceQuery {
+            sql: format!("SELECT * FROM pairwise_preferences WHERE {} ORDER BY job_id, created_at, id", conditions.join(" AND ")),
+            binds,
+        }
+    }
+
+    /// Stream matching preferences without loading them all into memory
+    pub fn fetch<'a>(&'a self, pool: &'a Pool) -> BoxStream<'a, Result<PairwisePreference, sqlx::Error>> {
+        let mut query = sqlx::query_as::<_, PairwisePreference>(&self.sql);
+        for value in &self.binds {
+            query = query.bind(value);
+        }
+        query.fetch(pool)
+    }
+}
+
+/// Stream a job's live applications joined with their talent and match
+pub fn stream_job_pipeline<'a>(pool: &'a Pool, job_id: &'a str) -> BoxStream<'a, Result<PipelineRow, sqlx::Error>> {
+    sqlx::query_as::<_, PipelineRow>(
+        r#"
+        SELECT a.id AS application_id, a.status AS application_status, a.created_at AS applied_at,
+               a.resume_data IS NOT NULL AS has_resume,
+               t.id AS talent_id, t.name, t.email, t.handle, t.title, t.location, t.experience, t.skills,
+               t.verified, t.candidate_score,
+               m.score AS match_score, m.rank AS match_rank, m.summary AS match_summary
+        FROM applications a
+        JOIN talents t ON t.id = a.talent_id AND t.deleted_at IS NULL
+        LEFT JOIN job_matches m ON m.job_id = a.job_id AND m.talent_id = a.talent_id
+        WHERE a.job_id = ? AND a.deleted_at IS NULL
+        ORDER BY a.created_at
+        "#
+    )
+        .bind(job_id)
+        .fetch(pool)
+}
+
+pub async fn get_latest_reorder_event(pool: &Pool, job_id: &str) -> Result<Option<ReorderEvent>, sqlx::Error> {
+    sqlx::query_as::<_, ReorderEvent>(
+        "SELECT * FROM reorder_events WHERE job_id = ? ORDER BY event_timestamp DESC LIMIT 1"
+    )
+        .bind(job_id)
+        .fetch_optional(pool)
+        .await
+}
+
+// Import functions
+
+/// Live job with this company name and title, used to match imported jobs
+pub async fn get_job_by_company_and_title(pool: &Pool, company_name: &str, title: &str) -> Result<Option<Job>, sqlx::Error> {
+    sqlx::query_as::<_, Job>(
+        "SELECT * FROM jobs WHERE company_name = ? AND title = ? AND deleted_at IS NULL ORDER BY created_at LIMIT 1"
+    )
+        .bind(company_name)
+        .bind(title)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Write one batch of imported talents; the whole batch rolls back on error
+pub async fn import_talents(pool: &Pool, batch: &[&TalentImport]) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    for item in batch {
+        match item {
+            TalentImport::Create(talent) => {
+                create_talent(&mut *tx, talent).await?;
+            }
+            TalentImport::Update(id, updates) => {
+                update_talent(&mut *tx, id.clone(), updates).await?.ok_or(sqlx::Error::RowNotFound)?;
+            }
+        }
+    }
+    tx.commit().await
+}
+
+/// Write one batch of imported jobs; the whole batch rolls back on error
+pub async fn import_jobs(pool: &Pool, batch: &[&JobImport]) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    for item in batch {
+        match item {
+            JobImport::Create(job) => {
+                create_job(&mut *tx, job).await?;
+            }
+            JobImport::Update(id, updates) => {
+                update_job(&mut *tx, id.clone(), updates).await?.ok_or(sqlx::Error::RowNotFound)?;
+            }
+        }
+    }
+    tx.commit().await
+}
+
+// Integrity functions
+
+pub async fn count_orphans(pool: &Pool, relationship: &Relationship) -> Result<u64, sqlx::Error> {
+    let count: i64 = sqlx::query_scalar(&format!(
+        "SELECT COUNT(*) FROM {} WHERE {}",
+        relationship.child_table,
+        relationship.orphan_filter()
+    ))
+        .fetch_one(pool)
+        .await?;
+    Ok(count as u64)
+}
+
+/// Delete orphaned rows for each relationship, in order, in one transaction.
+/// Returns the rows deleted per relationship.
+pub async fn delete_orphans(pool: &Pool, relationships: &[Relationship]) -> Result<Vec<u64>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let mut deleted = Vec::with_capacity(relationships.len());
+    for relationship in relationships {
+        let rows = sqlx::query(&format!(
+            "DELETE FROM {} WHERE {}",
+            relationship.child_table,
+            relationship.orphan_filter()
+        ))
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        deleted.push(rows);
+    }
+    tx.commit().await?;
+    Ok(deleted)
+}
+
+// Audit log functions
+
+/// Append an entry to the audit log
+pub async fn insert_audit_log_entry(pool: &Pool, entry: &AuditLogEntry) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO audit_log
+         (id, occurred_at, request_id, actor, method, route, path, target_ids, status, outcome, error_code, ip)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
+    )
+        .bind(&entry.id)
+        .bind(&entry.occurred_at)
+        .bind(&entry.request_id)
+        .bind(&entry.actor)
+        .bind(&entry.method)
+        .bind(&entry.route)
+        .bind(&entry.path)
+        .bind(&entry.target_ids)
+        .bind(entry.status)
+        .bind(&entry.outcome)
+        .bind(&entry.error_code)
+        .bind(&entry.ip)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Search the audit log, newest first
+pub async fn query_audit_log(pool: &Pool, filter: &AuditLogQuery, limit: i64, offset: i64) -> Result<Vec<AuditLogEntry>, sqlx::Error> {
+    let mut conditions: Vec<&str> = Vec::new();
+    let mut binds: Vec<String> = Vec::new();
+
+    if let Some(actor) = &filter.actor {
+        conditions.push("actor = ?");
+        binds.push(actor.clone());
+    }
+    if let Some(method) = &filter.method {
+        conditions.push("method = ?");
+        binds.push(method.to_uppercase());
+    }
+    if let Some(route) = &filter.route {
+        conditions.push("route LIKE ?");
+        binds.push(format!("%{}%", route));
+    }
+    if let Some(target_id) = &filter.target_id {
+        conditions.push("target_ids LIKE ?");
+        binds.push(format!("%\"{}\"%", target_id));
+    }
+    if let Some(outcome) = &filter.outcome {
+        conditions.push("outcome = ?");
+        binds.push(outcome.clone());
+    }
+    if let Some(since) = &filter.since {
+        conditions.push("occurred_at >= ?");
+        binds.push(since.clone());
+    }
+    if let Some(until) = &filter.until {
+        conditions.push("occurred_at < ?");
+        binds.push(until.clone());
+    }
+
+    let where_clause = if conditions.is_empty() {
+        String::new()
+    } else {
+        format!("WHERE {}", conditions.join(" AND "))
+    };
+    let query_str = format!(
+        "SELECT * FROM audit_log {} ORDER BY occurred_at DESC, id LIMIT ? OFFSET ?",
+        where_clause
+    );
+
+    let mut query = sqlx::query_as::<_, AuditLogEntry>(&query_str);
+    for value in &binds {
+        query = query.bind(value);
+    }
+    query.bind(limit).bind(offset).fetch_all(pool).await
+}
+
+// Evaluation functions
+
+pub async fn create_evaluation_run(pool: &Pool, run: &EvaluationRun) -> Result<EvaluationRun, sqlx::Error> {
+    sqlx::query_as::<_, EvaluationRun>(
+        "INSERT INTO evaluation_runs
+         (id, source, label, split, k, jobs_evaluated, preferences_evaluated, pairwise_accuracy, kendall_tau, ndcg,
+          match_run_id, model_version, job_results, created_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+         RETURNING *"
+    )
+        .bind(&run.id)
+        .bind(&run.source)
+        .bind(&run.label)
+        .bind(&run.split)
+        .bind(run.k)
+        .bind(run.jobs_evaluated)
+        .bind(run.preferences_evaluated)
+        .bind(run.pairwise_accuracy)
+        .bind(run.kendall_tau)
+        .bind(run.ndcg)
+        .bind(&run.match_run_id)
+        .bind(&run.model_version)
+        .bind(&run.job_results)
+        .bind(&run.created_at)
+        .fetch_one(pool)
+        .await
+}
+
+pub async fn get_evaluation_run(pool: &Pool, id: &str) -> Result<Option<EvaluationRun>, sqlx::Error> {
+    sqlx::query_as::<_, EvaluationRun>("SELECT * FROM evaluation_runs WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Evaluation runs oldest first, so they read as a time series
+pub async fn get_evaluation_runs(pool: &Pool, filter: &EvaluationRunQuery, limit: i64) -> Result<Vec<EvaluationRun>, sqlx::Error> {
+    sqlx::query_as::<_, EvaluationRun>(
+        "SELECT * FROM (
+             SELECT * FROM evaluation_runs
+             WHERE (?1 IS NULL OR source = ?1) AND (?2 IS NULL OR label = ?2) AND (?3 IS NULL OR created_at >= ?3)
+             ORDER BY created_at DESC LIMIT ?4
+         ) ORDER BY created_at"
+    )
+        .bind(&filter.source)
+        .bind(&filter.label)
+        .bind(&filter.since)
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+}
+
+/// A fresh, migrated database with one job, `j1`
+#[cfg(test)]
+pub async fn test_pool() -> Pool {
+    let path = std::env::temp_dir().join(format!("talent-server-test-{}.db", uuid::Uuid::new_v4()));
+    let pool = init_pool(&format!("sqlite://{}?mode=rwc", path.display())).await.unwrap();
+    create_job(&pool, &crate::test_support::job("j1")).await.unwrap();
+    pool
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use crate::import::TalentImport;
+    use crate::reorder::{self, PreferenceContext};
+    use crate::test_support::{self as fixtures, insert_applicant};
+
+    fn succeeded(run: &MatchRun, matches: &[JobMatch], completed_at: &str) -> MatchRun {
+        MatchRun {
+            status: "succeeded".to_string(),
+            match_count: matches.len() as i32,
+            model_version: Some("grok-test".to_string()),
+            completed_at: Some(completed_at.to_string()),
+            ..run.clone()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_repeated_implicit_signal_does_not_duplicate_preferences() {
+        let pool = test_pool().await;
+        let names = HashMap::new();
+        let ctx = PreferenceContext {
+            job_id: "j1",
+            job_text: "Engineer at Acme",
+            talent_names: &names,
+            reorder_event_id: None,
+            author: None,
+        };
+        let shortlisted = vec!["t1".to_string()];
+        let others = vec!["t2".to_string(), "t3".to_string()];
+
+        let first = reorder::derive_shortlist_preferences(&shortlisted, &others, &ctx);
+        assert_eq!(create_pairwise_preferences(&pool, &first).await.unwrap(), 2);
+        let again = reorder::derive_shortlist_preferences(&shortlisted, &others, &ctx);
+        assert_eq!(create_pairwise_preferences(&pool, &again).await.unwrap(), 0);
+
+        let rejected = reorder::derive_reject_preferences(&["t3".to_string()], &["t1".to_string()], &ctx);
+        assert_eq!(create_pairwise_preferences(&pool, &rejected).await.unwrap(), 1, "other sources are kept");
+        assert_eq!(get_pairwise_preferences_for_job(&pool, "j1".to_string()).await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_superseded_preference_can_be_recorded_again() {
+        let pool = test_pool().await;
+        let names = HashMap::new();
+        let ctx = PreferenceContext {
+            job_id: "j1",
+            job_text: "Engineer at Acme",
+            talent_names: &names,
+            reorder_event_id: None,
+            author: None,
+        };
+        let shortlist = || reorder::derive_shortlist_preferences(&["t1".to_string()], &["t2".to_string()], &ctx);
+        let original = shortlist();
+        create_pairwise_preferences(&pool, &original).await.unwrap();
+        let reversal = reorder::derive_shortlist_preferences(&["t2".to_string()], &["t1".to_string()], &ctx);
+        create_pairwise_preferences(&pool, &reversal).await.unwrap();
+        supersede_pairwise_preferences(&pool, &[(original[0].id.clone(), reversal[0].id.clone())], "2024-01-02")
+            .await
+            .unwrap();
+
+        assert_eq!(create_pairwise_preferences(&pool, &shortlist()).await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_only_one_match_run_runs_per_job() {
+        let pool = test_pool().await;
+        let first = create_match_run(&pool, &crate::match_runs::new_run("j1", 10, 0, 50)).await.unwrap();
+        assert!(first.is_some());
+        assert!(create_match_run(&pool, &crate::match_runs::new_run("j1", 10, 0, 50)).await.unwrap().is_none());
+
+        fail_interrupted_match_runs(&pool, "2024-01-02").await.unwrap();
+        assert!(create_match_run(&pool, &crate::match_runs::new_run("j1", 10, 0, 50)).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_only_one_offer_is_open_per_application() {
+        let pool = test_pool().await;
+        insert_applicant(&pool, "a1", "t1").await;
+        let offer = |id: &str| fixtures::offer(id, "a1");
+
+        let first = create_offer(&pool, &offer("o1")).await.unwrap().unwrap();
+        assert!(create_offer(&pool, &offer("o2")).await.unwrap().is_none());
+
+        update_offer(&pool, &Offer { status: "rescinded".to_string(), ..first }, "draft").await.unwrap();
+        assert!(create_offer(&pool, &offer("o2")).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_applications_on_held_jobs_are_detected() {
+        let pool = test_pool().await;
+        insert_applicant(&pool, "a1", "t1").await;
+        assert!(!talent_has_held_applications(&pool, "t1").await.unwrap());
+
+        set_job_legal_hold(&pool, "j1", true).await.unwrap();
+        assert!(talent_has_held_applications(&pool, "t1").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_login_tokens_are_counted_per_talent_since() {
+        let pool = test_pool().await;
+        create_talent(&pool, &fixtures::talent("t1")).await.unwrap();
+        let hour_ago = (chrono::Utc::now() - chrono::Duration::hours(1)).to_rfc3339();
+        create_candidate_login_token(&pool, "h1", "t1", "2099-01-01").await.unwrap();
+        create_candidate_login_token(&pool, "h2", "t1", "2099-01-01").await.unwrap();
+
+        assert_eq!(count_candidate_login_tokens_since(&pool, "t1", &hour_ago).await.unwrap(), 2);
+        assert_eq!(count_candidate_login_tokens_since(&pool, "t2", &hour_ago).await.unwrap(), 0);
+        assert_eq!(count_candidate_login_tokens_since(&pool, "t1", "2099-01-01").await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_completed_match_run_replaces_job_matches() {
+        let pool = test_pool().await;
+        for id in ["t1", "t2", "t3"] {
+            create_talent(&pool, &fixtures::talent(id)).await.unwrap();
+        }
+
+        let first = create_match_run(&pool, &crate::match_runs::new_run("j1", 10, 3, 50)).await.unwrap().unwrap();
+        let matches = [fixtures::job_match("j1", "t1", 1, 90.0), fixtures::job_match("j1", "t2", 2, 80.0)];
+        complete_match_run(&pool, &succeeded(&first, &matches, "2024-01-02T00:00:00Z"), &matches).await.unwrap().unwrap();
+
+        let second = create_match_run(&pool, &crate::match_runs::new_run("j1", 10, 3, 50)).await.unwrap().unwrap();
+        let matches = [JobMatch { id: "m-t3-2".to_string(), ..fixtures::job_match("j1", "t3", 1, 95.0) }];
+        complete_match_run(&pool, &succeeded(&second, &matches, "2024-01-03T00:00:00Z"), &matches).await.unwrap().unwrap();
+
+        let served: Vec<String> = get_job_matches(&pool, "j1").await.unwrap().into_iter().map(|m| m.talent_id).collect();
+        assert_eq!(served, vec!["t3"]);
+        assert_eq!(get_match_run_results(&pool, &first.id).await.unwrap().len(), 2, "earlier results are kept");
+        assert_eq!(get_served_match_run(&pool, "j1").await.unwrap().map(|run| run.id), Some(second.id));
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_match_run_does_not_replace_job_matches() {
+        let pool = test_pool().await;
+        create_talent(&pool, &fixtures::talent("t1")).await.unwrap();
+        let run = create_match_run(&pool, &crate::match_runs::new_run("j1", 10, 1, 50)).await.unwrap().unwrap();
+        cancel_match_run(&pool, "j1", &run.id, "2024-01-02T00:00:00Z").await.unwrap().unwrap();
+
+        let matches = [fixtures::job_match("j1", "t1", 1, 90.0)];
+        assert!(complete_match_run(&pool, &succeeded(&run, &matches, "2024-01-03T00:00:00Z"), &matches).await.unwrap().is_none());
+        assert!(get_job_matches(&pool, "j1").await.unwrap().is_empty());
+        assert!(get_match_run_results(&pool, &run.id).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_match_runs_start_only_once() {
+        let pool = test_pool().await;
+        let attempts = (0..8).map(|_| {
+            let pool = pool.clone();
+            tokio::spawn(async move { create_match_run(&pool, &crate::match_runs::new_run("j1", 10, 0, 50)).await })
+        });
+        let mut started = 0;
+        for attempt in attempts {
+            if attempt.await.unwrap().unwrap().is_some() {
+                started += 1;
+            }
+        }
+        assert_eq!(started, 1);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_offers_open_only_once() {
+        let pool = test_pool().await;
+        insert_applicant(&pool, "a1", "t1").await;
+        let attempts = (0..8).map(|i| {
+            let pool = pool.clone();
+            tokio::spawn(async move { create_offer(&pool, &fixtures::offer(&format!("o{}", i), "a1")).await })
+        });
+        let mut opened = 0;
+        for attempt in attempts {
+            if attempt.await.unwrap().unwrap().is_some() {
+                opened += 1;
+            }
+        }
+        assert_eq!(opened, 1);
+    }
+
+    #[tokio::test]
+    async fn test_erase_talent_removes_rows_and_anonymizes_preferences() {
+        let pool = test_pool().await;
+        insert_applicant(&pool, "a1", "t1").await;
+        insert_applicant(&pool, "a2", "t2").await;
+        create_job_match(&pool, &fixtures::job_match("j1", "t1", 1, 90.0)).await.unwrap();
+        let preference = PairwisePreference {
+            job_id: "j1".to_string(),
+            winner_text: "Ada Lovelace, Engineer".to_string(),
+            loser_text: "Grace Hopper, Engineer".to_string(),
+            ..fixtures::preference("t1", "t2")
+        };
+        create_pairwise_preferences(&pool, &[preference]).await.unwrap();
+
+        let tombstone = ErasureTombstone {
+            id: "e1".to_string(),
+            talent_id: "t1".to_string(),
+            email_hash: crate::privacy::email_hash("t1@example.com"),
+            reason: None,
+            requested_by: None,
+            deleted_counts: "{}".to_string(),
+            collection_id: None,
+            remote_status: "skipped".to_string(),
+            erased_at: "2024-01-02T00:00:00Z".to_string(),
+        };
+        let counts = erase_talent(&pool, "t1", "[erased]", &tombstone).await.unwrap();
+        assert_eq!((counts.applications, counts.job_matches, counts.preferences_anonymized), (1, 1, 1));
+
+        assert!(get_talent_by_id_including_deleted(&pool, "t1").await.unwrap().is_none());
+        assert!(get_talent_by_id_including_deleted(&pool, "t2").await.unwrap().is_some());
+        assert!(get_job_matches(&pool, "j1").await.unwrap().is_empty());
+        let kept = get_pairwise_preferences_for_job(&pool, "j1".to_string()).await.unwrap();
+        assert_eq!((kept[0].winner_text.as_str(), kept[0].loser_text.as_str()), ("[erased]", "Grace Hopper, Engineer"));
+        assert_eq!(get_erasure_tombstones(&pool).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_purge_trash_keeps_held_talents() {
+        let pool = test_pool().await;
+        insert_applicant(&pool, "a1", "t1").await;
+        insert_applicant(&pool, "a2", "t2").await;
+        delete_talents_bulk(&pool, &["t1".to_string(), "t2".to_string()]).await.unwrap();
+        set_talent_legal_hold(&pool, "t2", true).await.unwrap();
+
+        assert_eq!(purge_trash(&pool, "2999-01-01", true).await.unwrap(), 2);
+        assert_eq!(purge_trash(&pool, "2999-01-01", false).await.unwrap(), 2);
+        assert!(get_talent_by_id_including_deleted(&pool, "t1").await.unwrap().is_none());
+        assert!(get_talent_by_id_including_deleted(&pool, "t2").await.unwrap().is_some());
+        assert_eq!(get_applications_by_talent_including_deleted(&pool, "t2").await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_import_batch_rolls_back_as_a_whole() {
+        let pool = test_pool().await;
+        let created = TalentImport::Create(Box::new(fixtures::talent("t1")));
+        let missing = TalentImport::Update("missing".to_string(), Box::new(serde_json::from_str::<UpdateTalentRequest>("{}").unwrap()));
+
+        assert!(import_talents(&pool, &[&created, &missing]).await.is_err());
+        assert!(get_talent_by_id_including_deleted(&pool, "t1").await.unwrap().is_none());
+
+        import_talents(&pool, &[&created]).await.unwrap();
+        assert!(get_talent_by_id_including_deleted(&pool, "t1").await.unwrap().is_some());
+    }
+}
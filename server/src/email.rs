@@ -0,0 +1,540 @@
+//! Email delivery
+//!
+//! Messages are rendered from the plain-text templates in `templates/email`
+//! and written to the `email_outbox` table. A background worker sends due
+//! messages over SMTP and retries failures with exponential backoff, so a
+//! mail server outage never fails the request that triggered the email.
+//!
+//! The SMTP client is deliberately small. Without credentials it speaks
+//! plain SMTP, which is what local sinks such as MailHog (port 1025) and
+//! internal relays expect. With credentials it upgrades the connection with
+//! STARTTLS before `AUTH PLAIN`, and refuses to send them otherwise.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use chrono::{DateTime, SecondsFormat, Utc};
+use log::{error, info, warn};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio_native_tls::{native_tls, TlsConnector};
+use tokio::sync::Notify;
+use uuid::Uuid;
+
+use crate::database::Pool;
+use crate::models::OutboxEmail;
+
+/// Values substituted for `{{name}}` placeholders in a template
+pub type Vars = BTreeMap<&'static str, String>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Template {
+    MagicLink,
+    ApplicationReceived,
+    ApplicationStatusChanged,
+    InterviewScheduled,
+    ScoringFinished,
+}
+
+impl Template {
+    pub const ALL: [Template; 5] = [
+        Template::MagicLink,
+        Template::ApplicationReceived,
+        Template::ApplicationStatusChanged,
+        Template::InterviewScheduled,
+        Template::ScoringFinished,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Template::MagicLink => "magic_link",
+            Template::ApplicationReceived => "application_received",
+            Template::ApplicationStatusChanged => "application_status_changed",
+            Template::InterviewScheduled => "interview_scheduled",
+            Template::ScoringFinished => "scoring_finished",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Template::ALL.into_iter().find(|t| t.as_str() == value.trim().to_lowercase())
+    }
+
+    fn source(&self) -> &'static str {
+        match self {
+            Template::MagicLink => include_str!("templates/email/magic_link.txt"),
+            Template::ApplicationReceived => include_str!("templates/email/application_received.txt"),
+            Template::ApplicationStatusChanged => include_str!("templates/email/application_status_changed.txt"),
+            Template::InterviewScheduled => include_str!("templates/email/interview_scheduled.txt"),
+            Template::ScoringFinished => include_str!("templates/email/scoring_finished.txt"),
+        }
+    }
+
+    /// Whether recipients can turn the notification off. Sign-in links are
+    /// always sent.
+    pub fn is_optional(&self) -> bool {
+        !matches!(self, Template::MagicLink)
+    }
+
+    /// Whether the body holds a secret that must not outlive delivery
+    fn redact_after_send(&self) -> bool {
+        matches!(self, Template::MagicLink)
+    }
+}
+
+impl fmt::Display for Template {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// An outbox row as shown outside the worker. Bodies holding a secret are
+/// hidden whatever the message's status, so a pending or failed sign-in link
+/// cannot be read back through the API.
+pub fn redacted(email: OutboxEmail) -> OutboxEmail {
+    if Template::parse(&email.template).is_some_and(|t| t.redact_after_send()) {
+        OutboxEmail { body: "[redacted]".to_string(), ..email }
+    } else {
+        email
+    }
+}
+
+/// Templates recipients can opt out of
+pub fn optional_templates() -> impl Iterator<Item = Template> {
+    Template::ALL.into_iter().filter(|t| t.is_optional())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rendered {
+    pub subject: String,
+    pub body: String,
+}
+
+/// Fill in a template's placeholders. Every placeholder must have a value.
+pub fn render(template: Template, vars: &Vars) -> Result<Rendered, String> {
+    let source = template.source();
+    let (subject_line, body) = source.split_once("\n\n").unwrap_or((source, ""));
+    let subject = subject_line
+        .strip_prefix("Subject: ")
+        .ok_or_else(|| format!("Template {} does not start with a Subject line", template))?;
+
+    Ok(Rendered {
+        // Values may contain line breaks; a subject must stay on one line
+        subject: fill(subject, vars)?.replace(['\r', '\n'], " "),
+        body: fill(body, vars)?,
+    })
+}
+
+fn fill(text: &str, vars: &Vars) -> Result<String, String> {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let end = rest[start..]
+            .find("}}")
+            .map(|end| start + end)
+            .ok_or_else(|| "Unclosed placeholder in template".to_string())?;
+        let name = rest[start + 2..end].trim();
+        let value = vars.get(name).ok_or_else(|| format!("No value for placeholder '{}'", name))?;
+        out.push_str(&rest[..start]);
+        out.push_str(value);
+        rest = &rest[end + 2..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// Whether `address` is usable as a recipient: one mailbox, no header breaks
+pub fn is_valid_address(address: &str) -> bool {
+    let Some((local, domain)) = address.split_once('@') else {
+        return false;
+    };
+    !local.is_empty()
+        && domain.contains('.')
+        && !domain.starts_with('.')
+        && !domain.ends_with('.')
+        && !address.chars().any(|c| c.is_whitespace() || c.is_control() || matches!(c, '<' | '>' | ',' | ';'))
+}
+
+/// The bare address of a mailbox such as `Talent Pool <jobs@example.com>`
+pub fn mailbox_address(mailbox: &str) -> &str {
+    match (mailbox.rfind('<'), mailbox.rfind('>')) {
+        (Some(start), Some(end)) if start < end => mailbox[start + 1..end].trim(),
+        _ => mailbox.trim(),
+    }
+}
+
+/// RFC 2047 encode a header value that is not plain ASCII
+fn encode_header(value: &str) -> String {
+    if value.is_ascii() {
+        value.to_string()
+    } else {
+        format!("=?utf-8?B?{}?=", STANDARD.encode(value))
+    }
+}
+
+/// Build the RFC 5322 message: CRLF line endings, and lines starting with a
+/// dot doubled so the body cannot end the SMTP DATA section early
+pub fn format_message(from: &str, to: &str, subject: &str, body: &str, message_id: &str, date: DateTime<Utc>) -> String {
+    let domain = mailbox_address(from).rsplit_once('@').map(|(_, d)| d).unwrap_or("localhost");
+    let mut message = format!(
+        "From: {}\r\nTo: {}\r\nSubject: {}\r\nDate: {}\r\nMessage-ID: <{}@{}>\r\nMIME-Version: 1.0\r\n\
+         Content-Type: text/plain; charset=utf-8\r\nContent-Transfer-Encoding: 8bit\r\n\r\n",
+        from,
+        to,
+        encode_header(subject),
+        date.to_rfc2822(),
+        message_id,
+        domain,
+    );
+    for line in body.lines() {
+        if line.starts_with('.') {
+            message.push('.');
+        }
+        message.push_str(line);
+        message.push_str("\r\n");
+    }
+    message
+}
+
+/// Attempts per message before it is marked failed
+pub const MAX_ATTEMPTS: i32 = 6;
+const BASE_RETRY_DELAY_SECS: i64 = 60;
+const MAX_RETRY_DELAY_SECS: i64 = 60 * 60;
+
+const SMTP_TIMEOUT: Duration = Duration::from_secs(30);
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+const BATCH_SIZE: i64 = 20;
+
+/// Delay before the next attempt after `attempts` failed ones: 1m, 2m, 4m,
+/// ... capped at an hour
+pub fn retry_delay(attempts: i32) -> chrono::Duration {
+    let exponent = attempts.clamp(1, 20) as u32 - 1;
+    chrono::Duration::seconds((BASE_RETRY_DELAY_SECS << exponent).min(MAX_RETRY_DELAY_SECS))
+}
+
+/// Timestamp format of `next_attempt_at`, which is compared as text
+pub fn due_timestamp(at: DateTime<Utc>) -> String {
+    at.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+#[derive(Clone, Debug)]
+pub struct SmtpConfig {
+    pub host: String,
+    pub port: u16,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// Sender mailbox, e.g. `Talent Pool <jobs@example.com>`
+    pub from: String,
+}
+
+struct SmtpSession<S> {
+    stream: BufReader<S>,
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> SmtpSession<S> {
+    fn new(stream: S) -> Self {
+        SmtpSession { stream: BufReader::new(stream) }
+    }
+
+    /// Read a (possibly multi-line) reply and check its status class
+    async fn expect(&mut self, class: u16) -> Result<(), String> {
+        loop {
+            let mut line = String::new();
+            if self.stream.read_line(&mut line).await.map_err(|e| e.to_string())? == 0 {
+                return Err("SMTP server closed the connection".to_string());
+            }
+            let code: u16 = line.get(..3).and_then(|c| c.parse().ok())
+                .ok_or_else(|| format!("Malformed SMTP reply: {}", line.trim_end()))?;
+            if line.as_bytes().get(3) == Some(&b'-') {
+                continue;
+            }
+            if code / 100 != class {
+                return Err(format!("SMTP server replied: {}", line.trim_end()));
+            }
+            return Ok(());
+        }
+    }
+
+    async fn command(&mut self, command: &str, class: u16) -> Result<(), String> {
+        self.stream.write_all(format!("{}\r\n", command).as_bytes()).await.map_err(|e| e.to_string())?;
+        self.expect(class).await
+    }
+
+    /// Send one message, from MAIL FROM through QUIT
+    async fn transmit(&mut self, sender: &str, recipient: &str, message: &str) -> Result<(), String> {
+        self.command(&format!("MAIL FROM:<{}>", sender), 2).await?;
+        self.command(&format!("RCPT TO:<{}>", recipient), 2).await?;
+        self.command("DATA", 3).await?;
+        self.stream.write_all(message.as_bytes()).await.map_err(|e| e.to_string())?;
+        self.command(".", 2).await?;
+        // The message is accepted; a failed QUIT does not matter
+        let _ = self.command("QUIT", 2).await;
+        Ok(())
+    }
+}
+
+async fn smtp_send(config: &SmtpConfig, recipient: &str, message: &str) -> Result<(), String> {
+    let stream = TcpStream::connect((config.host.as_str(), config.port))
+        .await
+        .map_err(|e| format!("Could not connect to {}:{}: {}", config.host, config.port, e))?;
+    let mut session = SmtpSession::new(stream);
+
+    let sender = mailbox_address(&config.from);
+    let hello = sender.rsplit_once('@').map(|(_, d)| d).unwrap_or("localhost");
+    session.expect(2).await?;
+    session.command(&format!("EHLO {}", hello), 2).await?;
+
+    let (Some(username), Some(password)) = (&config.username, &config.password) else {
+        return session.transmit(sender, recipient, message).await;
+    };
+
+    // Credentials never cross an unencrypted connection
+    session.command("STARTTLS", 2)
+        .await
+        .map_err(|e| format!("SMTP server does not support STARTTLS, which is required to authenticate: {}", e))?;
+    let connector = native_tls::TlsConnector::new().map_err(|e| e.to_string())?;
+    let stream = TlsConnector::from(connector)
+        .connect(&config.host, session.stream.into_inner())
+        .await
+        .map_err(|e| format!("TLS handshake with {} failed: {}", config.host, e))?;
+    let mut session = SmtpSession::new(stream);
+
+    session.command(&format!("EHLO {}", hello), 2).await?;
+    let credentials = STANDARD.encode(format!("\0{}\0{}", username, password));
+    session.command(&format!("AUTH PLAIN {}", credentials), 2).await?;
+    session.transmit(sender, recipient, message).await
+}
+
+/// Handle for queueing email, shared through `AppState`
+#[derive(Clone)]
+pub struct Mailer {
+    pool: Pool,
+    smtp: Option<Arc<SmtpConfig>>,
+    wake: Arc<Notify>,
+}
+
+impl Mailer {
+    /// Start the outbox worker when SMTP is configured and return a handle.
+    /// Without SMTP, nothing is queued.
+    pub fn spawn(pool: Pool, smtp: Option<SmtpConfig>) -> Self {
+        let mailer = Mailer { pool, smtp: smtp.map(Arc::new), wake: Arc::new(Notify::new()) };
+        if let Some(smtp) = mailer.smtp.clone() {
+            info!("[email] Sending through SMTP server {}:{}", smtp.host, smtp.port);
+            tokio::spawn(run_worker(mailer.pool.clone(), smtp, mailer.wake.clone()));
+        }
+        mailer
+    }
+
+    pub fn is_configured(&self) -> bool {
+        self.smtp.is_some()
+    }
+
+    /// Wake the worker, e.g. after a failed message was queued for retry
+    pub fn wake(&self) {
+        self.wake.notify_one();
+    }
+
+    /// Render a template and queue it for `recipient`, unless SMTP is not
+    /// configured or the recipient turned the notification off. Failures are
+    /// logged rather than returned.
+    pub async fn send(&self, template: Template, recipient: &str, vars: &Vars) {
+        if !self.is_configured() {
+            return;
+        }
+        if !is_valid_address(recipient) {
+            warn!("[email] Not sending {} to invalid address '{}'", template, recipient);
+            return;
+        }
+        if template.is_optional() {
+            match crate::database::notification_enabled(&self.pool, recipient, template.as_str()).await {
+                Ok(true) => {}
+                Ok(false) => {
+                    info!("[email] {} turned off {} notifications", recipient, template);
+                    return;
+                }
+                Err(e) => {
+                    error!("[email] Failed to load notification preferences for {}: {}", recipient, e);
+                    return;
+                }
+            }
+        }
+        let rendered = match render(template, vars) {
+            Ok(rendered) => rendered,
+            Err(e) => {
+                error!("[email] Failed to render {}: {}", template, e);
+                return;
+            }
+        };
+
+        let now = Utc::now();
+        let email = OutboxEmail {
+            id: Uuid::new_v4().to_string(),
+            template: template.as_str().to_string(),
+            recipient: recipient.to_string(),
+            subject: rendered.subject,
+            body: rendered.body,
+            status: "pending".to_string(),
+            attempts: 0,
+            next_attempt_at: Some(due_timestamp(now)),
+            last_error: None,
+            created_at: now.to_rfc3339(),
+            sent_at: None,
+        };
+        match crate::database::insert_outbox_email(&self.pool, &email).await {
+            Ok(()) => self.wake(),
+            Err(e) => error!("[email] Failed to queue {} for {}: {}", template, recipient, e),
+        }
+    }
+}
+
+/// Send one message and record the outcome, rescheduling failures
+async fn deliver(pool: &Pool, smtp: &SmtpConfig, email: OutboxEmail) -> Result<(), sqlx::Error> {
+    let now = Utc::now();
+    let message = format_message(&smtp.from, &email.recipient, &email.subject, &email.body, &email.id, now);
+    let result = match tokio::time::timeout(SMTP_TIMEOUT, smtp_send(smtp, &email.recipient, &message)).await {
+        Ok(result) => result,
+        Err(_) => Err("SMTP exchange timed out".to_string()),
+    };
+    let attempts = email.attempts + 1;
+
+    let updated = match result {
+        Ok(()) => {
+            info!("[email] Sent {} {} to {}", email.template, email.id, email.recipient);
+            let redact = Template::parse(&email.template).is_some_and(|t| t.redact_after_send());
+            OutboxEmail {
+                status: "sent".to_string(),
+                attempts,
+                next_attempt_at: None,
+                last_error: None,
+                sent_at: Some(now.to_rfc3339()),
+                body: if redact { "[redacted after delivery]".to_string() } else { email.body },
+                ..email
+            }
+        }
+        Err(e) => {
+            warn!("[email] Sending {} to {} failed (attempt {}): {}", email.id, email.recipient, attempts, e);
+            let retry = attempts < MAX_ATTEMPTS;
+            OutboxEmail {
+                status: if retry { "pending" } else { "failed" }.to_string(),
+                attempts,
+                next_attempt_at: retry.then(|| due_timestamp(now + retry_delay(attempts))),
+                last_error: Some(e),
+                ..email
+            }
+        }
+    };
+    crate::database::update_outbox_email(pool, &updated).await
+}
+
+async fn run_worker(pool: Pool, smtp: Arc<SmtpConfig>, wake: Arc<Notify>) {
+    loop {
+        match crate::database::get_due_outbox_emails(&pool, &due_timestamp(Utc::now()), BATCH_SIZE).await {
+            Ok(due) => {
+                let full = due.len() as i64 == BATCH_SIZE;
+                for email in due {
+                    if let Err(e) = deliver(&pool, &smtp, email).await {
+                        error!("[email] Failed to record delivery: {}", e);
+                    }
+                }
+                // A full batch means more messages may be waiting
+                if full {
+                    continue;
+                }
+            }
+            Err(e) => error!("[email] Failed to load the outbox: {}", e),
+        }
+        tokio::select! {
+            _ = wake.notified() => {}
+            _ = tokio::time::sleep(POLL_INTERVAL) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&'static str, &str)]) -> Vars {
+        pairs.iter().map(|(k, v)| (*k, v.to_string())).collect()
+    }
+
+    #[test]
+    fn test_render() {
+        let rendered = render(Template::ApplicationReceived, &vars(&[
+            ("name", "Ann"),
+            ("job_title", "Rust Engineer"),
+            ("company_name", "Acme"),
+            ("portal_link", "https://jobs.example.com/portal"),
+        ])).unwrap_or_else(|e| panic!("{}", e));
+        assert_eq!(rendered.subject, "We received your application for Rust Engineer");
+        assert!(rendered.body.starts_with("Hi Ann,"));
+        assert!(rendered.body.contains("https://jobs.example.com/portal"));
+
+        let Err(e) = render(Template::ApplicationReceived, &vars(&[("name", "Ann")])) else {
+            panic!("rendered with missing values");
+        };
+        assert!(e.contains("job_title"));
+
+        let multiline = render(Template::ScoringFinished, &vars(&[
+            ("name", "Ann"),
+            ("score", "87"),
+            ("job_title", "Eng\r\nBcc: x@evil.test"),
+            ("company_name", "Acme"),
+            ("recommendation", "hire"),
+            ("summary", "Strong"),
+            ("job_link", "https://jobs.example.com/jobs?id=1"),
+        ])).unwrap_or_else(|e| panic!("{}", e));
+        assert!(!multiline.subject.contains('\n'));
+    }
+
+    #[test]
+    fn test_redacted_hides_pending_magic_links() {
+        let email = OutboxEmail {
+            id: "e1".to_string(),
+            template: Template::MagicLink.as_str().to_string(),
+            recipient: "ann@example.com".to_string(),
+            subject: "Sign in".to_string(),
+            body: "https://jobs.example.com/portal/login?token=secret".to_string(),
+            status: "pending".to_string(),
+            attempts: 2,
+            next_attempt_at: None,
+            last_error: Some("timeout".to_string()),
+            created_at: "2026-01-01T00:00:00Z".to_string(),
+            sent_at: None,
+        };
+        assert_eq!(redacted(email.clone()).body, "[redacted]");
+
+        let receipt = OutboxEmail { template: Template::ApplicationReceived.as_str().to_string(), ..email };
+        assert_eq!(redacted(receipt.clone()).body, receipt.body);
+    }
+
+    #[test]
+    fn test_templates_have_subjects() {
+        for template in Template::ALL {
+            assert!(template.source().starts_with("Subject: "), "{} has no subject", template);
+        }
+    }
+
+    #[test]
+    fn test_format_message() {
+        let date = DateTime::parse_from_rfc3339("2026-03-01T09:00:00Z").unwrap().with_timezone(&Utc);
+        let message = format_message(
+            "Talent Pool <jobs@example.com>",
+            "ann@example.com",
+            "Bienvenue, José",
+            "Hi\n.hidden\nBye",
+            "m1",
+            date,
+        );
+        assert!(message.contains("Subject: =?utf-8?B?"));
+        assert!(message.contains("Message-ID: <m1@example.com>\r\n"));
+        assert!(message.ends_with("\r\n\r\nHi\r\n..hidden\r\nBye\r\n"));
+
+        assert_eq!(mailbox_address("Talent Pool <jobs@example.com>"), "jobs@example.com");
+        assert!(is_valid_address("ann@example.com"));
+        assert!(!is_valid_address("ann@example.com\r\nBcc: x@evil.test"));
+        assert!(!is_valid_address("ann"));
+    }
+}
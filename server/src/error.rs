@@ -0,0 +1,198 @@
+//! Crate-wide error type for HTTP handlers
+//!
+//! Every handler returns `AppResult<T>`. Failures are mapped to a stable
+//! error code and HTTP status and always serialized as `ApiError`. Internal
+//! details (SQL text, upstream response bodies) are only written to the log,
+//! tagged with the request ID, and never sent to the client.
+
+use std::fmt;
+
+use actix_web::{http::StatusCode, HttpResponse, ResponseError};
+use paperclip::actix::api_v2_errors;
+
+use crate::httpd::request_id;
+use crate::models::ApiError;
+
+pub type AppResult<T> = Result<T, AppError>;
+
+#[api_v2_errors(
+    code = 400, description = "Invalid request",
+    code = 401, description = "Missing or invalid credentials",
+    code = 403, description = "Not allowed for this caller",
+    code = 404, description = "Resource not found",
+    code = 409, description = "Conflict with the current state of the resource",
+    code = 500, description = "Internal server error",
+    code = 502, description = "Upstream Grok service failure",
+)]
+#[derive(Debug)]
+pub enum AppError {
+    /// The requested resource does not exist
+    NotFound(String),
+    /// The request is malformed or fails validation
+    Validation(String),
+    /// The caller is not authenticated
+    Unauthorized(String),
+    /// The caller is known but not allowed to do this
+    Forbidden(String),
+    /// The request conflicts with the current state (e.g. duplicates)
+    Conflict(String),
+    /// A database operation failed
+    Database(sqlx::Error),
+    /// The Grok service could not be reached or returned a failure
+    Grok(String),
+    /// Any other unexpected failure
+    Internal(String),
+}
+
+impl AppError {
+    pub fn not_found(message: impl Into<String>) -> Self {
+        AppError::NotFound(message.into())
+    }
+
+    pub fn validation(message: impl Into<String>) -> Self {
+        AppError::Validation(message.into())
+    }
+
+    pub fn unauthorized(message: impl Into<String>) -> Self {
+        AppError::Unauthorized(message.into())
+    }
+
+    pub fn forbidden(message: impl Into<String>) -> Self {
+        AppError::Forbidden(message.into())
+    }
+
+    pub fn conflict(message: impl Into<String>) -> Self {
+        AppError::Conflict(message.into())
+    }
+
+    pub fn grok(message: impl Into<String>) -> Self {
+        AppError::Grok(message.into())
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        AppError::Internal(message.into())
+    }
+
+    /// Stable, machine-readable error code sent to clients
+    pub fn error_code(&self) -> &'static str {
+        match self {
+            AppError::NotFound(_) => "not_found",
+            AppError::Validation(_) => "validation_error",
+            AppError::Unauthorized(_) => "unauthorized",
+            AppError::Forbidden(_) => "forbidden",
+            AppError::Conflict(_) => "conflict",
+            AppError::Database(_) => "database_error",
+            AppError::Grok(_) => "upstream_error",
+            AppError::Internal(_) => "internal_error",
+        }
+    }
+
+    /// Message that is safe to expose to clients
+    pub fn public_message(&self) -> String {
+        match self {
+            AppError::NotFound(msg)
+            | AppError::Validation(msg)
+            | AppError::Unauthorized(msg)
+            | AppError::Forbidden(msg)
+            | AppError::Conflict(msg) => msg.clone(),
+            AppError::Database(_) => "A database error occurred".to_string(),
+            AppError::Grok(_) => "The AI service is currently unavailable".to_string(),
+            AppError::Internal(_) => "An internal error occurred".to_string(),
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::NotFound(msg) => write!(f, "Not found: {}", msg),
+            AppError::Validation(msg) => write!(f, "Validation error: {}", msg),
+            AppError::Unauthorized(msg) => write!(f, "Unauthorized: {}", msg),
+            AppError::Forbidden(msg) => write!(f, "Forbidden: {}", msg),
+            AppError::Conflict(msg) => write!(f, "Conflict: {}", msg),
+            AppError::Database(e) => write!(f, "Database error: {}", e),
+            AppError::Grok(msg) => write!(f, "Grok service error: {}", msg),
+            AppError::Internal(msg) => write!(f, "Internal error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl From<sqlx::Error> for AppError {
+    fn from(e: sqlx::Error) -> Self {
+        match &e {
+            sqlx::Error::RowNotFound => AppError::NotFound("Resource not found".to_string()),
+            sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+                AppError::Conflict("Resource already exists".to_string())
+            }
+            _ => AppError::Database(e),
+        }
+    }
+}
+
+impl From<serde_json::Error> for AppError {
+    fn from(e: serde_json::Error) -> Self {
+        AppError::Internal(format!("JSON serialization failed: {}", e))
+    }
+}
+
+impl ResponseError for AppError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            AppError::NotFound(_) => StatusCode::NOT_FOUND,
+            AppError::Validation(_) => StatusCode::BAD_REQUEST,
+            AppError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            AppError::Forbidden(_) => StatusCode::FORBIDDEN,
+            AppError::Conflict(_) => StatusCode::CONFLICT,
+            AppError::Database(_) | AppError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            AppError::Grok(_) => StatusCode::BAD_GATEWAY,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        let status = self.status_code();
+        HttpResponse::build(status).json(ApiError {
+            message: self.public_message(),
+            code: status.as_u16(),
+            error: self.error_code().to_string(),
+            request_id: request_id::current(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_codes() {
+        assert_eq!(AppError::not_found("x").status_code(), StatusCode::NOT_FOUND);
+        assert_eq!(AppError::validation("x").status_code(), StatusCode::BAD_REQUEST);
+        assert_eq!(AppError::unauthorized("x").status_code(), StatusCode::UNAUTHORIZED);
+        assert_eq!(AppError::forbidden("x").status_code(), StatusCode::FORBIDDEN);
+        assert_eq!(AppError::conflict("x").status_code(), StatusCode::CONFLICT);
+        assert_eq!(AppError::grok("x").status_code(), StatusCode::BAD_GATEWAY);
+        assert_eq!(AppError::internal("x").status_code(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[test]
+    fn test_row_not_found_maps_to_not_found() {
+        let err: AppError = sqlx::Error::RowNotFound.into();
+        assert_eq!(err.error_code(), "not_found");
+    }
+
+    #[test]
+    fn test_database_error_hides_details() {
+        let err: AppError = sqlx::Error::Protocol("near \"SELEC\": syntax error".to_string()).into();
+        assert_eq!(err.error_code(), "database_error");
+        assert!(!err.public_message().contains("SELEC"));
+        assert!(err.to_string().contains("SELEC"));
+    }
+
+    #[test]
+    fn test_grok_error_hides_details() {
+        let err = AppError::grok("Grok service returned error 500: traceback");
+        assert!(!err.public_message().contains("traceback"));
+    }
+}
@@ -0,0 +1,358 @@
+//! Evaluation of ranking sources against recruiter preferences
+//!
+//! A ranking source scores a job's candidates: `job_matches` by the rank grok
+//! gave them, `candidate_score` by their overall candidate score. Each job is
+//! scored against its active strict preferences (no ties, none superseded):
+//!
+//! - pairwise accuracy: the share of preferences the source orders the same
+//!   way, weighted by confidence and recency; a tie in the source counts half
+//! - Kendall's tau-b between the source's scores and each candidate's
+//!   preference win rate
+//! - NDCG@k of the source's order, using win rates as gains
+//!
+//! Runs over many jobs are stored with a label, so quality can be followed
+//! across prompt changes. Evaluations of `job_matches` also record the
+//! matching run that served each job's matches and its model version.
+
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::database::Pool;
+use crate::models::{EvaluationRun, Job, JobEvaluation, PairwisePreference};
+use crate::preference_dataset;
+use crate::reorder::recency_weight;
+
+pub const SOURCE_JOB_MATCHES: &str = "job_matches";
+pub const SOURCE_CANDIDATE_SCORE: &str = "candidate_score";
+pub const SOURCES: [&str; 2] = [SOURCE_JOB_MATCHES, SOURCE_CANDIDATE_SCORE];
+
+pub const SPLIT_ALL: &str = "all";
+
+pub const DEFAULT_K: usize = 10;
+
+/// What to evaluate in one run
+pub struct RunOptions {
+    pub source: String,
+    pub label: Option<String>,
+    pub job_ids: Option<Vec<String>>,
+    pub split: String,
+    pub validation_fraction: f64,
+    pub seed: String,
+    pub k: usize,
+}
+
+/// Candidate -> score under a source, higher is better. Candidates the
+/// source has no opinion on are left out.
+pub async fn source_scores(pool: &Pool, source: &str, job_id: &str) -> Result<HashMap<String, f64>, sqlx::Error> {
+    if source == SOURCE_CANDIDATE_SCORE {
+        let candidates = crate::database::get_job_candidate_ids(pool, job_id).await?;
+        return Ok(crate::database::get_talents_by_ids(pool, &candidates)
+            .await?
+            .into_iter()
+            .filter_map(|talent| talent.candidate_score.map(|score| (talent.id, score)))
+            .collect());
+    }
+    Ok(crate::database::get_job_matches(pool, job_id)
+        .await?
+        .into_iter()
+        .map(|job_match| (job_match.talent_id, -f64::from(job_match.rank)))
+        .collect())
+}
+
+/// Evaluate a source on one job
+pub async fn evaluate_job(pool: &Pool, source: &str, job: &Job, k: usize, now: DateTime<Utc>) -> Result<JobEvaluation, sqlx::Error> {
+    let preferences: Vec<PairwisePreference> = crate::database::get_pairwise_preferences_for_job(pool, job.id.clone())
+        .await?
+        .into_iter()
+        .filter(|p| !p.tie && p.superseded_by.is_none())
+        .collect();
+    let served = match source {
+        SOURCE_JOB_MATCHES => crate::database::get_served_match_run(pool, &job.id).await?,
+        _ => None,
+    };
+    let scores = source_scores(pool, source, &job.id).await?;
+    Ok(JobEvaluation {
+        match_run_id: served.as_ref().map(|run| run.id.clone()),
+        model_version: served.and_then(|run| run.model_version),
+        ..evaluate(&job.id, &job.title, &preferences, &scores, k, now)
+    })
+}
+
+/// Evaluate a source on many jobs and store the run
+pub async fn run(pool: &Pool, options: RunOptions) -> Result<EvaluationRun, sqlx::Error> {
+    let now = Utc::now();
+    let job_ids = match options.job_ids {
+        Some(job_ids) => job_ids,
+        None => crate::database::get_preference_job_ids(pool).await?,
+    };
+
+    let mut jobs = Vec::new();
+    for job_id in job_ids {
+        if options.split != SPLIT_ALL
+            && preference_dataset::split_for_job(&job_id, &options.seed, options.validation_fraction) != options.split
+        {
+            continue;
+        }
+        let Some(job) = crate::database::get_job_by_id(pool, job_id).await? else {
+            continue;
+        };
+        let evaluation = evaluate_job(pool, &options.source, &job, options.k, now).await?;
+        if evaluation.preferences > 0 {
+            jobs.push(evaluation);
+        }
+    }
+
+    let summary = summarize(&jobs);
+    let match_run_id = match jobs.as_slice() {
+        [job] => job.match_run_id.clone(),
+        _ => None,
+    };
+    let run = EvaluationRun {
+        id: Uuid::new_v4().to_string(),
+        source: options.source,
+        label: options.label,
+        split: options.split,
+        k: options.k as i64,
+        jobs_evaluated: jobs.len() as i64,
+        preferences_evaluated: summary.preferences as i64,
+        pairwise_accuracy: summary.pairwise_accuracy,
+        kendall_tau: summary.kendall_tau,
+        ndcg: summary.ndcg,
+        match_run_id,
+        model_version: shared_model_version(&jobs),
+        job_results: serde_json::to_string(&jobs).unwrap_or_else(|_| "[]".to_string()),
+        created_at: now.to_rfc3339(),
+    };
+    let run = crate::database::create_evaluation_run(pool, &run).await?;
+    log::info!(
+        "[evaluation] {} on {} jobs ({} preferences): accuracy {:?}, tau {:?}, ndcg {:?}",
+        run.source, run.jobs_evaluated, run.preferences_evaluated, run.pairwise_accuracy, run.kendall_tau, run.ndcg
+    );
+    Ok(run)
+}
+
+/// Metrics of one job, from its active strict preferences and the source's scores
+pub fn evaluate(
+    job_id: &str,
+    job_title: &str,
+    preferences: &[PairwisePreference],
+    scores: &HashMap<String, f64>,
+    k: usize,
+    now: DateTime<Utc>,
+) -> JobEvaluation {
+    let scored: Vec<(&PairwisePreference, f64, f64)> = preferences
+        .iter()
+        .filter_map(|p| Some((p, *scores.get(&p.winner_id)?, *scores.get(&p.loser_id)?)))
+        .collect();
+
+    let mut total = 0.0;
+    let mut correct = 0.0;
+    for (preference, winner, loser) in &scored {
+        let weight = recency_weight(preference.confidence, &preference.created_at, now);
+        total += weight;
+        correct += weight * if winner > loser { 1.0 } else if winner == loser { 0.5 } else { 0.0 };
+    }
+
+    let rates = win_rates(scored.iter().map(|(p, _, _)| *p), now);
+    let mut ranked: Vec<(&str, f64, f64)> = rates
+        .iter()
+        .map(|(id, rate)| (id.as_str(), scores[id], *rate))
+        .collect();
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+
+    JobEvaluation {
+        job_id: job_id.to_string(),
+        job_title: job_title.to_string(),
+        preferences: scored.len(),
+        ranked_candidates: ranked.len(),
+        pairwise_accuracy: (total > 0.0).then(|| correct / total),
+        kendall_tau: kendall_tau_b(&ranked.iter().map(|(_, score, rate)| (*score, *rate)).collect::<Vec<_>>()),
+        ndcg: ndcg(&ranked.iter().map(|(_, _, rate)| *rate).collect::<Vec<_>>(), k),
+        match_run_id: None,
+        model_version: None,
+    }
+}
+
+/// Share of (recency-weighted) comparisons each candidate won
+fn win_rates<'a>(preferences: impl Iterator<Item = &'a PairwisePreference>, now: DateTime<Utc>) -> HashMap<String, f64> {
+    let mut wins: HashMap<String, (f64, f64)> = HashMap::new();
+    for preference in preferences {
+        let weight = recency_weight(preference.confidence, &preference.created_at, now);
+        wins.entry(preference.winner_id.clone()).or_default().0 += weight;
+        wins.entry(preference.winner_id.clone()).or_default().1 += weight;
+        wins.entry(preference.loser_id.clone()).or_default().1 += weight;
+    }
+    wins.into_iter()
+        .map(|(id, (won, played))| (id, if played > 0.0 { won / played } else { 0.5 }))
+        .collect()
+}
+
+/// Kendall's tau-b of paired observations; None when either side is constant
+pub fn kendall_tau_b(pairs: &[(f64, f64)]) -> Option<f64> {
+    let (mut concordant, mut discordant, mut tied_x, mut tied_y) = (0.0_f64, 0.0_f64, 0.0_f64, 0.0_f64);
+    for (i, (x1, y1)) in pairs.iter().enumerate() {
+        for (x2, y2) in &pairs[i + 1..] {
+            match (x1.total_cmp(x2), y1.total_cmp(y2)) {
+                (Ordering::Equal, Ordering::Equal) => {}
+                (Ordering::Equal, _) => tied_x += 1.0,
+                (_, Ordering::Equal) => tied_y += 1.0,
+                (dx, dy) if dx == dy => concordant += 1.0,
+                _ => discordant += 1.0,
+            }
+        }
+    }
+    let denominator = ((concordant + discordant + tied_x) * (concordant + discordant + tied_y)).sqrt();
+    (denominator > 0.0).then(|| (concordant - discordant) / denominator)
+}
+
+/// NDCG@k of gains listed in ranked order; None when every gain is zero
+pub fn ndcg(gains: &[f64], k: usize) -> Option<f64> {
+    let dcg = |gains: &[f64]| -> f64 {
+        gains
+            .iter()
+            .take(k)
+            .enumerate()
+            .map(|(i, gain)| (2f64.powf(*gain) - 1.0) / (i as f64 + 2.0).log2())
+            .sum()
+    };
+    let mut ideal = gains.to_vec();
+    ideal.sort_by(|a, b| b.total_cmp(a));
+    let best = dcg(&ideal);
+    (best > 0.0).then(|| dcg(gains) / best)
+}
+
+/// Totals over jobs
+pub struct Summary {
+    pub preferences: usize,
+    pub pairwise_accuracy: Option<f64>,
+    pub kendall_tau: Option<f64>,
+    pub ndcg: Option<f64>,
+}
+
+/// Accuracy over all preferences; tau and NDCG averaged over the jobs that have them
+pub fn summarize(jobs: &[JobEvaluation]) -> Summary {
+    let mean = |values: Vec<f64>| (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64);
+    let judged: Vec<&JobEvaluation> = jobs.iter().filter(|job| job.pairwise_accuracy.is_some()).collect();
+    let judged_preferences: usize = judged.iter().map(|job| job.preferences).sum();
+    Summary {
+        preferences: jobs.iter().map(|job| job.preferences).sum(),
+        pairwise_accuracy: (judged_preferences > 0).then(|| {
+            judged.iter().map(|job| job.pairwise_accuracy.unwrap_or_default() * job.preferences as f64).sum::<f64>()
+                / judged_preferences as f64
+        }),
+        kendall_tau: mean(jobs.iter().filter_map(|job| job.kendall_tau).collect()),
+        ndcg: mean(jobs.iter().filter_map(|job| job.ndcg).collect()),
+    }
+}
+
+/// The model version every evaluated job's matching run used, if they agree
+pub fn shared_model_version(jobs: &[JobEvaluation]) -> Option<String> {
+    let first = jobs.first()?.model_version.as_ref()?;
+    jobs.iter()
+        .all(|job| job.model_version.as_ref() == Some(first))
+        .then(|| first.clone())
+}
+
+/// `job_ids` without repeats, in first-seen order
+pub fn dedup_job_ids(job_ids: Vec<String>) -> Vec<String> {
+    let mut seen = HashSet::new();
+    job_ids.into_iter().filter(|id| seen.insert(id.clone())).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::preference;
+
+    fn scores(scores: &[(&str, f64)]) -> HashMap<String, f64> {
+        scores.iter().map(|(id, score)| (id.to_string(), *score)).collect()
+    }
+
+    #[test]
+    fn test_perfect_and_reversed_rankings() {
+        let prefs = vec![preference("a", "b"), preference("b", "c"), preference("a", "c")];
+
+        let perfect = evaluate("job1", "Engineer", &prefs, &scores(&[("a", 3.0), ("b", 2.0), ("c", 1.0)]), 10, Utc::now());
+        assert_eq!(perfect.preferences, 3);
+        assert!((perfect.pairwise_accuracy.unwrap() - 1.0).abs() < 1e-9);
+        assert!((perfect.kendall_tau.unwrap() - 1.0).abs() < 1e-9);
+        assert!((perfect.ndcg.unwrap() - 1.0).abs() < 1e-9);
+
+        let reversed = evaluate("job1", "Engineer", &prefs, &scores(&[("a", 1.0), ("b", 2.0), ("c", 3.0)]), 10, Utc::now());
+        assert!(reversed.pairwise_accuracy.unwrap().abs() < 1e-9);
+        assert!((reversed.kendall_tau.unwrap() + 1.0).abs() < 1e-9);
+        assert!(reversed.ndcg.unwrap() < 1.0);
+    }
+
+    #[test]
+    fn test_unscored_candidates_and_ties() {
+        let prefs = vec![preference("a", "b"), preference("a", "z")];
+        let evaluation = evaluate("job1", "Engineer", &prefs, &scores(&[("a", 1.0), ("b", 1.0)]), 10, Utc::now());
+        // z is unranked by the source, and a tie in the source counts half
+        assert_eq!(evaluation.preferences, 1);
+        assert_eq!(evaluation.ranked_candidates, 2);
+        assert_eq!(evaluation.pairwise_accuracy, Some(0.5));
+        assert_eq!(evaluation.kendall_tau, None);
+
+        let none = evaluate("job1", "Engineer", &prefs, &HashMap::new(), 10, Utc::now());
+        assert_eq!((none.preferences, none.pairwise_accuracy, none.ndcg), (0, None, None));
+    }
+
+    #[test]
+    fn test_kendall_tau_b_and_ndcg() {
+        assert_eq!(kendall_tau_b(&[(1.0, 1.0), (2.0, 2.0), (3.0, 3.0)]), Some(1.0));
+        assert_eq!(kendall_tau_b(&[(1.0, 1.0)]), None);
+        // One tie in x: C = 2, D = 0, n0 = 3, n1 = 1, n2 = 0
+        let tau = kendall_tau_b(&[(1.0, 1.0), (1.0, 2.0), (2.0, 3.0)]).unwrap();
+        assert!((tau - 2.0 / 6f64.sqrt()).abs() < 1e-9);
+
+        assert_eq!(ndcg(&[1.0, 0.0], 10), Some(1.0));
+        assert_eq!(ndcg(&[0.0, 0.0], 10), None);
+        let swapped = ndcg(&[0.0, 1.0], 10).unwrap();
+        assert!((swapped - 1.0 / 3f64.log2()).abs() < 1e-9);
+        // Beyond the cutoff nothing counts
+        assert_eq!(ndcg(&[0.0, 1.0], 1), Some(0.0));
+    }
+
+    #[test]
+    fn test_summarize() {
+        let job = |preferences, accuracy, tau| JobEvaluation {
+            job_id: "job".to_string(),
+            job_title: "Engineer".to_string(),
+            preferences,
+            ranked_candidates: 0,
+            pairwise_accuracy: accuracy,
+            kendall_tau: tau,
+            ndcg: None,
+            match_run_id: None,
+            model_version: None,
+        };
+        let summary = summarize(&[job(3, Some(1.0), Some(1.0)), job(1, Some(0.0), None)]);
+        assert_eq!(summary.preferences, 4);
+        assert_eq!(summary.pairwise_accuracy, Some(0.75));
+        assert_eq!(summary.kendall_tau, Some(1.0));
+        assert_eq!(summary.ndcg, None);
+    }
+
+    #[test]
+    fn test_shared_model_version() {
+        let job = |model: Option<&str>| JobEvaluation {
+            job_id: "job".to_string(),
+            job_title: "Engineer".to_string(),
+            preferences: 1,
+            ranked_candidates: 2,
+            pairwise_accuracy: None,
+            kendall_tau: None,
+            ndcg: None,
+            match_run_id: None,
+            model_version: model.map(str::to_string),
+        };
+        assert_eq!(shared_model_version(&[job(Some("grok-4")), job(Some("grok-4"))]), Some("grok-4".to_string()));
+        assert_eq!(shared_model_version(&[job(Some("grok-4")), job(Some("grok-3"))]), None);
+        assert_eq!(shared_model_version(&[job(Some("grok-4")), job(None)]), None);
+        assert_eq!(shared_model_version(&[]), None);
+    }
+}
@@ -0,0 +1,315 @@
+//! Pipeline events
+//!
+//! Handlers publish an event when something happens in the hiring pipeline:
+//! an application arrives or changes status, a talent is scored, matches are
+//! generated for a job, or a job's candidate order changes. Every event is
+//! wrapped in the same envelope, handed to the subscribed webhooks and
+//! turned into any notification emails it calls for.
+//!
+//! Events are also recorded in a short history and broadcast in-process to
+//! event stream subscribers. The history's sequence number is the stream's
+//! event ID, so a client that reconnects can pick up where it left off.
+
+use std::fmt;
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+use log::{error, info};
+use serde::Serialize;
+use serde_json::{json, Value};
+use tokio::sync::{broadcast, Mutex, Notify};
+use uuid::Uuid;
+
+use crate::database::Pool;
+use crate::grok_client::CandidateScoringResult;
+use crate::models::{Application, JobMatchWithTalent, ReorderEvent, StoredEvent, Talent};
+use crate::notifications::Notifier;
+
+/// Events buffered for a stream subscriber before it is considered lagging
+const LIVE_CAPACITY: usize = 256;
+
+/// How long published events stay available for stream resumption
+pub const HISTORY_RETENTION_HOURS: i64 = 24;
+
+/// How often history older than `HISTORY_RETENTION_HOURS` is pruned
+pub const HISTORY_PRUNE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(15 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    ApplicationCreated,
+    ApplicationStatusChanged,
+    TalentScored,
+    TalentSocialAnalyzed,
+    JobMatchesGenerated,
+    ReorderCreated,
+    JobOrderReconciled,
+}
+
+impl EventKind {
+    pub const ALL: [EventKind; 7] = [
+        EventKind::ApplicationCreated,
+        EventKind::ApplicationStatusChanged,
+        EventKind::TalentScored,
+        EventKind::TalentSocialAnalyzed,
+        EventKind::JobMatchesGenerated,
+        EventKind::ReorderCreated,
+        EventKind::JobOrderReconciled,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventKind::ApplicationCreated => "application.created",
+            EventKind::ApplicationStatusChanged => "application.status_changed",
+            EventKind::TalentScored => "talent.scored",
+            EventKind::TalentSocialAnalyzed => "talent.social_analyzed",
+            EventKind::JobMatchesGenerated => "job_matches.generated",
+            EventKind::ReorderCreated => "reorder.created",
+            EventKind::JobOrderReconciled => "job_order.reconciled",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        EventKind::ALL.into_iter().find(|k| k.as_str() == value.trim().to_lowercase())
+    }
+}
+
+impl fmt::Display for EventKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The envelope every event is delivered in
+#[derive(Debug, Clone, Serialize)]
+pub struct Event {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub event_type: String,
+    pub created_at: String,
+    pub data: Value,
+}
+
+impl Event {
+    pub fn new(event_type: &str, data: Value) -> Self {
+        Event {
+            id: Uuid::new_v4().to_string(),
+            event_type: event_type.to_string(),
+            created_at: Utc::now().to_rfc3339(),
+            data,
+        }
+    }
+}
+
+/// Data of `application.created`; resumes are never included in events
+pub fn application_created(application: &Application) -> Value {
+    json!({
+        "application_id": application.id,
+        "talent_id": application.talent_id,
+        "job_id": application.job_id,
+        "status": application.status,
+        "has_resume": application.resume_data.is_some(),
+        "created_at": application.created_at,
+    })
+}
+
+/// Data of `application.status_changed`
+pub fn application_status_changed(application: &Application, previous_status: &str) -> Value {
+    json!({
+        "application_id": application.id,
+        "talent_id": application.talent_id,
+        "job_id": application.job_id,
+        "previous_status": previous_status,
+        "status": application.status,
+    })
+}
+
+/// Data of `talent.scored`: the full scoring result for the talent and job
+pub fn talent_scored(result: &CandidateScoringResult) -> Value {
+    serde_json::to_value(result).unwrap_or_default()
+}
+
+/// Data of `talent.social_analyzed`; the analysis itself is on the talent
+pub fn talent_social_analyzed(talent: &Talent) -> Value {
+    json!({
+        "talent_id": talent.id,
+        "x_handle_discovered": talent.x_handle_discovered,
+    })
+}
+
+/// Data of `job_matches.generated`: the ranked talents, without their profiles
+pub fn job_matches_generated(job_id: &str, matches: &[JobMatchWithTalent]) -> Value {
+    json!({
+        "job_id": job_id,
+        "match_count": matches.len(),
+        "matches": matches
+            .iter()
+            .map(|m| json!({ "talent_id": m.talent_id, "score": m.score, "rank": m.rank }))
+            .collect::<Vec<_>>(),
+    })
+}
+
+/// Data of `reorder.created`; `version` is the job's order version after it
+pub fn reorder_created(event: &ReorderEvent, preferences_created: i32, version: i64) -> Value {
+    let order = |json: &str| serde_json::from_str::<Vec<String>>(json).unwrap_or_default();
+    json!({
+        "reorder_event_id": event.id,
+        "job_id": event.job_id,
+        "moved_talent_id": event.moved_talent_id,
+        "before_order": order(&event.before_order),
+        "after_order": order(&event.after_order),
+        "preferences_created": preferences_created,
+        "version": version,
+    })
+}
+
+/// Job and talent an event is about, used to filter event streams
+fn subjects(data: &Value) -> (Option<String>, Option<String>) {
+    let field = |name: &str| data.get(name).and_then(Value::as_str).map(str::to_string);
+    (field("job_id"), field("talent_id").or_else(|| field("moved_talent_id")))
+}
+
+/// Which events an event stream subscriber receives
+#[derive(Debug, Clone, Default)]
+pub struct StreamFilter {
+    pub job_id: Option<String>,
+    pub talent_id: Option<String>,
+    pub kinds: Vec<EventKind>, // empty for every kind
+}
+
+impl StreamFilter {
+    pub fn matches(&self, event: &StoredEvent) -> bool {
+        self.job_id.as_ref().is_none_or(|id| event.job_id.as_ref() == Some(id))
+            && self.talent_id.as_ref().is_none_or(|id| event.talent_id.as_ref() == Some(id))
+            && (self.kinds.is_empty() || self.kinds.iter().any(|k| k.as_str() == event.event_type))
+    }
+}
+
+/// Server-sent events frame for an event. The payload is compact JSON, so it
+/// always fits on a single `data:` line
+pub fn sse_frame(event: &StoredEvent) -> String {
+    format!("id: {}\nevent: {}\ndata: {}\n\n", event.seq, event.event_type, event.payload)
+}
+
+/// Drop history older than `HISTORY_RETENTION_HOURS`. Called periodically
+/// by the webhook dispatcher rather than on every publish.
+pub async fn prune_history(pool: &Pool) {
+    let cutoff = (Utc::now() - Duration::hours(HISTORY_RETENTION_HOURS)).to_rfc3339();
+    if let Err(e) = crate::database::prune_event_history(pool, &cutoff).await {
+        error!("[events] Failed to prune event history: {}", e);
+    }
+}
+
+/// Handle for publishing events, shared through `AppState`
+#[derive(Clone)]
+pub struct Events {
+    pool: Pool,
+    wake: Arc<Notify>,
+    notifier: Notifier,
+    live: broadcast::Sender<StoredEvent>,
+    // Held while an event is recorded and broadcast, so subscribers see
+    // events in sequence order
+    ordering: Arc<Mutex<()>>,
+}
+
+impl Events {
+    pub fn new(pool: Pool, notifier: Notifier) -> Self {
+        let (live, _) = broadcast::channel(LIVE_CAPACITY);
+        Events { pool, wake: Arc::new(Notify::new()), notifier, live, ordering: Arc::new(Mutex::new(())) }
+    }
+
+    /// Receive every event published from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<StoredEvent> {
+        self.live.subscribe()
+    }
+
+    /// Notified whenever new deliveries are queued
+    pub fn wake_handle(&self) -> Arc<Notify> {
+        self.wake.clone()
+    }
+
+    /// Wake the webhook dispatcher, e.g. after a manual redelivery
+    pub fn wake(&self) {
+        self.wake.notify_one();
+    }
+
+    /// Add the event to the history and hand it to stream subscribers
+    async fn record(&self, event: &Event) {
+        let (job_id, talent_id) = subjects(&event.data);
+        let stored = StoredEvent {
+            seq: 0,
+            id: event.id.clone(),
+            event_type: event.event_type.clone(),
+            job_id,
+            talent_id,
+            payload: serde_json::to_string(event).unwrap_or_default(),
+            created_at: event.created_at.clone(),
+        };
+
+        let _ordered = self.ordering.lock().await;
+        match crate::database::insert_stored_event(&self.pool, &stored).await {
+            // Sending only fails when nobody is subscribed
+            Ok(stored) => { let _ = self.live.send(stored); }
+            Err(e) => error!("[events] Failed to record {} {}: {}", event.event_type, event.id, e),
+        }
+    }
+
+    /// Record and broadcast the event, queue a delivery of it to every
+    /// active webhook subscribed to it and send its notifications. Failures
+    /// are logged: a stream, webhook or email problem never fails the
+    /// request that caused the event.
+    pub async fn publish(&self, kind: EventKind, data: Value) {
+        let event = Event::new(kind.as_str(), data);
+        self.record(&event).await;
+        match crate::webhooks::enqueue(&self.pool, &event).await {
+            Ok(0) => {}
+            Ok(queued) => {
+                info!("[events] {} {} queued for {} webhooks", kind, event.id, queued);
+                self.wake();
+            }
+            Err(e) => error!("[events] Failed to queue {} {}: {}", kind, event.id, e),
+        }
+        self.notifier.on_event(kind, &event.data).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stored(event_type: &str, data: Value) -> StoredEvent {
+        let (job_id, talent_id) = subjects(&data);
+        let event = Event::new(event_type, data);
+        StoredEvent {
+            seq: 7,
+            id: event.id.clone(),
+            event_type: event.event_type.clone(),
+            job_id,
+            talent_id,
+            payload: serde_json::to_string(&event).unwrap(),
+            created_at: event.created_at,
+        }
+    }
+
+    #[test]
+    fn test_filters_by_job_talent_and_kind() {
+        let created = stored("application.created", json!({ "job_id": "j1", "talent_id": "t1" }));
+        let reorder = stored("reorder.created", json!({ "job_id": "j1", "moved_talent_id": "t2" }));
+
+        assert!(StreamFilter::default().matches(&created));
+        let job = StreamFilter { job_id: Some("j1".into()), ..Default::default() };
+        assert!(job.matches(&created) && job.matches(&reorder));
+        let talent = StreamFilter { talent_id: Some("t2".into()), ..Default::default() };
+        assert!(!talent.matches(&created) && talent.matches(&reorder));
+        let kinds = StreamFilter { kinds: vec![EventKind::ReorderCreated], ..Default::default() };
+        assert!(!kinds.matches(&created) && kinds.matches(&reorder));
+    }
+
+    #[test]
+    fn test_frames_event_on_one_data_line() {
+        let event = stored("talent.scored", json!({ "summary": "line one\nline two" }));
+        let frame = sse_frame(&event);
+        assert!(frame.starts_with("id: 7\nevent: talent.scored\ndata: {"));
+        assert!(frame.ends_with("}\n\n"));
+        assert_eq!(frame.lines().count(), 4);
+    }
+}
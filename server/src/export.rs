@@ -0,0 +1,220 @@
+//! Spreadsheet-friendly exports
+//!
+//! Rows are encoded one at a time so exports can be streamed. `excel` is CSV
+//! that spreadsheet applications open cleanly: a UTF-8 byte order mark and
+//! CRLF line endings. Both CSV flavours neutralize cells that a spreadsheet
+//! would otherwise evaluate as formulas.
+
+use serde_json::{Map, Value};
+
+/// Columns of a job pipeline export, in output order
+pub const PIPELINE_COLUMNS: &[&str] = &[
+    "application_id", "application_status", "applied_at", "has_resume",
+    "talent_id", "name", "email", "handle", "title", "location", "experience", "skills", "verified",
+    "candidate_score", "match_score", "match_rank", "match_summary", "recruiter_position",
+];
+
+/// Columns of a talent export, in output order
+pub const TALENT_COLUMNS: &[&str] = &[
+    "id", "name", "email", "handle", "title", "location", "experience", "skills", "bio", "verified",
+    "candidate_score", "linkedin_url", "github_url", "gitlab_url", "x_url", "created_at",
+];
+
+/// Fields of a preference training example, in output order
+pub const PREFERENCE_COLUMNS: &[&str] = &[
+    "id", "split", "job_id", "prompt", "chosen", "rejected", "chosen_talent_id", "rejected_talent_id",
+    "source", "confidence", "weight", "created_at",
+];
+
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    /// CSV for spreadsheet applications (BOM, CRLF)
+    Excel,
+    Json,
+    Ndjson,
+}
+
+impl ExportFormat {
+    pub fn parse(value: Option<&str>) -> Result<Self, String> {
+        match value.unwrap_or("csv") {
+            "csv" => Ok(ExportFormat::Csv),
+            "excel" => Ok(ExportFormat::Excel),
+            "json" => Ok(ExportFormat::Json),
+            "ndjson" => Ok(ExportFormat::Ndjson),
+            other => Err(format!("Unsupported format '{}'; use csv, excel, json or ndjson", other)),
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv | ExportFormat::Excel => "text/csv; charset=utf-8",
+            ExportFormat::Json => "application/json",
+            ExportFormat::Ndjson => "application/x-ndjson",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv | ExportFormat::Excel => "csv",
+            ExportFormat::Json => "json",
+            ExportFormat::Ndjson => "ndjson",
+        }
+    }
+}
+
+/// Resolve a comma-separated column list against the available columns;
+/// everything, in the default order, when none are given
+pub fn select_columns(spec: Option<&str>, available: &[&str]) -> Result<Vec<String>, String> {
+    let Some(spec) = spec.filter(|s| !s.trim().is_empty()) else {
+        return Ok(available.iter().map(|c| c.to_string()).collect());
+    };
+
+    let mut columns: Vec<String> = Vec::new();
+    for column in spec.split(',').map(str::trim).filter(|c| !c.is_empty()) {
+        if !available.contains(&column) {
+            return Err(format!("Unknown column '{}'; available: {}", column, available.join(", ")));
+        }
+        if !columns.iter().any(|c| c == column) {
+            columns.push(column.to_string());
+        }
+    }
+    Ok(columns)
+}
+
+/// Plain-text form of a JSON value for a CSV cell
+fn cell(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Bool(b)) => b.to_string(),
+        Some(other) => other.to_string(),
+    }
+}
+
+/// Prefix cells a spreadsheet would evaluate as a formula with a quote
+fn neutralize_formula(text: String) -> String {
+    match text.chars().next() {
+        Some('=' | '+' | '-' | '@' | '\t' | '\r') if text.parse::<f64>().is_err() => format!("'{}", text),
+        _ => text,
+    }
+}
+
+/// Encodes rows (JSON objects) into one export format, chunk by chunk
+pub struct RowEncoder {
+    format: ExportFormat,
+    columns: Vec<String>,
+    rows: usize,
+}
+
+impl RowEncoder {
+    pub fn new(format: ExportFormat, columns: Vec<String>) -> Self {
+        RowEncoder { format, columns, rows: 0 }
+    }
+
+    fn csv_line<I: IntoIterator<Item = String>>(&self, fields: I) -> Vec<u8> {
+        let terminator = if self.format == ExportFormat::Excel {
+            csv::Terminator::CRLF
+        } else {
+            csv::Terminator::Any(b'\n')
+        };
+        let mut writer = csv::WriterBuilder::new().terminator(terminator).from_writer(Vec::new());
+        // Writing to a Vec cannot fail
+        let _ = writer.write_record(fields);
+        writer.into_inner().unwrap_or_default()
+    }
+
+    pub fn header(&self) -> Vec<u8> {
+        match self.format {
+            ExportFormat::Csv => self.csv_line(self.columns.iter().cloned()),
+            ExportFormat::Excel => [UTF8_BOM, &self.csv_line(self.columns.iter().cloned())].concat(),
+            ExportFormat::Json => b"[".to_vec(),
+            ExportFormat::Ndjson => Vec::new(),
+        }
+    }
+
+    pub fn row(&mut self, row: &Value) -> Vec<u8> {
+        let object = row.as_object();
+        let get = |column: &String| object.and_then(|o| o.get(column));
+        let first = self.rows == 0;
+        self.rows += 1;
+
+        match self.format {
+            ExportFormat::Csv | ExportFormat::Excel => {
+                let fields: Vec<String> = self.columns.iter().map(|c| neutralize_formula(cell(get(c)))).collect();
+                self.csv_line(fields)
+            }
+            ExportFormat::Json | ExportFormat::Ndjson => {
+                let selected: Map<String, Value> = self.columns
+                    .iter()
+                    .map(|c| (c.clone(), get(c).cloned().unwrap_or(Value::Null)))
+                    .collect();
+                let json = serde_json::to_vec(&selected).unwrap_or_default();
+                match self.format {
+                    ExportFormat::Json if first => json,
+                    ExportFormat::Json => [b",".as_slice(), &json].concat(),
+                    _ => [json.as_slice(), b"\n"].concat(),
+                }
+            }
+        }
+    }
+
+    pub fn footer(&self) -> Vec<u8> {
+        match self.format {
+            ExportFormat::Json => b"]".to_vec(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn encode(format: ExportFormat, columns: &[&str], rows: &[Value]) -> Vec<u8> {
+        let mut encoder = RowEncoder::new(format, columns.iter().map(|c| c.to_string()).collect());
+        let mut out = encoder.header();
+        for row in rows {
+            out.extend(encoder.row(row));
+        }
+        out.extend(encoder.footer());
+        out
+    }
+
+    #[test]
+    fn test_select_columns() {
+        assert_eq!(select_columns(None, &["a", "b"]).unwrap(), vec!["a", "b"]);
+        assert_eq!(select_columns(Some("b, a,b"), &["a", "b"]).unwrap(), vec!["b", "a"]);
+        assert!(select_columns(Some("c"), &["a", "b"]).is_err());
+    }
+
+    #[test]
+    fn test_csv_and_excel() {
+        let rows = [json!({"name": "=HYPERLINK(\"x\")", "score": -1.5, "note": null, "ok": true})];
+        let csv = encode(ExportFormat::Csv, &["name", "score", "note", "ok"], &rows);
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "name,score,note,ok\n\"'=HYPERLINK(\"\"x\"\")\",-1.5,,true\n"
+        );
+
+        let excel = encode(ExportFormat::Excel, &["score"], &rows);
+        assert!(excel.starts_with(UTF8_BOM));
+        assert_eq!(&excel[UTF8_BOM.len()..], b"score\r\n-1.5\r\n");
+    }
+
+    #[test]
+    fn test_json_and_ndjson() {
+        let rows = [json!({"a": 1, "b": "x"}), json!({"a": 2})];
+        let json = encode(ExportFormat::Json, &["a", "b"], &rows);
+        let parsed: Value = serde_json::from_slice(&json).unwrap();
+        assert_eq!(parsed, json!([{"a": 1, "b": "x"}, {"a": 2, "b": null}]));
+
+        assert_eq!(encode(ExportFormat::Json, &["a"], &[]), b"[]");
+
+        let ndjson = String::from_utf8(encode(ExportFormat::Ndjson, &["a"], &rows)).unwrap();
+        assert_eq!(ndjson, "{\"a\":1}\n{\"a\":2}\n");
+    }
+}
@@ -0,0 +1,333 @@
+//! Public job board feeds
+//!
+//! Renders open jobs as RSS 2.0, JSON Feed 1.1 and schema.org `JobPosting`
+//! JSON-LD so openings can be syndicated to job boards and aggregators.
+
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value};
+
+use crate::job_lifecycle::{self, JobStatus};
+use crate::models::Job;
+
+pub const FEED_TITLE: &str = "X Talent Pool - Open Positions";
+pub const FEED_DESCRIPTION: &str = "Currently open positions on X Talent Pool";
+
+/// Filters accepted by the public feed endpoints
+#[derive(Debug, Default, Clone)]
+pub struct FeedFilter {
+    pub company: Option<String>,
+    pub location: Option<String>,
+}
+
+impl FeedFilter {
+    fn matches(&self, job: &Job) -> bool {
+        let contains = |haystack: Option<&str>, needle: &Option<String>| match needle {
+            Some(n) if !n.trim().is_empty() => haystack
+                .map(|h| h.to_lowercase().contains(&n.trim().to_lowercase()))
+                .unwrap_or(false),
+            _ => true,
+        };
+        contains(Some(&job.company_name), &self.company) && contains(job.location.as_deref(), &self.location)
+    }
+}
+
+/// Keep only jobs that are published, unexpired and match the filter
+pub fn open_jobs(jobs: Vec<Job>, filter: &FeedFilter, now: DateTime<Utc>) -> Vec<Job> {
+    jobs.into_iter()
+        .filter(|job| JobStatus::parse(&job.status).map(|s| s.is_open()).unwrap_or(false))
+        .filter(|job| !job_lifecycle::is_expired(job.expires_at.as_deref(), now))
+        .filter(|job| filter.matches(job))
+        .collect()
+}
+
+/// Public URL of a job on the board
+pub fn job_url(base_url: &str, job: &Job) -> String {
+    format!("{}/jobs?id={}", base_url.trim_end_matches('/'), job.id)
+}
+
+/// When a job was posted (falls back to creation time for legacy rows)
+pub fn date_posted(job: &Job) -> Option<DateTime<Utc>> {
+    job.published_at
+        .as_deref()
+        .unwrap_or(&job.created_at)
+        .parse::<DateTime<Utc>>()
+        .ok()
+}
+
+/// Most recent posting date across jobs, used for `Last-Modified`
+pub fn last_modified(jobs: &[Job]) -> Option<DateTime<Utc>> {
+    jobs.iter().filter_map(date_posted).max()
+}
+
+/// Map our employment_type values onto schema.org's vocabulary
+pub fn schema_employment_type(employment_type: &str) -> &'static str {
+    match employment_type.trim().to_lowercase().as_str() {
+        "full-time" | "full_time" | "fulltime" => "FULL_TIME",
+        "part-time" | "part_time" | "parttime" => "PART_TIME",
+        "contract" | "contractor" => "CONTRACTOR",
+        "temporary" | "temp" => "TEMPORARY",
+        "internship" | "intern" => "INTERN",
+        _ => "OTHER",
+    }
+}
+
+/// Render a single job as a schema.org `JobPosting`
+pub fn job_posting_jsonld(job: &Job, base_url: &str) -> Value {
+    let mut posting = json!({
+        "@context": "https://schema.org/",
+        "@type": "JobPosting",
+        "title": job.title,
+        "description": job.description,
+        "identifier": {
+            "@type": "PropertyValue",
+            "name": job.company_name,
+            "value": job.id,
+        },
+        "hiringOrganization": {
+            "@type": "Organization",
+            "name": job.company_name,
+        },
+        "employmentType": schema_employment_type(&job.employment_type),
+        "skills": job.skills_required,
+        "url": job_url(base_url, job),
+    });
+
+    if let Some(logo) = &job.company_logo {
+        posting["hiringOrganization"]["logo"] = json!(logo);
+    }
+    if let Some(posted) = date_posted(job) {
+        posting["datePosted"] = json!(posted.to_rfc3339());
+    }
+    if let Some(expiry) = job.expires_at.as_deref().and_then(job_lifecycle::parse_expiry) {
+        posting["validThrough"] = json!(expiry.to_rfc3339());
+    }
+
+    let place = job.location.as_ref().map(|location| {
+        json!({
+            "@type": "Place",
+            "address": {
+                "@type": "PostalAddress",
+                "addressLocality": location,
+            },
+        })
+    });
+    // A remote job's location is free text, not necessarily a country, so no
+    // applicantLocationRequirements is derived from it
+    if job.location_type.eq_ignore_ascii_case("remote") {
+        posting["jobLocationType"] = json!("TELECOMMUTE");
+    } else if let Some(place) = place {
+        posting["jobLocation"] = place;
+    }
+
+    // Without a currency the amounts cannot be read, so no baseSalary at all
+    let currency = job.salary_currency.as_deref().filter(|currency| !currency.trim().is_empty());
+    if let Some(currency) = currency.filter(|_| job.salary_min.is_some() || job.salary_max.is_some()) {
+        let mut value = json!({
+            "@type": "QuantitativeValue",
+            "unitText": "YEAR",
+        });
+        if let Some(min) = job.salary_min {
+            value["minValue"] = json!(min);
+        }
+        if let Some(max) = job.salary_max {
+            value["maxValue"] = json!(max);
+        }
+        posting["baseSalary"] = json!({
+            "@type": "MonetaryAmount",
+            "currency": currency,
+            "value": value,
+        });
+    }
+
+    posting
+}
+
+/// Render jobs as a JSON Feed 1.1 document
+pub fn json_feed(jobs: &[Job], base_url: &str, feed_url: &str) -> Value {
+    let items: Vec<Value> = jobs
+        .iter()
+        .map(|job| {
+            let mut item = json!({
+                "id": job.id,
+                "url": job_url(base_url, job),
+                "title": format!("{} at {}", job.title, job.company_name),
+                "content_text": job.description,
+                "tags": split_skills(&job.skills_required),
+                "authors": [{ "name": job.company_name }],
+                "_job_posting": job_posting_jsonld(job, base_url),
+            });
+            if let Some(posted) = date_posted(job) {
+                item["date_published"] = json!(posted.to_rfc3339());
+            }
+            if let Some(logo) = &job.company_logo {
+                item["image"] = json!(logo);
+            }
+            item
+        })
+        .collect();
+
+    json!({
+        "version": "https://jsonfeed.org/version/1.1",
+        "title": FEED_TITLE,
+        "description": FEED_DESCRIPTION,
+        "home_page_url": format!("{}/jobs", base_url.trim_end_matches('/')),
+        "feed_url": feed_url,
+        "items": items,
+    })
+}
+
+/// Render jobs as an RSS 2.0 document
+pub fn rss_feed(jobs: &[Job], base_url: &str, feed_url: &str) -> String {
+    let mut xml = String::new();
+    xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str("<rss version=\"2.0\" xmlns:atom=\"http://www.w3.org/2005/Atom\">\n");
+    xml.push_str("<channel>\n");
+    xml.push_str(&format!("  <title>{}</title>\n", xml_escape(FEED_TITLE)));
+    xml.push_str(&format!("  <link>{}</link>\n", xml_escape(&format!("{}/jobs", base_url.trim_end_matches('/')))));
+    xml.push_str(&format!("  <description>{}</description>\n", xml_escape(FEED_DESCRIPTION)));
+    xml.push_str(&format!(
+        "  <atom:link href=\"{}\" rel=\"self\" type=\"application/rss+xml\"/>\n",
+        xml_escape(feed_url)
+    ));
+    if let Some(updated) = last_modified(jobs) {
+        xml.push_str(&format!("  <lastBuildDate>{}</lastBuildDate>\n", updated.to_rfc2822()));
+    }
+
+    for job in jobs {
+        let url = job_url(base_url, job);
+        xml.push_str("  <item>\n");
+        xml.push_str(&format!("    <title>{}</title>\n", xml_escape(&format!("{} at {}", job.title, job.company_name))));
+        xml.push_str(&format!("    <link>{}</link>\n", xml_escape(&url)));
+        xml.push_str(&format!("    <guid isPermaLink=\"false\">{}</guid>\n", xml_escape(&job.id)));
+        xml.push_str(&format!("    <description>{}</description>\n", xml_escape(&job.description)));
+        if let Some(posted) = date_posted(job) {
+            xml.push_str(&format!("    <pubDate>{}</pubDate>\n", posted.to_rfc2822()));
+        }
+        xml.push_str(&format!("    <category>{}</category>\n", xml_escape(&job.employment_type)));
+        xml.push_str(&format!("    <category>{}</category>\n", xml_escape(&job.location_type)));
+        if let Some(location) = &job.location {
+            xml.push_str(&format!("    <category>{}</category>\n", xml_escape(location)));
+        }
+        xml.push_str("  </item>\n");
+    }
+
+    xml.push_str("</channel>\n");
+    xml.push_str("</rss>\n");
+    xml
+}
+
+/// Escape text for inclusion in XML element content or attributes
+pub fn xml_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // Control characters are not allowed in XML 1.0
+            c if c.is_control() && c != '\n' && c != '\r' && c != '\t' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Stable FNV-1a hash of a rendered feed, used as a weak ETag
+pub fn etag(body: &[u8]) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in body {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("W/\"{:016x}\"", hash)
+}
+
+fn split_skills(skills: &str) -> Vec<String> {
+    skills
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(id: &str, status: &str) -> Job {
+        Job {
+            title: "Rust Engineer".to_string(),
+            description: "Build <fast> things & more".to_string(),
+            location: Some("Berlin".to_string()),
+            location_type: "onsite".to_string(),
+            salary_min: Some(100000),
+            salary_max: Some(150000),
+            salary_currency: Some("EUR".to_string()),
+            skills_required: "rust, tokio".to_string(),
+            status: status.to_string(),
+            created_at: "2026-01-01T00:00:00+00:00".to_string(),
+            published_at: Some("2026-01-02T00:00:00+00:00".to_string()),
+            ..crate::test_support::job(id)
+        }
+    }
+
+    #[test]
+    fn test_open_jobs_filters_status_and_fields() {
+        let now = Utc::now();
+        let mut expired = job("j4", "published");
+        expired.expires_at = Some("2020-01-01".to_string());
+        let jobs = vec![job("j1", "published"), job("j2", "draft"), job("j3", "active"), expired];
+
+        let open = open_jobs(jobs.clone(), &FeedFilter::default(), now);
+        let ids: Vec<&str> = open.iter().map(|j| j.id.as_str()).collect();
+        assert_eq!(ids, vec!["j1", "j3"]);
+
+        let filter = FeedFilter { company: Some("acme".to_string()), location: Some("munich".to_string()) };
+        assert!(open_jobs(jobs, &filter, now).is_empty());
+    }
+
+    #[test]
+    fn test_job_posting_mapping() {
+        let posting = job_posting_jsonld(&job("j1", "published"), "https://jobs.example.com/");
+        assert_eq!(posting["@type"], "JobPosting");
+        assert_eq!(posting["employmentType"], "FULL_TIME");
+        assert_eq!(posting["baseSalary"]["currency"], "EUR");
+        assert_eq!(posting["baseSalary"]["value"]["minValue"], 100000);
+        assert_eq!(posting["baseSalary"]["value"]["maxValue"], 150000);
+        assert_eq!(posting["jobLocation"]["address"]["addressLocality"], "Berlin");
+        assert_eq!(posting["url"], "https://jobs.example.com/jobs?id=j1");
+    }
+
+    #[test]
+    fn test_remote_job_is_telecommute() {
+        let mut remote = job("j1", "published");
+        remote.location_type = "remote".to_string();
+        let posting = job_posting_jsonld(&remote, "https://jobs.example.com");
+        assert_eq!(posting["jobLocationType"], "TELECOMMUTE");
+        assert!(posting.get("jobLocation").is_none());
+        assert!(posting.get("applicantLocationRequirements").is_none());
+    }
+
+    #[test]
+    fn test_salary_without_currency_is_omitted() {
+        let mut unknown = job("j1", "published");
+        unknown.salary_currency = None;
+        assert!(job_posting_jsonld(&unknown, "https://jobs.example.com").get("baseSalary").is_none());
+    }
+
+    #[test]
+    fn test_rss_escapes_content() {
+        let rss = rss_feed(&[job("j1", "published")], "https://jobs.example.com", "https://jobs.example.com/feeds/jobs.rss");
+        assert!(rss.contains("<item>"));
+        assert!(rss.contains("Build &lt;fast&gt; things &amp; more"));
+        assert!(!rss.contains("<fast>"));
+    }
+
+    #[test]
+    fn test_etag_is_stable() {
+        assert_eq!(etag(b"hello"), etag(b"hello"));
+        assert_ne!(etag(b"hello"), etag(b"world"));
+    }
+}
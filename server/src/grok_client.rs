@@ -67,6 +67,12 @@ pub struct CollectionResponse {
     pub error: Option<String>,
 }
 
+/// Response from the delete collection endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeleteCollectionResponse {
+    pub success: bool,
+}
+
 /// Request to create a collection
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateCollectionRequest {
@@ -216,6 +222,8 @@ pub struct JobMatchingResult {
     pub job_id: String,
     pub matches: Vec<CandidateMatch>,
     pub total_evaluated: i32,
+    #[serde(default)]
+    pub model: Option<String>,
     pub timestamp: String,
 }
 
@@ -370,6 +378,43 @@ impl GrokClient {
         Ok(parsed)
     }
 
+    /// Delete a collection and every document in it
+    pub async fn delete_collection(&self, collection_id: &str) -> Result<bool, String> {
+        info!("[GrokClient] Deleting collection: {}", collection_id);
+
+        let url = format!("{}/api/v1/collections/{}", self.base_url, collection_id);
+        info!("[GrokClient] Sending DELETE to: {}", url);
+
+        let response = self
+            .client
+            .delete(&url)
+            .send()
+            .await
+            .map_err(|e| {
+                error!("[GrokClient] Delete collection request failed: {}", e);
+                format!("Failed to send delete collection request to Grok service: {}", e)
+            })?;
+
+        let status = response.status();
+        info!("[GrokClient] Delete collection response status: {}", status);
+
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            error!("[GrokClient] Delete collection error response: {}", body);
+            return Err(format!(
+                "Grok service returned error {}: {}",
+                status, body
+            ));
+        }
+
+        let parsed: DeleteCollectionResponse = response.json().await
+            .map_err(|e| format!("Failed to parse delete collection response: {}", e))?;
+
+        info!("[GrokClient] Collection deleted - success: {}", parsed.success);
+
+        Ok(parsed.success)
+    }
+
     /// Upload a document to a collection
     pub async fn upload_document(
         &self,
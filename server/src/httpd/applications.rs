@@ -1,11 +1,19 @@
-use actix_web::{web, HttpResponse, Result as ActixResult};
+use actix_web::{web, HttpRequest, HttpResponse};
 use paperclip::actix::api_v2_operation;
 use uuid::Uuid;
 use chrono::Utc;
-use log::{info, error, warn};
-
-use crate::models::{Application, CreateApplicationRequest, ApplicationResponse, ApiError, BulkDeleteRequest, BulkDeleteResponse};
-use crate::grok_client::{GrokClient, TalentInfo, SocialMediaAnalysisRequest, SocialMediaInput, ProfileUrls, CandidateScoringRequest, JobInfoForScoring};
+use log::info;
+
+use crate::models::{
+    Application, CreateApplicationRequest, ApplicationResponse, BulkDeleteRequest, BulkDeleteResponse, UpdateApplicationStatusRequest,
+};
+use crate::error::{AppError, AppResult};
+use crate::events::{self, EventKind};
+use crate::resume_pipeline;
+use crate::job_lifecycle::{self, JobStatus};
+use crate::job_order;
+use crate::reorder::{self, PreferenceContext};
+use super::audit;
 use super::server::AppState;
 
 #[api_v2_operation]
@@ -13,27 +21,21 @@ use super::server::AppState;
 pub async fn create_application(
     data: web::Data<AppState>,
     json: web::Json<CreateApplicationRequest>,
-) -> ActixResult<HttpResponse> {
+) -> AppResult<HttpResponse> {
     let pool = &data.db_pool;
 
     // Verify talent exists
-    let talent = crate::database::get_talent_by_id(pool, json.talent_id.clone()).await
-        .map_err(actix_web::error::ErrorInternalServerError)?;
-    if talent.is_none() {
-        return Ok(HttpResponse::BadRequest().json(ApiError {
-            message: "Talent not found".to_string(),
-            code: 400,
-        }));
-    }
+    let talent = crate::database::get_talent_by_id(pool, json.talent_id.clone()).await?
+        .ok_or_else(|| AppError::validation("Talent not found"))?;
 
     // Verify job exists
-    let job = crate::database::get_job_by_id(pool, json.job_id.clone()).await
-        .map_err(actix_web::error::ErrorInternalServerError)?;
-    if job.is_none() {
-        return Ok(HttpResponse::BadRequest().json(ApiError {
-            message: "Job not found".to_string(),
-            code: 400,
-        }));
+    let job = crate::database::get_job_by_id(pool, json.job_id.clone()).await?
+        .ok_or_else(|| AppError::validation("Job not found"))?;
+
+    // Only published, unexpired jobs accept applications
+    let open = JobStatus::parse(&job.status).map(|s| s.is_open()).unwrap_or(false);
+    if !open || job_lifecycle::is_expired(job.expires_at.as_deref(), Utc::now()) {
+        return Err(AppError::conflict("Job is not accepting applications"));
     }
 
     let new_application = Application {
@@ -46,329 +48,37 @@ pub async fn create_application(
         cover_letter: json.cover_letter.clone(),
         status: "pending".to_string(),
         created_at: Utc::now().to_rfc3339(),
+        deleted_at: None,
     };
 
-    let inserted = crate::database::create_application(pool, &new_application).await
-        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let inserted = crate::database::create_application(pool, &new_application).await?;
+    data.events.publish(EventKind::ApplicationCreated, events::application_created(&inserted)).await;
+    job_order::reconcile_jobs(pool, &data.events, [inserted.job_id.clone()]).await;
 
     // If there's a resume, analyze it with Grok service
-    if let (Some(resume_data), Some(talent)) = (&inserted.resume_data, talent) {
+    if let Some(resume_data) = &inserted.resume_data {
         // Decode base64 resume
         use base64::{Engine as _, engine::general_purpose::STANDARD};
         if let Ok(pdf_bytes) = STANDARD.decode(resume_data) {
-            let talent_info = TalentInfo {
-                id: talent.id.clone(),
-                name: talent.name.clone(),
-                email: talent.email.clone(),
-                handle: talent.handle.clone(),
-                skills: talent.skills.clone(),
-                title: talent.title.clone(),
-                location: talent.location.clone(),
-                experience: talent.experience.clone(),
-                bio: talent.bio.clone(),
-            };
-
             let filename = inserted.resume_filename
                 .clone()
                 .unwrap_or_else(|| "resume.pdf".to_string());
 
             // Call Grok service asynchronously (don't block response)
-            let grok_url = data.grok_service_url.clone();
-            let pool_clone = pool.clone();
-            let talent_id = talent.id.clone();
-            let talent_name = talent.name.clone();
-            let talent_title = talent.title.clone();
-            let talent_skills = talent.skills.clone();
-            let collection_id = talent.collection_id.clone();
-            let old_resume_document_id = talent.resume_document_id.clone();
-            let pdf_bytes_for_upload = pdf_bytes.clone();
-            let filename_for_upload = filename.clone();
-
-            // Clone job info for scoring
-            let job_for_scoring = job.clone().unwrap();
-
-            tokio::spawn(async move {
-                info!("======================================================================");
-                info!("GROK ANALYSIS STARTING");
-                info!("======================================================================");
-                info!("Talent ID: {}", talent_id);
-                info!("Grok service URL: {}", grok_url);
-                info!("Resume filename: {}", filename);
-                info!("PDF size: {} bytes", pdf_bytes.len());
-
-                let mut extracted_urls: Option<ProfileUrls> = None;
-                let client = GrokClient::new(&grok_url);
-
-                match client.analyze_resume(&talent_info, &pdf_bytes, &filename).await {
-                    Ok(response) => {
-                        info!("======================================================================");
-                        info!("GROK RESPONSE RECEIVED - Success: {}", response.success);
-                        if let Some(ref err) = response.error {
-                            error!("Grok error: {}", err);
-                        }
-
-                        if response.success {
-                            if let Some(result) = response.result {
-                                info!("======================================================================");
-                                info!("EXTRACTED INFORMATION FROM RESUME");
-                                info!("======================================================================");
-                                info!("Talent ID: {}", result.talent_id);
-                                info!("WORK EXPERIENCES ({} found):", result.experiences.len());
-                                for (i, exp) in result.experiences.iter().enumerate() {
-                                    info!("  {}. {} at {}", i + 1, exp.role, exp.company);
-                                    if let Some(ref duration) = exp.duration {
-                                        info!("     Duration: {}", duration);
-                                    }
-                                    info!("     Summary: {}", exp.summary);
-                                }
-                                info!("SOCIAL PROFILE URLS:");
-                                info!("  LinkedIn: {}", result.urls.linkedin.as_deref().unwrap_or("Not found"));
-                                info!("  X/Twitter: {}", result.urls.x.as_deref().unwrap_or("Not found"));
-                                info!("  GitHub: {}", result.urls.github.as_deref().unwrap_or("Not found"));
-                                info!("  GitLab: {}", result.urls.gitlab.as_deref().unwrap_or("Not found"));
-                                info!("======================================================================");
-
-                                extracted_urls = Some(result.urls.clone());
-
-                                // Serialize experiences to JSON
-                                let experiences_json = serde_json::to_string(&result.experiences)
-                                    .ok();
-
-                                // Update talent with extracted info
-                                match crate::database::update_talent_resume_fields(
-                                    &pool_clone,
-                                    talent_id.clone(),
-                                    experiences_json,
-                                    result.urls.linkedin,
-                                    result.urls.x,
-                                    result.urls.github,
-                                    result.urls.gitlab,
-                                ).await {
-                                    Ok(_updated) => {
-                                        info!("SUCCESS: Updated talent {} with resume data", talent_id);
-                                    },
-                                    Err(e) => {
-                                        error!("FAILED to update talent {}: {}", talent_id, e);
-                                    }
-                                }
-                            } else {
-                                info!("No result in response (result is None)");
-                            }
-                        } else {
-                            error!("Grok analysis failed: {:?}", response.error);
-                        }
-                    }
-                    Err(e) => {
-                        error!("======================================================================");
-                        error!("GROK SERVICE ERROR: {}", e);
-                        error!("======================================================================");
-                    }
-                }
-
-                // Get or create collection for document upload
-                let final_collection_id = if let Some(coll_id) = collection_id {
-                    Some(coll_id)
-                } else {
-                    // No collection exists, create one now
-                    warn!("======================================================================");
-                    warn!("DOCUMENT UPLOAD: No collection_id for talent {}, creating collection now", talent_id);
-                    warn!("======================================================================");
-
-                    match client.create_collection(&talent_id, &talent_name).await {
-                        Ok(response) => {
-                            if response.success {
-                                if let Some(collection) = response.collection {
-                                    info!("COLLECTION CREATED: {}", collection.collection_id);
-
-                                    // Update talent with collection_id
-                                    match crate::database::update_talent_collection_id(
-                                        &pool_clone,
-                                        talent_id.clone(),
-                                        collection.collection_id.clone(),
-                                    ).await {
-                                        Ok(_) => {
-                                            info!("SUCCESS: Updated talent {} with collection_id {}", talent_id, collection.collection_id);
-                                        },
-                                        Err(e) => {
-                                            error!("FAILED to update talent {} with collection_id: {}", talent_id, e);
-                                        }
-                                    }
-
-                                    Some(collection.collection_id)
-                                } else {
-                                    error!("COLLECTION CREATION: No collection in response");
-                                    None
-                                }
-                            } else {
-                                error!("COLLECTION CREATION: Failed - {:?}", response.error);
-                                None
-                            }
-                        }
-                        Err(e) => {
-                            error!("COLLECTION CREATION ERROR: {}", e);
-                            None
-                        }
-                    }
-                };
-
-                // Upload resume to collection
-                if let Some(coll_id) = final_collection_id {
-                    info!("======================================================================");
-                    info!("DOCUMENT UPLOAD: Uploading resume to collection");
-                    info!("======================================================================");
-                    info!("Collection ID: {}", coll_id);
-                    info!("Filename: {}", filename_for_upload);
-                    if let Some(ref old_doc_id) = old_resume_document_id {
-                        info!("Replacing old document: {}", old_doc_id);
-                    }
-
-                    match client.upload_document(
-                        &coll_id,
-                        &filename_for_upload,
-                        &pdf_bytes_for_upload,
-                        old_resume_document_id.as_deref(),
-                    ).await {
-                        Ok(doc_response) => {
-                            if doc_response.success {
-                                if let Some(doc) = doc_response.document {
-                                    info!("======================================================================");
-                                    info!("DOCUMENT UPLOAD: Success!");
-                                    info!("Document ID: {}", doc.document_id);
-                                    info!("======================================================================");
-
-                                    // Update talent with new document_id
-                                    match crate::database::update_talent_resume_document_id(
-                                        &pool_clone,
-                                        talent_id.clone(),
-                                        Some(doc.document_id.clone()),
-                                    ).await {
-                                        Ok(_) => {
-                                            info!("SUCCESS: Updated talent {} with document_id {}", talent_id, doc.document_id);
-                                        },
-                                        Err(e) => {
-                                            error!("FAILED to update talent {} with document_id: {}", talent_id, e);
-                                        }
-                                    }
-                                }
-                            } else {
-                                error!("DOCUMENT UPLOAD: Failed - {:?}", doc_response.error);
-                            }
-                        }
-                        Err(e) => {
-                            error!("======================================================================");
-                            error!("DOCUMENT UPLOAD ERROR: {}", e);
-                            error!("======================================================================");
-                        }
-                    }
-
-                    // Analyze social media if we have URLs
-                    if let Some(urls) = extracted_urls {
-                        info!("======================================================================");
-                        info!("SOCIAL MEDIA ANALYSIS: Starting");
-                        info!("======================================================================");
-
-                        let social_request = SocialMediaAnalysisRequest {
-                            talent_id: talent_id.clone(),
-                            collection_id: Some(coll_id.clone()),
-                            name: talent_name.clone(),
-                            email: Some(talent_info.email.clone()),
-                            social_urls: SocialMediaInput {
-                                linkedin: urls.linkedin,
-                                x: urls.x,
-                                github: urls.github,
-                                gitlab: urls.gitlab,
-                                stackoverflow: None,
-                            },
-                            platforms_to_search: vec!["X".to_string(), "GitHub".to_string(), "LinkedIn".to_string()],
-                        };
-
-                        match client.analyze_social_media(&social_request).await {
-                            Ok(response) => {
-                                if response.success {
-                                    info!("SOCIAL MEDIA ANALYSIS: Success!");
-                                    if let Some(result) = response.result {
-                                        // Update talent with social analysis
-                                        let analysis_json = serde_json::to_string(&result).ok();
-
-                                        // We need to extract x_handle if present
-                                        let x_handle = result.get("x_handle").and_then(|v| v.as_str()).map(|s| s.to_string());
-
-                                        match crate::database::update_talent_social_analysis(
-                                            &pool_clone,
-                                            talent_id.clone(),
-                                            analysis_json,
-                                            x_handle,
-                                        ).await {
-                                            Ok(_) => info!("SUCCESS: Updated talent {} with social analysis", talent_id),
-                                            Err(e) => error!("FAILED to update talent {} with social analysis: {}", talent_id, e),
-                                        }
-                                    }
-                                } else {
-                                    error!("SOCIAL MEDIA ANALYSIS: Failed - {:?}", response.error);
-                                }
-                            }
-                            Err(e) => error!("SOCIAL MEDIA ANALYSIS ERROR: {}", e),
-                        }
-                    }
-
-                    // Score candidate against the job using collection data
-                    info!("======================================================================");
-                    info!("CANDIDATE SCORING: Starting");
-                    info!("======================================================================");
-
-                    let scoring_request = CandidateScoringRequest {
-                        talent_id: talent_id.clone(),
-                        collection_id: coll_id.clone(),
-                        job: JobInfoForScoring {
-                            id: job_for_scoring.id.clone(),
-                            title: job_for_scoring.title.clone(),
-                            description: job_for_scoring.description.clone(),
-                            company_name: job_for_scoring.company_name.clone(),
-                            skills_required: job_for_scoring.skills_required.clone(),
-                            experience_level: job_for_scoring.experience_level.clone(),
-                            location: job_for_scoring.location.clone(),
-                            location_type: job_for_scoring.location_type.clone(),
-                        },
-                        candidate_name: talent_name.clone(),
-                        candidate_title: talent_title.clone(),
-                        candidate_skills: talent_skills.clone(),
-                    };
-
-                    match client.score_candidate(&scoring_request).await {
-                        Ok(response) => {
-                            if response.success {
-                                info!("CANDIDATE SCORING: Success!");
-                                if let Some(result) = response.result {
-                                    info!("Score: {}", result.overall_score);
-                                    info!("Recommendation: {}", result.recommendation);
-                                    info!("Summary: {}", result.summary);
-
-                                    // Store the scoring result as JSON in the talent record
-                                    let scoring_json = serde_json::to_string(&result).ok();
-
-                                    match crate::database::update_talent_candidate_score(
-                                        &pool_clone,
-                                        talent_id.clone(),
-                                        result.overall_score,
-                                        scoring_json,
-                                    ).await {
-                                        Ok(_) => info!("SUCCESS: Updated talent {} with candidate score", talent_id),
-                                        Err(e) => error!("FAILED to update talent {} with candidate score: {}", talent_id, e),
-                                    }
-                                }
-                            } else {
-                                error!("CANDIDATE SCORING: Failed - {:?}", response.error);
-                            }
-                        }
-                        Err(e) => error!("CANDIDATE SCORING ERROR: {}", e),
-                    }
-                } else {
-                    error!("DOCUMENT UPLOAD: Skipped - no collection available");
-                }
-            });
+            tokio::spawn(resume_pipeline::run(
+                pool.clone(),
+                data.events.clone(),
+                data.grok_service_url.clone(),
+                talent,
+                Some(job),
+                pdf_bytes,
+                filename,
+            ));
         }
     }
 
+    audit::add_targets([inserted.id.clone(), inserted.talent_id.clone(), inserted.job_id.clone()]);
+
     // Return response without the full resume data
     let response = ApplicationResponse {
         id: inserted.id,
@@ -389,30 +99,119 @@ pub async fn create_application(
 pub async fn get_application(
     data: web::Data<AppState>,
     path: web::Path<String>,
-) -> ActixResult<HttpResponse> {
+) -> AppResult<HttpResponse> {
     let id = path.into_inner();
     let pool = &data.db_pool;
 
-    match crate::database::get_application_by_id(pool, id).await {
-        Ok(Some(application)) => {
-            let response = ApplicationResponse {
-                id: application.id,
-                talent_id: application.talent_id,
-                job_id: application.job_id,
-                has_resume: application.resume_data.is_some(),
-                resume_filename: application.resume_filename,
-                cover_letter: application.cover_letter,
-                status: application.status,
-                created_at: application.created_at,
-            };
-            Ok(HttpResponse::Ok().json(response))
-        }
-        Ok(None) => Ok(HttpResponse::NotFound().json(ApiError {
-            message: "Application not found".to_string(),
-            code: 404,
-        })),
-        Err(e) => Err(actix_web::error::ErrorInternalServerError(e)),
+    let application = crate::database::get_application_by_id(pool, id).await?
+        .ok_or_else(|| AppError::not_found("Application not found"))?;
+
+    let response = ApplicationResponse {
+        id: application.id,
+        talent_id: application.talent_id,
+        job_id: application.job_id,
+        has_resume: application.resume_data.is_some(),
+        resume_filename: application.resume_filename,
+        cover_letter: application.cover_letter,
+        status: application.status,
+        created_at: application.created_at,
+    };
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Record a recruiter's status change as implicit preferences between the
+/// job's applicants
+async fn record_status_preferences(
+    pool: &crate::database::Pool,
+    application: &Application,
+    previous_status: &str,
+    author: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    let Some(job) = crate::database::get_job_by_id(pool, application.job_id.clone()).await? else {
+        return Ok(());
+    };
+    let others: Vec<(String, String)> = crate::database::get_applications_by_job(pool, job.id.clone()).await?
+        .into_iter()
+        .filter(|other| other.talent_id != application.talent_id)
+        .map(|other| (other.talent_id, other.status))
+        .collect();
+    let mut talent_ids: Vec<String> = others.iter().map(|(id, _)| id.clone()).collect();
+    talent_ids.push(application.talent_id.clone());
+
+    let job_text = format!("{} at {}", job.title, job.company_name);
+    let talent_names = super::reorder::talent_names(pool, &talent_ids).await?;
+    let ctx = PreferenceContext {
+        job_id: &job.id,
+        job_text: &job_text,
+        talent_names: &talent_names,
+        reorder_event_id: None,
+        author,
+    };
+    let preferences = reorder::derive_status_change_preferences(
+        &application.talent_id,
+        previous_status,
+        &application.status,
+        &others,
+        &ctx,
+    );
+    if !preferences.is_empty() {
+        crate::database::create_pairwise_preferences(pool, &preferences).await?;
     }
+    Ok(())
+}
+
+/// Statuses a recruiter can move an application to; candidates withdraw
+/// through the portal
+const RECRUITER_STATUSES: [&str; 4] = ["pending", "reviewed", "accepted", "rejected"];
+
+#[api_v2_operation]
+#[paperclip::actix::put("/api/v1/applications/{id}/status", summary = "Change an application's status")]
+pub async fn update_application_status(
+    req: HttpRequest,
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+    json: web::Json<UpdateApplicationStatusRequest>,
+) -> AppResult<HttpResponse> {
+    let pool = &data.db_pool;
+    let status = json.status.trim().to_lowercase();
+    if !RECRUITER_STATUSES.contains(&status.as_str()) {
+        return Err(AppError::validation(format!("status must be one of {}", RECRUITER_STATUSES.join(", "))));
+    }
+
+    let application = crate::database::get_application_by_id(pool, path.into_inner()).await?
+        .ok_or_else(|| AppError::not_found("Application not found"))?;
+    if application.status == "withdrawn" {
+        return Err(AppError::conflict("Application was withdrawn by the candidate"));
+    }
+
+    let updated = if application.status == status {
+        application.clone()
+    } else {
+        let updated = crate::database::update_application_status(pool, &application.id, &status).await?
+            .ok_or_else(|| AppError::not_found("Application not found"))?;
+        info!("Application {} moved from {} to {}", updated.id, application.status, updated.status);
+        let author = super::reorder::preference_author(&req, &data);
+        if let Err(e) = record_status_preferences(&data.db_pool, &updated, &application.status, author.as_deref()).await {
+            log::error!("Failed to record preferences for application {}: {}", updated.id, e);
+        }
+        data.events.publish(
+            EventKind::ApplicationStatusChanged,
+            events::application_status_changed(&updated, &application.status),
+        ).await;
+        updated
+    };
+
+    audit::add_targets([updated.id.clone(), updated.talent_id.clone(), updated.job_id.clone()]);
+    Ok(HttpResponse::Ok().json(ApplicationResponse {
+        id: updated.id,
+        talent_id: updated.talent_id,
+        job_id: updated.job_id,
+        has_resume: updated.resume_data.is_some(),
+        resume_filename: updated.resume_filename,
+        cover_letter: updated.cover_letter,
+        status: updated.status,
+        created_at: updated.created_at,
+    }))
 }
 
 #[api_v2_operation]
@@ -420,12 +219,11 @@ pub async fn get_application(
 pub async fn get_applications_by_talent(
     data: web::Data<AppState>,
     path: web::Path<String>,
-) -> ActixResult<HttpResponse> {
+) -> AppResult<HttpResponse> {
     let talent_id = path.into_inner();
     let pool = &data.db_pool;
 
-    let applications = crate::database::get_applications_by_talent(pool, talent_id).await
-        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let applications = crate::database::get_applications_by_talent(pool, talent_id).await?;
 
     let responses: Vec<ApplicationResponse> = applications
         .into_iter()
@@ -449,12 +247,11 @@ pub async fn get_applications_by_talent(
 pub async fn get_applications_by_job(
     data: web::Data<AppState>,
     path: web::Path<String>,
-) -> ActixResult<HttpResponse> {
+) -> AppResult<HttpResponse> {
     let job_id = path.into_inner();
     let pool = &data.db_pool;
 
-    let applications = crate::database::get_applications_by_job(pool, job_id).await
-        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let applications = crate::database::get_applications_by_job(pool, job_id).await?;
 
     let responses: Vec<ApplicationResponse> = applications
         .into_iter()
@@ -478,45 +275,43 @@ pub async fn get_applications_by_job(
 pub async fn get_application_resume(
     data: web::Data<AppState>,
     path: web::Path<String>,
-) -> ActixResult<HttpResponse> {
+) -> AppResult<HttpResponse> {
     let id = path.into_inner();
     let pool = &data.db_pool;
 
-    match crate::database::get_application_by_id(pool, id).await {
-        Ok(Some(application)) => {
-            if let Some(resume_data) = application.resume_data {
-                let content_type = application.resume_content_type
-                    .unwrap_or_else(|| "application/octet-stream".to_string());
-                let filename = application.resume_filename
-                    .unwrap_or_else(|| "resume".to_string());
-
-                // Decode base64
-                use base64::{Engine as _, engine::general_purpose::STANDARD};
-                match STANDARD.decode(&resume_data) {
-                    Ok(bytes) => {
-                        Ok(HttpResponse::Ok()
-                            .content_type(content_type)
-                            .insert_header(("Content-Disposition", format!("inline; filename=\"{}\"", filename)))
-                            .body(bytes))
-                    }
-                    Err(_) => Ok(HttpResponse::InternalServerError().json(ApiError {
-                        message: "Failed to decode resume".to_string(),
-                        code: 500,
-                    })),
-                }
-            } else {
-                Ok(HttpResponse::NotFound().json(ApiError {
-                    message: "No resume attached to this application".to_string(),
-                    code: 404,
-                }))
+    let application = crate::database::get_application_by_id(pool, id).await?
+        .ok_or_else(|| AppError::not_found("Application not found"))?;
+
+    let resume_data = application.resume_data
+        .ok_or_else(|| AppError::not_found("No resume attached to this application"))?;
+    let content_type = application.resume_content_type
+        .unwrap_or_else(|| "application/octet-stream".to_string());
+    let filename = application.resume_filename
+        .unwrap_or_else(|| "resume".to_string());
+
+    // Decode base64
+    use base64::{Engine as _, engine::general_purpose::STANDARD};
+    let bytes = STANDARD.decode(&resume_data)
+        .map_err(|e| AppError::internal(format!("Failed to decode resume: {}", e)))?;
+
+    Ok(HttpResponse::Ok()
+        .content_type(content_type)
+        .insert_header(("Content-Disposition", format!("inline; filename=\"{}\"", filename)))
+        .body(bytes))
+}
+
+/// Reconcile the candidate orders of the jobs the deleted applications were for
+async fn reconcile_application_jobs(data: &AppState, ids: &[String]) -> AppResult<()> {
+    let mut job_ids = Vec::new();
+    for id in ids {
+        if let Some(application) = crate::database::get_deleted_application(&data.db_pool, id).await? {
+            if !job_ids.contains(&application.job_id) {
+                job_ids.push(application.job_id);
             }
         }
-        Ok(None) => Ok(HttpResponse::NotFound().json(ApiError {
-            message: "Application not found".to_string(),
-            code: 404,
-        })),
-        Err(e) => Err(actix_web::error::ErrorInternalServerError(e)),
     }
+    job_order::reconcile_jobs(&data.db_pool, &data.events, job_ids).await;
+    Ok(())
 }
 
 #[api_v2_operation]
@@ -524,29 +319,20 @@ pub async fn get_application_resume(
 pub async fn delete_application(
     data: web::Data<AppState>,
     path: web::Path<String>,
-) -> ActixResult<HttpResponse> {
+) -> AppResult<HttpResponse> {
     let id = path.into_inner();
     let pool = &data.db_pool;
 
     info!("Deleting application with id: {}", id);
 
-    match crate::database::delete_application(pool, id.clone()).await {
-        Ok(true) => {
-            info!("Successfully deleted application: {}", id);
-            Ok(HttpResponse::NoContent().finish())
-        }
-        Ok(false) => {
-            warn!("Application not found for deletion: {}", id);
-            Ok(HttpResponse::NotFound().json(ApiError {
-                message: "Application not found".to_string(),
-                code: 404,
-            }))
-        }
-        Err(e) => {
-            error!("Failed to delete application {}: {}", id, e);
-            Err(actix_web::error::ErrorInternalServerError(e))
-        }
+    let deleted = crate::database::delete_application(pool, id.clone()).await?;
+    if !deleted {
+        return Err(AppError::not_found("Application not found"));
     }
+    reconcile_application_jobs(&data, std::slice::from_ref(&id)).await?;
+
+    info!("Successfully deleted application: {}", id);
+    Ok(HttpResponse::NoContent().finish())
 }
 
 #[api_v2_operation]
@@ -554,24 +340,50 @@ pub async fn delete_application(
 pub async fn delete_applications_bulk(
     data: web::Data<AppState>,
     json: web::Json<BulkDeleteRequest>,
-) -> ActixResult<HttpResponse> {
+) -> AppResult<HttpResponse> {
     let pool = &data.db_pool;
     let ids = &json.ids;
+    audit::add_targets(ids.iter().cloned());
 
     if ids.is_empty() {
-        return Ok(HttpResponse::BadRequest().json(ApiError {
-            message: "No application IDs provided".to_string(),
-            code: 400,
-        }));
+        return Err(AppError::validation("No application IDs provided"));
     }
 
     info!("Bulk deleting {} applications", ids.len());
 
-    let deleted_count = crate::database::delete_applications_bulk(pool, ids).await
-        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let deleted_count = crate::database::delete_applications_bulk(pool, ids).await?;
+    reconcile_application_jobs(&data, ids).await?;
 
     Ok(HttpResponse::Ok().json(BulkDeleteResponse {
         deleted_count,
         total_requested: ids.len(),
     }))
 }
+
+#[api_v2_operation]
+#[paperclip::actix::post("/api/v1/applications/{id}/restore", summary = "Restore a deleted application")]
+pub async fn restore_application(
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+) -> AppResult<HttpResponse> {
+    let id = path.into_inner();
+    let pool = &data.db_pool;
+
+    crate::database::get_deleted_application(pool, &id).await?
+        .ok_or_else(|| AppError::not_found("Application not found in trash"))?;
+    let restored = crate::database::restore_application(pool, &id).await?
+        .ok_or_else(|| AppError::conflict("Restore the application's talent and job first"))?;
+    info!("Restored application {}", id);
+    job_order::reconcile_jobs(pool, &data.events, [restored.job_id.clone()]).await;
+
+    Ok(HttpResponse::Ok().json(ApplicationResponse {
+        id: restored.id,
+        talent_id: restored.talent_id,
+        job_id: restored.job_id,
+        has_resume: restored.resume_data.is_some(),
+        resume_filename: restored.resume_filename,
+        cover_letter: restored.cover_letter,
+        status: restored.status,
+        created_at: restored.created_at,
+    }))
+}
@@ -0,0 +1,156 @@
+//! Audit middleware and audit log API
+//!
+//! Records every mutating request and sensitive read in the append-only
+//! `audit_log` table. Target IDs come from the matched path parameters;
+//! handlers add IDs that only appear in the request body (bulk deletes,
+//! reorders) through `add_targets`.
+
+use std::cell::RefCell;
+
+use actix_web::{
+    body::MessageBody,
+    dev::{ServiceRequest, ServiceResponse},
+    http::header::{self, HeaderMap},
+    middleware::Next,
+    web, Error, HttpResponse,
+};
+use chrono::Utc;
+use log::error;
+use paperclip::actix::api_v2_operation;
+use uuid::Uuid;
+
+use crate::audit::{self, ACTOR_HEADER, ANONYMOUS_ACTOR};
+use crate::error::{AppError, AppResult};
+use crate::models::{AuditLogEntry, AuditLogQuery};
+use super::request_id;
+use super::server::AppState;
+
+tokio::task_local! {
+    static TARGETS: RefCell<Vec<String>>;
+}
+
+/// Record additional target IDs for the request currently being handled
+pub fn add_targets<I, S>(ids: I)
+where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+{
+    let _ = TARGETS.try_with(|targets| {
+        targets.borrow_mut().extend(ids.into_iter().map(Into::into));
+    });
+}
+
+/// Who is making the request: the `X-Actor` header, else the signed-in
+/// portal candidate, else anonymous
+pub fn actor(headers: &HeaderMap, state: &AppState) -> String {
+    if let Some(actor) = headers
+        .get(ACTOR_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+        .filter(|v| !v.is_empty() && v.len() <= 128)
+    {
+        return actor.to_string();
+    }
+
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .and_then(|token| crate::portal::verify_session(&state.portal_secret, token.trim(), Utc::now()))
+        .map(|claims| format!("candidate:{}", claims.talent_id))
+        .unwrap_or_else(|| ANONYMOUS_ACTOR.to_string())
+}
+
+pub async fn middleware(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let route = req.match_pattern();
+    let state = req.app_data::<web::Data<AppState>>().cloned();
+    let (Some(route), Some(state)) = (route, state) else {
+        return next.call(req).await;
+    };
+    if !audit::is_audited(req.method(), &route) {
+        return next.call(req).await;
+    }
+
+    let actor = actor(req.headers(), &state);
+    let method = req.method().to_string();
+    let path = req.path().to_string();
+    let ip = req.connection_info().realip_remote_addr().map(str::to_string);
+
+    let (res, body_targets) = TARGETS
+        .scope(RefCell::new(Vec::new()), async {
+            let res = next.call(req).await;
+            (res, TARGETS.with(|t| t.take()))
+        })
+        .await;
+    let res = res?;
+
+    let mut target_ids: Vec<String> = res
+        .request()
+        .match_info()
+        .iter()
+        .map(|(_, value)| value.to_string())
+        .collect();
+    for id in body_targets {
+        if !target_ids.contains(&id) {
+            target_ids.push(id);
+        }
+    }
+
+    let status = res.status().as_u16();
+    let error_code = res
+        .response()
+        .error()
+        .and_then(|e| e.as_error::<AppError>())
+        .map(|e| e.error_code().to_string());
+
+    let entry = AuditLogEntry {
+        id: Uuid::new_v4().to_string(),
+        occurred_at: Utc::now().to_rfc3339(),
+        request_id: request_id::current(),
+        actor,
+        method,
+        route,
+        path,
+        target_ids: serde_json::to_string(&target_ids).unwrap_or_else(|_| "[]".to_string()),
+        status: i64::from(status),
+        outcome: audit::outcome(status).to_string(),
+        error_code,
+        ip,
+    };
+    if let Err(e) = crate::database::insert_audit_log_entry(&state.db_pool, &entry).await {
+        error!("[audit] Failed to record {} {}: {}", entry.method, entry.path, e);
+    }
+
+    Ok(res)
+}
+
+#[api_v2_operation]
+#[paperclip::actix::get("/api/v1/audit", summary = "Search the audit log (JSON or CSV)")]
+pub async fn get_audit_log(
+    data: web::Data<AppState>,
+    query: web::Query<AuditLogQuery>,
+) -> AppResult<HttpResponse> {
+    let limit = query.limit.unwrap_or(audit::DEFAULT_LIMIT);
+    if !(1..=audit::MAX_LIMIT).contains(&limit) {
+        return Err(AppError::validation(format!("limit must be between 1 and {}", audit::MAX_LIMIT)));
+    }
+    let offset = query.offset.unwrap_or(0).max(0);
+
+    let entries = crate::database::query_audit_log(&data.db_pool, &query, limit, offset).await?;
+
+    match query.format.as_deref().unwrap_or("json") {
+        "json" => Ok(HttpResponse::Ok().json(entries)),
+        "csv" => {
+            let body = audit::to_csv(&entries)
+                .map_err(|e| AppError::internal(format!("Failed to render audit CSV: {}", e)))?;
+            Ok(HttpResponse::Ok()
+                .content_type("text/csv; charset=utf-8")
+                .insert_header(("Content-Disposition", "attachment; filename=\"audit-log.csv\""))
+                .body(body))
+        }
+        other => Err(AppError::validation(format!("Unsupported format '{}'; use json or csv", other))),
+    }
+}
@@ -0,0 +1,120 @@
+use actix_web::{web, HttpResponse};
+use chrono::Utc;
+use paperclip::actix::api_v2_operation;
+
+use crate::error::{AppError, AppResult};
+use crate::evaluation::{self, RunOptions};
+use crate::models::{CreateEvaluationRequest, EvaluationRunDetail, EvaluationRunQuery, JobEvaluation, JobEvaluationQuery};
+use crate::preference_dataset;
+use super::server::AppState;
+
+const DEFAULT_RUN_LIMIT: i64 = 100;
+const MAX_RUN_LIMIT: i64 = 1000;
+const MAX_K: usize = 1000;
+
+fn valid_source(source: Option<String>) -> AppResult<String> {
+    let source = source.map(|s| s.trim().to_lowercase()).unwrap_or_else(|| evaluation::SOURCE_JOB_MATCHES.to_string());
+    if !evaluation::SOURCES.contains(&source.as_str()) {
+        return Err(AppError::validation(format!("source must be one of {}", evaluation::SOURCES.join(", "))));
+    }
+    Ok(source)
+}
+
+fn valid_k(k: Option<usize>) -> AppResult<usize> {
+    let k = k.unwrap_or(evaluation::DEFAULT_K);
+    if !(1..=MAX_K).contains(&k) {
+        return Err(AppError::validation(format!("k must be between 1 and {}", MAX_K)));
+    }
+    Ok(k)
+}
+
+fn detail(run: crate::models::EvaluationRun) -> EvaluationRunDetail {
+    let jobs: Vec<JobEvaluation> = serde_json::from_str(&run.job_results).unwrap_or_default();
+    EvaluationRunDetail { run, jobs }
+}
+
+/// Evaluate a ranking source against recruiter preferences
+///
+/// Scores every job with preferences (or the given `job_ids`) and stores the
+/// run, so runs with different labels can be compared over time. `split`
+/// restricts the run to jobs the preference export assigns to `train` or
+/// `validation` with the same `seed` and `validation_fraction`, e.g. to
+/// score only preferences held out from training.
+#[api_v2_operation]
+#[paperclip::actix::post("/api/v1/evaluations", summary = "Evaluate a ranking source against recruiter preferences")]
+pub async fn create_evaluation(
+    data: web::Data<AppState>,
+    json: web::Json<CreateEvaluationRequest>,
+) -> AppResult<HttpResponse> {
+    let request = json.into_inner();
+    let source = valid_source(request.source)?;
+    let k = valid_k(request.k)?;
+    let split = request.split.map(|s| s.trim().to_lowercase()).unwrap_or_else(|| evaluation::SPLIT_ALL.to_string());
+    if ![evaluation::SPLIT_ALL, preference_dataset::SPLIT_TRAIN, preference_dataset::SPLIT_VALIDATION].contains(&split.as_str()) {
+        return Err(AppError::validation("split must be all, train or validation"));
+    }
+    let validation_fraction = request.validation_fraction.unwrap_or(preference_dataset::DEFAULT_VALIDATION_FRACTION);
+    if !(0.0..=1.0).contains(&validation_fraction) {
+        return Err(AppError::validation("validation_fraction must be between 0 and 1"));
+    }
+    let label = request.label.map(|l| l.trim().to_string()).filter(|l| !l.is_empty());
+
+    let run = evaluation::run(&data.db_pool, RunOptions {
+        source,
+        label,
+        job_ids: request.job_ids.map(evaluation::dedup_job_ids),
+        split,
+        validation_fraction,
+        seed: request.seed.unwrap_or_default(),
+        k,
+    }).await?;
+    Ok(HttpResponse::Created().json(detail(run)))
+}
+
+/// List evaluation runs, oldest first, for charting quality over time
+#[api_v2_operation]
+#[paperclip::actix::get("/api/v1/evaluations", summary = "List evaluation runs over time")]
+pub async fn get_evaluations(
+    data: web::Data<AppState>,
+    query: web::Query<EvaluationRunQuery>,
+) -> AppResult<HttpResponse> {
+    let mut query = query.into_inner();
+    if let Some(source) = query.source.take() {
+        query.source = Some(valid_source(Some(source))?);
+    }
+    let limit = query.limit.unwrap_or(DEFAULT_RUN_LIMIT).clamp(1, MAX_RUN_LIMIT);
+    let runs = crate::database::get_evaluation_runs(&data.db_pool, &query, limit).await?;
+    Ok(HttpResponse::Ok().json(runs))
+}
+
+#[api_v2_operation]
+#[paperclip::actix::get("/api/v1/evaluations/{id}", summary = "Get an evaluation run with its per-job results")]
+pub async fn get_evaluation(
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+) -> AppResult<HttpResponse> {
+    let run = crate::database::get_evaluation_run(&data.db_pool, &path.into_inner())
+        .await?
+        .ok_or_else(|| AppError::not_found("Evaluation run not found"))?;
+    Ok(HttpResponse::Ok().json(detail(run)))
+}
+
+/// Evaluate a ranking source on one job without storing the result
+#[api_v2_operation]
+#[paperclip::actix::get("/api/v1/jobs/{job_id}/evaluation", summary = "Evaluate a ranking source against one job's preferences")]
+pub async fn get_job_evaluation(
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+    query: web::Query<JobEvaluationQuery>,
+) -> AppResult<HttpResponse> {
+    let query = query.into_inner();
+    let source = valid_source(query.source)?;
+    let k = valid_k(query.k)?;
+    let job_id = path.into_inner();
+    let job = crate::database::get_job_by_id(&data.db_pool, job_id.clone())
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Job not found: {}", job_id)))?;
+
+    let evaluation = evaluation::evaluate_job(&data.db_pool, &source, &job, k, Utc::now()).await?;
+    Ok(HttpResponse::Ok().json(evaluation))
+}
@@ -0,0 +1,163 @@
+//! Live event stream
+//!
+//! `GET /api/v1/events` is a server-sent events stream of pipeline events,
+//! meant for `EventSource` in the UI. Each event's SSE `id` is its place in
+//! the event history: a client that reconnects with `Last-Event-ID` first
+//! receives the matching events it missed, then live ones. A subscriber that
+//! falls too far behind is disconnected and catches up the same way. When not
+//! all of the events it missed can be replayed, the stream starts with a
+//! `stream.reset` event so the client knows to reload its state.
+
+use std::time::Duration;
+
+use actix_web::{http::header, web, HttpRequest, HttpResponse};
+use futures_util::stream;
+use log::{error, info};
+use paperclip::actix::api_v2_operation;
+use tokio::sync::{broadcast, mpsc};
+
+use crate::database::Pool;
+use crate::error::{AppError, AppResult};
+use crate::events::{self, EventKind, StreamFilter};
+use crate::models::{EventStreamQuery, StoredEvent};
+use super::server::AppState;
+
+/// Frames buffered between the subscription and the response
+const CHANNEL_CAPACITY: usize = 64;
+/// Comment sent on an idle stream so proxies keep it open
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+/// Most missed events replayed to a reconnecting client
+const MAX_REPLAY: i64 = 1000;
+/// Reconnection delay suggested to clients, in milliseconds
+const RECONNECT_MS: u64 = 3000;
+
+const LAST_EVENT_ID: &str = "Last-Event-ID";
+const RESET_EVENT: &str = "stream.reset";
+
+fn stream_filter(query: &EventStreamQuery) -> AppResult<StreamFilter> {
+    let mut kinds = Vec::new();
+    let mut unknown = Vec::new();
+    for name in query.types.as_deref().unwrap_or_default().split(',').map(str::trim).filter(|n| !n.is_empty()) {
+        match EventKind::parse(name) {
+            Some(kind) => kinds.push(kind),
+            None => unknown.push(name),
+        }
+    }
+    if !unknown.is_empty() {
+        return Err(AppError::validation(format!(
+            "Unknown event types: {}; use {}",
+            unknown.join(", "),
+            EventKind::ALL.map(|k| k.as_str()).join(", ")
+        )));
+    }
+    let id = |value: &Option<String>| value.as_deref().map(str::trim).filter(|v| !v.is_empty()).map(str::to_string);
+    Ok(StreamFilter { job_id: id(&query.job_id), talent_id: id(&query.talent_id), kinds })
+}
+
+/// Where to resume from: the `Last-Event-ID` header, which browsers send on
+/// reconnect, or else the `last_event_id` parameter
+fn last_event_id(req: &HttpRequest, query: &EventStreamQuery) -> AppResult<Option<i64>> {
+    match req.headers().get(LAST_EVENT_ID) {
+        Some(value) => value
+            .to_str()
+            .ok()
+            .and_then(|v| v.trim().parse().ok())
+            .map(Some)
+            .ok_or_else(|| AppError::validation("Last-Event-ID must be an event ID from this stream")),
+        None => Ok(query.last_event_id),
+    }
+}
+
+/// Matching events published after `last_id`, and whether that is all of
+/// them: some may have been pruned, or be beyond the replay limit
+async fn missed_events(pool: &Pool, last_id: i64, filter: &StreamFilter) -> Result<(Vec<StoredEvent>, bool), sqlx::Error> {
+    let (oldest, latest) = crate::database::get_event_history_bounds(pool).await?;
+    let pruned = latest > last_id && oldest.is_none_or(|oldest| oldest > last_id + 1);
+    let missed = crate::database::get_stored_events_after(pool, last_id, MAX_REPLAY).await?;
+    let complete = !pruned && (missed.len() as i64) < MAX_REPLAY;
+    Ok((missed.into_iter().filter(|e| filter.matches(e)).collect(), complete))
+}
+
+/// Write missed and then live events into the channel until the client goes
+/// away or falls behind
+async fn pump(
+    pool: Pool,
+    mut live: broadcast::Receiver<StoredEvent>,
+    filter: StreamFilter,
+    last_id: Option<i64>,
+    tx: mpsc::Sender<web::Bytes>,
+) {
+    let send = |frame: String| tx.send(web::Bytes::from(frame));
+    if send(format!("retry: {}\n\n", RECONNECT_MS)).await.is_err() {
+        return;
+    }
+
+    // Subscribed before reading the history, so nothing published in between
+    // is lost. Live events already replayed are skipped by sequence number
+    let mut last_sent = last_id.unwrap_or(0);
+    if let Some(last_id) = last_id {
+        let (missed, complete) = match missed_events(&pool, last_id, &filter).await {
+            Ok(missed) => missed,
+            Err(e) => {
+                error!("[events] Failed to read event history after {}: {}", last_id, e);
+                return;
+            }
+        };
+        if !complete && send(format!("event: {}\ndata: {{}}\n\n", RESET_EVENT)).await.is_err() {
+            return;
+        }
+        for event in missed {
+            last_sent = event.seq;
+            if send(events::sse_frame(&event)).await.is_err() {
+                return;
+            }
+        }
+    }
+
+    let mut keep_alive = tokio::time::interval(KEEP_ALIVE_INTERVAL);
+    keep_alive.tick().await;
+    loop {
+        let frame = tokio::select! {
+            received = live.recv() => match received {
+                Ok(event) if event.seq > last_sent && filter.matches(&event) => {
+                    last_sent = event.seq;
+                    events::sse_frame(&event)
+                }
+                Ok(_) => continue,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    info!("[events] Closing stream that fell {} events behind at {}", skipped, last_sent);
+                    return;
+                }
+                Err(broadcast::error::RecvError::Closed) => return,
+            },
+            _ = keep_alive.tick() => ": keep-alive\n\n".to_string(),
+        };
+        if send(frame).await.is_err() {
+            return;
+        }
+    }
+}
+
+#[api_v2_operation]
+#[paperclip::actix::get("/api/v1/events", summary = "Stream pipeline events as server-sent events")]
+pub async fn stream_events(
+    req: HttpRequest,
+    data: web::Data<AppState>,
+    query: web::Query<EventStreamQuery>,
+) -> AppResult<HttpResponse> {
+    let filter = stream_filter(&query)?;
+    let last_id = last_event_id(&req, &query)?;
+
+    let live = data.events.subscribe();
+    let (tx, rx) = mpsc::channel(CHANNEL_CAPACITY);
+    tokio::spawn(pump(data.db_pool.clone(), live, filter, last_id, tx));
+
+    let body = stream::unfold(rx, |mut rx| async move {
+        rx.recv().await.map(|frame| (Ok::<_, AppError>(frame), rx))
+    });
+    Ok(HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header((header::CACHE_CONTROL, "no-cache"))
+        .insert_header(("X-Accel-Buffering", "no"))
+        .streaming(body))
+}
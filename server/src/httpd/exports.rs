@@ -0,0 +1,301 @@
+//! Pipeline, talent and preference dataset export endpoints
+//!
+//! Rows are read from the database as a stream and encoded as they arrive, so
+//! large exports never sit in memory in full. An error part-way through ends
+//! the response early; the log records why.
+
+use std::collections::HashMap;
+
+use actix_web::{http::header, web, HttpResponse};
+use chrono::{DateTime, Utc};
+use futures_util::{stream, Stream, StreamExt};
+use log::{error, info};
+use paperclip::actix::api_v2_operation;
+use serde::Serialize;
+use serde_json::Value;
+use tokio::sync::mpsc;
+
+use crate::database::{decode_skills, Pool};
+use crate::error::{AppError, AppResult};
+use crate::export::{self, ExportFormat, RowEncoder};
+use crate::job_order;
+use crate::models::{ExportQuery, PairwisePreference, PreferenceExportQuery, TalentListQuery};
+use crate::preference_dataset::{self, PreferenceExample, Redaction};
+use crate::reorder;
+use super::server::AppState;
+
+/// Chunks buffered between the database reader and the response
+const CHANNEL_CAPACITY: usize = 64;
+
+type Chunk = Result<web::Bytes, AppError>;
+
+/// Encode rows from `rows` into the channel until it is exhausted or the
+/// client goes away
+async fn pump<T, S>(tx: mpsc::Sender<Chunk>, mut encoder: RowEncoder, rows: S, label: String)
+where
+    T: Serialize,
+    S: Stream<Item = Result<T, sqlx::Error>>,
+{
+    let mut rows = std::pin::pin!(rows);
+    if tx.send(Ok(encoder.header().into())).await.is_err() {
+        return;
+    }
+
+    let mut count = 0;
+    while let Some(row) = rows.next().await {
+        let chunk = match row.map(|r| serde_json::to_value(r).unwrap_or(Value::Null)) {
+            Ok(value) => Ok(encoder.row(&value).into()),
+            Err(e) => {
+                error!("[export] {} failed after {} rows: {}", label, count, e);
+                let _ = tx.send(Err(e.into())).await;
+                return;
+            }
+        };
+        if tx.send(chunk).await.is_err() {
+            info!("[export] {} cancelled by client after {} rows", label, count);
+            return;
+        }
+        count += 1;
+    }
+
+    let _ = tx.send(Ok(encoder.footer().into())).await;
+    info!("[export] {}: {} rows", label, count);
+}
+
+fn streaming_response(format: ExportFormat, filename: &str, rx: mpsc::Receiver<Chunk>) -> HttpResponse {
+    let body = stream::unfold(rx, |mut rx| async move { rx.recv().await.map(|chunk| (chunk, rx)) });
+    HttpResponse::Ok()
+        .content_type(format.content_type())
+        .insert_header((
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}.{}\"", filename, format.extension()),
+        ))
+        .streaming(body)
+}
+
+fn encoder(query: &ExportQuery, available: &[&str]) -> AppResult<(ExportFormat, RowEncoder)> {
+    let format = ExportFormat::parse(query.format.as_deref()).map_err(AppError::validation)?;
+    let columns = export::select_columns(query.columns.as_deref(), available).map_err(AppError::validation)?;
+    Ok((format, RowEncoder::new(format, columns)))
+}
+
+/// 1-based positions in the job's current recruiter order
+async fn recruiter_positions(pool: &Pool, job_id: &str) -> AppResult<HashMap<String, usize>> {
+    let Some((order, _)) = job_order::current(pool, job_id).await? else {
+        return Ok(HashMap::new());
+    };
+    Ok(order.into_iter().enumerate().map(|(i, id)| (id, i + 1)).collect())
+}
+
+#[api_v2_operation]
+#[paperclip::actix::get("/api/v1/jobs/{id}/export", summary = "Export a job's candidate pipeline")]
+pub async fn export_job_pipeline(
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+    query: web::Query<ExportQuery>,
+) -> AppResult<HttpResponse> {
+    let pool = data.db_pool.clone();
+    let job_id = path.into_inner();
+    let (format, encoder) = encoder(&query, export::PIPELINE_COLUMNS)?;
+
+    crate::database::get_job_by_id(&pool, job_id.clone())
+        .await?
+        .ok_or_else(|| AppError::not_found("Job not found"))?;
+    let positions = recruiter_positions(&pool, &job_id).await?;
+
+    let (tx, rx) = mpsc::channel(CHANNEL_CAPACITY);
+    let filename = format!("job-{}-pipeline", job_id);
+    tokio::spawn(async move {
+        let rows = crate::database::stream_job_pipeline(&pool, &job_id).map(|row| {
+            row.map(|mut row| {
+                row.recruiter_position = positions.get(&row.talent_id).copied();
+                row.skills = decode_skills(row.skills);
+                row
+            })
+        });
+        pump(tx, encoder, rows, format!("Pipeline for job {}", job_id)).await;
+    });
+
+    Ok(streaming_response(format, &filename, rx))
+}
+
+#[api_v2_operation]
+#[paperclip::actix::get("/api/v1/talents/export", summary = "Export talents matching the list filters")]
+pub async fn export_talents(
+    data: web::Data<AppState>,
+    filter: web::Query<TalentListQuery>,
+    query: web::Query<ExportQuery>,
+) -> AppResult<HttpResponse> {
+    let pool = data.db_pool.clone();
+    let (format, encoder) = encoder(&query, export::TALENT_COLUMNS)?;
+    let talents = crate::database::TalentQuery::new(&filter);
+
+    let (tx, rx) = mpsc::channel(CHANNEL_CAPACITY);
+    tokio::spawn(async move {
+        let rows = talents.fetch(&pool).map(|talent| {
+            talent.map(|mut talent| {
+                talent.skills = decode_skills(talent.skills);
+                talent
+            })
+        });
+        pump(tx, encoder, rows, "Talents".to_string()).await;
+    });
+
+    Ok(streaming_response(format, "talents", rx))
+}
+
+/// Turns stored preferences into training examples. Preferences arrive
+/// grouped by job, so rendered text is only kept for the current job.
+struct ExampleBuilder {
+    pool: Pool,
+    redaction: Redaction,
+    seed: String,
+    validation_fraction: f64,
+    split: Option<&'static str>,
+    now: DateTime<Utc>,
+    job_id: String,
+    prompt: Option<String>,
+    candidates: HashMap<String, Option<String>>,
+}
+
+impl ExampleBuilder {
+    async fn candidate(&mut self, talent_id: &str) -> Result<Option<String>, sqlx::Error> {
+        if let Some(text) = self.candidates.get(talent_id) {
+            return Ok(text.clone());
+        }
+        let text = crate::database::get_talent_by_id(&self.pool, talent_id.to_string())
+            .await?
+            .map(|talent| preference_dataset::render_candidate(&talent, &self.redaction));
+        self.candidates.insert(talent_id.to_string(), text.clone());
+        Ok(text)
+    }
+
+    /// The example for one preference; None when it falls outside the
+    /// requested split or its job or a candidate is gone
+    async fn build(&mut self, preference: PairwisePreference) -> Result<Option<PreferenceExample>, sqlx::Error> {
+        let split = preference_dataset::split_for_job(&preference.job_id, &self.seed, self.validation_fraction);
+        if self.split.is_some_and(|wanted| wanted != split) {
+            return Ok(None);
+        }
+        if preference.job_id != self.job_id {
+            self.job_id = preference.job_id.clone();
+            self.candidates.clear();
+            self.prompt = crate::database::get_job_by_id(&self.pool, preference.job_id.clone())
+                .await?
+                .map(|job| preference_dataset::render_job(&job));
+        }
+        let Some(prompt) = self.prompt.clone() else {
+            return Ok(None);
+        };
+        let (Some(chosen), Some(rejected)) = (
+            self.candidate(&preference.winner_id).await?,
+            self.candidate(&preference.loser_id).await?,
+        ) else {
+            return Ok(None);
+        };
+
+        let ids = !self.redaction.names;
+        Ok(Some(PreferenceExample {
+            weight: reorder::recency_weight(preference.confidence, &preference.created_at, self.now),
+            id: preference.id,
+            split,
+            job_id: preference.job_id,
+            prompt,
+            chosen,
+            rejected,
+            chosen_talent_id: ids.then_some(preference.winner_id),
+            rejected_talent_id: ids.then_some(preference.loser_id),
+            source: preference.source,
+            confidence: preference.confidence,
+            created_at: preference.created_at,
+        }))
+    }
+}
+
+/// Normalize an RFC 3339 bound to the UTC form preferences are stored in
+fn timestamp_bound(value: Option<String>, name: &str) -> AppResult<Option<String>> {
+    value
+        .map(|value| {
+            DateTime::parse_from_rfc3339(value.trim())
+                .map(|t| t.with_timezone(&Utc).to_rfc3339())
+                .map_err(|_| AppError::validation(format!("{} must be an RFC 3339 timestamp", name)))
+        })
+        .transpose()
+}
+
+/// Export pairwise preferences as reward-model training data
+///
+/// One JSON object per line with the job posting as `prompt` and the
+/// preferred and other candidate's profiles as `chosen` and `rejected`.
+/// Ties are left out. Jobs are assigned to the `train` or `validation` split
+/// by hashing their ID with `seed`, so the same seed always gives the same
+/// split. Names, contact details and links are redacted unless `redact`
+/// says otherwise.
+#[api_v2_operation]
+#[paperclip::actix::get("/api/v1/preferences/export", summary = "Export preferences as chosen/rejected training examples (JSONL)")]
+pub async fn export_preferences(
+    data: web::Data<AppState>,
+    query: web::Query<PreferenceExportQuery>,
+) -> AppResult<HttpResponse> {
+    let pool = data.db_pool.clone();
+    let query = query.into_inner();
+
+    let mut sources: Vec<String> = Vec::new();
+    for source in query.source.as_deref().unwrap_or("").split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        if !reorder::PREFERENCE_SOURCES.contains(&source) {
+            return Err(AppError::validation(format!(
+                "Unknown source '{}'; use {}",
+                source,
+                reorder::PREFERENCE_SOURCES.join(", ")
+            )));
+        }
+        sources.push(source.to_string());
+    }
+    let since = timestamp_bound(query.since, "since")?;
+    let until = timestamp_bound(query.until, "until")?;
+    let split = match query.split.as_deref().map(str::trim) {
+        None | Some("") => None,
+        Some(preference_dataset::SPLIT_TRAIN) => Some(preference_dataset::SPLIT_TRAIN),
+        Some(preference_dataset::SPLIT_VALIDATION) => Some(preference_dataset::SPLIT_VALIDATION),
+        Some(_) => return Err(AppError::validation("split must be train or validation")),
+    };
+    let validation_fraction = query.validation_fraction.unwrap_or(preference_dataset::DEFAULT_VALIDATION_FRACTION);
+    if !(0.0..=1.0).contains(&validation_fraction) {
+        return Err(AppError::validation("validation_fraction must be between 0 and 1"));
+    }
+    let redaction = Redaction::parse(query.redact.as_deref()).map_err(AppError::validation)?;
+
+    let columns = export::PREFERENCE_COLUMNS.iter().map(|c| c.to_string()).collect();
+    let encoder = RowEncoder::new(ExportFormat::Ndjson, columns);
+    let preferences = crate::database::PreferenceQuery::new(&sources, since.as_deref(), until.as_deref());
+    let builder = ExampleBuilder {
+        pool: pool.clone(),
+        redaction,
+        seed: query.seed.unwrap_or_default(),
+        validation_fraction,
+        split,
+        now: Utc::now(),
+        job_id: String::new(),
+        prompt: None,
+        candidates: HashMap::new(),
+    };
+
+    let (tx, rx) = mpsc::channel(CHANNEL_CAPACITY);
+    let filename = split.map_or("preferences".to_string(), |split| format!("preferences-{}", split));
+    tokio::spawn(async move {
+        let rows = stream::unfold((preferences.fetch(&pool), builder), |(mut rows, mut builder)| async move {
+            loop {
+                let example = match rows.next().await? {
+                    Ok(preference) => builder.build(preference).await,
+                    Err(e) => Err(e),
+                };
+                if let Some(result) = example.transpose() {
+                    return Some((result, (rows, builder)));
+                }
+            }
+        });
+        pump(tx, encoder, rows, "Preference dataset".to_string()).await;
+    });
+
+    Ok(streaming_response(ExportFormat::Ndjson, &filename, rx))
+}
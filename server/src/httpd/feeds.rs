@@ -0,0 +1,120 @@
+use actix_web::{http::header, web, HttpRequest, HttpResponse};
+use chrono::Utc;
+use paperclip::actix::api_v2_operation;
+
+use crate::error::{AppError, AppResult};
+use crate::feed::{self, FeedFilter};
+use crate::models::{Job, JobFeedQuery};
+use super::server::AppState;
+
+/// Feeds may be cached by aggregators for a few minutes
+const FEED_CACHE_CONTROL: &str = "public, max-age=300";
+
+async fn load_open_jobs(data: &AppState, query: &JobFeedQuery) -> AppResult<Vec<Job>> {
+    let jobs = crate::database::get_published_jobs(&data.db_pool).await?;
+    let filter = FeedFilter {
+        company: query.company.clone(),
+        location: query.location.clone(),
+    };
+    Ok(feed::open_jobs(jobs, &filter, Utc::now()))
+}
+
+/// Build a cacheable feed response, answering 304 when the client's ETag matches
+fn feed_response(req: &HttpRequest, content_type: &str, body: Vec<u8>, jobs: &[Job]) -> HttpResponse {
+    let etag = feed::etag(&body);
+    let not_modified = req
+        .headers()
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.split(',').any(|tag| tag.trim() == etag || tag.trim() == "*"))
+        .unwrap_or(false);
+
+    let mut builder = if not_modified {
+        HttpResponse::NotModified()
+    } else {
+        HttpResponse::Ok()
+    };
+    builder
+        .insert_header((header::CACHE_CONTROL, FEED_CACHE_CONTROL))
+        .insert_header((header::ETAG, etag));
+    if let Some(modified) = feed::last_modified(jobs) {
+        builder.insert_header((
+            header::LAST_MODIFIED,
+            modified.format("%a, %d %b %Y %H:%M:%S GMT").to_string(),
+        ));
+    }
+
+    if not_modified {
+        builder.finish()
+    } else {
+        builder.content_type(content_type).body(body)
+    }
+}
+
+fn feed_url(data: &AppState, req: &HttpRequest) -> String {
+    let query = req.query_string();
+    if query.is_empty() {
+        format!("{}{}", data.public_api_url, req.path())
+    } else {
+        format!("{}{}?{}", data.public_api_url, req.path(), query)
+    }
+}
+
+#[api_v2_operation]
+#[paperclip::actix::get("/feeds/jobs.rss", summary = "RSS 2.0 feed of open jobs")]
+pub async fn jobs_rss_feed(
+    req: HttpRequest,
+    data: web::Data<AppState>,
+    query: web::Query<JobFeedQuery>,
+) -> AppResult<HttpResponse> {
+    let jobs = load_open_jobs(&data, &query).await?;
+    let body = feed::rss_feed(&jobs, &data.public_board_url, &feed_url(&data, &req));
+    Ok(feed_response(&req, "application/rss+xml; charset=utf-8", body.into_bytes(), &jobs))
+}
+
+#[api_v2_operation]
+#[paperclip::actix::get("/feeds/jobs.json", summary = "JSON Feed of open jobs")]
+pub async fn jobs_json_feed(
+    req: HttpRequest,
+    data: web::Data<AppState>,
+    query: web::Query<JobFeedQuery>,
+) -> AppResult<HttpResponse> {
+    let jobs = load_open_jobs(&data, &query).await?;
+    let body = serde_json::to_vec(&feed::json_feed(&jobs, &data.public_board_url, &feed_url(&data, &req)))?;
+    Ok(feed_response(&req, "application/feed+json; charset=utf-8", body, &jobs))
+}
+
+#[api_v2_operation]
+#[paperclip::actix::get("/feeds/jobs.jsonld", summary = "schema.org JobPosting list of open jobs")]
+pub async fn jobs_jsonld_feed(
+    req: HttpRequest,
+    data: web::Data<AppState>,
+    query: web::Query<JobFeedQuery>,
+) -> AppResult<HttpResponse> {
+    let jobs = load_open_jobs(&data, &query).await?;
+    let postings: Vec<serde_json::Value> = jobs
+        .iter()
+        .map(|job| feed::job_posting_jsonld(job, &data.public_board_url))
+        .collect();
+    let body = serde_json::to_vec(&postings)?;
+    Ok(feed_response(&req, "application/ld+json; charset=utf-8", body, &jobs))
+}
+
+#[api_v2_operation]
+#[paperclip::actix::get("/feeds/jobs/{id}/jsonld", summary = "schema.org JobPosting for a single open job")]
+pub async fn job_jsonld(
+    req: HttpRequest,
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+) -> AppResult<HttpResponse> {
+    let id = path.into_inner();
+    let job = crate::database::get_job_by_id(&data.db_pool, id).await?
+        .ok_or_else(|| AppError::not_found("Job not found"))?;
+
+    // Only open positions are public
+    let jobs = feed::open_jobs(vec![job], &FeedFilter::default(), Utc::now());
+    let job = jobs.first().ok_or_else(|| AppError::not_found("Job not found"))?;
+
+    let body = serde_json::to_vec(&feed::job_posting_jsonld(job, &data.public_board_url))?;
+    Ok(feed_response(&req, "application/ld+json; charset=utf-8", body, &jobs))
+}
@@ -0,0 +1,259 @@
+//! Bulk import endpoints
+//!
+//! Rows are validated and matched against existing records first; the
+//! resulting writes are then applied in transactional batches. A failed
+//! batch rolls back on its own and its rows are reported as failed.
+
+use std::collections::HashMap;
+
+use actix_web::{http::header, web, HttpRequest, HttpResponse};
+use log::{error, info};
+use paperclip::actix::api_v2_operation;
+
+use crate::error::{AppError, AppResult};
+use crate::import::{self, ImportFormat, JobImport, OnDuplicate, Row, TalentImport};
+use crate::models::{ImportQuery, ImportReport, ImportRowResult, UpdateJobRequest};
+use super::audit;
+use super::jobs::new_job;
+use super::server::AppState;
+use super::talents::{merge_update, new_talent};
+
+struct ImportOptions {
+    format: ImportFormat,
+    mapping: HashMap<String, String>,
+    on_duplicate: OnDuplicate,
+    batch_size: usize,
+    dry_run: bool,
+}
+
+impl ImportOptions {
+    fn new(req: &HttpRequest, query: &ImportQuery) -> AppResult<Self> {
+        let content_type = req
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("");
+        let batch_size = query.batch_size.unwrap_or(import::DEFAULT_BATCH_SIZE);
+        if !(1..=import::MAX_BATCH_SIZE).contains(&batch_size) {
+            return Err(AppError::validation(format!(
+                "batch_size must be between 1 and {}",
+                import::MAX_BATCH_SIZE
+            )));
+        }
+
+        Ok(ImportOptions {
+            format: ImportFormat::detect(query.format.as_deref(), content_type).map_err(AppError::validation)?,
+            mapping: import::parse_mapping(query.map.as_deref()).map_err(AppError::validation)?,
+            on_duplicate: OnDuplicate::parse(query.on_duplicate.as_deref()).map_err(AppError::validation)?,
+            batch_size,
+            dry_run: query.dry_run.unwrap_or(false),
+        })
+    }
+
+    /// Outcome for a row that matches an existing record (or an earlier row)
+    fn duplicate(&self, line: usize, id: Option<String>, message: String) -> ImportRowResult {
+        let status = if self.on_duplicate == OnDuplicate::Error { "failed" } else { "skipped" };
+        row_result(line, status, id, vec![message])
+    }
+}
+
+fn row_result(line: usize, status: &str, id: Option<String>, errors: Vec<String>) -> ImportRowResult {
+    ImportRowResult { line, status: status.to_string(), id, errors }
+}
+
+/// Mark every row of a rolled-back batch as failed. The database error is
+/// logged by the caller and never reaches the client.
+fn fail_batch<T>(rows: &mut [ImportRowResult], batch: &[(usize, T)]) {
+    for (index, _) in batch {
+        let row = &mut rows[*index];
+        if row.status == "created" {
+            row.id = None;
+        }
+        row.status = "failed".to_string();
+        row.errors.push("Batch rolled back".to_string());
+    }
+}
+
+fn report(dry_run: bool, ignored_columns: Vec<String>, mut rows: Vec<ImportRowResult>) -> ImportReport {
+    if dry_run {
+        // Nothing was written, so new rows have no ID yet
+        rows.iter_mut().filter(|r| r.status == "created").for_each(|r| r.id = None);
+    } else {
+        audit::add_targets(
+            rows.iter()
+                .filter(|r| r.status == "created" || r.status == "updated")
+                .filter_map(|r| r.id.clone()),
+        );
+    }
+    let count = |status: &str| rows.iter().filter(|r| r.status == status).count();
+    ImportReport {
+        dry_run,
+        total_rows: rows.len(),
+        created: count("created"),
+        updated: count("updated"),
+        skipped: count("skipped"),
+        failed: count("failed"),
+        ignored_columns,
+        rows,
+    }
+}
+
+#[api_v2_operation]
+#[paperclip::actix::post("/api/v1/import/talents", summary = "Import talents from CSV or NDJSON")]
+pub async fn import_talents(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    query: web::Query<ImportQuery>,
+    body: web::Bytes,
+) -> AppResult<HttpResponse> {
+    let pool = &data.db_pool;
+    let options = ImportOptions::new(&req, &query)?;
+    let input = import::parse_rows(&body, options.format, &options.mapping).map_err(AppError::validation)?;
+    let ignored_columns = import::ignored_columns(&input, import::TALENT_FIELDS);
+
+    let mut rows = Vec::with_capacity(input.len());
+    let mut writes = Vec::new();
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    for Row { line, fields } in input {
+        let request = match fields.map_err(|e| vec![e]).and_then(|f| import::talent_from_row(&f)) {
+            Ok(request) => request,
+            Err(errors) => {
+                rows.push(row_result(line, "failed", None, errors));
+                continue;
+            }
+        };
+
+        if let Some(first) = seen.get(&request.email.to_lowercase()) {
+            rows.push(options.duplicate(line, None, format!("Duplicate of line {} in this import", first)));
+            continue;
+        }
+        seen.insert(request.email.to_lowercase(), line);
+
+        match crate::database::get_talent_by_email(pool, request.email.clone()).await? {
+            None => {
+                let talent = new_talent(&request);
+                rows.push(row_result(line, "created", Some(talent.id.clone()), vec![]));
+                writes.push((rows.len() - 1, TalentImport::Create(Box::new(talent))));
+            }
+            Some(existing) if options.on_duplicate == OnDuplicate::Update => {
+                let id = existing.id.clone();
+                rows.push(row_result(line, "updated", Some(id.clone()), vec![]));
+                writes.push((rows.len() - 1, TalentImport::Update(id, Box::new(merge_update(existing, request)))));
+            }
+            Some(existing) => {
+                let message = format!("A talent with email {} already exists", request.email);
+                rows.push(options.duplicate(line, Some(existing.id), message));
+            }
+        }
+    }
+
+    if !options.dry_run {
+        for batch in writes.chunks(options.batch_size) {
+            let items: Vec<&TalentImport> = batch.iter().map(|(_, item)| item).collect();
+            if let Err(e) = crate::database::import_talents(pool, &items).await {
+                error!("[import] Talent batch of {} rows rolled back: {}", batch.len(), e);
+                fail_batch(&mut rows, batch);
+                continue;
+            }
+            for item in items {
+                if let TalentImport::Create(talent) = item {
+                    data.collections.enqueue(talent.id.clone(), talent.name.clone());
+                }
+            }
+        }
+    }
+
+    let report = report(options.dry_run, ignored_columns, rows);
+    info!(
+        "[import] Talents{}: {} created, {} updated, {} skipped, {} failed",
+        if report.dry_run { " (dry run)" } else { "" },
+        report.created, report.updated, report.skipped, report.failed
+    );
+    Ok(HttpResponse::Ok().json(report))
+}
+
+#[api_v2_operation]
+#[paperclip::actix::post("/api/v1/import/jobs", summary = "Import jobs from CSV or NDJSON")]
+pub async fn import_jobs(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    query: web::Query<ImportQuery>,
+    body: web::Bytes,
+) -> AppResult<HttpResponse> {
+    let pool = &data.db_pool;
+    let options = ImportOptions::new(&req, &query)?;
+    let input = import::parse_rows(&body, options.format, &options.mapping).map_err(AppError::validation)?;
+    let ignored_columns = import::ignored_columns(&input, import::JOB_FIELDS);
+
+    let mut rows = Vec::with_capacity(input.len());
+    let mut writes = Vec::new();
+    let mut seen: HashMap<(String, String), usize> = HashMap::new();
+    for Row { line, fields } in input {
+        let request = match fields.map_err(|e| vec![e]).and_then(|f| import::job_from_row(&f)) {
+            Ok(request) => request,
+            Err(errors) => {
+                rows.push(row_result(line, "failed", None, errors));
+                continue;
+            }
+        };
+
+        let key = (request.company_name.to_lowercase(), request.title.to_lowercase());
+        if let Some(first) = seen.get(&key) {
+            rows.push(options.duplicate(line, None, format!("Duplicate of line {} in this import", first)));
+            continue;
+        }
+        seen.insert(key, line);
+
+        match crate::database::get_job_by_company_and_title(pool, &request.company_name, &request.title).await? {
+            None => {
+                let job = new_job(&request);
+                rows.push(row_result(line, "created", Some(job.id.clone()), vec![]));
+                writes.push((rows.len() - 1, JobImport::Create(job)));
+            }
+            Some(existing) if options.on_duplicate == OnDuplicate::Update => {
+                // update_job keeps current values for anything left empty; the
+                // status is left alone, so `publish` only applies to new jobs
+                let updates = UpdateJobRequest {
+                    title: Some(request.title),
+                    description: Some(request.description),
+                    company_name: Some(request.company_name),
+                    company_logo: request.company_logo,
+                    location: request.location,
+                    location_type: Some(request.location_type),
+                    employment_type: Some(request.employment_type),
+                    salary_min: request.salary_min,
+                    salary_max: request.salary_max,
+                    salary_currency: request.salary_currency,
+                    skills_required: Some(request.skills_required),
+                    experience_level: Some(request.experience_level),
+                    status: None,
+                    expires_at: request.expires_at,
+                };
+                rows.push(row_result(line, "updated", Some(existing.id.clone()), vec![]));
+                writes.push((rows.len() - 1, JobImport::Update(existing.id, updates)));
+            }
+            Some(existing) => {
+                let message = format!("A job '{}' at {} already exists", request.title, request.company_name);
+                rows.push(options.duplicate(line, Some(existing.id), message));
+            }
+        }
+    }
+
+    if !options.dry_run {
+        for batch in writes.chunks(options.batch_size) {
+            let items: Vec<&JobImport> = batch.iter().map(|(_, item)| item).collect();
+            if let Err(e) = crate::database::import_jobs(pool, &items).await {
+                error!("[import] Job batch of {} rows rolled back: {}", batch.len(), e);
+                fail_batch(&mut rows, batch);
+            }
+        }
+    }
+
+    let report = report(options.dry_run, ignored_columns, rows);
+    info!(
+        "[import] Jobs{}: {} created, {} updated, {} skipped, {} failed",
+        if report.dry_run { " (dry run)" } else { "" },
+        report.created, report.updated, report.skipped, report.failed
+    );
+    Ok(HttpResponse::Ok().json(report))
+}
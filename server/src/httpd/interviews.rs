@@ -0,0 +1,320 @@
+use std::collections::BTreeMap;
+
+use actix_web::{web, HttpResponse};
+use chrono::{DateTime, Utc};
+use log::info;
+use paperclip::actix::api_v2_operation;
+use uuid::Uuid;
+
+use crate::database::Pool;
+use crate::error::{AppError, AppResult};
+use crate::models::{
+    Application, ApplicationFeedback, CompetencyFeedback, CreateInterviewRequest, Interview, InterviewResponse,
+    PendingScorecard, RecommendationTally, Scorecard, ScorecardResponse, SubmitScorecardRequest, UpdateInterviewRequest,
+};
+use crate::scorecard::{self, InterviewStatus, Recommendation};
+use super::audit;
+use super::server::AppState;
+
+/// Longest interview that can be scheduled, in minutes
+const MAX_DURATION_MINUTES: i32 = 8 * 60;
+
+async fn live_application(pool: &Pool, id: String) -> AppResult<Application> {
+    crate::database::get_application_by_id(pool, id).await?
+        .ok_or_else(|| AppError::not_found("Application not found"))
+}
+
+async fn live_interview(pool: &Pool, id: &str) -> AppResult<Interview> {
+    crate::database::get_interview(pool, id).await?
+        .ok_or_else(|| AppError::not_found("Interview not found"))
+}
+
+/// Add the interview's application and talent to the audit targets, as
+/// creating it does
+async fn audit_interview_targets(pool: &Pool, interview: &Interview) -> AppResult<()> {
+    let application = crate::database::get_application_by_id(pool, interview.application_id.clone()).await?;
+    audit::add_targets(std::iter::once(interview.application_id.clone()).chain(application.map(|a| a.talent_id)));
+    Ok(())
+}
+
+/// Whether the interview has taken place and can be given feedback: it is
+/// completed, or still scheduled but its start time has passed
+fn takes_scorecards(interview: &Interview, now: DateTime<Utc>) -> bool {
+    match InterviewStatus::parse(&interview.status) {
+        Some(InterviewStatus::Completed) => true,
+        Some(InterviewStatus::Scheduled) => DateTime::parse_from_rfc3339(&interview.scheduled_at).is_ok_and(|at| at <= now),
+        _ => false,
+    }
+}
+
+/// Trimmed, de-duplicated interviewer list; at least one is required
+fn interviewers(names: &[String]) -> AppResult<String> {
+    let mut list: Vec<&str> = Vec::new();
+    for name in names.iter().map(|n| n.trim()).filter(|n| !n.is_empty()) {
+        if !list.iter().any(|existing| existing.eq_ignore_ascii_case(name)) {
+            list.push(name);
+        }
+    }
+    if list.is_empty() {
+        return Err(AppError::validation("At least one interviewer is required"));
+    }
+    Ok(serde_json::to_string(&list)?)
+}
+
+fn validate_schedule(interview: &Interview) -> AppResult<()> {
+    if DateTime::parse_from_rfc3339(&interview.scheduled_at).is_err() {
+        return Err(AppError::validation("scheduled_at must be an RFC 3339 timestamp"));
+    }
+    if !(1..=MAX_DURATION_MINUTES).contains(&interview.duration_minutes) {
+        return Err(AppError::validation(format!("duration_minutes must be between 1 and {}", MAX_DURATION_MINUTES)));
+    }
+    if interview.round < 1 {
+        return Err(AppError::validation("round must be 1 or more"));
+    }
+    if let Some(link) = &interview.video_link {
+        if !link.starts_with("https://") && !link.starts_with("http://") {
+            return Err(AppError::validation("video_link must be an http(s) URL"));
+        }
+    }
+    Ok(())
+}
+
+#[api_v2_operation]
+#[paperclip::actix::post("/api/v1/applications/{id}/interviews", summary = "Schedule an interview for an application")]
+pub async fn create_interview(
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+    json: web::Json<CreateInterviewRequest>,
+) -> AppResult<HttpResponse> {
+    let pool = &data.db_pool;
+    let application = live_application(pool, path.into_inner()).await?;
+
+    let round = match json.round {
+        Some(round) => round,
+        None => crate::database::next_interview_round(pool, &application.id).await?,
+    };
+    let interview = Interview {
+        id: Uuid::new_v4().to_string(),
+        application_id: application.id.clone(),
+        round,
+        title: json.title.clone(),
+        interviewers: interviewers(&json.interviewers)?,
+        scheduled_at: json.scheduled_at.clone(),
+        duration_minutes: json.duration_minutes.unwrap_or(60),
+        location: json.location.clone(),
+        video_link: json.video_link.clone(),
+        status: InterviewStatus::Scheduled.as_str().to_string(),
+        created_at: Utc::now().to_rfc3339(),
+    };
+    validate_schedule(&interview)?;
+
+    let inserted = crate::database::create_interview(pool, &interview).await?;
+    data.notifier.interview_scheduled(&inserted, &application).await;
+    audit::add_targets([inserted.id.clone(), application.id, application.talent_id]);
+    info!("Scheduled round {} interview {} for application {}", inserted.round, inserted.id, inserted.application_id);
+    Ok(HttpResponse::Created().json(InterviewResponse::from(inserted)))
+}
+
+#[api_v2_operation]
+#[paperclip::actix::get("/api/v1/applications/{id}/interviews", summary = "List an application's interviews")]
+pub async fn get_interviews_by_application(
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+) -> AppResult<HttpResponse> {
+    let pool = &data.db_pool;
+    let application = live_application(pool, path.into_inner()).await?;
+    let interviews = crate::database::get_interviews_by_application(pool, &application.id).await?;
+    let response: Vec<InterviewResponse> = interviews.into_iter().map(InterviewResponse::from).collect();
+    Ok(HttpResponse::Ok().json(response))
+}
+
+#[api_v2_operation]
+#[paperclip::actix::get("/api/v1/interviews/{id}", summary = "Get an interview")]
+pub async fn get_interview(
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+) -> AppResult<HttpResponse> {
+    let interview = live_interview(&data.db_pool, &path.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(InterviewResponse::from(interview)))
+}
+
+#[api_v2_operation]
+#[paperclip::actix::put("/api/v1/interviews/{id}", summary = "Reschedule or update the status of an interview")]
+pub async fn update_interview(
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+    json: web::Json<UpdateInterviewRequest>,
+) -> AppResult<HttpResponse> {
+    let pool = &data.db_pool;
+    let current = live_interview(pool, &path.into_inner()).await?;
+    let json = json.into_inner();
+
+    let status = match json.status.as_deref() {
+        Some(value) => InterviewStatus::parse(value)
+            .ok_or_else(|| AppError::validation(format!(
+                "Unknown status '{}'; use scheduled, completed, cancelled or no_show",
+                value
+            )))?
+            .as_str()
+            .to_string(),
+        None => current.status.clone(),
+    };
+    let interview = Interview {
+        round: json.round.unwrap_or(current.round),
+        title: json.title.or(current.title),
+        interviewers: match &json.interviewers {
+            Some(names) => interviewers(names)?,
+            None => current.interviewers,
+        },
+        scheduled_at: json.scheduled_at.unwrap_or(current.scheduled_at),
+        duration_minutes: json.duration_minutes.unwrap_or(current.duration_minutes),
+        location: json.location.or(current.location),
+        video_link: json.video_link.or(current.video_link),
+        status,
+        ..current
+    };
+    validate_schedule(&interview)?;
+
+    let updated = crate::database::update_interview(pool, &interview).await?
+        .ok_or_else(|| AppError::not_found("Interview not found"))?;
+    audit_interview_targets(pool, &updated).await?;
+    Ok(HttpResponse::Ok().json(InterviewResponse::from(updated)))
+}
+
+#[api_v2_operation]
+#[paperclip::actix::delete("/api/v1/interviews/{id}", summary = "Delete an interview and its scorecards")]
+pub async fn delete_interview(
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+) -> AppResult<HttpResponse> {
+    let pool = &data.db_pool;
+    let interview = live_interview(pool, &path.into_inner()).await?;
+    crate::database::delete_interview(pool, &interview.id).await?;
+    audit_interview_targets(pool, &interview).await?;
+    info!("Deleted interview {}", interview.id);
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[api_v2_operation]
+#[paperclip::actix::post("/api/v1/interviews/{id}/scorecards", summary = "Submit (or replace) an interviewer's scorecard")]
+pub async fn submit_scorecard(
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+    json: web::Json<SubmitScorecardRequest>,
+) -> AppResult<HttpResponse> {
+    let pool = &data.db_pool;
+    let interview = live_interview(pool, &path.into_inner()).await?;
+
+    if !takes_scorecards(&interview, Utc::now()) {
+        return Err(AppError::conflict(match InterviewStatus::parse(&interview.status) {
+            Some(InterviewStatus::Scheduled) => "Interview has not taken place yet; scorecards cannot be submitted".to_string(),
+            _ => format!("Interview is {}; scorecards cannot be submitted", interview.status),
+        }));
+    }
+    let panel: Vec<String> = serde_json::from_str(&interview.interviewers)?;
+    let interviewer = panel
+        .into_iter()
+        .find(|name| name.eq_ignore_ascii_case(json.interviewer.trim()))
+        .ok_or_else(|| AppError::validation(format!("{} is not on this interview's panel", json.interviewer)))?;
+    let recommendation = Recommendation::parse(&json.recommendation)
+        .ok_or_else(|| AppError::validation("recommendation must be strong_no_hire, no_hire, hire or strong_hire"))?;
+    let ratings: BTreeMap<&str, i32> = scorecard::validate_ratings(&json.ratings)
+        .map_err(|errors| AppError::validation(errors.join("; ")))?
+        .into_iter()
+        .map(|(competency, rating)| (competency.as_str(), rating))
+        .collect();
+
+    let saved = crate::database::upsert_scorecard(pool, &Scorecard {
+        id: Uuid::new_v4().to_string(),
+        interview_id: interview.id.clone(),
+        interviewer,
+        ratings: serde_json::to_string(&ratings)?,
+        recommendation: recommendation.as_str().to_string(),
+        notes: json.notes.clone(),
+        submitted_at: Utc::now().to_rfc3339(),
+    }).await?;
+    audit::add_targets([saved.id.clone(), interview.application_id]);
+    info!("Scorecard from {} for interview {}: {}", saved.interviewer, saved.interview_id, saved.recommendation);
+    Ok(HttpResponse::Created().json(ScorecardResponse::from(saved)))
+}
+
+#[api_v2_operation]
+#[paperclip::actix::get("/api/v1/interviews/{id}/scorecards", summary = "List an interview's scorecards")]
+pub async fn get_scorecards(
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+) -> AppResult<HttpResponse> {
+    let pool = &data.db_pool;
+    let interview = live_interview(pool, &path.into_inner()).await?;
+    let scorecards = crate::database::get_scorecards_by_interview(pool, &interview.id).await?;
+    let response: Vec<ScorecardResponse> = scorecards.into_iter().map(ScorecardResponse::from).collect();
+    Ok(HttpResponse::Ok().json(response))
+}
+
+#[api_v2_operation]
+#[paperclip::actix::get("/api/v1/applications/{id}/feedback", summary = "Aggregate interview feedback for an application")]
+pub async fn get_application_feedback(
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+) -> AppResult<HttpResponse> {
+    let pool = &data.db_pool;
+    let application = live_application(pool, path.into_inner()).await?;
+    let interviews = crate::database::get_interviews_by_application(pool, &application.id).await?;
+    let scorecards: Vec<ScorecardResponse> = crate::database::get_scorecards_by_application(pool, &application.id)
+        .await?
+        .into_iter()
+        .map(ScorecardResponse::from)
+        .collect();
+
+    let ratings: Vec<_> = scorecards
+        .iter()
+        .map(|s| s.ratings.iter().filter_map(|(name, rating)| Some((scorecard::Competency::parse(name)?, *rating))).collect())
+        .collect();
+    let averages = scorecard::competency_averages(&ratings);
+
+    let recommendations: Vec<Recommendation> = scorecards.iter().filter_map(|s| Recommendation::parse(&s.recommendation)).collect();
+    let mut tally = RecommendationTally::default();
+    for recommendation in &recommendations {
+        match recommendation {
+            Recommendation::StrongNoHire => tally.strong_no_hire += 1,
+            Recommendation::NoHire => tally.no_hire += 1,
+            Recommendation::Hire => tally.hire += 1,
+            Recommendation::StrongHire => tally.strong_hire += 1,
+        }
+    }
+
+    // Interviewers of interviews that have taken place but who have not submitted
+    let now = Utc::now();
+    let mut pending = Vec::new();
+    for interview in &interviews {
+        if !takes_scorecards(interview, now) {
+            continue;
+        }
+        let panel: Vec<String> = serde_json::from_str(&interview.interviewers).unwrap_or_default();
+        for interviewer in panel {
+            let submitted = scorecards.iter().any(|s| s.interview_id == interview.id && s.interviewer == interviewer);
+            if !submitted {
+                pending.push(PendingScorecard { interview_id: interview.id.clone(), round: interview.round, interviewer });
+            }
+        }
+    }
+
+    Ok(HttpResponse::Ok().json(ApplicationFeedback {
+        application_id: application.id,
+        interviews: interviews.len(),
+        weighted_score: scorecard::weighted_score(&averages),
+        competencies: averages
+            .into_iter()
+            .map(|a| CompetencyFeedback {
+                competency: a.competency.to_string(),
+                weight: a.competency.weight(),
+                average_rating: (a.average * 100.0).round() / 100.0,
+                ratings: a.ratings,
+            })
+            .collect(),
+        recommendations: tally,
+        consensus: scorecard::consensus(&recommendations).map(str::to_string),
+        pending,
+        scorecards,
+    }))
+}
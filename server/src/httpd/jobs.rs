@@ -1,30 +1,35 @@
-use actix_web::{web, HttpResponse, Result as ActixResult};
-use log::{info, error};
+use actix_web::{web, HttpResponse};
+use log::info;
 use paperclip::actix::api_v2_operation;
 use uuid::Uuid;
 use chrono::Utc;
 
-use crate::models::{Job, CreateJobRequest, UpdateJobRequest, ApiError, JobMatch, JobMatchWithTalent};
-use crate::grok_client::{GrokClient, JobMatchingRequest, TalentForMatching};
+use crate::models::{Job, CreateJobRequest, UpdateJobRequest, CloseJobQuery, JobTransitionResponse, JobMatchWithTalent, LegalHoldRequest, GenerateMatchesQuery, MatchRunDetail, MatchRunDiffQuery};
+use crate::error::{AppError, AppResult};
+use crate::events::{self, EventKind};
+use crate::job_lifecycle::JobStatus;
+use crate::match_runs;
+use crate::grok_client::{JobMatchingRequest, TalentForMatching};
+use super::audit;
 use super::server::AppState;
 
 #[api_v2_operation]
 #[paperclip::actix::get("/api/v1/jobs", summary = "List all jobs")]
-pub async fn get_jobs(data: web::Data<AppState>) -> ActixResult<HttpResponse> {
+pub async fn get_jobs(data: web::Data<AppState>) -> AppResult<HttpResponse> {
     let pool = &data.db_pool;
-    let jobs = crate::database::get_all_jobs(pool).await
-        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let jobs = crate::database::get_all_jobs(pool).await?;
     Ok(HttpResponse::Ok().json(jobs))
 }
 
-#[api_v2_operation]
-#[paperclip::actix::post("/api/v1/jobs", summary = "Create a new job posting")]
-pub async fn create_job(
-    data: web::Data<AppState>,
-    json: web::Json<CreateJobRequest>,
-) -> ActixResult<HttpResponse> {
-    let pool = &data.db_pool;
-    let new_job = Job {
+/// Build a new job record from a create request; drafts unless `publish` is set
+pub(crate) fn new_job(json: &CreateJobRequest) -> Job {
+    let now = Utc::now().to_rfc3339();
+    let status = if json.publish.unwrap_or(false) {
+        JobStatus::Published
+    } else {
+        JobStatus::Draft
+    };
+    Job {
         id: Uuid::new_v4().to_string(),
         title: json.title.clone(),
         description: json.description.clone(),
@@ -38,12 +43,26 @@ pub async fn create_job(
         salary_currency: json.salary_currency.clone(),
         skills_required: json.skills_required.clone(),
         experience_level: json.experience_level.clone(),
-        status: "active".to_string(),
-        created_at: Utc::now().to_rfc3339(),
+        status: status.to_string(),
+        created_at: now.clone(),
         expires_at: json.expires_at.clone(),
-    };
-    let inserted = crate::database::create_job(pool, &new_job).await
-        .map_err(actix_web::error::ErrorInternalServerError)?;
+        published_at: status.is_open().then_some(now),
+        closed_at: None,
+        legal_hold: 0,
+        deleted_at: None,
+    }
+}
+
+#[api_v2_operation]
+#[paperclip::actix::post("/api/v1/jobs", summary = "Create a new job posting")]
+pub async fn create_job(
+    data: web::Data<AppState>,
+    json: web::Json<CreateJobRequest>,
+) -> AppResult<HttpResponse> {
+    let pool = &data.db_pool;
+    let new_job = new_job(&json);
+    let inserted = crate::database::create_job(pool, &new_job).await?;
+    audit::add_targets([inserted.id.clone()]);
     Ok(HttpResponse::Created().json(inserted))
 }
 
@@ -52,12 +71,11 @@ pub async fn create_job(
 pub async fn get_job(
     data: web::Data<AppState>,
     path: web::Path<String>,
-) -> ActixResult<HttpResponse> {
+) -> AppResult<HttpResponse> {
     let id = path.into_inner();
     let pool = &data.db_pool;
-    let job = crate::database::get_job_by_id(pool, id).await
-        .map_err(actix_web::error::ErrorInternalServerError)?
-        .ok_or(actix_web::error::ErrorNotFound("Job not found"))?;
+    let job = crate::database::get_job_by_id(pool, id).await?
+        .ok_or_else(|| AppError::not_found("Job not found"))?;
     Ok(HttpResponse::Ok().json(job))
 }
 
@@ -67,12 +85,32 @@ pub async fn update_job(
     data: web::Data<AppState>,
     path: web::Path<String>,
     json: web::Json<UpdateJobRequest>,
-) -> ActixResult<HttpResponse> {
+) -> AppResult<HttpResponse> {
     let id = path.into_inner();
     let pool = &data.db_pool;
-    let updated = crate::database::update_job(pool, id, &json).await
-        .map_err(actix_web::error::ErrorInternalServerError)?
-        .ok_or(actix_web::error::ErrorNotFound("Job not found"))?;
+
+    // Status changes must follow the lifecycle; use the transition endpoints
+    // to get published_at / closed_at bookkeeping. A status matching the
+    // current one is stored in its canonical spelling, so a legacy "active"
+    // is saved as "published".
+    let mut json = json.into_inner();
+    if let Some(status) = json.status.as_deref().filter(|s| !s.is_empty()) {
+        let job = crate::database::get_job_by_id(pool, id.clone()).await?
+            .ok_or_else(|| AppError::not_found("Job not found"))?;
+        let next = JobStatus::parse(status)
+            .ok_or_else(|| AppError::validation(format!("Unknown job status: {}", status)))?;
+        let current = JobStatus::parse(&job.status).unwrap_or(JobStatus::Draft);
+        if current != next {
+            return Err(AppError::conflict(format!(
+                "Use the lifecycle endpoints to move a job from {} to {}",
+                current, next
+            )));
+        }
+        json.status = Some(next.as_str().to_string());
+    }
+
+    let updated = crate::database::update_job(pool, id, &json).await?
+        .ok_or_else(|| AppError::not_found("Job not found"))?;
     Ok(HttpResponse::Ok().json(updated))
 }
 
@@ -81,19 +119,120 @@ pub async fn update_job(
 pub async fn delete_job(
     data: web::Data<AppState>,
     path: web::Path<String>,
-) -> ActixResult<HttpResponse> {
+) -> AppResult<HttpResponse> {
     let id = path.into_inner();
     let pool = &data.db_pool;
-    let deleted = crate::database::delete_job(pool, id).await
-        .map_err(actix_web::error::ErrorInternalServerError)?;
-    if deleted {
-        Ok(HttpResponse::NoContent().finish())
-    } else {
-        Ok(HttpResponse::NotFound().json(ApiError {
-            message: "Job not found".to_string(),
-            code: 404,
-        }))
+    let deleted = crate::database::delete_job(pool, id).await?;
+    if !deleted {
+        return Err(AppError::not_found("Job not found"));
     }
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Move a job to `next`, validating the lifecycle transition
+async fn transition_job(
+    data: &AppState,
+    id: String,
+    next: JobStatus,
+    reject_remaining: bool,
+) -> AppResult<JobTransitionResponse> {
+    let pool = &data.db_pool;
+    let job = crate::database::get_job_by_id(pool, id.clone()).await?
+        .ok_or_else(|| AppError::not_found("Job not found"))?;
+
+    let current = JobStatus::parse(&job.status)
+        .ok_or_else(|| AppError::internal(format!("Job {} has unknown status {}", job.id, job.status)))?;
+    if !current.can_transition_to(next) {
+        return Err(AppError::conflict(format!("Cannot move job from {} to {}", current, next)));
+    }
+
+    // Another transition, e.g. the expiry sweep, may have moved the job since it was read
+    let job = crate::database::set_job_status(pool, &id, &job.status, next).await?
+        .ok_or_else(|| AppError::conflict(format!("Job {} changed status concurrently; reload and retry", id)))?;
+    info!("[job_lifecycle] Job {} moved from {} to {}", job.id, current, next);
+
+    let mut rejected_applications = 0;
+    if next.is_terminal() && reject_remaining {
+        let rejected = crate::database::reject_open_applications_for_job(pool, &id).await?;
+        info!("[job_lifecycle] Auto-rejected {} remaining applications for job {}", rejected.len(), id);
+        rejected_applications = rejected.len();
+        for (application, previous) in &rejected {
+            data.events.publish(EventKind::ApplicationStatusChanged, events::application_status_changed(application, previous)).await;
+        }
+    }
+
+    Ok(JobTransitionResponse { job, rejected_applications })
+}
+
+#[api_v2_operation]
+#[paperclip::actix::post("/api/v1/jobs/{id}/publish", summary = "Publish a draft or paused job")]
+pub async fn publish_job(
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+) -> AppResult<HttpResponse> {
+    let response = transition_job(&data, path.into_inner(), JobStatus::Published, false).await?;
+    Ok(HttpResponse::Ok().json(response))
+}
+
+#[api_v2_operation]
+#[paperclip::actix::post("/api/v1/jobs/{id}/pause", summary = "Pause a published job")]
+pub async fn pause_job(
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+) -> AppResult<HttpResponse> {
+    let response = transition_job(&data, path.into_inner(), JobStatus::Paused, false).await?;
+    Ok(HttpResponse::Ok().json(response))
+}
+
+#[api_v2_operation]
+#[paperclip::actix::post("/api/v1/jobs/{id}/close", summary = "Close a job without filling it")]
+pub async fn close_job(
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+    query: web::Query<CloseJobQuery>,
+) -> AppResult<HttpResponse> {
+    let reject_remaining = query.reject_remaining.unwrap_or(false);
+    let response = transition_job(&data, path.into_inner(), JobStatus::Closed, reject_remaining).await?;
+    Ok(HttpResponse::Ok().json(response))
+}
+
+#[api_v2_operation]
+#[paperclip::actix::post("/api/v1/jobs/{id}/fill", summary = "Mark a job as filled")]
+pub async fn fill_job(
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+    query: web::Query<CloseJobQuery>,
+) -> AppResult<HttpResponse> {
+    let reject_remaining = query.reject_remaining.unwrap_or(false);
+    let response = transition_job(&data, path.into_inner(), JobStatus::Filled, reject_remaining).await?;
+    Ok(HttpResponse::Ok().json(response))
+}
+
+#[api_v2_operation]
+#[paperclip::actix::put("/api/v1/jobs/{id}/legal-hold", summary = "Place or lift a legal hold on a job")]
+pub async fn set_job_legal_hold(
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+    json: web::Json<LegalHoldRequest>,
+) -> AppResult<HttpResponse> {
+    let id = path.into_inner();
+    let updated = crate::database::set_job_legal_hold(&data.db_pool, &id, json.legal_hold).await?
+        .ok_or_else(|| AppError::not_found("Job not found"))?;
+    info!("Legal hold on job {} set to {}", id, json.legal_hold);
+    Ok(HttpResponse::Ok().json(updated))
+}
+
+#[api_v2_operation]
+#[paperclip::actix::post("/api/v1/jobs/{id}/restore", summary = "Restore a deleted job and the applications deleted with it")]
+pub async fn restore_job(
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+) -> AppResult<HttpResponse> {
+    let id = path.into_inner();
+    let restored = crate::database::restore_job(&data.db_pool, &id).await?
+        .ok_or_else(|| AppError::not_found("Job not found in trash"))?;
+    info!("Restored job {}", id);
+    Ok(HttpResponse::Ok().json(restored))
 }
 
 #[api_v2_operation]
@@ -101,78 +240,63 @@ pub async fn delete_job(
 pub async fn get_job_matches(
     data: web::Data<AppState>,
     path: web::Path<String>,
-) -> ActixResult<HttpResponse> {
+) -> AppResult<HttpResponse> {
     let job_id = path.into_inner();
     let pool = &data.db_pool;
 
     // Verify job exists
-    let _job = crate::database::get_job_by_id(pool, job_id.clone()).await
-        .map_err(actix_web::error::ErrorInternalServerError)?
-        .ok_or(actix_web::error::ErrorNotFound("Job not found"))?;
+    let _job = crate::database::get_job_by_id(pool, job_id.clone()).await?
+        .ok_or_else(|| AppError::not_found("Job not found"))?;
 
     // Get matches
-    let matches = crate::database::get_job_matches(pool, &job_id).await
-        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let matches = crate::database::get_job_matches(pool, &job_id).await?;
 
     // Enrich with talent data
     let mut matches_with_talent: Vec<JobMatchWithTalent> = Vec::new();
     for m in matches {
-        let talent = crate::database::get_talent_by_id(pool, m.talent_id.clone()).await
-            .map_err(actix_web::error::ErrorInternalServerError)?;
-
-        matches_with_talent.push(JobMatchWithTalent {
-            id: m.id,
-            job_id: m.job_id,
-            talent_id: m.talent_id,
-            score: m.score,
-            rank: m.rank,
-            match_reasons: m.match_reasons
-                .map(|s| serde_json::from_str(&s).unwrap_or_default())
-                .unwrap_or_default(),
-            concerns: m.concerns
-                .map(|s| serde_json::from_str(&s).unwrap_or_default())
-                .unwrap_or_default(),
-            summary: m.summary.unwrap_or_default(),
-            created_at: m.created_at,
-            talent,
-        });
+        matches_with_talent.push(match_runs::with_talent(pool, m).await?);
     }
 
     Ok(HttpResponse::Ok().json(matches_with_talent))
 }
 
+/// Start a matching run and return it with 202. The run works through the
+/// candidate pool in batches of `batch_size` in the background; follow its
+/// progress at `GET /api/v1/jobs/{id}/matches/runs/{run_id}`. Only one run
+/// per job may be running at a time; starting another answers 409. With no
+/// candidates to match, the returned run has already failed.
 #[api_v2_operation]
 #[paperclip::actix::post("/api/v1/jobs/{id}/matches/generate", summary = "Generate candidate matches for a job using AI")]
 pub async fn generate_job_matches(
     data: web::Data<AppState>,
     path: web::Path<String>,
-) -> ActixResult<HttpResponse> {
+    query: web::Query<GenerateMatchesQuery>,
+) -> AppResult<HttpResponse> {
     let job_id = path.into_inner();
     let pool = &data.db_pool;
 
+    let top_n = query.top_n.unwrap_or(match_runs::DEFAULT_TOP_N);
+    if !(1..=match_runs::MAX_TOP_N).contains(&top_n) {
+        return Err(AppError::validation(format!("top_n must be between 1 and {}", match_runs::MAX_TOP_N)));
+    }
+    let batch_size = query.batch_size.unwrap_or(match_runs::DEFAULT_BATCH_SIZE);
+    if !(1..=match_runs::MAX_BATCH_SIZE).contains(&batch_size) {
+        return Err(AppError::validation(format!("batch_size must be between 1 and {}", match_runs::MAX_BATCH_SIZE)));
+    }
+
     info!("[generate_job_matches] Starting for job: {}", job_id);
 
     // Get the job
-    let job = crate::database::get_job_by_id(pool, job_id.clone()).await
-        .map_err(actix_web::error::ErrorInternalServerError)?
-        .ok_or(actix_web::error::ErrorNotFound("Job not found"))?;
+    let job = crate::database::get_job_by_id(pool, job_id.clone()).await?
+        .ok_or_else(|| AppError::not_found("Job not found"))?;
 
     info!("[generate_job_matches] Job found: {} - {}", job.id, job.title);
 
     // Get all talents with collections
-    let talents = crate::database::get_talents_with_collections(pool).await
-        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let talents = crate::database::get_talents_with_collections(pool).await?;
 
     info!("[generate_job_matches] Found {} talents with collections", talents.len());
 
-    if talents.is_empty() {
-        return Ok(HttpResponse::Ok().json(serde_json::json!({
-            "success": true,
-            "message": "No candidates with profiles to match",
-            "matches": []
-        })));
-    }
-
     // Build candidates list for Grok
     let candidates: Vec<TalentForMatching> = talents
         .iter()
@@ -190,9 +314,23 @@ pub async fn generate_job_matches(
 
     info!("[generate_job_matches] Prepared {} candidates for matching", candidates.len());
 
-    // Call Grok service
-    let grok_url = std::env::var("GROK_SERVICE_URL").unwrap_or_else(|_| "http://localhost:8001".to_string());
-    let grok_client = GrokClient::new(&grok_url);
+    let Some(run) = crate::database::create_match_run(pool, &match_runs::new_run(&job_id, top_n, candidates.len(), batch_size)).await? else {
+        let running = crate::database::get_running_match_run(pool, &job_id).await?;
+        return Err(AppError::conflict(match running {
+            Some(running) => format!("Matching run {} is already in progress for this job", running.id),
+            None => "A matching run is already in progress for this job".to_string(),
+        }));
+    };
+
+    // With nobody to match the run fails at once, so the last successful
+    // run's matches stay served instead of being replaced by an empty list
+    if candidates.is_empty() {
+        crate::database::fail_match_run(pool, &run.id, "No candidates with profiles to match", &Utc::now().to_rfc3339()).await?;
+        let run = crate::database::get_match_run(pool, &job_id, &run.id).await?
+            .ok_or_else(|| AppError::internal(format!("Matching run {} disappeared", run.id)))?;
+        info!("[generate_job_matches] No candidates to match; run {} failed", run.id);
+        return Ok(HttpResponse::Accepted().json(run));
+    }
 
     let request = JobMatchingRequest {
         job_id: job.id.clone(),
@@ -202,75 +340,94 @@ pub async fn generate_job_matches(
         skills_required: job.skills_required.clone(),
         experience_level: job.experience_level.clone(),
         candidates,
-        top_n: 10,
+        top_n,
     };
 
-    info!("[generate_job_matches] Calling Grok service...");
+    info!("[generate_job_matches] Started run {} in {} batches", run.id, run.batch_count);
+    data.match_runner.start(run.clone(), request);
+
+    Ok(HttpResponse::Accepted().json(run))
+}
+
+/// List a job's matching runs, newest first. The latest succeeded run is the
+/// one `GET /api/v1/jobs/{id}/matches` serves.
+#[api_v2_operation]
+#[paperclip::actix::get("/api/v1/jobs/{id}/matches/runs", summary = "List matching runs for a job")]
+pub async fn get_match_runs(
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+) -> AppResult<HttpResponse> {
+    let job_id = path.into_inner();
+    let pool = &data.db_pool;
+
+    crate::database::get_job_by_id(pool, job_id.clone()).await?
+        .ok_or_else(|| AppError::not_found("Job not found"))?;
+
+    let runs = crate::database::get_match_runs(pool, &job_id).await?;
+    Ok(HttpResponse::Ok().json(runs))
+}
+
+#[api_v2_operation]
+#[paperclip::actix::get("/api/v1/jobs/{id}/matches/runs/{run_id}", summary = "Get a matching run and its matches")]
+pub async fn get_match_run(
+    data: web::Data<AppState>,
+    path: web::Path<(String, String)>,
+) -> AppResult<HttpResponse> {
+    let (job_id, run_id) = path.into_inner();
+    let pool = &data.db_pool;
 
-    let response = grok_client.match_candidates_to_job(&request).await
-        .map_err(|e| {
-            error!("[generate_job_matches] Grok service error: {}", e);
-            actix_web::error::ErrorInternalServerError(format!("Matching failed: {}", e))
-        })?;
+    let run = crate::database::get_match_run(pool, &job_id, &run_id).await?
+        .ok_or_else(|| AppError::not_found("Match run not found"))?;
 
-    if !response.success {
-        error!("[generate_job_matches] Grok returned error: {:?}", response.error);
-        return Err(actix_web::error::ErrorInternalServerError(
-            response.error.unwrap_or_else(|| "Unknown error".to_string())
-        ));
+    let mut matches = Vec::new();
+    for m in crate::database::get_match_run_results(pool, &run.id).await? {
+        matches.push(match_runs::with_talent(pool, m).await?);
     }
+    Ok(HttpResponse::Ok().json(MatchRunDetail { run, matches }))
+}
+
+/// Cancel a running matching run. The matches being served are left as
+/// they are.
+#[api_v2_operation]
+#[paperclip::actix::post("/api/v1/jobs/{id}/matches/runs/{run_id}/cancel", summary = "Cancel a running matching run")]
+pub async fn cancel_match_run(
+    data: web::Data<AppState>,
+    path: web::Path<(String, String)>,
+) -> AppResult<HttpResponse> {
+    let (job_id, run_id) = path.into_inner();
+    let pool = &data.db_pool;
 
-    let result = response.result.ok_or_else(|| {
-        actix_web::error::ErrorInternalServerError("No result from matching service")
-    })?;
-
-    info!("[generate_job_matches] Got {} matches from Grok", result.matches.len());
-
-    // Delete existing matches for this job
-    crate::database::delete_job_matches_by_job_id(pool, &job_id).await
-        .map_err(actix_web::error::ErrorInternalServerError)?;
-
-    // Store new matches
-    let mut stored_matches: Vec<JobMatchWithTalent> = Vec::new();
-    for m in result.matches {
-        let job_match = JobMatch {
-            id: Uuid::new_v4().to_string(),
-            job_id: job_id.clone(),
-            talent_id: m.talent_id.clone(),
-            score: m.score,
-            rank: m.rank,
-            match_reasons: Some(serde_json::to_string(&m.match_reasons).unwrap_or_default()),
-            concerns: Some(serde_json::to_string(&m.concerns).unwrap_or_default()),
-            summary: Some(m.summary.clone()),
-            created_at: Utc::now().to_rfc3339(),
-        };
-
-        let stored = crate::database::create_job_match(pool, &job_match).await
-            .map_err(actix_web::error::ErrorInternalServerError)?;
-
-        // Get talent for response
-        let talent = crate::database::get_talent_by_id(pool, m.talent_id.clone()).await
-            .map_err(actix_web::error::ErrorInternalServerError)?;
-
-        stored_matches.push(JobMatchWithTalent {
-            id: stored.id,
-            job_id: stored.job_id,
-            talent_id: stored.talent_id,
-            score: stored.score,
-            rank: stored.rank,
-            match_reasons: m.match_reasons,
-            concerns: m.concerns,
-            summary: m.summary,
-            created_at: stored.created_at,
-            talent,
-        });
+    if let Some(run) = data.match_runner.cancel(&job_id, &run_id).await? {
+        info!("[cancel_match_run] Cancelled run {} for job {}", run.id, job_id);
+        return Ok(HttpResponse::Ok().json(run));
     }
+    let run = crate::database::get_match_run(pool, &job_id, &run_id).await?
+        .ok_or_else(|| AppError::not_found("Match run not found"))?;
+    Err(AppError::conflict(format!("Match run has already {}", run.status)))
+}
+
+/// Compare a run's matches with another run of the same job, by default the
+/// succeeded run before it
+#[api_v2_operation]
+#[paperclip::actix::get("/api/v1/jobs/{id}/matches/runs/{run_id}/diff", summary = "Compare a matching run with an earlier one")]
+pub async fn diff_match_runs(
+    data: web::Data<AppState>,
+    path: web::Path<(String, String)>,
+    query: web::Query<MatchRunDiffQuery>,
+) -> AppResult<HttpResponse> {
+    let (job_id, run_id) = path.into_inner();
+    let pool = &data.db_pool;
 
-    info!("[generate_job_matches] Stored {} matches in database", stored_matches.len());
+    let run = crate::database::get_match_run(pool, &job_id, &run_id).await?
+        .ok_or_else(|| AppError::not_found("Match run not found"))?;
+    let against = match &query.against {
+        Some(against) => crate::database::get_match_run(pool, &job_id, against).await?
+            .ok_or_else(|| AppError::not_found("Match run to compare against not found"))?,
+        None => crate::database::get_previous_match_run(pool, &run).await?
+            .ok_or_else(|| AppError::not_found("No earlier succeeded run to compare against"))?,
+    };
 
-    Ok(HttpResponse::Ok().json(serde_json::json!({
-        "success": true,
-        "message": format!("Generated {} matches", stored_matches.len()),
-        "matches": stored_matches
-    })))
+    let current = crate::database::get_match_run_results(pool, &run.id).await?;
+    let previous = crate::database::get_match_run_results(pool, &against.id).await?;
+    Ok(HttpResponse::Ok().json(match_runs::diff(&run.id, &current, &against.id, &previous)))
 }
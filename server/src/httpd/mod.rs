@@ -1,5 +1,19 @@
 pub mod server;
+pub mod request_id;
+pub mod audit;
 pub mod talents;
 pub mod jobs;
 pub mod applications;
-pub mod reorder;
\ No newline at end of file
+pub mod interviews;
+pub mod offers;
+pub mod webhooks;
+pub mod notifications;
+pub mod events;
+pub mod reorder;
+pub mod evaluations;
+pub mod feeds;
+pub mod import;
+pub mod exports;
+pub mod portal;
+pub mod retention;
+pub mod trash;
@@ -0,0 +1,94 @@
+use actix_web::{web, HttpResponse};
+use chrono::Utc;
+use log::info;
+use paperclip::actix::api_v2_operation;
+
+use crate::error::{AppError, AppResult};
+use crate::email;
+use crate::models::{OutboxQuery, UpdateNotificationPreferencesRequest};
+use crate::notifications;
+use super::server::AppState;
+
+const DEFAULT_OUTBOX_LIMIT: i64 = 50;
+const MAX_OUTBOX_LIMIT: i64 = 500;
+
+fn valid_email(email: String) -> AppResult<String> {
+    let email = email.trim().to_lowercase();
+    if !email::is_valid_address(&email) {
+        return Err(AppError::validation("Invalid email address"));
+    }
+    Ok(email)
+}
+
+#[api_v2_operation]
+#[paperclip::actix::get("/api/v1/notifications/preferences/{email}", summary = "Get which optional emails an address receives")]
+pub async fn get_notification_preferences(
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+) -> AppResult<HttpResponse> {
+    let email = valid_email(path.into_inner())?;
+    Ok(HttpResponse::Ok().json(notifications::preferences(&data.db_pool, &email).await?))
+}
+
+#[api_v2_operation]
+#[paperclip::actix::put("/api/v1/notifications/preferences/{email}", summary = "Opt an address in or out of optional emails")]
+pub async fn update_notification_preferences(
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+    json: web::Json<UpdateNotificationPreferencesRequest>,
+) -> AppResult<HttpResponse> {
+    let email = valid_email(path.into_inner())?;
+    let changes = notifications::validate_preferences(&json.preferences)
+        .map_err(|errors| AppError::validation(errors.join("; ")))?;
+
+    crate::database::set_notification_preferences(&data.db_pool, &email, &changes).await?;
+    info!("[notifications] Updated {} notification preferences for {}", changes.len(), email);
+    Ok(HttpResponse::Ok().json(notifications::preferences(&data.db_pool, &email).await?))
+}
+
+#[api_v2_operation]
+#[paperclip::actix::get("/api/v1/notifications/outbox", summary = "List queued and sent emails, newest first")]
+pub async fn get_outbox(
+    data: web::Data<AppState>,
+    query: web::Query<OutboxQuery>,
+) -> AppResult<HttpResponse> {
+    let query = query.into_inner();
+    let status = query.status.as_deref().map(str::trim).map(str::to_lowercase);
+    if status.as_deref().is_some_and(|s| !matches!(s, "pending" | "sent" | "failed")) {
+        return Err(AppError::validation("status must be pending, sent or failed"));
+    }
+    let limit = query.limit.unwrap_or(DEFAULT_OUTBOX_LIMIT).clamp(1, MAX_OUTBOX_LIMIT);
+    let filter = OutboxQuery {
+        status,
+        recipient: query.recipient.map(|r| r.trim().to_string()).filter(|r| !r.is_empty()),
+        limit: Some(limit),
+    };
+
+    let emails: Vec<_> = crate::database::get_outbox_emails(&data.db_pool, &filter, limit).await?
+        .into_iter()
+        .map(email::redacted)
+        .collect();
+    Ok(HttpResponse::Ok().json(emails))
+}
+
+#[api_v2_operation]
+#[paperclip::actix::post("/api/v1/notifications/outbox/{id}/retry", summary = "Queue a failed email to be sent again")]
+pub async fn retry_outbox_email(
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+) -> AppResult<HttpResponse> {
+    let pool = &data.db_pool;
+    let id = path.into_inner();
+    let now = email::due_timestamp(Utc::now());
+
+    let Some(queued) = crate::database::retry_outbox_email(pool, &id, &now).await? else {
+        return match crate::database::get_outbox_email(pool, &id).await? {
+            Some(existing) => Err(AppError::conflict(format!("Only failed emails can be retried; this one is {}", existing.status))),
+            None => Err(AppError::not_found("Email not found")),
+        };
+    };
+    data.notifier.mailer().wake();
+
+    info!("[notifications] Queued email {} to be sent again", queued.id);
+    Ok(HttpResponse::Accepted().json(email::redacted(queued)))
+}
@@ -0,0 +1,384 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use chrono::{DateTime, Utc};
+use log::info;
+use paperclip::actix::api_v2_operation;
+use sqlx::SqliteConnection;
+use uuid::Uuid;
+
+use crate::database::Pool;
+use crate::error::{AppError, AppResult};
+use crate::events::{self, EventKind};
+use crate::models::{
+    CreateOfferRequest, Job, Offer, OfferActionRequest, OfferApproval, OfferDetail, OfferEvent, UpdateOfferRequest,
+};
+use crate::offers::{self, OfferAction, OfferStatus};
+use super::audit;
+use super::server::AppState;
+
+async fn live_offer(pool: &Pool, id: &str) -> AppResult<Offer> {
+    crate::database::get_offer(pool, id).await?
+        .ok_or_else(|| AppError::not_found("Offer not found"))
+}
+
+fn status(offer: &Offer) -> AppResult<OfferStatus> {
+    OfferStatus::parse(&offer.status)
+        .ok_or_else(|| AppError::internal(format!("Offer {} has unknown status '{}'", offer.id, offer.status)))
+}
+
+async fn offer_job(pool: &Pool, offer: &Offer) -> AppResult<Job> {
+    let application = crate::database::get_application_by_id(pool, offer.application_id.clone()).await?
+        .ok_or_else(|| AppError::not_found("Application not found"))?;
+    crate::database::get_job_by_id(pool, application.job_id).await?
+        .ok_or_else(|| AppError::not_found("Job not found"))
+}
+
+/// Check the offer's terms against the job before it is saved or submitted
+fn validate_terms(job: &Job, offer: &Offer) -> AppResult<()> {
+    let mut errors = offers::validate_compensation(job, offer.base_salary, &offer.currency, offer.bonus)
+        .err()
+        .unwrap_or_default();
+    if let Err(e) = offers::parse_start_date(&offer.start_date) {
+        errors.push(e);
+    }
+    if offer.expires_at.as_deref().is_some_and(|at| DateTime::parse_from_rfc3339(at).is_err()) {
+        errors.push("expires_at must be an RFC 3339 timestamp".to_string());
+    }
+    if errors.is_empty() { Ok(()) } else { Err(AppError::validation(errors.join("; "))) }
+}
+
+fn expired(offer: &Offer) -> bool {
+    offer.expires_at
+        .as_deref()
+        .and_then(|at| DateTime::parse_from_rfc3339(at).ok())
+        .is_some_and(|at| at <= Utc::now())
+}
+
+/// Move an offer to `to` and record the change in its history. The update
+/// only applies if the offer still has the status it was loaded with.
+async fn record_transition(
+    conn: &mut SqliteConnection,
+    offer: &Offer,
+    to: OfferStatus,
+    action: &str,
+    actor: &str,
+    comment: Option<String>,
+) -> AppResult<Offer> {
+    let now = Utc::now().to_rfc3339();
+    let updated = Offer { status: to.as_str().to_string(), updated_at: now.clone(), ..offer.clone() };
+    let saved = crate::database::update_offer(&mut *conn, &updated, &offer.status).await?
+        .ok_or_else(|| AppError::conflict("Offer was changed by another request; reload it and try again"))?;
+
+    crate::database::insert_offer_event(&mut *conn, &OfferEvent {
+        id: Uuid::new_v4().to_string(),
+        offer_id: offer.id.clone(),
+        action: action.to_string(),
+        actor: actor.to_string(),
+        from_status: Some(offer.status.clone()),
+        to_status: saved.status.clone(),
+        comment,
+        created_at: now,
+    }).await?;
+    Ok(saved)
+}
+
+async fn detail(pool: &Pool, offer: Offer) -> AppResult<OfferDetail> {
+    Ok(OfferDetail {
+        approvals: crate::database::get_offer_approvals(pool, &offer.id).await?,
+        history: crate::database::get_offer_events(pool, &offer.id).await?,
+        offer,
+    })
+}
+
+/// The approval step waiting on a decision, which `actor` must own
+fn current_approval(approvals: &[OfferApproval], actor: &str) -> AppResult<OfferApproval> {
+    let current = approvals
+        .iter()
+        .find(|a| a.decision.is_none())
+        .ok_or_else(|| AppError::conflict("Offer has no pending approval step"))?;
+    if !current.approver.eq_ignore_ascii_case(actor) {
+        return Err(AppError::forbidden(format!(
+            "Offer is waiting on approval from {} (step {})",
+            current.approver, current.step
+        )));
+    }
+    Ok(current.clone())
+}
+
+#[api_v2_operation]
+#[paperclip::actix::post("/api/v1/applications/{id}/offers", summary = "Draft an offer for an application")]
+pub async fn create_offer(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<String>,
+    json: web::Json<CreateOfferRequest>,
+) -> AppResult<HttpResponse> {
+    let pool = &data.db_pool;
+    let application = crate::database::get_application_by_id(pool, path.into_inner()).await?
+        .ok_or_else(|| AppError::not_found("Application not found"))?;
+    if matches!(application.status.as_str(), "rejected" | "withdrawn") {
+        return Err(AppError::conflict(format!("Application is {}", application.status)));
+    }
+    if let Some(open) = crate::database::get_open_offer(pool, &application.id).await? {
+        return Err(AppError::conflict(format!("Application already has an open offer ({})", open.id)));
+    }
+    let job = crate::database::get_job_by_id(pool, application.job_id.clone()).await?
+        .ok_or_else(|| AppError::not_found("Job not found"))?;
+
+    let currency = json.currency.clone()
+        .or_else(|| job.salary_currency.clone())
+        .ok_or_else(|| AppError::validation("currency is required when the job has no salary currency"))?;
+    let actor = audit::actor(req.headers(), &data);
+    let now = Utc::now().to_rfc3339();
+    let offer = Offer {
+        id: Uuid::new_v4().to_string(),
+        application_id: application.id.clone(),
+        status: OfferStatus::Draft.as_str().to_string(),
+        base_salary: json.base_salary,
+        currency: currency.trim().to_uppercase(),
+        bonus: json.bonus,
+        equity: json.equity.clone(),
+        start_date: json.start_date.trim().to_string(),
+        expires_at: json.expires_at.clone(),
+        notes: json.notes.clone(),
+        created_by: actor.clone(),
+        created_at: now.clone(),
+        updated_at: now.clone(),
+    };
+    validate_terms(&job, &offer)?;
+
+    let mut tx = pool.begin().await?;
+    // The index on open offers settles a race with a concurrent request
+    let inserted = crate::database::create_offer(&mut *tx, &offer).await?
+        .ok_or_else(|| AppError::conflict("Application already has an open offer"))?;
+    crate::database::insert_offer_event(&mut *tx, &OfferEvent {
+        id: Uuid::new_v4().to_string(),
+        offer_id: inserted.id.clone(),
+        action: "create".to_string(),
+        actor,
+        from_status: None,
+        to_status: inserted.status.clone(),
+        comment: None,
+        created_at: now,
+    }).await?;
+    tx.commit().await?;
+
+    audit::add_targets([inserted.id.clone(), application.id, application.talent_id]);
+    info!("Drafted offer {} for application {}", inserted.id, inserted.application_id);
+    Ok(HttpResponse::Created().json(detail(pool, inserted).await?))
+}
+
+#[api_v2_operation]
+#[paperclip::actix::get("/api/v1/applications/{id}/offers", summary = "List an application's offers")]
+pub async fn get_offers_by_application(
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+) -> AppResult<HttpResponse> {
+    let pool = &data.db_pool;
+    let application = crate::database::get_application_by_id(pool, path.into_inner()).await?
+        .ok_or_else(|| AppError::not_found("Application not found"))?;
+    let offers = crate::database::get_offers_by_application(pool, &application.id).await?;
+    Ok(HttpResponse::Ok().json(offers))
+}
+
+#[api_v2_operation]
+#[paperclip::actix::get("/api/v1/offers/{id}", summary = "Get an offer with its approvals and history")]
+pub async fn get_offer(
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+) -> AppResult<HttpResponse> {
+    let pool = &data.db_pool;
+    let offer = live_offer(pool, &path.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(detail(pool, offer).await?))
+}
+
+#[api_v2_operation]
+#[paperclip::actix::put("/api/v1/offers/{id}", summary = "Edit the terms of a draft offer")]
+pub async fn update_offer(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<String>,
+    json: web::Json<UpdateOfferRequest>,
+) -> AppResult<HttpResponse> {
+    let pool = &data.db_pool;
+    let current = live_offer(pool, &path.into_inner()).await?;
+    if status(&current)? != OfferStatus::Draft {
+        return Err(AppError::conflict(format!("Only draft offers can be edited; this offer is {}", current.status)));
+    }
+    let job = offer_job(pool, &current).await?;
+
+    let json = json.into_inner();
+    let updated = Offer {
+        base_salary: json.base_salary.unwrap_or(current.base_salary),
+        currency: json.currency.map(|c| c.trim().to_uppercase()).unwrap_or_else(|| current.currency.clone()),
+        bonus: json.bonus.or(current.bonus),
+        equity: json.equity.or_else(|| current.equity.clone()),
+        start_date: json.start_date.map(|d| d.trim().to_string()).unwrap_or_else(|| current.start_date.clone()),
+        expires_at: json.expires_at.or_else(|| current.expires_at.clone()),
+        notes: json.notes.or_else(|| current.notes.clone()),
+        ..current.clone()
+    };
+    validate_terms(&job, &updated)?;
+
+    let mut tx = pool.begin().await?;
+    let saved = record_transition(&mut tx, &updated, OfferStatus::Draft, "update", &audit::actor(req.headers(), &data), None).await?;
+    tx.commit().await?;
+    Ok(HttpResponse::Ok().json(detail(pool, saved).await?))
+}
+
+#[api_v2_operation]
+#[paperclip::actix::post("/api/v1/offers/{id}/submit", summary = "Submit a draft offer for approval")]
+pub async fn submit_offer(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<String>,
+    json: web::Json<OfferActionRequest>,
+) -> AppResult<HttpResponse> {
+    let pool = &data.db_pool;
+    let offer = live_offer(pool, &path.into_inner()).await?;
+    let approvers = &data.offer_approvers;
+    let next = offers::transition(status(&offer)?, OfferAction::Submit, approvers.len())
+        .map_err(AppError::conflict)?;
+
+    // The job's salary range may have changed since the offer was drafted
+    validate_terms(&offer_job(pool, &offer).await?, &offer)?;
+    if expired(&offer) {
+        return Err(AppError::validation("expires_at has already passed"));
+    }
+
+    let mut tx = pool.begin().await?;
+    crate::database::reset_offer_approvals(&mut tx, &offer.id, approvers).await?;
+    let saved = record_transition(&mut tx, &offer, next, "submit", &audit::actor(req.headers(), &data), json.into_inner().comment).await?;
+    tx.commit().await?;
+
+    info!("Offer {} submitted; now {}", saved.id, saved.status);
+    Ok(HttpResponse::Ok().json(detail(pool, saved).await?))
+}
+
+#[api_v2_operation]
+#[paperclip::actix::post("/api/v1/offers/{id}/approve", summary = "Approve the current step of an offer's approval chain (as X-Actor)")]
+pub async fn approve_offer(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<String>,
+    json: web::Json<OfferActionRequest>,
+) -> AppResult<HttpResponse> {
+    decide(data, req, path.into_inner(), json.into_inner(), OfferAction::Approve).await
+}
+
+#[api_v2_operation]
+#[paperclip::actix::post("/api/v1/offers/{id}/reject", summary = "Reject an offer at the current approval step, returning it to draft (as X-Actor)")]
+pub async fn reject_offer(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<String>,
+    json: web::Json<OfferActionRequest>,
+) -> AppResult<HttpResponse> {
+    decide(data, req, path.into_inner(), json.into_inner(), OfferAction::Reject).await
+}
+
+async fn decide(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    id: String,
+    request: OfferActionRequest,
+    action: OfferAction,
+) -> AppResult<HttpResponse> {
+    let pool = &data.db_pool;
+    let offer = live_offer(pool, &id).await?;
+    let actor = audit::actor(req.headers(), &data);
+    if status(&offer)? != OfferStatus::PendingApproval {
+        return Err(AppError::conflict(format!("Offer is {}, not pending approval", offer.status)));
+    }
+    let approvals = crate::database::get_offer_approvals(pool, &offer.id).await?;
+    let step = current_approval(&approvals, &actor)?;
+    let remaining = approvals.iter().filter(|a| a.decision.is_none()).count() - 1;
+    let next = offers::transition(OfferStatus::PendingApproval, action, remaining).map_err(AppError::conflict)?;
+
+    let decision = if action == OfferAction::Approve { "approved" } else { "rejected" };
+    let mut tx = pool.begin().await?;
+    let decided = crate::database::decide_offer_approval(
+        &mut *tx,
+        &offer.id,
+        step.step,
+        decision,
+        request.comment.as_deref(),
+        &Utc::now().to_rfc3339(),
+    ).await?;
+    if !decided {
+        return Err(AppError::conflict("Approval step was decided by another request"));
+    }
+    let saved = record_transition(&mut tx, &offer, next, action.as_str(), &actor, request.comment).await?;
+    tx.commit().await?;
+
+    info!("Offer {} {} by {} at step {}; now {}", saved.id, decision, actor, step.step, saved.status);
+    Ok(HttpResponse::Ok().json(detail(pool, saved).await?))
+}
+
+/// Record the candidate's answer, or withdraw the offer
+async fn respond(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    id: String,
+    request: OfferActionRequest,
+    action: OfferAction,
+) -> AppResult<HttpResponse> {
+    let pool = &data.db_pool;
+    let offer = live_offer(pool, &id).await?;
+    let next = offers::transition(status(&offer)?, action, 0).map_err(AppError::conflict)?;
+    if action == OfferAction::Accept && expired(&offer) {
+        return Err(AppError::conflict(format!(
+            "Offer expired at {}",
+            offer.expires_at.as_deref().unwrap_or_default()
+        )));
+    }
+
+    let mut tx = pool.begin().await?;
+    let saved = record_transition(&mut tx, &offer, next, action.as_str(), &audit::actor(req.headers(), &data), request.comment).await?;
+    let mut status_change = None;
+    if next == OfferStatus::Accepted {
+        let previous = crate::database::get_application_by_id(&mut *tx, offer.application_id.clone()).await?
+            .map(|application| application.status);
+        let accepted = crate::database::update_application_status(&mut *tx, &offer.application_id, "accepted").await?;
+        status_change = accepted.zip(previous).filter(|(accepted, previous)| accepted.status != *previous);
+    }
+    tx.commit().await?;
+
+    info!("Offer {} {}", saved.id, saved.status);
+    if let Some((application, previous)) = status_change {
+        data.events.publish(EventKind::ApplicationStatusChanged, events::application_status_changed(&application, &previous)).await;
+    }
+    Ok(HttpResponse::Ok().json(detail(pool, saved).await?))
+}
+
+#[api_v2_operation]
+#[paperclip::actix::post("/api/v1/offers/{id}/accept", summary = "Record that the candidate accepted an extended offer")]
+pub async fn accept_offer(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<String>,
+    json: web::Json<OfferActionRequest>,
+) -> AppResult<HttpResponse> {
+    respond(data, req, path.into_inner(), json.into_inner(), OfferAction::Accept).await
+}
+
+#[api_v2_operation]
+#[paperclip::actix::post("/api/v1/offers/{id}/decline", summary = "Record that the candidate declined an extended offer")]
+pub async fn decline_offer(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<String>,
+    json: web::Json<OfferActionRequest>,
+) -> AppResult<HttpResponse> {
+    respond(data, req, path.into_inner(), json.into_inner(), OfferAction::Decline).await
+}
+
+#[api_v2_operation]
+#[paperclip::actix::post("/api/v1/offers/{id}/rescind", summary = "Rescind an offer that has not been answered")]
+pub async fn rescind_offer(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<String>,
+    json: web::Json<OfferActionRequest>,
+) -> AppResult<HttpResponse> {
+    respond(data, req, path.into_inner(), json.into_inner(), OfferAction::Rescind).await
+}
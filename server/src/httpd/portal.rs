@@ -0,0 +1,320 @@
+//! Candidate self-service portal
+//!
+//! Every endpoint except login/session requires a `CandidateSession` and only
+//! ever touches the data of the talent the session belongs to.
+
+use std::future::{ready, Ready};
+
+use actix_web::{dev::Payload, http::header, web, FromRequest, HttpRequest, HttpResponse};
+use chrono::{Duration, Utc};
+use log::{info, warn};
+use paperclip::actix::{api_v2_operation, Apiv2Security};
+
+use crate::error::{AppError, AppResult};
+use crate::events::{self, EventKind};
+use crate::models::{
+    Application, CandidateApplication, CandidateProfile, PortalLoginRequest, PortalLoginResponse,
+    PortalResumeUploadRequest, PortalSessionRequest, PortalSessionResponse, Talent,
+    UpdateCandidateProfileRequest, UpdateNotificationPreferencesRequest, UpdateTalentRequest,
+};
+use crate::notifications;
+use crate::portal;
+use crate::resume_pipeline;
+use super::server::AppState;
+
+/// Authenticated candidate, extracted from `Authorization: Bearer <session token>`
+#[derive(Apiv2Security)]
+#[openapi(
+    apiKey,
+    in = "header",
+    name = "Authorization",
+    description = "Candidate portal session: 'Bearer <session_token>'"
+)]
+pub struct CandidateSession {
+    pub talent_id: String,
+}
+
+impl FromRequest for CandidateSession {
+    type Error = AppError;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        let Some(state) = req.app_data::<web::Data<AppState>>() else {
+            return ready(Err(AppError::internal("Application state missing")));
+        };
+
+        let token = req
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .map(|v| v.trim());
+
+        let claims = token.and_then(|t| portal::verify_session(&state.portal_secret, t, Utc::now()));
+        ready(match claims {
+            Some(claims) => Ok(CandidateSession { talent_id: claims.talent_id }),
+            None => Err(AppError::unauthorized("Missing or invalid candidate session")),
+        })
+    }
+}
+
+impl CandidateSession {
+    async fn talent(&self, data: &AppState) -> AppResult<Talent> {
+        crate::database::get_talent_by_id(&data.db_pool, self.talent_id.clone()).await?
+            .ok_or_else(|| AppError::unauthorized("Candidate profile no longer exists"))
+    }
+
+    /// Load an application, enforcing that it belongs to this candidate
+    async fn owned_application(&self, data: &AppState, id: String) -> AppResult<Application> {
+        crate::database::get_application_by_id(&data.db_pool, id).await?
+            .filter(|app| app.talent_id == self.talent_id)
+            .ok_or_else(|| AppError::not_found("Application not found"))
+    }
+}
+
+#[api_v2_operation]
+#[paperclip::actix::post("/api/v1/portal/login", summary = "Request a magic sign-in link for the candidate portal")]
+pub async fn portal_login(
+    data: web::Data<AppState>,
+    json: web::Json<PortalLoginRequest>,
+) -> AppResult<HttpResponse> {
+    let pool = &data.db_pool;
+    let email = json.email.trim().to_string();
+    if email.is_empty() {
+        return Err(AppError::validation("Email is required"));
+    }
+
+    // Same response whether or not the email is known, to avoid account enumeration
+    if let Some(talent) = crate::database::get_talent_by_email(pool, email).await? {
+        // The link is a sign-in credential, so it is only ever sent by email
+        let window_start = Utc::now() - Duration::minutes(portal::MAGIC_LINK_TTL_MINUTES);
+        let recent = crate::database::count_candidate_login_tokens_since(pool, &talent.id, &window_start.to_rfc3339()).await?;
+        if !data.notifier.mailer().is_configured() {
+            warn!("[portal] No mailer configured; no sign-in link sent to talent {}", talent.id);
+        } else if recent >= portal::MAX_MAGIC_LINKS_PER_TTL {
+            warn!("[portal] Sign-in link rate limit reached; no link sent to talent {}", talent.id);
+        } else {
+            let token = portal::generate_token();
+            let expires_at = Utc::now() + Duration::minutes(portal::MAGIC_LINK_TTL_MINUTES);
+            crate::database::create_candidate_login_token(
+                pool,
+                &portal::hash_token(&token),
+                &talent.id,
+                &expires_at.to_rfc3339(),
+            ).await?;
+
+            let link = format!("{}/portal/login?token={}", data.public_board_url, token);
+            data.notifier.magic_link(&talent, &link, portal::MAGIC_LINK_TTL_MINUTES).await;
+        }
+    }
+
+    Ok(HttpResponse::Accepted().json(PortalLoginResponse {
+        message: "If this email belongs to a candidate, a sign-in link has been sent".to_string(),
+    }))
+}
+
+#[api_v2_operation]
+#[paperclip::actix::post("/api/v1/portal/session", summary = "Exchange a magic link token for a portal session")]
+pub async fn portal_session(
+    data: web::Data<AppState>,
+    json: web::Json<PortalSessionRequest>,
+) -> AppResult<HttpResponse> {
+    let now = Utc::now();
+    let talent_id = crate::database::consume_candidate_login_token(
+        &data.db_pool,
+        &portal::hash_token(json.token.trim()),
+        &now.to_rfc3339(),
+    ).await?
+    .ok_or_else(|| AppError::unauthorized("Sign-in link is invalid or has expired"))?;
+
+    let (session_token, expires_at) = portal::new_session(&data.portal_secret, &talent_id, now);
+    info!("[portal] Session started for talent {}", talent_id);
+
+    Ok(HttpResponse::Ok().json(PortalSessionResponse {
+        session_token,
+        talent_id,
+        expires_at: expires_at.to_rfc3339(),
+    }))
+}
+
+#[api_v2_operation]
+#[paperclip::actix::get("/api/v1/portal/me", summary = "Get the signed-in candidate's profile")]
+pub async fn get_portal_profile(
+    data: web::Data<AppState>,
+    session: CandidateSession,
+) -> AppResult<HttpResponse> {
+    let talent = session.talent(&data).await?;
+    Ok(HttpResponse::Ok().json(CandidateProfile::from(talent)))
+}
+
+#[api_v2_operation]
+#[paperclip::actix::put("/api/v1/portal/me", summary = "Update the signed-in candidate's profile")]
+pub async fn update_portal_profile(
+    data: web::Data<AppState>,
+    session: CandidateSession,
+    json: web::Json<UpdateCandidateProfileRequest>,
+) -> AppResult<HttpResponse> {
+    let talent = session.talent(&data).await?;
+    let json = json.into_inner();
+
+    // update_talent overwrites every column, so carry over the current values
+    // for anything the candidate did not send. Email and verification stay
+    // under recruiter control.
+    let updates = UpdateTalentRequest {
+        name: Some(json.name.unwrap_or(talent.name)),
+        email: Some(talent.email),
+        handle: Some(json.handle.unwrap_or(talent.handle)),
+        avatar: json.avatar.or(talent.avatar),
+        title: Some(json.title.unwrap_or(talent.title)),
+        location: json.location.or(talent.location),
+        experience: Some(json.experience.unwrap_or(talent.experience)),
+        // Stored skills are JSON encoded; decode before carrying them over
+        skills: Some(json.skills.unwrap_or_else(|| {
+            serde_json::from_str::<String>(&talent.skills).unwrap_or(talent.skills)
+        })),
+        bio: json.bio.or(talent.bio),
+        verified: Some(talent.verified != 0),
+    };
+
+    let updated = crate::database::update_talent(&data.db_pool, talent.id, &updates).await?
+        .ok_or_else(|| AppError::not_found("Talent not found"))?;
+    Ok(HttpResponse::Ok().json(CandidateProfile::from(updated)))
+}
+
+#[api_v2_operation]
+#[paperclip::actix::get("/api/v1/portal/applications", summary = "List the signed-in candidate's applications")]
+pub async fn get_portal_applications(
+    data: web::Data<AppState>,
+    session: CandidateSession,
+) -> AppResult<HttpResponse> {
+    let pool = &data.db_pool;
+    let applications = crate::database::get_applications_by_talent(pool, session.talent_id.clone()).await?;
+
+    let mut responses = Vec::with_capacity(applications.len());
+    for app in applications {
+        let job = crate::database::get_job_by_id(pool, app.job_id.clone()).await?;
+        responses.push(CandidateApplication {
+            id: app.id,
+            job_id: app.job_id,
+            job_title: job.as_ref().map(|j| j.title.clone()),
+            company_name: job.map(|j| j.company_name),
+            has_resume: app.resume_data.is_some(),
+            resume_filename: app.resume_filename,
+            cover_letter: app.cover_letter,
+            status: app.status,
+            created_at: app.created_at,
+        });
+    }
+
+    Ok(HttpResponse::Ok().json(responses))
+}
+
+#[api_v2_operation]
+#[paperclip::actix::post("/api/v1/portal/applications/{id}/withdraw", summary = "Withdraw one of the signed-in candidate's applications")]
+pub async fn withdraw_portal_application(
+    data: web::Data<AppState>,
+    session: CandidateSession,
+    path: web::Path<String>,
+) -> AppResult<HttpResponse> {
+    let application = session.owned_application(&data, path.into_inner()).await?;
+
+    if !matches!(application.status.as_str(), "pending" | "reviewed") {
+        return Err(AppError::conflict(format!(
+            "An application with status '{}' can no longer be withdrawn",
+            application.status
+        )));
+    }
+
+    let updated = crate::database::update_application_status(&data.db_pool, &application.id, "withdrawn").await?
+        .ok_or_else(|| AppError::not_found("Application not found"))?;
+    info!("[portal] Talent {} withdrew application {}", session.talent_id, updated.id);
+    data.events.publish(
+        EventKind::ApplicationStatusChanged,
+        events::application_status_changed(&updated, &application.status),
+    ).await;
+
+    Ok(HttpResponse::Ok().json(CandidateApplication {
+        id: updated.id,
+        job_id: updated.job_id,
+        job_title: None,
+        company_name: None,
+        has_resume: updated.resume_data.is_some(),
+        resume_filename: updated.resume_filename,
+        cover_letter: updated.cover_letter,
+        status: updated.status,
+        created_at: updated.created_at,
+    }))
+}
+
+#[api_v2_operation]
+#[paperclip::actix::post("/api/v1/portal/resume", summary = "Upload a new resume for the signed-in candidate")]
+pub async fn upload_portal_resume(
+    data: web::Data<AppState>,
+    session: CandidateSession,
+    json: web::Json<PortalResumeUploadRequest>,
+) -> AppResult<HttpResponse> {
+    let pool = &data.db_pool;
+    let talent = session.talent(&data).await?;
+
+    use base64::{Engine as _, engine::general_purpose::STANDARD};
+    let pdf_bytes = STANDARD.decode(&json.resume_data)
+        .map_err(|_| AppError::validation("resume_data must be base64 encoded"))?;
+    if pdf_bytes.is_empty() {
+        return Err(AppError::validation("resume_data is empty"));
+    }
+
+    // Optionally attach the new resume to one of the candidate's applications
+    let mut job = None;
+    if let Some(application_id) = json.application_id.clone() {
+        let application = session.owned_application(&data, application_id).await?;
+        crate::database::update_application_resume(
+            pool,
+            &application.id,
+            &json.resume_data,
+            json.resume_filename.as_deref(),
+            json.resume_content_type.as_deref(),
+        ).await?;
+        job = crate::database::get_job_by_id(pool, application.job_id).await?;
+    }
+
+    let filename = json.resume_filename.clone().unwrap_or_else(|| "resume.pdf".to_string());
+    info!("[portal] Talent {} uploaded a new resume ({} bytes)", talent.id, pdf_bytes.len());
+
+    tokio::spawn(resume_pipeline::run(
+        pool.clone(),
+        data.events.clone(),
+        data.grok_service_url.clone(),
+        talent.clone(),
+        job,
+        pdf_bytes,
+        filename,
+    ));
+
+    Ok(HttpResponse::Accepted().json(CandidateProfile::from(talent)))
+}
+
+#[api_v2_operation]
+#[paperclip::actix::get("/api/v1/portal/notifications", summary = "Get which optional emails the signed-in candidate receives")]
+pub async fn get_portal_notifications(
+    data: web::Data<AppState>,
+    session: CandidateSession,
+) -> AppResult<HttpResponse> {
+    let talent = session.talent(&data).await?;
+    Ok(HttpResponse::Ok().json(notifications::preferences(&data.db_pool, &talent.email).await?))
+}
+
+#[api_v2_operation]
+#[paperclip::actix::put("/api/v1/portal/notifications", summary = "Opt the signed-in candidate in or out of optional emails")]
+pub async fn update_portal_notifications(
+    data: web::Data<AppState>,
+    session: CandidateSession,
+    json: web::Json<UpdateNotificationPreferencesRequest>,
+) -> AppResult<HttpResponse> {
+    let talent = session.talent(&data).await?;
+    let changes = notifications::validate_preferences(&json.preferences)
+        .map_err(|errors| AppError::validation(errors.join("; ")))?;
+
+    crate::database::set_notification_preferences(&data.db_pool, &talent.email, &changes).await?;
+    info!("[portal] Talent {} updated their notification preferences", talent.id);
+    Ok(HttpResponse::Ok().json(notifications::preferences(&data.db_pool, &talent.email).await?))
+}
@@ -1,144 +1,550 @@
-use actix_web::{web, HttpResponse, Result as ActixResult};
+use actix_web::{web, HttpRequest, HttpResponse};
+use actix_ws::{Message, Session};
 use paperclip::actix::api_v2_operation;
+use serde_json::Value;
+use tokio::sync::broadcast;
 use uuid::Uuid;
 use chrono::Utc;
 use std::collections::HashMap;
+use std::time::Duration;
 
 use crate::{
-    database,
-    models::{ApiError, CreateReorderEventRequest, ReorderEvent, ReorderResponse},
-    reorder::derive_pairwise_preferences,
+    database::{self, Pool},
+    error::{AppError, AppResult},
+    events::{self, EventKind},
+    job_order, preference_analysis,
+    models::{
+        CandidateOrderMessage, CreatePreferenceSignalRequest, CreateReorderEventRequest, JobCandidateOrder,
+        JobCandidateOrderResponse, OrderedCandidate, PreferenceSignalResponse, ReorderEvent, ReorderResponse,
+        ResolvePreferencesResponse, StoredEvent,
+    },
+    reorder::{self, derive_pairwise_preferences, validate_rearrangement, PreferenceContext},
 };
+use super::audit;
 use super::server::AppState;
 
+/// How often idle order sockets are pinged to keep them open
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
+
 /// Reorder candidates for a job
 ///
 /// This endpoint accepts a reorder event (before/after candidate order) and:
-/// 1. Stores the raw reorder event
-/// 2. Derives pairwise preferences (C ≻ D) from position changes
-/// 3. Persists preferences to the database (idempotent via UNIQUE constraint)
+/// 1. Checks `before_order` against the job's current order, rejecting
+///    reorders made against a stale order with 409
+/// 2. Stores `after_order` as the job's new current order
+/// 3. Stores the raw reorder event
+/// 4. Derives pairwise preferences (C ≻ D) from position changes, either
+///    around `moved_talent_id` (`derivation: moved`, the default) or from
+///    every flipped pair with confidence decaying by distance
+///    (`derivation: inversions`)
+/// 5. Persists preferences to the database (idempotent via UNIQUE constraint)
+///
+/// Steps 2 to 5 happen in one transaction.
 #[api_v2_operation]
 #[paperclip::actix::post("/api/v1/reorder", summary = "Reorder candidates and derive preferences")]
 pub async fn reorder_candidates(
+    req: HttpRequest,
     data: web::Data<AppState>,
     request: web::Json<CreateReorderEventRequest>,
-) -> ActixResult<HttpResponse> {
+) -> AppResult<HttpResponse> {
     let pool = &data.db_pool;
+    let author = preference_author(&req, &data);
     let job_id = &request.job_id;
+    audit::add_targets(std::iter::once(job_id.clone()).chain(request.moved_talent_id.clone()));
     let before_order = &request.before_order;
     let after_order = &request.after_order;
 
     // Validate input
     if before_order.len() != after_order.len() {
-        return Ok(HttpResponse::BadRequest().json(ApiError {
-            message: "Before and after order lengths must match".to_string(),
-            code: 400,
-        }));
+        return Err(AppError::validation("Before and after order lengths must match"));
     }
 
     if before_order.is_empty() {
-        return Ok(HttpResponse::BadRequest().json(ApiError {
-            message: "Order arrays cannot be empty".to_string(),
-            code: 400,
-        }));
+        return Err(AppError::validation("Order arrays cannot be empty"));
+    }
+
+    validate_rearrangement(before_order, after_order).map_err(AppError::validation)?;
+
+    let derivation = request.derivation.as_deref().map(str::trim).unwrap_or("moved");
+    if !matches!(derivation, "moved" | "inversions") {
+        return Err(AppError::validation("derivation must be moved or inversions"));
     }
 
     // Fetch job details for job_text
-    let job = database::get_job_by_id(&pool, job_id.clone())
-        .await
-        .map_err(actix_web::error::ErrorInternalServerError)?
-        .ok_or_else(|| {
-            actix_web::error::ErrorNotFound(format!("Job not found: {}", job_id))
-        })?;
+    let job = database::get_job_by_id(pool, job_id.clone())
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Job not found: {}", job_id)))?;
 
     let job_text = format!("{} at {}", job.title, job.company_name);
 
-    // Fetch talent names for all candidates
-    let mut talent_names = HashMap::new();
-    for talent_id in before_order.iter().chain(after_order.iter()) {
-        if !talent_names.contains_key(talent_id) {
-            if let Ok(Some(talent)) = database::get_talent_by_id(&pool, talent_id.clone()).await {
-                talent_names.insert(talent_id.clone(), talent.name);
-            }
-        }
+    // Preferences derived from a stale order would contradict what the other
+    // recruiter just did, so the client must reload and reapply its change.
+    // Candidates that came or went since the last change count as a change.
+    job_order::reconcile(pool, &data.events, job_id).await?;
+    // A job never reordered is at its natural order, version 0, as GET
+    // /order serves it, so a stale first reorder is caught too
+    let (current, version) = job_order::current_or_default(pool, job_id).await?;
+    if request.expected_version.is_some_and(|expected| expected != version) {
+        return Err(AppError::conflict(format!(
+            "The order of this job is at version {}, not {}; reload it and try again",
+            version,
+            request.expected_version.unwrap_or_default()
+        )));
+    }
+    if &current != before_order {
+        return Err(AppError::conflict(format!(
+            "Before order does not match the current order of this job (version {}); reload it and try again",
+            version
+        )));
     }
 
-    // Create reorder event
+    // Fetch talent names for all candidates
+    let talent_names = talent_names(pool, before_order).await?;
+
+    // Claim the new order first so only one of two concurrent reorders wins
+    let mut tx = pool.begin().await?;
     let event_id = Uuid::new_v4().to_string();
+    let order = JobCandidateOrder {
+        job_id: job_id.clone(),
+        talent_ids: serde_json::to_string(&after_order)?,
+        version: version + 1,
+        reorder_event_id: Some(event_id.clone()),
+        updated_at: Utc::now().to_rfc3339(),
+    };
+    let saved = database::save_job_candidate_order(&mut *tx, &order, version).await?
+        .ok_or_else(|| AppError::conflict("This job was reordered at the same time; reload its order and try again"))?;
+
+    // Create reorder event
     let event = ReorderEvent {
         id: event_id.clone(),
         job_id: job_id.clone(),
-        before_order: serde_json::to_string(&before_order).unwrap(),
-        after_order: serde_json::to_string(&after_order).unwrap(),
+        before_order: serde_json::to_string(&before_order)?,
+        after_order: serde_json::to_string(&after_order)?,
         moved_talent_id: request.moved_talent_id.clone(),
         event_timestamp: Utc::now().to_rfc3339(),
         created_at: Utc::now().to_rfc3339(),
     };
 
     // Store reorder event
-    database::create_reorder_event(&pool, &event)
-        .await
-        .map_err(actix_web::error::ErrorInternalServerError)?;
+    database::create_reorder_event(&mut *tx, &event).await?;
 
     // Derive pairwise preferences
-    let preferences = derive_pairwise_preferences(
-        before_order,
-        after_order,
-        job_id,
-        &job_text,
-        &talent_names,
-        &event_id,
-        request.moved_talent_id.as_deref(),
-    );
+    let preferences = if derivation == "inversions" {
+        let ctx = PreferenceContext {
+            job_id,
+            job_text: &job_text,
+            talent_names: &talent_names,
+            reorder_event_id: Some(&event_id),
+            author: author.as_deref(),
+        };
+        reorder::derive_inversion_preferences(before_order, after_order, &ctx)
+    } else {
+        let mut preferences = derive_pairwise_preferences(
+            before_order,
+            after_order,
+            job_id,
+            &job_text,
+            &talent_names,
+            &event_id,
+            request.moved_talent_id.as_deref(),
+        );
+        for pref in &mut preferences {
+            pref.author = author.clone();
+        }
+        preferences
+    };
 
     // Store preferences (INSERT OR IGNORE for idempotency)
     let mut created_count = 0;
     for pref in preferences {
-        match database::create_pairwise_preference(&pool, &pref).await {
-            Ok(Some(_)) => created_count += 1,
-            Ok(None) => {
+        match database::create_pairwise_preference(&mut *tx, &pref).await? {
+            Some(_) => created_count += 1,
+            None => {
                 // Duplicate preference, ignored
                 log::debug!("Duplicate preference ignored: {} ≻ {}", pref.winner_id, pref.loser_id);
             }
-            Err(e) => {
-                log::error!("Failed to create preference: {}", e);
-            }
         }
     }
+    tx.commit().await?;
+
+    data.events.publish(EventKind::ReorderCreated, events::reorder_created(&event, created_count, saved.version)).await;
 
     Ok(HttpResponse::Ok().json(ReorderResponse {
         event_id,
         preferences_created: created_count,
+        version: saved.version,
         message: format!("Reorder event saved with {} preferences created", created_count),
     }))
 }
 
+/// Get a job's current candidate order
+///
+/// Candidates are listed in the recruiters' order, which is kept up to date
+/// as talents apply or are matched. Until the job is first reordered the
+/// order is applicants newest first, then matches by rank, at version 0.
+#[api_v2_operation]
+#[paperclip::actix::get("/api/v1/jobs/{job_id}/order", summary = "Get a job's current candidate order")]
+pub async fn get_job_order(
+    data: web::Data<AppState>,
+    job_id: web::Path<String>,
+) -> AppResult<HttpResponse> {
+    let job_id = job_id.into_inner();
+    ensure_job(&data.db_pool, &job_id).await?;
+    job_order_response(&data.db_pool, job_id).await
+}
+
+/// Reconcile a job's candidate order
+///
+/// Brings the stored order in line with the job's current applicants and
+/// matches, e.g. after a reconciliation failed, and returns it. Publishes
+/// `job_order.reconciled` when the order changed.
+#[api_v2_operation]
+#[paperclip::actix::post("/api/v1/jobs/{job_id}/order/reconcile", summary = "Reconcile a job's candidate order")]
+pub async fn reconcile_job_order(
+    data: web::Data<AppState>,
+    job_id: web::Path<String>,
+) -> AppResult<HttpResponse> {
+    let job_id = job_id.into_inner();
+    ensure_job(&data.db_pool, &job_id).await?;
+    job_order::reconcile(&data.db_pool, &data.events, &job_id).await?;
+    job_order_response(&data.db_pool, job_id).await
+}
+
+async fn ensure_job(pool: &Pool, job_id: &str) -> AppResult<()> {
+    database::get_job_by_id(pool, job_id.to_string())
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Job not found: {}", job_id)))?;
+    Ok(())
+}
+
+async fn job_order_response(pool: &Pool, job_id: String) -> AppResult<HttpResponse> {
+    let stored = database::get_job_candidate_order(pool, &job_id).await?;
+    let (order, version) = job_order::current_or_default(pool, &job_id).await?;
+
+    let mut talents: HashMap<String, _> = database::get_talents_by_ids(pool, &order).await?
+        .into_iter()
+        .map(|talent| (talent.id.clone(), talent))
+        .collect();
+    let applications: HashMap<String, _> = database::get_applications_by_job(pool, job_id.clone()).await?
+        .into_iter()
+        .map(|application| (application.talent_id.clone(), application))
+        .collect();
+    let matches: HashMap<String, _> = database::get_job_matches(pool, &job_id).await?
+        .into_iter()
+        .map(|job_match| (job_match.talent_id.clone(), job_match))
+        .collect();
+
+    let candidates = order
+        .iter()
+        .filter_map(|id| talents.remove(id))
+        .enumerate()
+        .map(|(i, talent)| {
+            let application = applications.get(&talent.id);
+            let job_match = matches.get(&talent.id);
+            OrderedCandidate {
+                position: i + 1,
+                application_id: application.map(|a| a.id.clone()),
+                application_status: application.map(|a| a.status.clone()),
+                match_score: job_match.map(|m| m.score),
+                match_rank: job_match.map(|m| m.rank),
+                talent,
+            }
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok().json(JobCandidateOrderResponse {
+        job_id,
+        version,
+        updated_at: stored.as_ref().map(|order| order.updated_at.clone()),
+        reorder_event_id: stored.and_then(|order| order.reorder_event_id),
+        candidates,
+    }))
+}
+
+/// Who is recording preferences, from `X-Actor`; None when anonymous so
+/// agreement is only measured between known recruiters
+pub fn preference_author(req: &HttpRequest, data: &AppState) -> Option<String> {
+    Some(audit::actor(req.headers(), data)).filter(|actor| actor != crate::audit::ANONYMOUS_ACTOR)
+}
+
+/// Names of the given talents, for the human-readable side of preferences
+pub async fn talent_names(pool: &Pool, talent_ids: &[String]) -> Result<HashMap<String, String>, sqlx::Error> {
+    Ok(database::get_talents_by_ids(pool, talent_ids).await?
+        .into_iter()
+        .map(|talent| (talent.id, talent.name))
+        .collect())
+}
+
+/// Record a shortlist, rejection or tie as preferences
+///
+/// `shortlist` prefers the given candidates over the job's other candidates
+/// and `reject` prefers the others over them; both count for less than a
+/// reorder. `tie` records every pair of the given candidates as equally good.
+#[api_v2_operation]
+#[paperclip::actix::post("/api/v1/jobs/{job_id}/signals", summary = "Record a shortlist, rejection or tie as preferences")]
+pub async fn create_preference_signal(
+    req: HttpRequest,
+    data: web::Data<AppState>,
+    job_id: web::Path<String>,
+    request: web::Json<CreatePreferenceSignalRequest>,
+) -> AppResult<HttpResponse> {
+    let pool = &data.db_pool;
+    let author = preference_author(&req, &data);
+    let job_id = job_id.into_inner();
+    let kind = request.kind.trim().to_lowercase();
+    if !matches!(kind.as_str(), "shortlist" | "reject" | "tie") {
+        return Err(AppError::validation("kind must be shortlist, reject or tie"));
+    }
+    let mut talent_ids: Vec<String> = Vec::new();
+    for id in &request.talent_ids {
+        if !talent_ids.contains(id) {
+            talent_ids.push(id.clone());
+        }
+    }
+    if talent_ids.is_empty() {
+        return Err(AppError::validation("talent_ids cannot be empty"));
+    }
+    if kind == "tie" && talent_ids.len() < 2 {
+        return Err(AppError::validation("A tie needs at least two candidates"));
+    }
+
+    let job = database::get_job_by_id(pool, job_id.clone())
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Job not found: {}", job_id)))?;
+    let candidates = database::get_job_candidate_ids(pool, &job_id).await?;
+    if let Some(unknown) = talent_ids.iter().find(|id| !candidates.contains(id)) {
+        return Err(AppError::validation(format!("Talent {} is not a candidate for this job", unknown)));
+    }
+    audit::add_targets(std::iter::once(job_id.clone()).chain(talent_ids.iter().cloned()));
+
+    let job_text = format!("{} at {}", job.title, job.company_name);
+    let talent_names = talent_names(pool, &candidates).await?;
+    let ctx = PreferenceContext {
+        job_id: &job_id,
+        job_text: &job_text,
+        talent_names: &talent_names,
+        reorder_event_id: None,
+        author: author.as_deref(),
+    };
+    let others: Vec<String> = candidates.into_iter().filter(|id| !talent_ids.contains(id)).collect();
+    let preferences = match kind.as_str() {
+        "shortlist" => reorder::derive_shortlist_preferences(&talent_ids, &others, &ctx),
+        "reject" => reorder::derive_reject_preferences(&talent_ids, &others, &ctx),
+        _ => reorder::derive_tie_preferences(&talent_ids, &ctx),
+    };
+
+    let created_count = database::create_pairwise_preferences(pool, &preferences).await?;
+    log::info!("[reorder] Recorded {} signal for {} candidates of job {} as {} preferences", kind, talent_ids.len(), job_id, created_count);
+    Ok(HttpResponse::Ok().json(PreferenceSignalResponse {
+        preferences_created: created_count,
+        message: format!("{} signal saved with {} preferences created", kind, created_count),
+    }))
+}
+
 /// Get all pairwise preferences for a job
+///
+/// Each preference carries a `weight`: its confidence halved every
+/// `RECENCY_HALF_LIFE_DAYS` days since it was recorded.
 #[api_v2_operation]
 #[paperclip::actix::get("/api/v1/jobs/{job_id}/preferences", summary = "Get pairwise preferences for a job")]
 pub async fn get_preferences_for_job(
     data: web::Data<AppState>,
     job_id: web::Path<String>,
-) -> ActixResult<HttpResponse> {
+) -> AppResult<HttpResponse> {
     let pool = &data.db_pool;
-    let preferences = database::get_pairwise_preferences_for_job(pool, job_id.to_string())
-        .await
-        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let now = Utc::now();
+    let mut preferences = database::get_pairwise_preferences_for_job(pool, job_id.to_string())
+        .await?;
+    for preference in &mut preferences {
+        preference.weight = Some(reorder::recency_weight(preference.confidence, &preference.created_at, now));
+    }
 
     Ok(HttpResponse::Ok().json(preferences))
 }
 
+/// Check a job's preferences for contradictions, cycles and disagreement
+///
+/// Counts only preferences that are neither ties nor superseded. Agreement
+/// is reported between authors (the `X-Actor` that recorded a preference)
+/// over the pairs both of them judged.
+#[api_v2_operation]
+#[paperclip::actix::get("/api/v1/jobs/{job_id}/preferences/analysis", summary = "Find contradictions and cycles in a job's preferences")]
+pub async fn get_preference_analysis(
+    data: web::Data<AppState>,
+    job_id: web::Path<String>,
+) -> AppResult<HttpResponse> {
+    let pool = &data.db_pool;
+    let job_id = job_id.into_inner();
+    database::get_job_by_id(pool, job_id.clone())
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Job not found: {}", job_id)))?;
+
+    let preferences = database::get_pairwise_preferences_for_job(pool, job_id.clone()).await?;
+    Ok(HttpResponse::Ok().json(preference_analysis::analyze(&job_id, &preferences, Utc::now())))
+}
+
+/// Resolve a job's contradictions by keeping the latest judgement
+///
+/// For every pair an author judged both ways, their preferences that
+/// disagree with their most recent one are marked as superseded by it. They
+/// stay on record but no longer count towards analysis or the training
+/// export. Disagreements between different authors and cycles without a
+/// direct contradiction are left alone.
+#[api_v2_operation]
+#[paperclip::actix::post("/api/v1/jobs/{job_id}/preferences/resolve", summary = "Supersede preferences contradicted by a later judgement")]
+pub async fn resolve_preference_contradictions(
+    data: web::Data<AppState>,
+    job_id: web::Path<String>,
+) -> AppResult<HttpResponse> {
+    let pool = &data.db_pool;
+    let job_id = job_id.into_inner();
+    database::get_job_by_id(pool, job_id.clone())
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Job not found: {}", job_id)))?;
+    audit::add_targets([job_id.clone()]);
+
+    let preferences = database::get_pairwise_preferences_for_job(pool, job_id.clone()).await?;
+    let resolutions = preference_analysis::resolutions(&preferences);
+    let contradictions_resolved = resolutions.iter().map(|(_, by)| by).collect::<std::collections::HashSet<_>>().len();
+    let superseded = database::supersede_pairwise_preferences(pool, &resolutions, &Utc::now().to_rfc3339()).await?;
+
+    log::info!("[reorder] Superseded {} preferences of job {} to resolve {} contradictions", superseded, job_id, contradictions_resolved);
+    Ok(HttpResponse::Ok().json(ResolvePreferencesResponse {
+        superseded,
+        contradictions_resolved,
+        message: format!("Resolved {} contradictions by superseding {} preferences", contradictions_resolved, superseded),
+    }))
+}
+
 /// Get all reorder events for a job
 #[api_v2_operation]
 #[paperclip::actix::get("/api/v1/jobs/{job_id}/reorder-events", summary = "Get reorder events for a job")]
 pub async fn get_reorder_events_for_job(
     data: web::Data<AppState>,
     job_id: web::Path<String>,
-) -> ActixResult<HttpResponse> {
+) -> AppResult<HttpResponse> {
     let pool = &data.db_pool;
     let events = database::get_reorder_events_for_job(pool, job_id.to_string())
-        .await
-        .map_err(actix_web::error::ErrorInternalServerError)?;
+        .await?;
 
     Ok(HttpResponse::Ok().json(events))
 }
+
+async fn send(session: &mut Session, message: &CandidateOrderMessage) -> Result<(), actix_ws::Closed> {
+    session.text(serde_json::to_string(message).unwrap_or_default()).await
+}
+
+async fn snapshot(pool: &Pool, job_id: &str) -> AppResult<CandidateOrderMessage> {
+    let (order, version) = job_order::current_or_default(pool, job_id).await?;
+    Ok(CandidateOrderMessage::Snapshot { job_id: job_id.to_string(), version, order })
+}
+
+/// `order_changed` message for a published reorder or reconciliation of the job
+fn order_changed(job_id: &str, event: &StoredEvent) -> Option<CandidateOrderMessage> {
+    if event.job_id.as_deref() != Some(job_id) {
+        return None;
+    }
+    let payload: Value = serde_json::from_str(&event.payload).ok()?;
+    let data = payload.get("data")?;
+    let order = match EventKind::parse(&event.event_type)? {
+        EventKind::ReorderCreated => data.get("after_order")?,
+        EventKind::JobOrderReconciled => data.get("order")?,
+        _ => return None,
+    };
+    Some(CandidateOrderMessage::OrderChanged {
+        job_id: job_id.to_string(),
+        version: data.get("version")?.as_i64()?,
+        order: serde_json::from_value(order.clone()).ok()?,
+        moved_talent_id: data.get("moved_talent_id").and_then(Value::as_str).map(str::to_string),
+        reorder_event_id: data.get("reorder_event_id").and_then(Value::as_str).map(str::to_string),
+    })
+}
+
+/// Relay the job's order changes to one collaborator until either side closes
+async fn order_session(
+    pool: Pool,
+    job_id: String,
+    mut session: Session,
+    mut messages: actix_ws::MessageStream,
+    mut live: broadcast::Receiver<StoredEvent>,
+) {
+    let mut heartbeat = tokio::time::interval(HEARTBEAT_INTERVAL);
+    heartbeat.tick().await;
+    let mut resync = true;
+    loop {
+        // Sent on connect, when the client asks and after missing changes
+        if std::mem::take(&mut resync) {
+            match snapshot(&pool, &job_id).await {
+                Ok(message) => {
+                    if send(&mut session, &message).await.is_err() {
+                        return;
+                    }
+                }
+                Err(e) => {
+                    log::error!("Failed to load the order of job {}: {}", job_id, e);
+                    let _ = session.close(None).await;
+                    return;
+                }
+            }
+        }
+
+        tokio::select! {
+            message = messages.recv() => match message {
+                Some(Ok(Message::Text(_))) => resync = true,
+                Some(Ok(Message::Ping(bytes))) => {
+                    if session.pong(&bytes).await.is_err() {
+                        return;
+                    }
+                }
+                Some(Ok(Message::Close(reason))) => {
+                    let _ = session.close(reason).await;
+                    return;
+                }
+                Some(Ok(_)) => {}
+                Some(Err(_)) | None => return,
+            },
+            event = live.recv() => match event {
+                Ok(event) => {
+                    if let Some(message) = order_changed(&job_id, &event) {
+                        if send(&mut session, &message).await.is_err() {
+                            return;
+                        }
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(_)) => resync = true,
+                Err(broadcast::error::RecvError::Closed) => return,
+            },
+            _ = heartbeat.tick() => {
+                if session.ping(b"").await.is_err() {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+/// Collaborate on a job's candidate order
+///
+/// Upgrades to a WebSocket that first sends a `snapshot` of the job's current
+/// order and version, then an `order_changed` message for every accepted
+/// reorder and every reconciliation. Any text message from the client is
+/// answered with a fresh snapshot, e.g. after a reorder was rejected as stale.
+#[api_v2_operation]
+#[paperclip::actix::get("/api/v1/jobs/{job_id}/order/ws", summary = "Follow changes to a job's candidate order over a WebSocket")]
+pub async fn job_order_socket(
+    req: HttpRequest,
+    body: web::Payload,
+    data: web::Data<AppState>,
+    job_id: web::Path<String>,
+) -> AppResult<HttpResponse> {
+    let job_id = job_id.into_inner();
+    database::get_job_by_id(&data.db_pool, job_id.clone())
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Job not found: {}", job_id)))?;
+
+    let (response, session, messages) = actix_ws::handle(&req, body)
+        .map_err(|e| AppError::validation(format!("WebSocket handshake failed: {}", e)))?;
+    let live = data.events.subscribe();
+    actix_web::rt::spawn(order_session(data.db_pool.clone(), job_id, session, messages, live));
+    Ok(response)
+}
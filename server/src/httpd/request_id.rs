@@ -0,0 +1,66 @@
+//! Request ID middleware
+//!
+//! Assigns every request an ID (reusing an incoming `X-Request-Id` header when
+//! present), exposes it to handlers through a task-local, echoes it back in the
+//! response headers and logs handler errors together with it.
+
+use actix_web::{
+    body::MessageBody,
+    dev::{ServiceRequest, ServiceResponse},
+    http::header::{HeaderName, HeaderValue},
+    middleware::Next,
+    Error, ResponseError,
+};
+use log::{error, warn};
+use uuid::Uuid;
+
+use crate::error::AppError;
+
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// Request ID of the request currently being handled, if any
+pub fn current() -> Option<String> {
+    REQUEST_ID.try_with(|id| id.clone()).ok()
+}
+
+pub async fn middleware(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let request_id = req
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .filter(|v| !v.is_empty() && v.len() <= 128)
+        .map(|v| v.to_string())
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+
+    let method = req.method().clone();
+    let path = req.path().to_string();
+
+    let mut res = REQUEST_ID.scope(request_id.clone(), next.call(req)).await?;
+
+    if let Some(err) = res.response().error() {
+        match err.as_error::<AppError>() {
+            Some(app_err) if app_err.status_code().is_server_error() => {
+                error!("[{}] {} {} failed: {}", request_id, method, path, app_err);
+            }
+            Some(app_err) => {
+                warn!("[{}] {} {} rejected: {}", request_id, method, path, app_err);
+            }
+            None => {
+                warn!("[{}] {} {} failed: {}", request_id, method, path, err);
+            }
+        }
+    }
+
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        res.headers_mut().insert(HeaderName::from_static(REQUEST_ID_HEADER), value);
+    }
+
+    Ok(res)
+}
@@ -0,0 +1,23 @@
+use actix_web::{web, HttpResponse};
+use chrono::Utc;
+use log::info;
+use paperclip::actix::api_v2_operation;
+
+use crate::error::AppResult;
+use super::server::AppState;
+
+#[api_v2_operation]
+#[paperclip::actix::get("/api/v1/retention/report", summary = "Dry run: report what the retention policy would purge now")]
+pub async fn get_retention_report(data: web::Data<AppState>) -> AppResult<HttpResponse> {
+    let report = crate::retention::apply(&data.db_pool, &data.retention, Utc::now(), true).await?;
+    Ok(HttpResponse::Ok().json(report))
+}
+
+#[api_v2_operation]
+#[paperclip::actix::post("/api/v1/retention/purge", summary = "Enforce the retention policy immediately")]
+pub async fn run_retention_purge(data: web::Data<AppState>) -> AppResult<HttpResponse> {
+    let report = crate::retention::apply(&data.db_pool, &data.retention, Utc::now(), false).await?;
+    let total: u64 = report.classes.iter().map(|c| c.affected).sum();
+    info!("[retention] Manual purge affected {} rows", total);
+    Ok(HttpResponse::Ok().json(report))
+}
@@ -1,33 +1,138 @@
-use actix_web::{web, App, HttpServer, HttpResponse, middleware::Logger};
+use actix_web::{web, App, HttpServer, HttpResponse, middleware::{from_fn, Logger}};
 use actix_cors::Cors;
 use paperclip::actix::{self, OpenApiExt, HttpResponseWrapper};
 use log::info;
 
+use std::time::Duration;
+
+use crate::collection_queue::CollectionQueue;
+use crate::email::{Mailer, SmtpConfig};
+use crate::error::AppError;
+use crate::events::Events;
+use crate::job_lifecycle;
+use crate::match_runs::MatchRunner;
+use crate::notifications::Notifier;
+use crate::retention::{self, RetentionPolicy};
+use crate::webhooks;
+
 use super::talents::{
     get_talents, create_talent, get_talent, get_talent_by_email, update_talent, delete_talent, delete_talents_bulk, trigger_scoring,
+    export_talent_data, erase_talent, get_erasure_tombstones, set_talent_legal_hold, restore_talent,
+    import_json_resume, export_json_resume,
 };
 use super::jobs::{
-    get_jobs, create_job, get_job, update_job, delete_job, get_job_matches, generate_job_matches,
+    get_jobs, create_job, get_job, update_job, delete_job, publish_job, pause_job, close_job, fill_job,
+    get_job_matches, generate_job_matches, get_match_runs, get_match_run, cancel_match_run, diff_match_runs, set_job_legal_hold, restore_job,
 };
 use super::applications::{
-    create_application, get_application, get_applications_by_talent, get_applications_by_job, get_application_resume, delete_application, delete_applications_bulk,
+    create_application, get_application, update_application_status, get_applications_by_talent, get_applications_by_job, get_application_resume, delete_application, delete_applications_bulk,
+    restore_application,
+};
+use super::feeds::{
+    jobs_rss_feed, jobs_json_feed, jobs_jsonld_feed, job_jsonld,
+};
+use super::portal::{
+    portal_login, portal_session, get_portal_profile, update_portal_profile, get_portal_applications,
+    withdraw_portal_application, upload_portal_resume, get_portal_notifications, update_portal_notifications,
+};
+use super::audit::{self, get_audit_log};
+use super::request_id;
+use super::retention::{get_retention_report, run_retention_purge};
+use super::trash::get_trash;
+use super::import::{import_talents, import_jobs};
+use super::interviews::{
+    create_interview, get_interviews_by_application, get_interview, update_interview, delete_interview,
+    submit_scorecard, get_scorecards, get_application_feedback,
+};
+use super::offers::{
+    create_offer, get_offers_by_application, get_offer, update_offer, submit_offer, approve_offer, reject_offer,
+    accept_offer, decline_offer, rescind_offer,
+};
+use super::exports::{export_job_pipeline, export_talents, export_preferences};
+use super::webhooks::{
+    create_webhook, get_webhooks, get_webhook, update_webhook, delete_webhook, get_webhook_deliveries, test_webhook,
+    get_webhook_delivery, redeliver_webhook_delivery,
+};
+use super::events::stream_events;
+use super::notifications::{
+    get_notification_preferences, update_notification_preferences, get_outbox, retry_outbox_email,
 };
+use super::evaluations::{create_evaluation, get_evaluations, get_evaluation, get_job_evaluation};
 use super::reorder::{
-    reorder_candidates, get_preferences_for_job, get_reorder_events_for_job,
+    reorder_candidates, create_preference_signal, get_preferences_for_job, get_preference_analysis,
+    resolve_preference_contradictions, get_reorder_events_for_job, get_job_order, reconcile_job_order,
+    job_order_socket,
 };
 
+/// Runtime configuration for the HTTP server and its background tasks
+#[derive(Clone, Debug)]
+pub struct ServerConfig {
+    pub host: String,
+    pub port: u16,
+    pub database_url: String,
+    pub grok_service_url: String,
+    /// Public base URL of the job board UI, used for links in feeds
+    pub public_board_url: String,
+    /// Public base URL of this API, used for self links in feeds
+    pub public_api_url: String,
+    /// How often the job expiry sweeper runs
+    pub job_sweep_interval: Duration,
+    /// Reject remaining applications when the sweeper closes an expired job
+    pub auto_reject_on_expiry: bool,
+    /// Secret used to sign candidate portal session tokens
+    pub portal_session_secret: String,
+    /// Retention periods per data class
+    pub retention: RetentionPolicy,
+    /// How often the retention sweeper runs
+    pub retention_sweep_interval: Duration,
+    /// Approvers who must sign off on every offer, in order
+    pub offer_approvers: Vec<String>,
+    /// Outgoing mail server; without it no email is sent
+    pub smtp: Option<SmtpConfig>,
+    /// Recruiters notified when a candidate has been scored
+    pub recruiter_emails: Vec<String>,
+}
+
 #[derive(Clone)]
 pub struct AppState {
     pub db_pool: sqlx::SqlitePool,
     pub grok_service_url: String,
+    pub public_board_url: String,
+    pub public_api_url: String,
+    pub portal_secret: Vec<u8>,
+    pub retention: RetentionPolicy,
+    pub collections: CollectionQueue,
+    pub offer_approvers: Vec<String>,
+    pub events: Events,
+    pub notifier: Notifier,
+    pub match_runner: MatchRunner,
 }
 
 impl AppState {
-    pub async fn new(database_url: &str, grok_service_url: &str) -> Result<Self, Box<dyn std::error::Error>> {
-        let db_pool = crate::database::init_pool(database_url).await?;
+    pub async fn new(config: &ServerConfig) -> Result<Self, Box<dyn std::error::Error>> {
+        let db_pool = crate::database::init_pool(&config.database_url).await?;
+        let collections = CollectionQueue::spawn(db_pool.clone(), config.grok_service_url.clone());
+        let public_board_url = config.public_board_url.trim_end_matches('/').to_string();
+        let mailer = Mailer::spawn(db_pool.clone(), config.smtp.clone());
+        let notifier = Notifier::new(db_pool.clone(), mailer, config.recruiter_emails.clone(), public_board_url.clone());
+        let events = Events::new(db_pool.clone(), notifier.clone());
+        let interrupted = crate::database::fail_interrupted_match_runs(&db_pool, &chrono::Utc::now().to_rfc3339()).await?;
+        if interrupted > 0 {
+            info!("Marked {} matching runs interrupted by the last shutdown as failed", interrupted);
+        }
+        let match_runner = MatchRunner::new(db_pool.clone(), events.clone(), config.grok_service_url.clone());
         Ok(Self {
             db_pool,
-            grok_service_url: grok_service_url.to_string(),
+            grok_service_url: config.grok_service_url.clone(),
+            public_board_url,
+            public_api_url: config.public_api_url.trim_end_matches('/').to_string(),
+            portal_secret: config.portal_session_secret.as_bytes().to_vec(),
+            retention: config.retention,
+            collections,
+            offer_approvers: config.offer_approvers.clone(),
+            events,
+            notifier,
+            match_runner,
         })
     }
 }
@@ -72,10 +177,25 @@ pub async fn swagger_ui() -> HttpResponseWrapper {
     )
 }
 
-pub async fn run_server(rest_host: &str, rest_port: u16, database_url: &str, grok_service_url: &str) -> Result<(), Box<dyn std::error::Error>> {
-    let state = AppState::new(database_url, grok_service_url).await?;
+pub async fn run_server(config: ServerConfig) -> Result<(), Box<dyn std::error::Error>> {
+    let state = AppState::new(&config).await?;
+    crate::integrity::report_orphans(&state.db_pool).await?;
+
+    // Background tasks
+    job_lifecycle::spawn_expiry_sweeper(
+        state.db_pool.clone(),
+        state.events.clone(),
+        config.job_sweep_interval,
+        config.auto_reject_on_expiry,
+    );
+    retention::spawn_retention_sweeper(
+        state.db_pool.clone(),
+        config.retention,
+        config.retention_sweep_interval,
+    );
+    webhooks::spawn_dispatcher(state.db_pool.clone(), state.events.wake_handle());
 
-    let bind_address = format!("{}:{}", rest_host, rest_port);
+    let bind_address = format!("{}:{}", config.host, config.port);
     info!("Starting X Talent Pool Server on http://{}", bind_address);
 
     let server = HttpServer::new(move || {
@@ -89,6 +209,18 @@ pub async fn run_server(rest_host: &str, rest_port: u16, database_url: &str, gro
 
         App::new()
             .app_data(web::Data::new(state.clone()))
+            .app_data(web::JsonConfig::default().error_handler(|err, _req| {
+                AppError::validation(format!("Invalid JSON body: {}", err)).into()
+            }))
+            .app_data(web::PathConfig::default().error_handler(|err, _req| {
+                AppError::validation(format!("Invalid path parameter: {}", err)).into()
+            }))
+            .app_data(web::PayloadConfig::new(crate::import::MAX_BODY_BYTES))
+            .app_data(web::QueryConfig::default().error_handler(|err, _req| {
+                AppError::validation(format!("Invalid query string: {}", err)).into()
+            }))
+            .wrap(from_fn(audit::middleware))
+            .wrap(from_fn(request_id::middleware))
             .wrap(cors)
             .wrap(Logger::default())
             .wrap_api()
@@ -98,31 +230,127 @@ pub async fn run_server(rest_host: &str, rest_port: u16, database_url: &str, gro
             .service(get_talents)
             .service(create_talent)
             .service(get_talent_by_email)
+            // Static paths before `{id}` so route patterns resolve to them (used by the audit log)
+            .service(delete_talents_bulk)
+            .service(export_talents)
+            .service(import_json_resume)
             .service(get_talent)
             .service(update_talent)
             .service(delete_talent)
-            .service(delete_talents_bulk)
+            .service(restore_talent)
             .service(trigger_scoring)
+            .service(export_talent_data)
+            .service(export_json_resume)
+            .service(erase_talent)
+            .service(get_erasure_tombstones)
+            .service(set_talent_legal_hold)
             // Job routes
             .service(get_jobs)
             .service(create_job)
             .service(get_job)
             .service(update_job)
             .service(delete_job)
+            .service(restore_job)
+            .service(publish_job)
+            .service(pause_job)
+            .service(close_job)
+            .service(fill_job)
+            .service(set_job_legal_hold)
             .service(get_job_matches)
             .service(generate_job_matches)
+            .service(get_match_runs)
+            .service(get_match_run)
+            .service(cancel_match_run)
+            .service(diff_match_runs)
+            .service(export_job_pipeline)
             // Application routes
             .service(create_application)
             .service(get_applications_by_talent)
             .service(get_applications_by_job)
             .service(get_application_resume)
+            .service(delete_applications_bulk)
             .service(get_application)
+            .service(update_application_status)
             .service(delete_application)
-            .service(delete_applications_bulk)
+            .service(restore_application)
+            // Interview routes
+            .service(create_interview)
+            .service(get_interviews_by_application)
+            .service(get_application_feedback)
+            .service(get_interview)
+            .service(update_interview)
+            .service(delete_interview)
+            .service(submit_scorecard)
+            .service(get_scorecards)
+            // Offer routes
+            .service(create_offer)
+            .service(get_offers_by_application)
+            .service(get_offer)
+            .service(update_offer)
+            .service(submit_offer)
+            .service(approve_offer)
+            .service(reject_offer)
+            .service(accept_offer)
+            .service(decline_offer)
+            .service(rescind_offer)
+            // Webhook routes
+            .service(create_webhook)
+            .service(get_webhooks)
+            .service(get_webhook_delivery)
+            .service(redeliver_webhook_delivery)
+            .service(get_webhook)
+            .service(update_webhook)
+            .service(delete_webhook)
+            .service(get_webhook_deliveries)
+            .service(test_webhook)
+            // Event stream routes
+            .service(stream_events)
+            // Notification routes
+            .service(get_notification_preferences)
+            .service(update_notification_preferences)
+            .service(get_outbox)
+            .service(retry_outbox_email)
+            // Bulk import routes
+            .service(import_talents)
+            .service(import_jobs)
             // Reorder routes
             .service(reorder_candidates)
+            .service(export_preferences)
+            .service(create_preference_signal)
             .service(get_preferences_for_job)
+            .service(get_preference_analysis)
+            .service(resolve_preference_contradictions)
             .service(get_reorder_events_for_job)
+            .service(get_job_order)
+            .service(reconcile_job_order)
+            .service(job_order_socket)
+            // Evaluation routes
+            .service(create_evaluation)
+            .service(get_evaluations)
+            .service(get_evaluation)
+            .service(get_job_evaluation)
+            // Public feed routes
+            .service(jobs_rss_feed)
+            .service(jobs_json_feed)
+            .service(jobs_jsonld_feed)
+            .service(job_jsonld)
+            // Audit routes
+            .service(get_audit_log)
+            // Data retention routes
+            .service(get_retention_report)
+            .service(run_retention_purge)
+            // Trash routes
+            .service(get_trash)
+            // Candidate portal routes
+            .service(portal_login)
+            .service(portal_session)
+            .service(get_portal_profile)
+            .service(update_portal_profile)
+            .service(get_portal_applications)
+            .service(withdraw_portal_application)
+            .service(upload_portal_resume)
+            .service(get_portal_notifications)
+            .service(update_portal_notifications)
             .build()
     })
     .bind(&bind_address)?
@@ -1,8 +1,9 @@
-use actix_web::{web, HttpResponse, Result as ActixResult};
+use actix_web::{web, HttpResponse};
 use paperclip::actix::api_v2_operation;
 use log::info;
 
 use crate::models::{SocialMediaAnalysis};
+use crate::error::{AppError, AppResult};
 use super::server::AppState;
 
 #[api_v2_operation]
@@ -11,13 +12,12 @@ pub async fn update_social_analysis(
     data: web::Data<AppState>,
     path: web::Path<String>,
     json: web::Json<SocialMediaAnalysis>,
-) -> ActixResult<HttpResponse> {
+) -> AppResult<HttpResponse> {
     let talent_id = path.into_inner();
     let pool = &data.db_pool;
 
     // Serialize the social media analysis to JSON
-    let social_analysis_json = serde_json::to_string(&json)
-        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let social_analysis_json = serde_json::to_string(&json)?;
 
     let x_handle = json.x_handle.clone();
 
@@ -27,9 +27,8 @@ pub async fn update_social_analysis(
         talent_id.clone(),
         Some(social_analysis_json),
         x_handle,
-    ).await
-    .map_err(actix_web::error::ErrorInternalServerError)?
-    .ok_or(actix_web::error::ErrorNotFound("Talent not found"))?;
+    ).await?
+    .ok_or_else(|| AppError::not_found("Talent not found"))?;
 
     info!("Updated social media analysis for talent: {}", talent_id);
 
@@ -1,30 +1,32 @@
-use actix_web::{web, HttpResponse, Result as ActixResult};
+use actix_web::{web, HttpResponse};
 use paperclip::actix::api_v2_operation;
 use uuid::Uuid;
 use chrono::Utc;
 use log::{info, error};
 
-use crate::models::{Talent, CreateTalentRequest, UpdateTalentRequest, ApiError, BulkDeleteRequest, BulkDeleteResponse, TriggerScoringRequest, TriggerScoringResponse};
+use crate::models::{
+    Talent, CreateTalentRequest, UpdateTalentRequest, BulkDeleteRequest, BulkDeleteResponse, TriggerScoringRequest, TriggerScoringResponse,
+    TalentExportQuery, EraseTalentRequest, LegalHoldRequest, TalentListQuery, JsonResume,
+};
+use crate::error::{AppError, AppResult};
+use crate::events::{self, EventKind};
+use crate::job_order;
+use crate::jsonresume::ResumeImport;
 use crate::grok_client::{GrokClient, CandidateScoringRequest, JobInfoForScoring};
+use super::audit;
 use super::server::AppState;
 
 #[api_v2_operation]
-#[paperclip::actix::get("/api/v1/talents", summary = "List all talents")]
-async fn get_talents(data: web::Data<AppState>) -> ActixResult<HttpResponse> {
+#[paperclip::actix::get("/api/v1/talents", summary = "List talents, optionally filtered")]
+async fn get_talents(data: web::Data<AppState>, query: web::Query<TalentListQuery>) -> AppResult<HttpResponse> {
     let pool = &data.db_pool;
-    let talents = crate::database::get_all_talents(pool).await
-        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let talents = crate::database::get_all_talents(pool, &query).await?;
     Ok(HttpResponse::Ok().json(talents))
 }
 
-#[api_v2_operation]
-#[paperclip::actix::post("/api/v1/talents", summary = "Create a new talent")]
-async fn create_talent(
-    data: web::Data<AppState>,
-    json: web::Json<CreateTalentRequest>,
-) -> ActixResult<HttpResponse> {
-    let pool = &data.db_pool;
-    let new_talent = Talent {
+/// Build a new talent record from a create request
+pub(crate) fn new_talent(json: &CreateTalentRequest) -> Talent {
+    Talent {
         id: Uuid::new_v4().to_string(),
         name: json.name.clone(),
         email: json.email.clone(),
@@ -49,59 +51,42 @@ async fn create_talent(
         x_handle_discovered: None,
         candidate_score: None,
         candidate_score_details: None,
-    };
-    let inserted = crate::database::create_talent(&pool, &new_talent).await
-        .map_err(actix_web::error::ErrorInternalServerError)?;
-
-    // Create a collection for this talent asynchronously
-    let grok_url = data.grok_service_url.clone();
-    let pool_clone = pool.clone();
-    let talent_id = inserted.id.clone();
-    let talent_name = inserted.name.clone();
-
-    tokio::spawn(async move {
-        info!("======================================================================");
-        info!("COLLECTION CREATION: Starting for talent {}", talent_id);
-        info!("======================================================================");
-
-        let client = GrokClient::new(&grok_url);
-
-        match client.create_collection(&talent_id, &talent_name).await {
-            Ok(response) => {
-                if response.success {
-                    if let Some(collection) = response.collection {
-                        info!("======================================================================");
-                        info!("COLLECTION CREATION: Success!");
-                        info!("Talent ID: {}", talent_id);
-                        info!("Collection ID: {}", collection.collection_id);
-                        info!("Collection Name: {}", collection.collection_name);
-                        info!("======================================================================");
-
-                        // Update talent with collection_id
-                        match crate::database::update_talent_collection_id(
-                            &pool_clone,
-                            talent_id.clone(),
-                            collection.collection_id.clone(),
-                        ).await {
-                            Ok(_) => {
-                                info!("SUCCESS: Updated talent {} with collection_id {}", talent_id, collection.collection_id);
-                            },
-                            Err(e) => {
-                                error!("FAILED to update talent {} with collection_id: {}", talent_id, e);
-                            }
-                        }
-                    }
-                } else {
-                    error!("COLLECTION CREATION: Failed - {:?}", response.error);
-                }
-            }
-            Err(e) => {
-                error!("======================================================================");
-                error!("COLLECTION CREATION: Error - {}", e);
-                error!("======================================================================");
-            }
-        }
-    });
+        legal_hold: 0,
+        deleted_at: None,
+    }
+}
+
+/// Update an existing talent from a create request. update_talent overwrites
+/// every column, so optional fields the request leaves empty keep their
+/// current values
+pub(crate) fn merge_update(existing: Talent, request: CreateTalentRequest) -> UpdateTalentRequest {
+    UpdateTalentRequest {
+        name: Some(request.name),
+        email: Some(existing.email),
+        handle: Some(request.handle),
+        avatar: request.avatar.or(existing.avatar),
+        title: Some(request.title),
+        location: request.location.or(existing.location),
+        experience: Some(request.experience),
+        skills: Some(request.skills),
+        bio: request.bio.or(existing.bio),
+        verified: Some(request.verified || existing.verified != 0),
+    }
+}
+
+#[api_v2_operation]
+#[paperclip::actix::post("/api/v1/talents", summary = "Create a new talent")]
+async fn create_talent(
+    data: web::Data<AppState>,
+    json: web::Json<CreateTalentRequest>,
+) -> AppResult<HttpResponse> {
+    let pool = &data.db_pool;
+    let new_talent = new_talent(&json);
+    let inserted = crate::database::create_talent(pool, &new_talent).await?;
+    audit::add_targets([inserted.id.clone()]);
+
+    // Create a collection for this talent in the background
+    data.collections.enqueue(inserted.id.clone(), inserted.name.clone());
 
     Ok(HttpResponse::Created().json(inserted))
 }
@@ -111,12 +96,11 @@ async fn create_talent(
 async fn get_talent(
     data: web::Data<AppState>,
     path: web::Path<String>,
-) -> ActixResult<HttpResponse> {
+) -> AppResult<HttpResponse> {
     let id = path.into_inner();
     let pool = &data.db_pool;
-    let talent = crate::database::get_talent_by_id(pool, id).await
-        .map_err(actix_web::error::ErrorInternalServerError)?
-        .ok_or(actix_web::error::ErrorNotFound("Talent not found"))?;
+    let talent = crate::database::get_talent_by_id(pool, id).await?
+        .ok_or_else(|| AppError::not_found("Talent not found"))?;
     Ok(HttpResponse::Ok().json(talent))
 }
 
@@ -125,17 +109,12 @@ async fn get_talent(
 pub async fn get_talent_by_email(
     data: web::Data<AppState>,
     path: web::Path<String>,
-) -> ActixResult<HttpResponse> {
+) -> AppResult<HttpResponse> {
     let email = path.into_inner();
     let pool = &data.db_pool;
-    match crate::database::get_talent_by_email(pool, email).await {
-        Ok(Some(talent)) => Ok(HttpResponse::Ok().json(talent)),
-        Ok(None) => Ok(HttpResponse::NotFound().json(crate::models::ApiError {
-            message: "Talent not found".to_string(),
-            code: 404,
-        })),
-        Err(e) => Err(actix_web::error::ErrorInternalServerError(e)),
-    }
+    let talent = crate::database::get_talent_by_email(pool, email).await?
+        .ok_or_else(|| AppError::not_found("Talent not found"))?;
+    Ok(HttpResponse::Ok().json(talent))
 }
 
 #[api_v2_operation]
@@ -144,12 +123,11 @@ async fn update_talent(
     data: web::Data<AppState>,
     path: web::Path<String>,
     json: web::Json<UpdateTalentRequest>,
-) -> ActixResult<HttpResponse> {
+) -> AppResult<HttpResponse> {
     let id = path.into_inner();
     let pool = &data.db_pool;
-    let updated = crate::database::update_talent(pool, id, &json).await
-        .map_err(actix_web::error::ErrorInternalServerError)?
-        .ok_or(actix_web::error::ErrorNotFound("Talent not found"))?;
+    let updated = crate::database::update_talent(pool, id, &json).await?
+        .ok_or_else(|| AppError::not_found("Talent not found"))?;
     Ok(HttpResponse::Ok().json(updated))
 }
 
@@ -158,19 +136,160 @@ async fn update_talent(
 async fn delete_talent(
     data: web::Data<AppState>,
     path: web::Path<String>,
-) -> ActixResult<HttpResponse> {
+) -> AppResult<HttpResponse> {
+    let id = path.into_inner();
+    let pool = &data.db_pool;
+    let deleted = crate::database::delete_talent(pool, id.clone()).await?;
+    if !deleted {
+        return Err(AppError::not_found("Talent not found"));
+    }
+    job_order::reconcile_for_talent(pool, &data.events, &id).await;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[api_v2_operation]
+#[paperclip::actix::post("/api/v1/talents/{id}/restore", summary = "Restore a deleted talent and the applications deleted with them")]
+pub async fn restore_talent(
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+) -> AppResult<HttpResponse> {
+    let id = path.into_inner();
+    let restored = crate::database::restore_talent(&data.db_pool, &id).await?
+        .ok_or_else(|| AppError::not_found("Talent not found in trash"))?;
+    info!("Restored talent {}", id);
+    job_order::reconcile_for_talent(&data.db_pool, &data.events, &id).await;
+    Ok(HttpResponse::Ok().json(restored))
+}
+
+#[api_v2_operation]
+#[paperclip::actix::get("/api/v1/talents/{id}/export", summary = "Export all data held about a talent (JSON or zip)")]
+pub async fn export_talent_data(
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+    query: web::Query<TalentExportQuery>,
+) -> AppResult<HttpResponse> {
+    let id = path.into_inner();
+    let pool = &data.db_pool;
+    // Trashed talents are still held, so they can be exported
+    let talent = crate::database::get_talent_by_id_including_deleted(pool, &id).await?
+        .ok_or_else(|| AppError::not_found("Talent not found"))?;
+
+    let export = crate::privacy::collect_export(pool, talent).await?;
+    info!("Exported data for talent {}", id);
+
+    match query.format.as_deref().unwrap_or("json") {
+        "json" => Ok(HttpResponse::Ok()
+            .insert_header(("Content-Disposition", format!("attachment; filename=\"talent-{}.json\"", id)))
+            .json(export)),
+        "zip" => {
+            let bytes = crate::privacy::export_zip(&export)
+                .map_err(|e| AppError::internal(format!("Failed to build export archive: {}", e)))?;
+            Ok(HttpResponse::Ok()
+                .content_type("application/zip")
+                .insert_header(("Content-Disposition", format!("attachment; filename=\"talent-{}.zip\"", id)))
+                .body(bytes))
+        }
+        other => Err(AppError::validation(format!("Unsupported export format '{}'; use json or zip", other))),
+    }
+}
+
+#[api_v2_operation]
+#[paperclip::actix::post("/api/v1/talents/jsonresume", summary = "Create or update a talent (matched by email) from a JSON Resume")]
+pub async fn import_json_resume(
+    data: web::Data<AppState>,
+    json: web::Json<JsonResume>,
+) -> AppResult<HttpResponse> {
+    let pool = &data.db_pool;
+    let ResumeImport { talent: request, resume } = crate::jsonresume::to_import(&json, Utc::now().date_naive())
+        .map_err(|errors| AppError::validation(errors.join("; ")))?;
+    let existing = crate::database::get_talent_by_email(pool, request.email.clone()).await?;
+
+    let mut tx = pool.begin().await?;
+    let (id, resume, created) = match existing {
+        None => (crate::database::create_talent(&mut *tx, &new_talent(&request.to_create())).await?.id, resume, true),
+        Some(existing) => {
+            let id = existing.id.clone();
+            let resume = resume.or(&existing);
+            crate::database::update_talent(&mut *tx, id.clone(), &request.to_update(existing)).await?;
+            (id, resume, false)
+        }
+    };
+    let saved = crate::database::update_talent_resume_fields(
+        &mut *tx,
+        id,
+        resume.experiences,
+        resume.linkedin_url,
+        resume.x_url,
+        resume.github_url,
+        resume.gitlab_url,
+    ).await?
+        .ok_or_else(|| AppError::not_found("Talent not found"))?;
+    tx.commit().await?;
+    audit::add_targets([saved.id.clone()]);
+
+    info!("Imported JSON Resume for talent {} ({})", saved.id, if created { "created" } else { "updated" });
+    if created {
+        data.collections.enqueue(saved.id.clone(), saved.name.clone());
+        return Ok(HttpResponse::Created().json(saved));
+    }
+    Ok(HttpResponse::Ok().json(saved))
+}
+
+#[api_v2_operation]
+#[paperclip::actix::get("/api/v1/talents/{id}/jsonresume", summary = "Export a talent as a JSON Resume")]
+pub async fn export_json_resume(
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+) -> AppResult<HttpResponse> {
+    let id = path.into_inner();
+    let talent = crate::database::get_talent_by_id(&data.db_pool, id).await?
+        .ok_or_else(|| AppError::not_found("Talent not found"))?;
+    Ok(HttpResponse::Ok().json(crate::jsonresume::from_talent(&talent)))
+}
+
+#[api_v2_operation]
+#[paperclip::actix::post("/api/v1/talents/{id}/erase", summary = "Erase a talent and all related data (right to erasure)")]
+pub async fn erase_talent(
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+    json: web::Json<EraseTalentRequest>,
+) -> AppResult<HttpResponse> {
     let id = path.into_inner();
     let pool = &data.db_pool;
-    let deleted = crate::database::delete_talent(&pool, id).await
-        .map_err(actix_web::error::ErrorInternalServerError)?;
-    if deleted {
-        Ok(HttpResponse::NoContent().finish())
-    } else {
-        Ok(HttpResponse::NotFound().json(ApiError {
-            message: "Talent not found".to_string(),
-            code: 404,
-        }))
+    let talent = crate::database::get_talent_by_id_including_deleted(pool, &id).await?
+        .ok_or_else(|| AppError::not_found("Talent not found"))?;
+    if talent.legal_hold != 0 {
+        return Err(AppError::conflict("Talent is under legal hold and cannot be erased"));
+    }
+    if crate::database::talent_has_held_applications(pool, &id).await? {
+        return Err(AppError::conflict("Talent has applications on jobs under legal hold and cannot be erased"));
     }
+
+    let json = json.into_inner();
+    let tombstone = crate::privacy::erase(pool, &data.grok_service_url, &talent, json.reason, json.requested_by).await?;
+    job_order::reconcile_for_talent(pool, &data.events, &id).await;
+    Ok(HttpResponse::Ok().json(tombstone))
+}
+
+#[api_v2_operation]
+#[paperclip::actix::put("/api/v1/talents/{id}/legal-hold", summary = "Place or lift a legal hold on a talent")]
+pub async fn set_talent_legal_hold(
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+    json: web::Json<LegalHoldRequest>,
+) -> AppResult<HttpResponse> {
+    let id = path.into_inner();
+    let updated = crate::database::set_talent_legal_hold(&data.db_pool, &id, json.legal_hold).await?
+        .ok_or_else(|| AppError::not_found("Talent not found"))?;
+    info!("Legal hold on talent {} set to {}", id, json.legal_hold);
+    Ok(HttpResponse::Ok().json(updated))
+}
+
+#[api_v2_operation]
+#[paperclip::actix::get("/api/v1/erasures", summary = "List erasure tombstones")]
+pub async fn get_erasure_tombstones(data: web::Data<AppState>) -> AppResult<HttpResponse> {
+    let tombstones = crate::database::get_erasure_tombstones(&data.db_pool).await?;
+    Ok(HttpResponse::Ok().json(tombstones))
 }
 
 #[api_v2_operation]
@@ -178,21 +297,21 @@ async fn delete_talent(
 pub async fn delete_talents_bulk(
     data: web::Data<AppState>,
     json: web::Json<BulkDeleteRequest>,
-) -> ActixResult<HttpResponse> {
+) -> AppResult<HttpResponse> {
     let pool = &data.db_pool;
     let ids = &json.ids;
+    audit::add_targets(ids.iter().cloned());
 
     if ids.is_empty() {
-        return Ok(HttpResponse::BadRequest().json(ApiError {
-            message: "No talent IDs provided".to_string(),
-            code: 400,
-        }));
+        return Err(AppError::validation("No talent IDs provided"));
     }
 
     info!("Bulk deleting {} talents", ids.len());
 
-    let deleted_count = crate::database::delete_talents_bulk(pool, ids).await
-        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let deleted_count = crate::database::delete_talents_bulk(pool, ids).await?;
+    for id in ids {
+        job_order::reconcile_for_talent(pool, &data.events, id).await;
+    }
 
     Ok(HttpResponse::Ok().json(BulkDeleteResponse {
         deleted_count,
@@ -206,7 +325,7 @@ pub async fn trigger_scoring(
     data: web::Data<AppState>,
     path: web::Path<String>,
     json: web::Json<TriggerScoringRequest>,
-) -> ActixResult<HttpResponse> {
+) -> AppResult<HttpResponse> {
     let talent_id = path.into_inner();
     let pool = &data.db_pool;
 
@@ -217,61 +336,17 @@ pub async fn trigger_scoring(
     info!("======================================================================");
 
     // Get talent
-    let talent = match crate::database::get_talent_by_id(pool, talent_id.clone()).await {
-        Ok(Some(t)) => t,
-        Ok(None) => {
-            return Ok(HttpResponse::NotFound().json(TriggerScoringResponse {
-                success: false,
-                message: "Talent not found".to_string(),
-                score: None,
-                recommendation: None,
-            }));
-        }
-        Err(e) => {
-            error!("Failed to get talent: {}", e);
-            return Ok(HttpResponse::InternalServerError().json(TriggerScoringResponse {
-                success: false,
-                message: format!("Database error: {}", e),
-                score: None,
-                recommendation: None,
-            }));
-        }
-    };
+    let talent = crate::database::get_talent_by_id(pool, talent_id.clone()).await?
+        .ok_or_else(|| AppError::not_found("Talent not found"))?;
 
     // Check if talent has a collection
-    let collection_id = match &talent.collection_id {
-        Some(id) => id.clone(),
-        None => {
-            return Ok(HttpResponse::BadRequest().json(TriggerScoringResponse {
-                success: false,
-                message: "Talent does not have a collection. Please upload a resume first.".to_string(),
-                score: None,
-                recommendation: None,
-            }));
-        }
-    };
+    let collection_id = talent.collection_id.clone().ok_or_else(|| {
+        AppError::validation("Talent does not have a collection. Please upload a resume first.")
+    })?;
 
     // Get job
-    let job = match crate::database::get_job_by_id(pool, json.job_id.clone()).await {
-        Ok(Some(j)) => j,
-        Ok(None) => {
-            return Ok(HttpResponse::NotFound().json(TriggerScoringResponse {
-                success: false,
-                message: "Job not found".to_string(),
-                score: None,
-                recommendation: None,
-            }));
-        }
-        Err(e) => {
-            error!("Failed to get job: {}", e);
-            return Ok(HttpResponse::InternalServerError().json(TriggerScoringResponse {
-                success: false,
-                message: format!("Database error: {}", e),
-                score: None,
-                recommendation: None,
-            }));
-        }
-    };
+    let job = crate::database::get_job_by_id(pool, json.job_id.clone()).await?
+        .ok_or_else(|| AppError::not_found("Job not found"))?;
 
     // Call Grok scoring service
     let client = GrokClient::new(&data.grok_service_url);
@@ -294,50 +369,37 @@ pub async fn trigger_scoring(
         candidate_skills: talent.skills.clone(),
     };
 
-    match client.score_candidate(&scoring_request).await {
-        Ok(response) => {
-            if response.success {
-                if let Some(result) = response.result {
-                    info!("TRIGGER SCORING: Success!");
-                    info!("Score: {}", result.overall_score);
-                    info!("Recommendation: {}", result.recommendation);
-
-                    // Store the scoring result
-                    let scoring_json = serde_json::to_string(&result).ok();
-
-                    if let Err(e) = crate::database::update_talent_candidate_score(
-                        pool,
-                        talent.id.clone(),
-                        result.overall_score,
-                        scoring_json,
-                    ).await {
-                        error!("Failed to update talent score: {}", e);
-                    }
-
-                    return Ok(HttpResponse::Ok().json(TriggerScoringResponse {
-                        success: true,
-                        message: "Scoring completed successfully".to_string(),
-                        score: Some(result.overall_score),
-                        recommendation: Some(result.recommendation),
-                    }));
-                }
-            }
-
-            Ok(HttpResponse::InternalServerError().json(TriggerScoringResponse {
-                success: false,
-                message: response.error.unwrap_or_else(|| "Scoring failed".to_string()),
-                score: None,
-                recommendation: None,
-            }))
-        }
-        Err(e) => {
-            error!("TRIGGER SCORING: Error - {}", e);
-            Ok(HttpResponse::InternalServerError().json(TriggerScoringResponse {
-                success: false,
-                message: format!("Scoring service error: {}", e),
-                score: None,
-                recommendation: None,
-            }))
+    let response = client.score_candidate(&scoring_request).await
+        .map_err(AppError::grok)?;
+
+    let result = match (response.success, response.result) {
+        (true, Some(result)) => result,
+        _ => {
+            return Err(AppError::grok(response.error.unwrap_or_else(|| "Scoring failed".to_string())));
         }
+    };
+
+    info!("TRIGGER SCORING: Success!");
+    info!("Score: {}", result.overall_score);
+    info!("Recommendation: {}", result.recommendation);
+
+    // Store the scoring result
+    let scoring_json = serde_json::to_string(&result).ok();
+
+    match crate::database::update_talent_candidate_score(
+        pool,
+        talent.id.clone(),
+        result.overall_score,
+        scoring_json,
+    ).await {
+        Ok(_) => data.events.publish(EventKind::TalentScored, events::talent_scored(&result)).await,
+        Err(e) => error!("Failed to update talent score: {}", e),
     }
-}
\ No newline at end of file
+
+    Ok(HttpResponse::Ok().json(TriggerScoringResponse {
+        success: true,
+        message: "Scoring completed successfully".to_string(),
+        score: Some(result.overall_score),
+        recommendation: Some(result.recommendation),
+    }))
+}
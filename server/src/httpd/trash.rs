@@ -0,0 +1,21 @@
+use actix_web::{web, HttpResponse};
+use paperclip::actix::api_v2_operation;
+
+use crate::error::AppResult;
+use crate::models::{TrashListing, TrashedApplication};
+use crate::retention::DataClass;
+use super::server::AppState;
+
+#[api_v2_operation]
+#[paperclip::actix::get("/api/v1/trash", summary = "List soft-deleted talents, jobs and applications")]
+pub async fn get_trash(data: web::Data<AppState>) -> AppResult<HttpResponse> {
+    let pool = &data.db_pool;
+    let applications = crate::database::get_deleted_applications(pool).await?;
+
+    Ok(HttpResponse::Ok().json(TrashListing {
+        grace_days: data.retention.days_for(DataClass::Trash),
+        talents: crate::database::get_deleted_talents(pool).await?,
+        jobs: crate::database::get_deleted_jobs(pool).await?,
+        applications: applications.into_iter().map(TrashedApplication::from).collect(),
+    }))
+}
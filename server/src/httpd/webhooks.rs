@@ -0,0 +1,241 @@
+use actix_web::{web, HttpResponse};
+use chrono::Utc;
+use log::{error, info};
+use paperclip::actix::api_v2_operation;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::database::Pool;
+use crate::error::{AppError, AppResult};
+use crate::events::Event;
+use crate::models::{
+    CreateWebhookRequest, UpdateWebhookRequest, Webhook, WebhookDelivery, WebhookDeliveryQuery, WebhookDeliveryResponse,
+    WebhookResponse,
+};
+use crate::webhooks;
+use super::server::AppState;
+
+const MIN_SECRET_LENGTH: usize = 16;
+const DEFAULT_DELIVERY_LIMIT: i64 = 50;
+const MAX_DELIVERY_LIMIT: i64 = 500;
+
+async fn live_webhook(pool: &Pool, id: &str) -> AppResult<Webhook> {
+    crate::database::get_webhook(pool, id).await?
+        .ok_or_else(|| AppError::not_found("Webhook not found"))
+}
+
+fn validate_webhook(url: &str, events: &[String], secret: Option<&str>) -> AppResult<Vec<String>> {
+    let mut errors = Vec::new();
+    if let Err(e) = webhooks::validate_url(url) {
+        errors.push(e);
+    }
+    let events = webhooks::normalize_events(events).unwrap_or_else(|e| {
+        errors.extend(e);
+        Vec::new()
+    });
+    if secret.is_some_and(|s| s.trim().len() < MIN_SECRET_LENGTH) {
+        errors.push(format!("secret must be at least {} characters", MIN_SECRET_LENGTH));
+    }
+    if errors.is_empty() { Ok(events) } else { Err(AppError::validation(errors.join("; "))) }
+}
+
+/// Response that includes the signing secret, which is only shown when set
+fn with_secret(webhook: Webhook) -> WebhookResponse {
+    let secret = webhook.secret.clone();
+    WebhookResponse { secret: Some(secret), ..WebhookResponse::from(webhook) }
+}
+
+#[api_v2_operation]
+#[paperclip::actix::post("/api/v1/webhooks", summary = "Register a webhook endpoint")]
+pub async fn create_webhook(
+    data: web::Data<AppState>,
+    json: web::Json<CreateWebhookRequest>,
+) -> AppResult<HttpResponse> {
+    let events = validate_webhook(&json.url, &json.events, json.secret.as_deref())?;
+    let now = Utc::now().to_rfc3339();
+    let webhook = Webhook {
+        id: Uuid::new_v4().to_string(),
+        url: json.url.trim().to_string(),
+        secret: json.secret.as_deref().map(str::trim).map(str::to_string).unwrap_or_else(webhooks::generate_secret),
+        events: serde_json::to_string(&events)?,
+        description: json.description.clone(),
+        active: 1,
+        created_at: now.clone(),
+        updated_at: now,
+    };
+
+    let created = crate::database::create_webhook(&data.db_pool, &webhook).await?;
+    info!("[webhooks] Registered webhook {} for {} ({})", created.id, created.url, events.join(", "));
+    Ok(HttpResponse::Created().json(with_secret(created)))
+}
+
+#[api_v2_operation]
+#[paperclip::actix::get("/api/v1/webhooks", summary = "List webhook endpoints")]
+pub async fn get_webhooks(data: web::Data<AppState>) -> AppResult<HttpResponse> {
+    let webhooks: Vec<WebhookResponse> = crate::database::get_webhooks(&data.db_pool).await?
+        .into_iter()
+        .map(WebhookResponse::from)
+        .collect();
+    Ok(HttpResponse::Ok().json(webhooks))
+}
+
+#[api_v2_operation]
+#[paperclip::actix::get("/api/v1/webhooks/{id}", summary = "Get a webhook endpoint")]
+pub async fn get_webhook(
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+) -> AppResult<HttpResponse> {
+    let webhook = live_webhook(&data.db_pool, &path.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(WebhookResponse::from(webhook)))
+}
+
+#[api_v2_operation]
+#[paperclip::actix::put("/api/v1/webhooks/{id}", summary = "Update, enable or disable a webhook endpoint, or rotate its secret")]
+pub async fn update_webhook(
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+    json: web::Json<UpdateWebhookRequest>,
+) -> AppResult<HttpResponse> {
+    let pool = &data.db_pool;
+    let existing = live_webhook(pool, &path.into_inner()).await?;
+
+    let url = json.url.as_deref().map(str::trim).unwrap_or(&existing.url).to_string();
+    let events = match &json.events {
+        Some(events) => events.clone(),
+        None => serde_json::from_str(&existing.events).unwrap_or_default(),
+    };
+    let events = validate_webhook(&url, &events, None)?;
+    let rotate = json.rotate_secret.unwrap_or(false);
+
+    let webhook = Webhook {
+        url,
+        secret: if rotate { webhooks::generate_secret() } else { existing.secret.clone() },
+        events: serde_json::to_string(&events)?,
+        description: json.description.clone().or(existing.description.clone()),
+        active: json.active.map(i32::from).unwrap_or(existing.active),
+        updated_at: Utc::now().to_rfc3339(),
+        ..existing
+    };
+    let updated = crate::database::update_webhook(pool, &webhook).await?
+        .ok_or_else(|| AppError::not_found("Webhook not found"))?;
+
+    // Re-enabling lets deliveries queued while the webhook was disabled go out
+    if updated.active != 0 {
+        data.events.wake();
+    }
+    if rotate {
+        info!("[webhooks] Rotated the secret of webhook {}", updated.id);
+        return Ok(HttpResponse::Ok().json(with_secret(updated)));
+    }
+    Ok(HttpResponse::Ok().json(WebhookResponse::from(updated)))
+}
+
+#[api_v2_operation]
+#[paperclip::actix::delete("/api/v1/webhooks/{id}", summary = "Delete a webhook endpoint and its delivery log")]
+pub async fn delete_webhook(
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+) -> AppResult<HttpResponse> {
+    let id = path.into_inner();
+    if !crate::database::delete_webhook(&data.db_pool, &id).await? {
+        return Err(AppError::not_found("Webhook not found"));
+    }
+    info!("[webhooks] Deleted webhook {}", id);
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[api_v2_operation]
+#[paperclip::actix::get("/api/v1/webhooks/{id}/deliveries", summary = "List a webhook's deliveries, newest first")]
+pub async fn get_webhook_deliveries(
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+    query: web::Query<WebhookDeliveryQuery>,
+) -> AppResult<HttpResponse> {
+    let pool = &data.db_pool;
+    let webhook = live_webhook(pool, &path.into_inner()).await?;
+
+    let status = query.status.as_deref().map(str::trim).map(str::to_lowercase);
+    if status.as_deref().is_some_and(|s| !matches!(s, "pending" | "sending" | "succeeded" | "failed")) {
+        return Err(AppError::validation("status must be pending, sending, succeeded or failed"));
+    }
+    let limit = query.limit.unwrap_or(DEFAULT_DELIVERY_LIMIT).clamp(1, MAX_DELIVERY_LIMIT);
+
+    let deliveries: Vec<WebhookDeliveryResponse> =
+        crate::database::get_webhook_deliveries(pool, &webhook.id, status.as_deref(), limit).await?
+            .into_iter()
+            .map(WebhookDeliveryResponse::from)
+            .collect();
+    Ok(HttpResponse::Ok().json(deliveries))
+}
+
+#[api_v2_operation]
+#[paperclip::actix::post("/api/v1/webhooks/{id}/test", summary = "Send a test event to a webhook and return the delivery")]
+pub async fn test_webhook(
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+) -> AppResult<HttpResponse> {
+    let pool = &data.db_pool;
+    let webhook = live_webhook(pool, &path.into_inner()).await?;
+
+    let event = Event::new(webhooks::TEST_EVENT, json!({ "webhook_id": webhook.id }));
+    let payload = serde_json::to_string(&event)?;
+    let delivery = webhooks::new_inline_delivery(&webhook.id, &event.id, &event.event_type, payload);
+    let delivery = crate::database::insert_webhook_delivery(pool, &delivery).await?;
+
+    // Test deliveries are attempted once, right away, and never retried. If
+    // the outcome cannot be recorded the delivery would stay `sending`
+    // forever, so it is marked failed instead.
+    let delivered = match webhooks::deliver(pool, &webhooks::http_client(), &webhook, delivery.clone(), false).await {
+        Ok(delivered) => delivered,
+        Err(e) => {
+            error!("[webhooks] Failed to record test delivery {}: {}", delivery.id, e);
+            let failed = WebhookDelivery {
+                status: "failed".to_string(),
+                error: Some("Delivery outcome could not be recorded".to_string()),
+                ..delivery
+            };
+            if let Err(e) = crate::database::update_webhook_delivery(pool, &failed).await {
+                error!("[webhooks] Failed to mark test delivery {} failed: {}", failed.id, e);
+            }
+            return Err(e.into());
+        }
+    };
+    Ok(HttpResponse::Ok().json(WebhookDeliveryResponse::from(delivered)))
+}
+
+#[api_v2_operation]
+#[paperclip::actix::get("/api/v1/webhooks/deliveries/{id}", summary = "Get a webhook delivery")]
+pub async fn get_webhook_delivery(
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+) -> AppResult<HttpResponse> {
+    let delivery = crate::database::get_webhook_delivery(&data.db_pool, &path.into_inner()).await?
+        .ok_or_else(|| AppError::not_found("Delivery not found"))?;
+    Ok(HttpResponse::Ok().json(WebhookDeliveryResponse::from(delivery)))
+}
+
+#[api_v2_operation]
+#[paperclip::actix::post("/api/v1/webhooks/deliveries/{id}/redeliver", summary = "Queue a delivery to be sent again")]
+pub async fn redeliver_webhook_delivery(
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+) -> AppResult<HttpResponse> {
+    let pool = &data.db_pool;
+    let original = crate::database::get_webhook_delivery(pool, &path.into_inner()).await?
+        .ok_or_else(|| AppError::not_found("Delivery not found"))?;
+    let webhook = live_webhook(pool, &original.webhook_id).await?;
+    if webhook.active == 0 {
+        return Err(AppError::conflict("Webhook is disabled; enable it before redelivering"));
+    }
+
+    // Same event ID and payload so receivers can deduplicate, new delivery ID
+    let delivery = WebhookDelivery {
+        redelivery_of: Some(original.id.clone()),
+        ..webhooks::new_delivery(&webhook.id, &original.event_id, &original.event_type, original.payload)
+    };
+    let queued = crate::database::insert_webhook_delivery(pool, &delivery).await?;
+    data.events.wake();
+
+    info!("[webhooks] Queued redelivery {} of {}", queued.id, original.id);
+    Ok(HttpResponse::Accepted().json(WebhookDeliveryResponse::from(queued)))
+}
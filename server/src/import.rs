@@ -0,0 +1,356 @@
+//! Bulk import of talents and jobs
+//!
+//! Rows arrive as CSV (with a header row) or NDJSON. Source columns can be
+//! renamed with a `source:target` mapping, every row is validated on its
+//! own, and valid rows are written in transactional batches. Talents are
+//! matched to existing ones by email, jobs by company name and title.
+
+use std::collections::{BTreeSet, HashMap};
+
+use serde_json::{Map, Value};
+
+use crate::models::{CreateJobRequest, CreateTalentRequest, Job, Talent, UpdateJobRequest, UpdateTalentRequest};
+
+pub const DEFAULT_BATCH_SIZE: usize = 100;
+pub const MAX_BATCH_SIZE: usize = 1000;
+/// Request body limit for import endpoints
+pub const MAX_BODY_BYTES: usize = 10 * 1024 * 1024;
+
+pub const TALENT_FIELDS: &[&str] = &[
+    "name", "email", "handle", "avatar", "title", "location", "experience", "skills", "bio", "verified",
+];
+
+pub const JOB_FIELDS: &[&str] = &[
+    "title", "description", "company_name", "company_logo", "location", "location_type", "employment_type",
+    "salary_min", "salary_max", "salary_currency", "skills_required", "experience_level", "expires_at", "publish",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    Csv,
+    Ndjson,
+}
+
+impl ImportFormat {
+    /// From the `format` query parameter, falling back to the Content-Type
+    pub fn detect(format: Option<&str>, content_type: &str) -> Result<Self, String> {
+        match format {
+            Some("csv") => Ok(ImportFormat::Csv),
+            Some("ndjson") | Some("jsonl") => Ok(ImportFormat::Ndjson),
+            Some(other) => Err(format!("Unsupported format '{}'; use csv or ndjson", other)),
+            None if content_type.contains("ndjson") || content_type.contains("jsonl") => Ok(ImportFormat::Ndjson),
+            None => Ok(ImportFormat::Csv),
+        }
+    }
+}
+
+/// What to do with a row that matches an existing record
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OnDuplicate {
+    Skip,
+    Update,
+    Error,
+}
+
+impl OnDuplicate {
+    pub fn parse(value: Option<&str>) -> Result<Self, String> {
+        match value.unwrap_or("skip") {
+            "skip" => Ok(OnDuplicate::Skip),
+            "update" => Ok(OnDuplicate::Update),
+            "error" => Ok(OnDuplicate::Error),
+            other => Err(format!("Unsupported on_duplicate '{}'; use skip, update or error", other)),
+        }
+    }
+}
+
+/// Parse a column mapping such as `Full Name:name,E-mail:email`
+pub fn parse_mapping(spec: Option<&str>) -> Result<HashMap<String, String>, String> {
+    let mut mapping = HashMap::new();
+    for pair in spec.unwrap_or("").split(',').filter(|p| !p.trim().is_empty()) {
+        let (source, target) = pair
+            .rsplit_once(':')
+            .ok_or_else(|| format!("Invalid mapping '{}'; expected source:target", pair.trim()))?;
+        mapping.insert(source.trim().to_string(), target.trim().to_string());
+    }
+    Ok(mapping)
+}
+
+/// One input row: its 1-based line number and either its fields (already
+/// renamed through the mapping) or why it could not be read
+#[derive(Debug)]
+pub struct Row {
+    pub line: usize,
+    pub fields: Result<Map<String, Value>, String>,
+}
+
+fn mapped(mapping: &HashMap<String, String>, column: &str) -> String {
+    let column = column.trim();
+    mapping.get(column).cloned().unwrap_or_else(|| column.to_string())
+}
+
+pub fn parse_rows(body: &[u8], format: ImportFormat, mapping: &HashMap<String, String>) -> Result<Vec<Row>, String> {
+    match format {
+        ImportFormat::Csv => parse_csv(body, mapping),
+        ImportFormat::Ndjson => parse_ndjson(body, mapping),
+    }
+}
+
+fn parse_csv(body: &[u8], mapping: &HashMap<String, String>) -> Result<Vec<Row>, String> {
+    let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(body);
+    let headers: Vec<String> = reader
+        .headers()
+        .map_err(|e| format!("Invalid CSV header: {}", e))?
+        .iter()
+        .map(|h| mapped(mapping, h))
+        .collect();
+
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let row = match record {
+            Ok(record) => {
+                let line = record.position().map(|p| p.line() as usize).unwrap_or(0);
+                let fields = headers
+                    .iter()
+                    .zip(record.iter())
+                    .filter(|(_, value)| !value.trim().is_empty())
+                    .map(|(header, value)| (header.clone(), Value::String(value.trim().to_string())))
+                    .collect();
+                Row { line, fields: Ok(fields) }
+            }
+            Err(e) => Row {
+                line: e.position().map(|p| p.line() as usize).unwrap_or(0),
+                fields: Err(format!("Invalid CSV record: {}", e)),
+            },
+        };
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+fn parse_ndjson(body: &[u8], mapping: &HashMap<String, String>) -> Result<Vec<Row>, String> {
+    let text = std::str::from_utf8(body).map_err(|_| "NDJSON body must be UTF-8".to_string())?;
+    Ok(text
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            let fields = match serde_json::from_str::<Value>(line) {
+                Ok(Value::Object(object)) => Ok(object
+                    .into_iter()
+                    .filter(|(_, value)| !value.is_null())
+                    .map(|(key, value)| (mapped(mapping, &key), value))
+                    .collect()),
+                Ok(_) => Err("Expected a JSON object".to_string()),
+                Err(e) => Err(format!("Invalid JSON: {}", e)),
+            };
+            Row { line: i + 1, fields }
+        })
+        .collect())
+}
+
+/// Columns present in the input that do not correspond to any known field
+pub fn ignored_columns(rows: &[Row], known: &[&str]) -> Vec<String> {
+    rows.iter()
+        .filter_map(|row| row.fields.as_ref().ok())
+        .flat_map(|fields| fields.keys())
+        .filter(|key| !known.contains(&key.as_str()))
+        .cloned()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// Collects the validation errors for one row
+struct Fields<'a> {
+    fields: &'a Map<String, Value>,
+    errors: Vec<String>,
+}
+
+impl<'a> Fields<'a> {
+    fn new(fields: &'a Map<String, Value>) -> Self {
+        Fields { fields, errors: Vec::new() }
+    }
+
+    fn optional(&mut self, name: &str) -> Option<String> {
+        match self.fields.get(name)? {
+            Value::String(s) => Some(s.trim().to_string()).filter(|s| !s.is_empty()),
+            Value::Number(n) => Some(n.to_string()),
+            Value::Bool(b) => Some(b.to_string()),
+            Value::Array(items) => Some(
+                items.iter()
+                    .map(|item| item.as_str().map(str::to_string).unwrap_or_else(|| item.to_string()))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            ),
+            _ => {
+                self.errors.push(format!("{} must be a string", name));
+                None
+            }
+        }
+    }
+
+    fn required(&mut self, name: &str) -> String {
+        self.optional(name).unwrap_or_else(|| {
+            self.errors.push(format!("{} is required", name));
+            String::new()
+        })
+    }
+
+    fn bool(&mut self, name: &str) -> Option<bool> {
+        match self.fields.get(name)? {
+            Value::Bool(b) => Some(*b),
+            value => match value.as_str().map(|s| s.trim().to_lowercase()).as_deref() {
+                Some("true" | "yes" | "1") => Some(true),
+                Some("false" | "no" | "0") => Some(false),
+                _ => {
+                    self.errors.push(format!("{} must be true or false", name));
+                    None
+                }
+            },
+        }
+    }
+
+    fn integer(&mut self, name: &str) -> Option<i64> {
+        let value = self.fields.get(name)?;
+        let parsed = value.as_i64().or_else(|| value.as_str().and_then(|s| s.trim().parse().ok()));
+        if parsed.is_none() {
+            self.errors.push(format!("{} must be a whole number", name));
+        }
+        parsed
+    }
+
+    fn finish<T>(self, value: T) -> Result<T, Vec<String>> {
+        if self.errors.is_empty() { Ok(value) } else { Err(self.errors) }
+    }
+}
+
+pub fn talent_from_row(fields: &Map<String, Value>) -> Result<CreateTalentRequest, Vec<String>> {
+    let mut f = Fields::new(fields);
+    let request = CreateTalentRequest {
+        name: f.required("name"),
+        email: f.required("email"),
+        handle: f.required("handle"),
+        avatar: f.optional("avatar"),
+        title: f.required("title"),
+        location: f.optional("location"),
+        experience: f.required("experience"),
+        skills: f.required("skills"),
+        bio: f.optional("bio"),
+        verified: f.bool("verified").unwrap_or(false),
+    };
+    if !request.email.is_empty() && !request.email.contains('@') {
+        f.errors.push(format!("email '{}' is not a valid address", request.email));
+    }
+    f.finish(request)
+}
+
+pub fn job_from_row(fields: &Map<String, Value>) -> Result<CreateJobRequest, Vec<String>> {
+    let mut f = Fields::new(fields);
+    let request = CreateJobRequest {
+        title: f.required("title"),
+        description: f.required("description"),
+        company_name: f.required("company_name"),
+        company_logo: f.optional("company_logo"),
+        location: f.optional("location"),
+        location_type: f.required("location_type"),
+        employment_type: f.required("employment_type"),
+        salary_min: f.integer("salary_min"),
+        salary_max: f.integer("salary_max"),
+        salary_currency: f.optional("salary_currency"),
+        skills_required: f.required("skills_required"),
+        experience_level: f.required("experience_level"),
+        expires_at: f.optional("expires_at"),
+        publish: f.bool("publish"),
+    };
+    if let (Some(min), Some(max)) = (request.salary_min, request.salary_max) {
+        if min > max {
+            f.errors.push("salary_min must not exceed salary_max".to_string());
+        }
+    }
+    if let Some(expires_at) = &request.expires_at {
+        if crate::job_lifecycle::parse_expiry(expires_at).is_none() {
+            f.errors.push(format!("expires_at '{}' is not an RFC 3339 timestamp or YYYY-MM-DD date", expires_at));
+        }
+    }
+    f.finish(request)
+}
+
+/// A planned write for one talent row
+pub enum TalentImport {
+    Create(Box<Talent>),
+    Update(String, Box<UpdateTalentRequest>),
+}
+
+/// A planned write for one job row
+pub enum JobImport {
+    Create(Job),
+    Update(String, UpdateJobRequest),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_mapping() {
+        let mapping = parse_mapping(Some("Full Name:name, E-mail:email")).unwrap();
+        assert_eq!(mapping["Full Name"], "name");
+        assert_eq!(mapping["E-mail"], "email");
+        assert!(parse_mapping(None).unwrap().is_empty());
+        assert!(parse_mapping(Some("name")).is_err());
+    }
+
+    #[test]
+    fn test_detect_format() {
+        assert_eq!(ImportFormat::detect(None, "text/csv").unwrap(), ImportFormat::Csv);
+        assert_eq!(ImportFormat::detect(None, "application/x-ndjson").unwrap(), ImportFormat::Ndjson);
+        assert_eq!(ImportFormat::detect(Some("csv"), "application/x-ndjson").unwrap(), ImportFormat::Csv);
+        assert!(ImportFormat::detect(Some("xml"), "").is_err());
+    }
+
+    #[test]
+    fn test_csv_rows_with_mapping() {
+        let body = b"Full Name,email,handle,title,experience,skills,verified,Team\n\
+                     Ann Lee,ann@example.com,ann,Engineer,5y,\"rust, go\",yes,Core\n\
+                     Bob,bob-at-example,bob,,3y,python,maybe,\n";
+        let mapping = parse_mapping(Some("Full Name:name")).unwrap();
+        let rows = parse_rows(body, ImportFormat::Csv, &mapping).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].line, 2);
+        assert_eq!(ignored_columns(&rows, TALENT_FIELDS), vec!["Team".to_string()]);
+
+        let ann = talent_from_row(rows[0].fields.as_ref().unwrap()).unwrap();
+        assert_eq!(ann.name, "Ann Lee");
+        assert_eq!(ann.skills, "rust, go");
+        assert!(ann.verified);
+
+        let Err(errors) = talent_from_row(rows[1].fields.as_ref().unwrap()) else { panic!("row should be invalid") };
+        assert!(errors.contains(&"title is required".to_string()));
+        assert!(errors.contains(&"verified must be true or false".to_string()));
+        assert!(errors.iter().any(|e| e.contains("not a valid address")));
+    }
+
+    #[test]
+    fn test_ndjson_rows() {
+        let body = b"{\"title\":\"Dev\",\"description\":\"d\",\"company_name\":\"Acme\",\"location_type\":\"remote\",\
+                     \"employment_type\":\"full-time\",\"skills_required\":[\"rust\",\"sql\"],\"experience_level\":\"senior\",\
+                     \"salary_min\":\"90000\",\"salary_max\":120000,\"expires_at\":\"2030-06-30\"}\n\
+                     \n\
+                     [1, 2]\n\
+                     {\"title\":\"Ops\",\"salary_min\":5,\"salary_max\":1,\"expires_at\":\"soon\"}\n";
+        let rows = parse_rows(body, ImportFormat::Ndjson, &HashMap::new()).unwrap();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[1].line, 3);
+        assert!(rows[1].fields.is_err());
+
+        let dev = job_from_row(rows[0].fields.as_ref().unwrap()).unwrap();
+        assert_eq!(dev.skills_required, "rust, sql");
+        assert_eq!(dev.salary_min, Some(90000));
+        assert_eq!(dev.expires_at.as_deref(), Some("2030-06-30"));
+
+        let Err(errors) = job_from_row(rows[2].fields.as_ref().unwrap()) else { panic!("row should be invalid") };
+        assert!(errors.contains(&"salary_min must not exceed salary_max".to_string()));
+        assert!(errors.iter().any(|e| e.starts_with("expires_at")));
+        assert!(errors.contains(&"company_name is required".to_string()));
+    }
+}
@@ -0,0 +1,143 @@
+//! Referential integrity
+//!
+//! Every parent/child relationship in the schema and what deleting the parent
+//! does to the child. SQLite enforces the declared foreign keys on every
+//! pooled connection; this module additionally finds orphans left behind by
+//! databases created before enforcement was on, and repairs them.
+
+use std::fmt;
+
+use log::{info, warn};
+
+use crate::database::Pool;
+
+/// What happens to child rows when their parent row is hard-deleted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeletePolicy {
+    /// Child rows are deleted with the parent
+    Cascade,
+    /// The parent cannot be deleted while child rows exist
+    Restrict,
+}
+
+impl DeletePolicy {
+    pub fn as_sql(&self) -> &'static str {
+        match self {
+            DeletePolicy::Cascade => "CASCADE",
+            DeletePolicy::Restrict => "RESTRICT",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Relationship {
+    pub child_table: &'static str,
+    pub child_column: &'static str,
+    pub parent_table: &'static str,
+    pub on_delete: DeletePolicy,
+}
+
+impl Relationship {
+    /// SQL condition matching child rows whose parent does not exist
+    pub fn orphan_filter(&self) -> String {
+        format!(
+            "{column} IS NOT NULL AND {column} NOT IN (SELECT id FROM {parent})",
+            column = self.child_column,
+            parent = self.parent_table,
+        )
+    }
+}
+
+impl fmt::Display for Relationship {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{} -> {}", self.child_table, self.child_column, self.parent_table)
+    }
+}
+
+/// All relationships, ordered so that a parent's orphans are repaired before
+/// its own children are checked. Historical applications block hard-deleting
+/// their job; everything else follows its parent.
+pub const RELATIONSHIPS: [Relationship; 18] = [
+    Relationship { child_table: "applications", child_column: "talent_id", parent_table: "talents", on_delete: DeletePolicy::Cascade },
+    Relationship { child_table: "applications", child_column: "job_id", parent_table: "jobs", on_delete: DeletePolicy::Restrict },
+    Relationship { child_table: "job_matches", child_column: "talent_id", parent_table: "talents", on_delete: DeletePolicy::Cascade },
+    Relationship { child_table: "job_matches", child_column: "job_id", parent_table: "jobs", on_delete: DeletePolicy::Cascade },
+    Relationship { child_table: "match_runs", child_column: "job_id", parent_table: "jobs", on_delete: DeletePolicy::Cascade },
+    Relationship { child_table: "match_run_results", child_column: "run_id", parent_table: "match_runs", on_delete: DeletePolicy::Cascade },
+    Relationship { child_table: "match_run_results", child_column: "talent_id", parent_table: "talents", on_delete: DeletePolicy::Cascade },
+    Relationship { child_table: "candidate_login_tokens", child_column: "talent_id", parent_table: "talents", on_delete: DeletePolicy::Cascade },
+    Relationship { child_table: "reorder_events", child_column: "job_id", parent_table: "jobs", on_delete: DeletePolicy::Cascade },
+    Relationship { child_table: "pairwise_preferences", child_column: "job_id", parent_table: "jobs", on_delete: DeletePolicy::Cascade },
+    Relationship { child_table: "pairwise_preferences", child_column: "reorder_event_id", parent_table: "reorder_events", on_delete: DeletePolicy::Cascade },
+    Relationship { child_table: "job_candidate_orders", child_column: "job_id", parent_table: "jobs", on_delete: DeletePolicy::Cascade },
+    Relationship { child_table: "interviews", child_column: "application_id", parent_table: "applications", on_delete: DeletePolicy::Cascade },
+    Relationship { child_table: "scorecards", child_column: "interview_id", parent_table: "interviews", on_delete: DeletePolicy::Cascade },
+    Relationship { child_table: "offers", child_column: "application_id", parent_table: "applications", on_delete: DeletePolicy::Cascade },
+    Relationship { child_table: "offer_approvals", child_column: "offer_id", parent_table: "offers", on_delete: DeletePolicy::Cascade },
+    Relationship { child_table: "offer_events", child_column: "offer_id", parent_table: "offers", on_delete: DeletePolicy::Cascade },
+    Relationship { child_table: "webhook_deliveries", child_column: "webhook_id", parent_table: "webhooks", on_delete: DeletePolicy::Cascade },
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrphanCount {
+    pub relationship: Relationship,
+    pub rows: u64,
+}
+
+/// Count orphaned rows per relationship
+pub async fn check(pool: &Pool) -> Result<Vec<OrphanCount>, sqlx::Error> {
+    let mut counts = Vec::with_capacity(RELATIONSHIPS.len());
+    for relationship in RELATIONSHIPS {
+        let rows = crate::database::count_orphans(pool, &relationship).await?;
+        counts.push(OrphanCount { relationship, rows });
+    }
+    Ok(counts)
+}
+
+/// Startup check: log every relationship that has orphaned rows
+pub async fn report_orphans(pool: &Pool) -> Result<(), sqlx::Error> {
+    let orphans: Vec<OrphanCount> = check(pool).await?.into_iter().filter(|c| c.rows > 0).collect();
+    if orphans.is_empty() {
+        info!("[integrity] No orphaned rows found");
+        return Ok(());
+    }
+    for orphan in &orphans {
+        warn!("[integrity] {} orphaned rows in {}", orphan.rows, orphan.relationship);
+    }
+    warn!("[integrity] Run the server with --repair-integrity to delete orphaned rows");
+    Ok(())
+}
+
+/// Delete all orphaned rows in one transaction
+pub async fn repair(pool: &Pool) -> Result<Vec<OrphanCount>, sqlx::Error> {
+    let deleted = crate::database::delete_orphans(pool, &RELATIONSHIPS).await?;
+    Ok(RELATIONSHIPS
+        .into_iter()
+        .zip(deleted)
+        .map(|(relationship, rows)| OrphanCount { relationship, rows })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_orphan_filter() {
+        assert_eq!(
+            RELATIONSHIPS[0].orphan_filter(),
+            "talent_id IS NOT NULL AND talent_id NOT IN (SELECT id FROM talents)"
+        );
+        assert_eq!(RELATIONSHIPS[1].to_string(), "applications.job_id -> jobs");
+    }
+
+    #[test]
+    fn test_parents_are_repaired_before_children() {
+        for (i, relationship) in RELATIONSHIPS.iter().enumerate() {
+            let later_parent = RELATIONSHIPS[i..]
+                .iter()
+                .any(|other| other.child_table == relationship.parent_table);
+            assert!(!later_parent, "{} is repaired before its parent table", relationship);
+        }
+    }
+}
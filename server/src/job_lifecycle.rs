@@ -0,0 +1,211 @@
+//! Job posting lifecycle
+//!
+//! Jobs move through `draft → published ⇄ paused → closed | filled`. Only
+//! published jobs accept applications. A background sweeper closes jobs whose
+//! `expires_at` has passed and can optionally reject the remaining applicants.
+
+use std::fmt;
+use std::time::Duration;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use log::{error, info};
+
+use crate::database::Pool;
+use crate::events::{self, EventKind, Events};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobStatus {
+    Draft,
+    Published,
+    Paused,
+    Closed,
+    Filled,
+}
+
+impl JobStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobStatus::Draft => "draft",
+            JobStatus::Published => "published",
+            JobStatus::Paused => "paused",
+            JobStatus::Closed => "closed",
+            JobStatus::Filled => "filled",
+        }
+    }
+
+    /// Parse a stored status. The legacy `active` value maps to `Published`.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "draft" => Some(JobStatus::Draft),
+            "published" | "active" => Some(JobStatus::Published),
+            "paused" => Some(JobStatus::Paused),
+            "closed" => Some(JobStatus::Closed),
+            "filled" => Some(JobStatus::Filled),
+            _ => None,
+        }
+    }
+
+    /// Whether candidates can currently apply to a job in this status
+    pub fn is_open(&self) -> bool {
+        matches!(self, JobStatus::Published)
+    }
+
+    /// Whether this is a final status
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, JobStatus::Closed | JobStatus::Filled)
+    }
+
+    /// Whether a job may move from `self` to `next`
+    pub fn can_transition_to(&self, next: JobStatus) -> bool {
+        use JobStatus::*;
+        matches!(
+            (self, next),
+            (Draft, Published)
+                | (Draft, Closed)
+                | (Published, Paused)
+                | (Published, Closed)
+                | (Published, Filled)
+                | (Paused, Published)
+                | (Paused, Closed)
+                | (Paused, Filled)
+        )
+    }
+}
+
+impl fmt::Display for JobStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Parse a job's `expires_at` value.
+///
+/// Accepts RFC 3339 timestamps as well as plain `YYYY-MM-DD` dates, which are
+/// treated as expiring at the end of that day (UTC).
+pub fn parse_expiry(expires_at: &str) -> Option<DateTime<Utc>> {
+    let value = expires_at.trim();
+    if value.is_empty() {
+        return None;
+    }
+    if let Ok(ts) = DateTime::parse_from_rfc3339(value) {
+        return Some(ts.with_timezone(&Utc));
+    }
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .and_then(|d| d.and_hms_opt(23, 59, 59))
+        .map(|dt| dt.and_utc())
+}
+
+/// Whether a job with the given `expires_at` is past its expiry at `now`
+pub fn is_expired(expires_at: Option<&str>, now: DateTime<Utc>) -> bool {
+    expires_at
+        .and_then(parse_expiry)
+        .map(|ts| ts <= now)
+        .unwrap_or(false)
+}
+
+/// Close every open job whose `expires_at` has passed.
+///
+/// With `reject_remaining`, each auto-rejected application publishes
+/// `application.status_changed`, so its candidate is emailed like any other
+/// rejection. Returns the IDs of the jobs that were closed.
+pub async fn close_expired_jobs(pool: &Pool, events: &Events, reject_remaining: bool) -> Result<Vec<String>, sqlx::Error> {
+    let now = Utc::now();
+    let candidates = crate::database::get_jobs_with_expiry(pool).await?;
+
+    let mut closed = Vec::new();
+    for job in candidates {
+        let open = JobStatus::parse(&job.status)
+            .map(|s| !s.is_terminal() && s != JobStatus::Draft)
+            .unwrap_or(false);
+        if !open || !is_expired(job.expires_at.as_deref(), now) {
+            continue;
+        }
+
+        // Skip jobs moved by someone else since they were read
+        if crate::database::set_job_status(pool, &job.id, &job.status, JobStatus::Closed).await?.is_none() {
+            continue;
+        }
+        info!("[job_lifecycle] Closed expired job {} ({})", job.id, job.title);
+
+        if reject_remaining {
+            let rejected = crate::database::reject_open_applications_for_job(pool, &job.id).await?;
+            info!("[job_lifecycle] Auto-rejected {} remaining applications for job {}", rejected.len(), job.id);
+            for (application, previous) in &rejected {
+                events.publish(EventKind::ApplicationStatusChanged, events::application_status_changed(application, previous)).await;
+            }
+        }
+        closed.push(job.id);
+    }
+
+    Ok(closed)
+}
+
+/// Spawn the background task that periodically closes expired jobs
+pub fn spawn_expiry_sweeper(pool: Pool, events: Events, interval: Duration, reject_remaining: bool) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            match close_expired_jobs(&pool, &events, reject_remaining).await {
+                Ok(closed) if !closed.is_empty() => {
+                    info!("[job_lifecycle] Expiry sweep closed {} jobs", closed.len());
+                }
+                Ok(_) => {}
+                Err(e) => error!("[job_lifecycle] Expiry sweep failed: {}", e),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_legacy_active_is_published() {
+        assert_eq!(JobStatus::parse("active"), Some(JobStatus::Published));
+        assert_eq!(JobStatus::parse("Published"), Some(JobStatus::Published));
+        assert_eq!(JobStatus::parse("archived"), None);
+    }
+
+    #[test]
+    fn test_transitions() {
+        assert!(JobStatus::Draft.can_transition_to(JobStatus::Published));
+        assert!(JobStatus::Published.can_transition_to(JobStatus::Paused));
+        assert!(JobStatus::Paused.can_transition_to(JobStatus::Published));
+        assert!(JobStatus::Published.can_transition_to(JobStatus::Filled));
+        assert!(!JobStatus::Draft.can_transition_to(JobStatus::Filled));
+        assert!(!JobStatus::Closed.can_transition_to(JobStatus::Published));
+        assert!(!JobStatus::Filled.can_transition_to(JobStatus::Closed));
+    }
+
+    #[test]
+    fn test_only_published_is_open() {
+        assert!(JobStatus::Published.is_open());
+        assert!(!JobStatus::Draft.is_open());
+        assert!(!JobStatus::Paused.is_open());
+        assert!(!JobStatus::Closed.is_open());
+    }
+
+    #[test]
+    fn test_parse_expiry_formats() {
+        let ts = parse_expiry("2026-01-15T10:00:00Z").unwrap();
+        assert_eq!(ts.to_rfc3339(), "2026-01-15T10:00:00+00:00");
+
+        let day = parse_expiry("2026-01-15").unwrap();
+        assert_eq!(day.to_rfc3339(), "2026-01-15T23:59:59+00:00");
+
+        assert!(parse_expiry("").is_none());
+        assert!(parse_expiry("next week").is_none());
+    }
+
+    #[test]
+    fn test_is_expired() {
+        let now = parse_expiry("2026-01-15T12:00:00Z").unwrap();
+        assert!(is_expired(Some("2026-01-14"), now));
+        assert!(!is_expired(Some("2026-01-15"), now));
+        assert!(!is_expired(None, now));
+        assert!(!is_expired(Some("garbage"), now));
+    }
+}
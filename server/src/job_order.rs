@@ -0,0 +1,100 @@
+//! Recruiter-curated candidate order per job
+//!
+//! Each job's current order is stored with a version that every change bumps.
+//! Reorders replace it (see the reorder endpoint); reconciliation keeps it in
+//! line with the job's candidates as talents apply, are matched or are
+//! deleted. Reconciled orders are published as `job_order.reconciled` so
+//! collaborators see them.
+
+use log::{error, info};
+use serde_json::json;
+
+use crate::database::Pool;
+use crate::events::{EventKind, Events};
+use crate::models::JobCandidateOrder;
+use crate::reorder::reconcile_order;
+
+/// Attempts at saving a reconciled order before giving up to a busier writer
+const MAX_RECONCILE_ATTEMPTS: usize = 3;
+
+/// A job's current candidate order and its version. Jobs reordered before
+/// orders were stored start from their latest reorder, at version 0; jobs
+/// never reordered have no order yet.
+pub async fn current(pool: &Pool, job_id: &str) -> Result<Option<(Vec<String>, i64)>, sqlx::Error> {
+    if let Some(order) = crate::database::get_job_candidate_order(pool, job_id).await? {
+        return Ok(Some((serde_json::from_str(&order.talent_ids).unwrap_or_default(), order.version)));
+    }
+    Ok(crate::database::get_latest_reorder_event(pool, job_id)
+        .await?
+        .map(|event| (serde_json::from_str(&event.after_order).unwrap_or_default(), 0)))
+}
+
+/// The job's current order and version, or its candidates in their natural
+/// order (applicants newest first, then matches by rank) at version 0
+pub async fn current_or_default(pool: &Pool, job_id: &str) -> Result<(Vec<String>, i64), sqlx::Error> {
+    match current(pool, job_id).await? {
+        Some(current) => Ok(current),
+        None => Ok((crate::database::get_job_candidate_ids(pool, job_id).await?, 0)),
+    }
+}
+
+/// Update the job's stored order after its candidates changed. Returns the
+/// new order, or None if it was already up to date or the job has none.
+pub async fn reconcile(pool: &Pool, events: &Events, job_id: &str) -> Result<Option<JobCandidateOrder>, sqlx::Error> {
+    for _ in 0..MAX_RECONCILE_ATTEMPTS {
+        let Some((order, version)) = current(pool, job_id).await? else {
+            return Ok(None);
+        };
+        let candidates = crate::database::get_job_candidate_ids(pool, job_id).await?;
+        let Some(reconciled) = reconcile_order(&order, &candidates) else {
+            return Ok(None);
+        };
+
+        let update = JobCandidateOrder {
+            job_id: job_id.to_string(),
+            talent_ids: serde_json::to_string(&reconciled).unwrap_or_default(),
+            version: version + 1,
+            reorder_event_id: None,
+            updated_at: chrono::Utc::now().to_rfc3339(),
+        };
+        // A reorder saved in the meantime wins; reconcile on top of it
+        let Some(saved) = crate::database::save_job_candidate_order(pool, &update, version).await? else {
+            continue;
+        };
+
+        let added: Vec<&String> = reconciled.iter().filter(|id| !order.contains(id)).collect();
+        let removed: Vec<&String> = order.iter().filter(|id| !reconciled.contains(id)).collect();
+        info!(
+            "[job_order] Reconciled job {} to version {}: {} added, {} removed",
+            job_id, saved.version, added.len(), removed.len()
+        );
+        events.publish(EventKind::JobOrderReconciled, json!({
+            "job_id": job_id,
+            "version": saved.version,
+            "order": reconciled,
+            "added": added,
+            "removed": removed,
+        })).await;
+        return Ok(Some(saved));
+    }
+    Ok(None)
+}
+
+/// Reconcile the orders of the given jobs, logging failures: a stale order is
+/// fixed by the next reorder or an explicit reconcile, so it never fails the
+/// change that caused it
+pub async fn reconcile_jobs(pool: &Pool, events: &Events, job_ids: impl IntoIterator<Item = String>) {
+    for job_id in job_ids {
+        if let Err(e) = reconcile(pool, events, &job_id).await {
+            error!("[job_order] Failed to reconcile the order of job {}: {}", job_id, e);
+        }
+    }
+}
+
+/// Reconcile every job whose order the talent is or should be part of
+pub async fn reconcile_for_talent(pool: &Pool, events: &Events, talent_id: &str) {
+    match crate::database::get_job_ids_for_talent_orders(pool, talent_id).await {
+        Ok(job_ids) => reconcile_jobs(pool, events, job_ids).await,
+        Err(e) => error!("[job_order] Failed to find the jobs of talent {}: {}", talent_id, e),
+    }
+}
@@ -0,0 +1,400 @@
+//! JSON Resume (https://jsonresume.org) interchange
+//!
+//! `basics` maps onto the talent profile, `work` onto the resume experiences
+//! and `basics.profiles` onto the profile URLs. Sections without a talent
+//! equivalent (education, projects, ...) are ignored on import, and talent
+//! fields without a JSON Resume equivalent (handle, free-text location) are
+//! left out of exports. Re-importing a resume keeps a talent's values for
+//! whatever the resume leaves out.
+
+use chrono::{Datelike, NaiveDate};
+
+use crate::grok_client::ExperienceSummary;
+use crate::models::{
+    CreateTalentRequest, JsonResume, ResumeBasics, ResumeProfile, ResumeSkill, ResumeWork, Talent, UpdateTalentRequest,
+};
+
+pub const SCHEMA_URL: &str = "https://raw.githubusercontent.com/jsonresume/resume-schema/v1.0.0/schema.json";
+
+/// Placeholder for new talents whose work history has no usable dates
+const UNKNOWN_EXPERIENCE: &str = "Not specified";
+
+/// Talent profile fields carried by a JSON Resume; `None` where the resume
+/// has nothing to say
+#[derive(Debug)]
+pub struct ResumeTalent {
+    pub name: String,
+    pub email: String,
+    pub title: String,
+    pub handle: Option<String>,
+    pub avatar: Option<String>,
+    pub location: Option<String>,
+    pub experience: Option<String>,
+    pub skills: Option<String>,
+    pub bio: Option<String>,
+}
+
+impl ResumeTalent {
+    /// A request for a new talent, with placeholders for the required
+    /// fields the resume leaves out
+    pub fn to_create(&self) -> CreateTalentRequest {
+        CreateTalentRequest {
+            name: self.name.clone(),
+            email: self.email.clone(),
+            handle: self.handle.clone()
+                .unwrap_or_else(|| self.email.split('@').next().unwrap_or_default().to_string()),
+            avatar: self.avatar.clone(),
+            title: self.title.clone(),
+            location: self.location.clone(),
+            experience: self.experience.clone().unwrap_or_else(|| UNKNOWN_EXPERIENCE.to_string()),
+            skills: self.skills.clone().unwrap_or_default(),
+            bio: self.bio.clone(),
+            verified: false,
+        }
+    }
+
+    /// Update an existing talent, keeping its current values for anything
+    /// the resume leaves out
+    pub fn to_update(self, talent: Talent) -> UpdateTalentRequest {
+        UpdateTalentRequest {
+            name: Some(self.name),
+            email: Some(talent.email),
+            handle: Some(self.handle.unwrap_or(talent.handle)),
+            avatar: self.avatar.or(talent.avatar),
+            title: Some(self.title),
+            location: self.location.or(talent.location),
+            experience: Some(self.experience.unwrap_or(talent.experience)),
+            skills: Some(self.skills.unwrap_or_else(|| crate::database::decode_skills(talent.skills))),
+            bio: self.bio.or(talent.bio),
+            verified: Some(talent.verified != 0),
+        }
+    }
+}
+
+/// Resume-extracted talent fields carried by a JSON Resume
+#[derive(Debug, Default)]
+pub struct ResumeFields {
+    pub experiences: Option<String>, // JSON array of ExperienceSummary
+    pub linkedin_url: Option<String>,
+    pub x_url: Option<String>,
+    pub github_url: Option<String>,
+    pub gitlab_url: Option<String>,
+}
+
+impl ResumeFields {
+    /// Keep the talent's current values for anything the resume leaves out
+    pub fn or(self, talent: &Talent) -> Self {
+        ResumeFields {
+            experiences: self.experiences.or_else(|| talent.resume_experiences.clone()),
+            linkedin_url: self.linkedin_url.or_else(|| talent.linkedin_url.clone()),
+            x_url: self.x_url.or_else(|| talent.x_url.clone()),
+            github_url: self.github_url.or_else(|| talent.github_url.clone()),
+            gitlab_url: self.gitlab_url.or_else(|| talent.gitlab_url.clone()),
+        }
+    }
+}
+
+pub struct ResumeImport {
+    pub talent: ResumeTalent,
+    pub resume: ResumeFields,
+}
+
+fn non_empty(value: &Option<String>) -> Option<String> {
+    value.as_deref().map(str::trim).filter(|s| !s.is_empty()).map(str::to_string)
+}
+
+/// Parse a JSON Resume date (YYYY, YYYY-MM or YYYY-MM-DD) to its first day
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    let mut parts = value.trim().splitn(3, '-');
+    let year = parts.next()?.parse().ok()?;
+    let month = parts.next().map_or(Some(1), |m| m.parse().ok())?;
+    NaiveDate::from_ymd_opt(year, month, 1)
+}
+
+/// Total span of the work history, from the earliest start to the latest end
+fn experience_span(work: &[ResumeWork], today: NaiveDate) -> Option<String> {
+    let starts = work.iter().filter_map(|w| w.start_date.as_deref().and_then(parse_date));
+    let start = starts.min()?;
+    let end = work
+        .iter()
+        .filter(|w| w.start_date.as_deref().and_then(parse_date).is_some())
+        .map(|w| w.end_date.as_deref().and_then(parse_date).unwrap_or(today))
+        .max()?;
+
+    let months = (end.year() - start.year()) * 12 + end.month() as i32 - start.month() as i32;
+    Some(match months / 12 {
+        years if years < 1 => "Less than a year".to_string(),
+        1 => "1 year".to_string(),
+        years => format!("{} years", years),
+    })
+}
+
+fn experience_from_work(work: &ResumeWork) -> Option<ExperienceSummary> {
+    let company = non_empty(&work.name)?;
+    let summary = non_empty(&work.summary)
+        .into_iter()
+        .chain(work.highlights.iter().map(|h| h.trim().to_string()).filter(|h| !h.is_empty()))
+        .collect::<Vec<_>>()
+        .join(" ");
+    Some(ExperienceSummary {
+        company,
+        role: non_empty(&work.position).unwrap_or_default(),
+        duration: non_empty(&work.start_date)
+            .map(|start| format!("{} - {}", start, non_empty(&work.end_date).unwrap_or_else(|| "Present".to_string()))),
+        summary,
+    })
+}
+
+fn work_from_experience(experience: ExperienceSummary) -> ResumeWork {
+    // Durations written on import ("2019-03 - Present") map back to dates;
+    // free-text durations from resume screening have no JSON Resume field
+    let (start_date, end_date) = match experience.duration.as_deref().and_then(|d| d.split_once(" - ")) {
+        Some((start, end)) if parse_date(start).is_some() => {
+            (Some(start.trim().to_string()), parse_date(end).map(|_| end.trim().to_string()))
+        }
+        _ => (None, None),
+    };
+    ResumeWork {
+        name: Some(experience.company),
+        position: Some(experience.role).filter(|r| !r.is_empty()),
+        start_date,
+        end_date,
+        summary: Some(experience.summary).filter(|s| !s.is_empty()),
+        highlights: Vec::new(),
+    }
+}
+
+/// Which profile URL field a JSON Resume network maps to
+fn profile_slot<'a>(fields: &'a mut ResumeFields, network: &str) -> Option<&'a mut Option<String>> {
+    match network.trim().to_lowercase().as_str() {
+        "linkedin" => Some(&mut fields.linkedin_url),
+        "github" => Some(&mut fields.github_url),
+        "gitlab" => Some(&mut fields.gitlab_url),
+        "x" | "twitter" => Some(&mut fields.x_url),
+        _ => None,
+    }
+}
+
+/// Map a JSON Resume document onto talent profile and resume fields
+pub fn to_import(resume: &JsonResume, today: NaiveDate) -> Result<ResumeImport, Vec<String>> {
+    let basics = &resume.basics;
+    let mut errors = Vec::new();
+
+    let name = non_empty(&basics.name).unwrap_or_else(|| {
+        errors.push("basics.name is required".to_string());
+        String::new()
+    });
+    let email = match non_empty(&basics.email) {
+        Some(email) if email.contains('@') => email,
+        Some(email) => {
+            errors.push(format!("basics.email '{}' is not a valid address", email));
+            String::new()
+        }
+        None => {
+            errors.push("basics.email is required".to_string());
+            String::new()
+        }
+    };
+    let title = non_empty(&basics.label)
+        .or_else(|| resume.work.iter().find_map(|w| non_empty(&w.position)))
+        .unwrap_or_else(|| {
+            errors.push("basics.label (or a work position) is required".to_string());
+            String::new()
+        });
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    // JSON Resume has no handle: prefer a GitHub username, then any username
+    let username = |network: Option<&str>| {
+        basics.profiles.iter()
+            .filter(|p| network.is_none_or(|n| p.network.as_deref().is_some_and(|pn| pn.eq_ignore_ascii_case(n))))
+            .find_map(|p| non_empty(&p.username))
+    };
+    let handle = username(Some("github")).or_else(|| username(None));
+
+    let location = basics.location.as_ref().map(|l| {
+        [&l.city, &l.region, &l.country_code].into_iter().filter_map(non_empty).collect::<Vec<_>>().join(", ")
+    });
+
+    let skills = resume.skills.iter().filter_map(|s| non_empty(&s.name)).collect::<Vec<_>>().join(", ");
+    let skills = Some(skills).filter(|s| !s.is_empty());
+
+    let mut fields = ResumeFields::default();
+    let experiences: Vec<ExperienceSummary> = resume.work.iter().filter_map(experience_from_work).collect();
+    if !experiences.is_empty() {
+        fields.experiences = serde_json::to_string(&experiences).ok();
+    }
+    for profile in &basics.profiles {
+        if let (Some(network), Some(url)) = (profile.network.as_deref(), non_empty(&profile.url)) {
+            if let Some(slot) = profile_slot(&mut fields, network) {
+                slot.get_or_insert(url);
+            }
+        }
+    }
+
+    Ok(ResumeImport {
+        talent: ResumeTalent {
+            name,
+            email,
+            title,
+            handle,
+            avatar: non_empty(&basics.image),
+            location: location.filter(|l| !l.is_empty()),
+            experience: experience_span(&resume.work, today),
+            skills,
+            bio: non_empty(&basics.summary),
+        },
+        resume: fields,
+    })
+}
+
+/// Export a talent as a JSON Resume document
+pub fn from_talent(talent: &Talent) -> JsonResume {
+    let profiles = [
+        ("LinkedIn", &talent.linkedin_url),
+        ("GitHub", &talent.github_url),
+        ("GitLab", &talent.gitlab_url),
+        ("X", &talent.x_url),
+    ]
+    .into_iter()
+    .filter_map(|(network, url)| {
+        url.clone().map(|url| ResumeProfile {
+            network: Some(network.to_string()),
+            username: None,
+            url: Some(url),
+        })
+    })
+    .collect();
+
+    let experiences: Vec<ExperienceSummary> = talent
+        .resume_experiences
+        .as_deref()
+        .and_then(|json| serde_json::from_str(json).ok())
+        .unwrap_or_default();
+
+    JsonResume {
+        schema: Some(SCHEMA_URL.to_string()),
+        basics: ResumeBasics {
+            name: Some(talent.name.clone()),
+            label: Some(talent.title.clone()),
+            image: talent.avatar.clone(),
+            email: Some(talent.email.clone()),
+            summary: talent.bio.clone(),
+            location: None,
+            profiles,
+        },
+        work: experiences.into_iter().map(work_from_experience).collect(),
+        skills: crate::database::decode_skills(talent.skills.clone())
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(|s| ResumeSkill { name: Some(s.to_string()), keywords: Vec::new() })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 6, 15).unwrap()
+    }
+
+    fn resume() -> JsonResume {
+        serde_json::from_value(serde_json::json!({
+            "basics": {
+                "name": "Ann Lee",
+                "label": "Backend Engineer",
+                "email": "ann@example.com",
+                "summary": "Builds databases.",
+                "location": {"city": "Berlin", "countryCode": "DE"},
+                "profiles": [
+                    {"network": "Twitter", "username": "annl", "url": "https://x.com/annl"},
+                    {"network": "GitHub", "username": "ann-lee", "url": "https://github.com/ann-lee"}
+                ]
+            },
+            "work": [
+                {"name": "Acme", "position": "Engineer", "startDate": "2019-03", "summary": "Storage.", "highlights": ["Led the rewrite"]},
+                {"company": "Initech", "position": "Intern", "startDate": "2017-09-01", "endDate": "2018-06-30"}
+            ],
+            "skills": [{"name": "Rust", "keywords": ["tokio"]}, {"name": "SQL"}]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_to_import() {
+        let ResumeImport { talent, resume } = to_import(&resume(), today()).unwrap_or_else(|_| panic!("invalid"));
+        assert_eq!(talent.handle.as_deref(), Some("ann-lee"));
+        assert_eq!(talent.title, "Backend Engineer");
+        assert_eq!(talent.location.as_deref(), Some("Berlin, DE"));
+        assert_eq!(talent.skills.as_deref(), Some("Rust, SQL"));
+        assert_eq!(talent.experience.as_deref(), Some("8 years"));
+        assert_eq!(resume.x_url.as_deref(), Some("https://x.com/annl"));
+        assert_eq!(resume.github_url.as_deref(), Some("https://github.com/ann-lee"));
+        assert!(resume.linkedin_url.is_none());
+
+        let experiences: Vec<ExperienceSummary> = serde_json::from_str(&resume.experiences.unwrap()).unwrap();
+        assert_eq!(experiences[0].duration.as_deref(), Some("2019-03 - Present"));
+        assert_eq!(experiences[0].summary, "Storage. Led the rewrite");
+        assert_eq!(experiences[1].company, "Initech");
+    }
+
+    #[test]
+    fn test_to_import_requires_basics() {
+        let Err(errors) = to_import(&JsonResume::default(), today()) else {
+            panic!("empty resume accepted");
+        };
+        assert_eq!(errors.len(), 3);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let ResumeImport { talent: request, resume: fields } = to_import(&resume(), today()).unwrap_or_else(|_| panic!("invalid"));
+        let talent = Talent {
+            resume_experiences: fields.experiences,
+            x_url: fields.x_url,
+            github_url: fields.github_url,
+            ..crate::httpd::talents::new_talent(&request.to_create())
+        };
+
+        let exported = from_talent(&talent);
+        assert_eq!(exported.basics.email.as_deref(), Some("ann@example.com"));
+        assert_eq!(exported.basics.profiles.len(), 2);
+        assert!(exported.basics.profiles.iter().all(|p| p.username.is_none()));
+        assert!(exported.basics.location.is_none());
+        assert_eq!(exported.work[0].start_date.as_deref(), Some("2019-03"));
+        assert_eq!(exported.work[0].end_date, None);
+        assert_eq!(exported.work[1].end_date.as_deref(), Some("2018-06-30"));
+        assert_eq!(exported.skills.len(), 2);
+
+        let reimported = to_import(&exported, today()).unwrap_or_else(|_| panic!("invalid"));
+        assert_eq!(reimported.talent.experience, request.experience);
+        assert_eq!(reimported.talent.handle, None);
+    }
+
+    #[test]
+    fn test_basics_only_update_keeps_talent_values() {
+        let ResumeImport { talent: request, .. } = to_import(&resume(), today()).unwrap_or_else(|_| panic!("invalid"));
+        let existing = Talent {
+            skills: "\"Rust, SQL\"".to_string(),
+            ..crate::httpd::talents::new_talent(&request.to_create())
+        };
+
+        let basics_only: JsonResume = serde_json::from_value(serde_json::json!({
+            "basics": {"name": "Ann Lee", "label": "Staff Engineer", "email": "ann@example.com"}
+        }))
+        .unwrap();
+        let ResumeImport { talent: update, .. } = to_import(&basics_only, today()).unwrap_or_else(|_| panic!("invalid"));
+        assert_eq!(update.to_create().experience, UNKNOWN_EXPERIENCE);
+
+        let update = update.to_update(existing);
+        assert_eq!(update.title.as_deref(), Some("Staff Engineer"));
+        assert_eq!(update.handle.as_deref(), Some("ann-lee"));
+        assert_eq!(update.experience.as_deref(), Some("8 years"));
+        assert_eq!(update.skills.as_deref(), Some("Rust, SQL"));
+        assert_eq!(update.location.as_deref(), Some("Berlin, DE"));
+    }
+}
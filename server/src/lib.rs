@@ -1,5 +1,31 @@
+pub mod audit;
+pub mod collection_queue;
 pub mod database;
+pub mod email;
+pub mod error;
+pub mod evaluation;
+pub mod events;
+pub mod export;
+pub mod feed;
 pub mod grok_client;
 pub mod httpd;
+pub mod import;
+pub mod integrity;
+pub mod jsonresume;
+pub mod job_lifecycle;
+pub mod job_order;
+pub mod match_runs;
 pub mod models;
-pub mod reorder;
\ No newline at end of file
+pub mod notifications;
+pub mod offers;
+pub mod portal;
+pub mod preference_analysis;
+pub mod preference_dataset;
+pub mod privacy;
+pub mod reorder;
+pub mod resume_pipeline;
+pub mod retention;
+pub mod scorecard;
+#[cfg(test)]
+pub mod test_support;
+pub mod webhooks;
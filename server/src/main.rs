@@ -1,6 +1,10 @@
 use clap::Parser;
-use log::info;
-use talent_server::httpd::server::run_server;
+use log::{info, warn};
+use std::time::Duration;
+
+use talent_server::email::SmtpConfig;
+use talent_server::httpd::server::{run_server, ServerConfig};
+use talent_server::retention::RetentionPolicy;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -20,6 +24,83 @@ struct Args {
     /// Grok service URL for resume analysis
     #[arg(long, env = "GROK_SERVICE_URL", default_value = "http://localhost:8001")]
     pub grok_service_url: String,
+
+    /// Public URL of the job board UI, used for links in job feeds
+    #[arg(long, env = "PUBLIC_BOARD_URL", default_value = "http://localhost:5173")]
+    pub public_board_url: String,
+
+    /// Public URL of this API, used for feed self links (defaults to http://<host>:<port>)
+    #[arg(long, env = "PUBLIC_API_URL")]
+    pub public_api_url: Option<String>,
+
+    /// Interval in seconds between sweeps that close expired jobs
+    #[arg(long, env = "JOB_SWEEP_INTERVAL_SECS", default_value_t = 300)]
+    pub job_sweep_interval_secs: u64,
+
+    /// Reject remaining applications when a job is closed for expiring
+    #[arg(long, env = "AUTO_REJECT_ON_EXPIRY", default_value_t = false)]
+    pub auto_reject_on_expiry: bool,
+
+    /// Secret used to sign candidate portal sessions (random per process if unset)
+    #[arg(long, env = "PORTAL_SESSION_SECRET")]
+    pub portal_session_secret: Option<String>,
+
+    /// Days to keep resumes of rejected/withdrawn applications (unset keeps them forever)
+    #[arg(long, env = "RETENTION_RESUMES_DAYS")]
+    pub retention_resumes_days: Option<u32>,
+
+    /// Days to keep talents' social media analysis (unset keeps it forever)
+    #[arg(long, env = "RETENTION_SOCIAL_ANALYSIS_DAYS")]
+    pub retention_social_analysis_days: Option<u32>,
+
+    /// Days to keep reorder events and their derived preferences (unset keeps them forever)
+    #[arg(long, env = "RETENTION_REORDER_EVENTS_DAYS")]
+    pub retention_reorder_events_days: Option<u32>,
+
+    /// Days to keep rejected applications (unset keeps them forever)
+    #[arg(long, env = "RETENTION_REJECTED_APPLICATIONS_DAYS")]
+    pub retention_rejected_applications_days: Option<u32>,
+
+    /// Days deleted talents, jobs and applications stay restorable before being purged
+    #[arg(long, env = "TRASH_GRACE_DAYS", default_value_t = 30)]
+    pub trash_grace_days: u32,
+
+    /// Interval in seconds between retention sweeps
+    #[arg(long, env = "RETENTION_SWEEP_INTERVAL_SECS", default_value_t = 86400)]
+    pub retention_sweep_interval_secs: u64,
+
+    /// Comma-separated approvers who must sign off on every offer, in order
+    #[arg(long, env = "OFFER_APPROVERS", value_delimiter = ',')]
+    pub offer_approvers: Vec<String>,
+
+    /// SMTP server for outgoing email, e.g. localhost with port 1025 for MailHog (unset disables email)
+    #[arg(long, env = "SMTP_HOST")]
+    pub smtp_host: Option<String>,
+
+    /// SMTP server port
+    #[arg(long, env = "SMTP_PORT", default_value_t = 25)]
+    pub smtp_port: u16,
+
+    /// SMTP username, for servers that require AUTH PLAIN. Credentials are
+    /// only sent after upgrading the connection with STARTTLS
+    #[arg(long, env = "SMTP_USERNAME")]
+    pub smtp_username: Option<String>,
+
+    /// SMTP password
+    #[arg(long, env = "SMTP_PASSWORD")]
+    pub smtp_password: Option<String>,
+
+    /// Sender of outgoing email
+    #[arg(long, env = "MAIL_FROM", default_value = "Talent Pool <no-reply@localhost>")]
+    pub mail_from: String,
+
+    /// Comma-separated recruiter emails notified when candidate scoring finishes
+    #[arg(long, env = "RECRUITER_EMAILS", value_delimiter = ',')]
+    pub recruiter_emails: Vec<String>,
+
+    /// Delete rows whose parent talent, job or reorder event no longer exists, then exit
+    #[arg(long, default_value_t = false)]
+    pub repair_integrity: bool,
 }
 
 #[tokio::main]
@@ -27,12 +108,69 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     env_logger::init_from_env(env_logger::Env::default().default_filter_or("info"));
 
     let args = Args::parse();
+
+    if args.repair_integrity {
+        let pool = talent_server::database::init_pool(&args.database_url).await?;
+        for repaired in talent_server::integrity::repair(&pool).await? {
+            info!("Deleted {} orphaned rows in {}", repaired.rows, repaired.relationship);
+        }
+        return Ok(());
+    }
+
     info!("Starting server with host: {}, port: {}", args.host, args.port);
     info!("HTTP API hosted at: http://{}:{}/", args.host, args.port);
     info!("OpenAPI docs: http://{}:{}/", args.host, args.port);
     info!("Swagger UI: http://{}:{}/", args.host, args.port);
 
-    run_server(&args.host, args.port, &args.database_url, &args.grok_service_url).await?;
+    let public_api_url = args.public_api_url
+        .unwrap_or_else(|| format!("http://{}:{}", args.host, args.port));
+
+    let portal_session_secret = args.portal_session_secret.unwrap_or_else(|| {
+        warn!("PORTAL_SESSION_SECRET not set; candidate portal sessions will not survive a restart");
+        talent_server::portal::generate_token()
+    });
+
+    let smtp = args.smtp_host.map(|host| SmtpConfig {
+        host,
+        port: args.smtp_port,
+        username: args.smtp_username,
+        password: args.smtp_password,
+        from: args.mail_from,
+    });
+    if smtp.is_none() {
+        warn!("SMTP_HOST not set; notification emails are disabled");
+    }
+
+    run_server(ServerConfig {
+        host: args.host,
+        port: args.port,
+        database_url: args.database_url,
+        grok_service_url: args.grok_service_url,
+        public_board_url: args.public_board_url,
+        public_api_url,
+        job_sweep_interval: Duration::from_secs(args.job_sweep_interval_secs.max(1)),
+        auto_reject_on_expiry: args.auto_reject_on_expiry,
+        portal_session_secret,
+        retention: RetentionPolicy {
+            resumes_days: args.retention_resumes_days,
+            social_analysis_days: args.retention_social_analysis_days,
+            reorder_events_days: args.retention_reorder_events_days,
+            rejected_applications_days: args.retention_rejected_applications_days,
+            trash_days: Some(args.trash_grace_days),
+        },
+        retention_sweep_interval: Duration::from_secs(args.retention_sweep_interval_secs.max(1)),
+        offer_approvers: args.offer_approvers
+            .into_iter()
+            .map(|approver| approver.trim().to_string())
+            .filter(|approver| !approver.is_empty())
+            .collect(),
+        smtp,
+        recruiter_emails: args.recruiter_emails
+            .into_iter()
+            .map(|email| email.trim().to_string())
+            .filter(|email| !email.is_empty())
+            .collect(),
+    }).await?;
 
     Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,335 @@
+//! Versioned job matching runs
+//!
+//! Every generation of matches for a job is a run. A run starts `running`
+//! with its parameters and is worked through in the background, sending the
+//! candidate pool to grok-service in batches and recording progress after
+//! each. It ends `failed` with the error, `cancelled`, or `succeeded` with
+//! its results stored in the same transaction that swaps them into
+//! `job_matches`. `job_matches` therefore always holds the latest succeeded
+//! run, and earlier runs stay around to be browsed and compared.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use chrono::Utc;
+use log::{error, info};
+use tokio::sync::Notify;
+use uuid::Uuid;
+
+use crate::database::Pool;
+use crate::events::{self, EventKind, Events};
+use crate::grok_client::{CandidateMatch, GrokClient, JobMatchingRequest};
+use crate::job_order;
+use crate::models::{JobMatch, JobMatchWithTalent, MatchRankChange, MatchRun, MatchRunDiff};
+
+pub const STATUS_RUNNING: &str = "running";
+pub const STATUS_SUCCEEDED: &str = "succeeded";
+pub const STATUS_FAILED: &str = "failed";
+pub const STATUS_CANCELLED: &str = "cancelled";
+
+pub const DEFAULT_TOP_N: i32 = 10;
+pub const MAX_TOP_N: i32 = 100;
+pub const DEFAULT_BATCH_SIZE: i32 = 25;
+pub const MAX_BATCH_SIZE: i32 = 200;
+
+/// A run that has just started
+pub fn new_run(job_id: &str, top_n: i32, candidate_count: usize, batch_size: i32) -> MatchRun {
+    MatchRun {
+        id: Uuid::new_v4().to_string(),
+        job_id: job_id.to_string(),
+        status: STATUS_RUNNING.to_string(),
+        top_n,
+        candidate_count: candidate_count as i32,
+        batch_size,
+        batch_count: candidate_count.div_ceil(batch_size as usize) as i32,
+        current_batch: 0,
+        evaluated: 0,
+        total_evaluated: None,
+        match_count: 0,
+        model_version: None,
+        error: None,
+        created_at: Utc::now().to_rfc3339(),
+        completed_at: None,
+    }
+}
+
+/// Keep the best `top_n` of every batch's matches, re-ranked by score
+pub fn merge_batches(mut matches: Vec<CandidateMatch>, top_n: usize) -> Vec<CandidateMatch> {
+    matches.sort_by(|a, b| b.score.total_cmp(&a.score));
+    matches.truncate(top_n);
+    for (i, m) in matches.iter_mut().enumerate() {
+        m.rank = i as i32 + 1;
+    }
+    matches
+}
+
+/// The stored matches for a job's grok results
+pub fn matches_from(job_id: &str, matches: &[CandidateMatch]) -> Vec<JobMatch> {
+    let created_at = Utc::now().to_rfc3339();
+    matches
+        .iter()
+        .map(|m| JobMatch {
+            id: Uuid::new_v4().to_string(),
+            job_id: job_id.to_string(),
+            talent_id: m.talent_id.clone(),
+            score: m.score,
+            rank: m.rank,
+            match_reasons: Some(serde_json::to_string(&m.match_reasons).unwrap_or_default()),
+            concerns: Some(serde_json::to_string(&m.concerns).unwrap_or_default()),
+            summary: Some(m.summary.clone()),
+            created_at: created_at.clone(),
+        })
+        .collect()
+}
+
+/// A stored match with its JSON fields decoded and its talent attached
+pub async fn with_talent(pool: &Pool, m: JobMatch) -> Result<JobMatchWithTalent, sqlx::Error> {
+    let talent = crate::database::get_talent_by_id(pool, m.talent_id.clone()).await?;
+
+    Ok(JobMatchWithTalent {
+        id: m.id,
+        job_id: m.job_id,
+        talent_id: m.talent_id,
+        score: m.score,
+        rank: m.rank,
+        match_reasons: m.match_reasons
+            .map(|s| serde_json::from_str(&s).unwrap_or_default())
+            .unwrap_or_default(),
+        concerns: m.concerns
+            .map(|s| serde_json::from_str(&s).unwrap_or_default())
+            .unwrap_or_default(),
+        summary: m.summary.unwrap_or_default(),
+        created_at: m.created_at,
+        talent,
+    })
+}
+
+/// Handle for starting and cancelling runs, shared through `AppState`
+#[derive(Clone)]
+pub struct MatchRunner {
+    pool: Pool,
+    events: Events,
+    grok_url: String,
+    cancellations: Arc<Mutex<HashMap<String, Arc<Notify>>>>,
+}
+
+impl MatchRunner {
+    pub fn new(pool: Pool, events: Events, grok_url: String) -> Self {
+        MatchRunner { pool, events, grok_url, cancellations: Arc::new(Mutex::new(HashMap::new())) }
+    }
+
+    /// Work through a stored `running` run in the background. `request`
+    /// holds the whole candidate pool; it is split into `run.batch_size`
+    /// candidates per grok-service request.
+    pub fn start(&self, run: MatchRun, request: JobMatchingRequest) {
+        let cancelled = Arc::new(Notify::new());
+        self.cancellations.lock().unwrap().insert(run.id.clone(), cancelled.clone());
+
+        let runner = self.clone();
+        tokio::spawn(async move {
+            let run_id = run.id.clone();
+            // Generate in a task of its own so a panic fails the run instead
+            // of leaving it running, which would block the job's next run
+            let generation = tokio::spawn({
+                let runner = runner.clone();
+                async move { runner.generate(run, request).await }
+            });
+            let generation_abort = generation.abort_handle();
+            let outcome = tokio::select! {
+                joined = generation => joined.unwrap_or_else(|e| Err(format!("Run stopped unexpectedly: {}", e))),
+                _ = cancelled.notified() => {
+                    generation_abort.abort();
+                    info!("[match_runs] Run {} cancelled", run_id);
+                    Ok(())
+                }
+            };
+            if let Err(e) = outcome {
+                error!("[match_runs] Run {} failed: {}", run_id, e);
+                if let Err(e) = crate::database::fail_match_run(&runner.pool, &run_id, &e, &Utc::now().to_rfc3339()).await {
+                    error!("[match_runs] Failed to record failure of run {}: {}", run_id, e);
+                }
+            }
+            runner.cancellations.lock().unwrap().remove(&run_id);
+        });
+    }
+
+    /// Cancel a running run, dropping any grok-service request in flight.
+    /// Returns `None` when the run is not running.
+    pub async fn cancel(&self, job_id: &str, run_id: &str) -> Result<Option<MatchRun>, sqlx::Error> {
+        let run = crate::database::cancel_match_run(&self.pool, job_id, run_id, &Utc::now().to_rfc3339()).await?;
+        if run.is_some() {
+            if let Some(cancelled) = self.cancellations.lock().unwrap().get(run_id) {
+                cancelled.notify_one();
+            }
+        }
+        Ok(run)
+    }
+
+    async fn generate(&self, mut run: MatchRun, request: JobMatchingRequest) -> Result<(), String> {
+        let client = GrokClient::new(&self.grok_url);
+        let mut matches = Vec::new();
+        let mut total_evaluated = 0;
+        let mut model_version = None;
+
+        for (i, batch) in request.candidates.chunks(run.batch_size as usize).enumerate() {
+            run.current_batch = i as i32 + 1;
+            crate::database::update_match_run_progress(&self.pool, &run.id, run.current_batch, run.evaluated).await
+                .map_err(|e| e.to_string())?;
+            info!("[match_runs] Run {}: batch {}/{} ({} candidates)", run.id, run.current_batch, run.batch_count, batch.len());
+
+            let batch_request = JobMatchingRequest { candidates: batch.to_vec(), ..request.clone() };
+            let result = match client.match_candidates_to_job(&batch_request).await {
+                Ok(response) if response.success => response.result
+                    .ok_or_else(|| "No result from matching service".to_string())?,
+                Ok(response) => return Err(response.error.unwrap_or_else(|| "Unknown error".to_string())),
+                Err(e) => return Err(format!("Matching failed: {}", e)),
+            };
+
+            run.evaluated += batch.len() as i32;
+            total_evaluated += result.total_evaluated;
+            model_version = result.model.or(model_version);
+            matches.extend(result.matches);
+        }
+
+        // Store the run's matches and serve them in place of the previous run's
+        let matches = matches_from(&run.job_id, &merge_batches(matches, run.top_n as usize));
+        let completed = MatchRun {
+            status: STATUS_SUCCEEDED.to_string(),
+            total_evaluated: Some(total_evaluated),
+            match_count: matches.len() as i32,
+            model_version,
+            completed_at: Some(Utc::now().to_rfc3339()),
+            ..run
+        };
+        let Some(run) = crate::database::complete_match_run(&self.pool, &completed, &matches).await
+            .map_err(|e| e.to_string())?
+        else {
+            info!("[match_runs] Run {} stopped running before it completed; results discarded", completed.id);
+            return Ok(());
+        };
+
+        let mut stored_matches: Vec<JobMatchWithTalent> = Vec::new();
+        for m in matches {
+            stored_matches.push(with_talent(&self.pool, m).await.map_err(|e| e.to_string())?);
+        }
+
+        info!("[match_runs] Stored {} matches for job {} from run {}", stored_matches.len(), run.job_id, run.id);
+        self.events.publish(EventKind::JobMatchesGenerated, events::job_matches_generated(&run.job_id, &stored_matches)).await;
+        job_order::reconcile_jobs(&self.pool, &self.events, [run.job_id.clone()]).await;
+        Ok(())
+    }
+}
+
+/// Compare `run`'s matches against an earlier run's. Talents are keyed by
+/// ID; each list comes out ordered by rank, removed ones by their old rank.
+pub fn diff(run_id: &str, run: &[JobMatch], against_run_id: &str, against: &[JobMatch]) -> MatchRunDiff {
+    let previous: HashMap<&str, &JobMatch> = against.iter().map(|m| (m.talent_id.as_str(), m)).collect();
+    let current: HashMap<&str, &JobMatch> = run.iter().map(|m| (m.talent_id.as_str(), m)).collect();
+
+    let mut added = Vec::new();
+    let mut moved = Vec::new();
+    let mut unchanged = 0;
+    for m in run {
+        match previous.get(m.talent_id.as_str()) {
+            None => added.push(change(&m.talent_id, None, Some(m))),
+            Some(old) if old.rank != m.rank => moved.push(change(&m.talent_id, Some(old), Some(m))),
+            Some(_) => unchanged += 1,
+        }
+    }
+    let mut removed: Vec<MatchRankChange> = against
+        .iter()
+        .filter(|m| !current.contains_key(m.talent_id.as_str()))
+        .map(|m| change(&m.talent_id, Some(m), None))
+        .collect();
+
+    added.sort_by_key(|c| c.rank);
+    moved.sort_by_key(|c| c.rank);
+    removed.sort_by_key(|c| c.previous_rank);
+
+    MatchRunDiff {
+        run_id: run_id.to_string(),
+        against_run_id: against_run_id.to_string(),
+        added,
+        removed,
+        moved,
+        unchanged,
+    }
+}
+
+fn change(talent_id: &str, previous: Option<&JobMatch>, current: Option<&JobMatch>) -> MatchRankChange {
+    MatchRankChange {
+        talent_id: talent_id.to_string(),
+        previous_rank: previous.map(|m| m.rank),
+        rank: current.map(|m| m.rank),
+        previous_score: previous.map(|m| m.score),
+        score: current.map(|m| m.score),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::job_match;
+
+    fn candidate(talent_id: &str, rank: i32, score: f64) -> CandidateMatch {
+        CandidateMatch {
+            talent_id: talent_id.to_string(),
+            talent_name: talent_id.to_uppercase(),
+            talent_title: "Engineer".to_string(),
+            score,
+            rank,
+            match_reasons: vec![],
+            concerns: vec![],
+            summary: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_merge_batches_keeps_best_scores_and_reranks() {
+        // Two batches, each ranked on its own
+        let matches = vec![
+            candidate("a", 1, 70.0), candidate("b", 2, 40.0),
+            candidate("c", 1, 95.0), candidate("d", 2, 60.0),
+        ];
+
+        let merged = merge_batches(matches, 3);
+
+        assert_eq!(merged.iter().map(|m| m.talent_id.as_str()).collect::<Vec<_>>(), ["c", "a", "d"]);
+        assert_eq!(merged.iter().map(|m| m.rank).collect::<Vec<_>>(), [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_new_run_counts_batches() {
+        assert_eq!(new_run("job", 10, 51, 25).batch_count, 3);
+        assert_eq!(new_run("job", 10, 50, 25).batch_count, 2);
+        assert_eq!(new_run("job", 10, 0, 25).batch_count, 0);
+    }
+
+    #[test]
+    fn test_diff_reports_added_removed_and_moved_talents() {
+        let before = [job_match("job", "a", 1, 90.0), job_match("job", "b", 2, 80.0), job_match("job", "c", 3, 70.0)];
+        let after = [job_match("job", "b", 1, 88.0), job_match("job", "a", 2, 85.0), job_match("job", "c", 3, 72.0), job_match("job", "d", 4, 60.0)];
+
+        let diff = diff("new", &after, "old", &before);
+
+        assert_eq!(diff.added.iter().map(|c| c.talent_id.as_str()).collect::<Vec<_>>(), ["d"]);
+        assert!(diff.removed.is_empty());
+        assert_eq!(diff.moved.iter().map(|c| c.talent_id.as_str()).collect::<Vec<_>>(), ["b", "a"]);
+        assert_eq!(diff.moved[0].previous_rank, Some(2));
+        assert_eq!(diff.moved[0].score, Some(88.0));
+        assert_eq!(diff.unchanged, 1);
+    }
+
+    #[test]
+    fn test_diff_orders_removed_talents_by_previous_rank() {
+        let before = [job_match("job", "a", 1, 90.0), job_match("job", "b", 2, 80.0), job_match("job", "c", 3, 70.0)];
+        let after = [job_match("job", "b", 2, 80.0)];
+
+        let diff = diff("new", &after, "old", &before);
+
+        assert_eq!(diff.removed.iter().map(|c| c.talent_id.as_str()).collect::<Vec<_>>(), ["a", "c"]);
+        assert_eq!(diff.removed[1].rank, None);
+        assert_eq!(diff.unchanged, 1);
+        assert!(diff.added.is_empty() && diff.moved.is_empty());
+    }
+}
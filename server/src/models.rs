@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use paperclip::actix::Apiv2Schema;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
@@ -31,6 +33,9 @@ pub struct Talent {
     // Candidate Scoring
     pub candidate_score: Option<f64>,
     pub candidate_score_details: Option<String>, // JSON string of scoring breakdown
+    // Retention
+    pub legal_hold: i32,
+    pub deleted_at: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Apiv2Schema, PartialEq, Debug)]
@@ -90,6 +95,9 @@ pub struct UpdateTalentRequest {
 pub struct ApiError {
     pub message: String,
     pub code: u16,
+    pub error: String, // stable machine-readable error code, e.g. "not_found"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
 }
 
 #[derive(Deserialize, Apiv2Schema)]
@@ -133,9 +141,13 @@ pub struct Job {
     pub salary_currency: Option<String>,
     pub skills_required: String, // comma-separated
     pub experience_level: String, // entry, mid, senior, lead
-    pub status: String, // active, closed, draft
+    pub status: String, // draft, published, paused, closed, filled
     pub created_at: String,
     pub expires_at: Option<String>,
+    pub published_at: Option<String>,
+    pub closed_at: Option<String>,
+    pub legal_hold: i32,
+    pub deleted_at: Option<String>,
 }
 
 #[derive(Deserialize, Apiv2Schema)]
@@ -153,6 +165,7 @@ pub struct CreateJobRequest {
     pub skills_required: String,
     pub experience_level: String,
     pub expires_at: Option<String>,
+    pub publish: Option<bool>, // publish immediately instead of creating a draft
 }
 
 #[derive(Deserialize, Apiv2Schema)]
@@ -173,6 +186,23 @@ pub struct UpdateJobRequest {
     pub expires_at: Option<String>,
 }
 
+#[derive(Deserialize, Apiv2Schema)]
+pub struct CloseJobQuery {
+    pub reject_remaining: Option<bool>, // reject all pending/reviewed applications
+}
+
+#[derive(Serialize, Apiv2Schema)]
+pub struct JobTransitionResponse {
+    pub job: Job,
+    pub rejected_applications: usize,
+}
+
+#[derive(Deserialize, Apiv2Schema)]
+pub struct JobFeedQuery {
+    pub company: Option<String>,  // case-insensitive substring match
+    pub location: Option<String>, // case-insensitive substring match
+}
+
 // Job Match models
 
 #[derive(Serialize, Deserialize, Clone, Apiv2Schema, PartialEq, Debug, FromRow)]
@@ -202,6 +232,61 @@ pub struct JobMatchWithTalent {
     pub talent: Option<Talent>,
 }
 
+#[derive(Deserialize, Apiv2Schema)]
+pub struct GenerateMatchesQuery {
+    pub top_n: Option<i32>,       // default 10
+    pub batch_size: Option<i32>,  // candidates per grok-service request, default 25
+}
+
+#[derive(Serialize, Deserialize, Clone, Apiv2Schema, PartialEq, Debug, FromRow)]
+pub struct MatchRun {
+    pub id: String,
+    pub job_id: String,
+    pub status: String,  // running, succeeded, failed or cancelled
+    pub top_n: i32,
+    pub candidate_count: i32,
+    pub batch_size: i32,
+    pub batch_count: i32,
+    pub current_batch: i32,  // 1-based, 0 before the first batch starts
+    pub evaluated: i32,      // Candidates in finished batches
+    pub total_evaluated: Option<i32>,
+    pub match_count: i32,
+    pub model_version: Option<String>,
+    pub error: Option<String>,
+    pub created_at: String,
+    pub completed_at: Option<String>,
+}
+
+#[derive(Serialize, Apiv2Schema)]
+pub struct MatchRunDetail {
+    pub run: MatchRun,
+    pub matches: Vec<JobMatchWithTalent>,
+}
+
+#[derive(Deserialize, Apiv2Schema)]
+pub struct MatchRunDiffQuery {
+    pub against: Option<String>,  // run ID, defaults to the previous succeeded run
+}
+
+#[derive(Serialize, Apiv2Schema, PartialEq, Debug)]
+pub struct MatchRankChange {
+    pub talent_id: String,
+    pub previous_rank: Option<i32>,
+    pub rank: Option<i32>,
+    pub previous_score: Option<f64>,
+    pub score: Option<f64>,
+}
+
+#[derive(Serialize, Apiv2Schema, PartialEq, Debug)]
+pub struct MatchRunDiff {
+    pub run_id: String,
+    pub against_run_id: String,
+    pub added: Vec<MatchRankChange>,    // Only in run_id
+    pub removed: Vec<MatchRankChange>,  // Only in against_run_id
+    pub moved: Vec<MatchRankChange>,    // In both, at a different rank
+    pub unchanged: usize,
+}
+
 // Application models
 
 #[derive(Serialize, Deserialize, Clone, Apiv2Schema, PartialEq, Debug, FromRow)]
@@ -215,6 +300,7 @@ pub struct Application {
     pub cover_letter: Option<String>,
     pub status: String,                    // pending, reviewed, accepted, rejected
     pub created_at: String,
+    pub deleted_at: Option<String>,
 }
 
 #[derive(Deserialize, Apiv2Schema)]
@@ -227,6 +313,11 @@ pub struct CreateApplicationRequest {
     pub cover_letter: Option<String>,
 }
 
+#[derive(Deserialize, Apiv2Schema)]
+pub struct UpdateApplicationStatusRequest {
+    pub status: String, // pending, reviewed, accepted, rejected or withdrawn
+}
+
 #[derive(Serialize, Apiv2Schema)]
 pub struct ApplicationResponse {
     pub id: String,
@@ -295,6 +386,8 @@ pub struct CreateReorderEventRequest {
     pub before_order: Vec<String>,  // Array of talent IDs
     pub after_order: Vec<String>,   // Array of talent IDs
     pub moved_talent_id: Option<String>,
+    pub expected_version: Option<i64>,  // Order version the client last saw; rejected if it has moved on
+    pub derivation: Option<String>,     // moved (default) or inversions
 }
 
 #[derive(Serialize, Deserialize, Clone, Apiv2Schema, PartialEq, Debug, FromRow)]
@@ -310,11 +403,1028 @@ pub struct PairwisePreference {
     pub confidence: f64,
     pub reorder_event_id: Option<String>,
     pub created_at: String,
+    pub tie: bool,  // Winner and loser are equally good; stored with the lower ID as winner
+    pub author: Option<String>,         // Who recorded it (X-Actor), when known
+    pub superseded_by: Option<String>,  // Newer preference that reversed this one
+    pub superseded_at: Option<String>,
+    #[sqlx(skip)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weight: Option<f64>,  // Confidence decayed by age, filled in when served
+}
+
+/// Both directions of one pair are held by active preferences
+#[derive(Serialize, Apiv2Schema, Debug, PartialEq)]
+pub struct PreferenceContradiction {
+    pub winner_id: String,  // Winner of the most recent judgement
+    pub loser_id: String,
+    pub winner_text: String,
+    pub loser_text: String,
+    pub preference_ids: Vec<String>,           // Active preferences agreeing with the latest judgement
+    pub contradicted_preference_ids: Vec<String>,  // Older active preferences the other way
+    pub authors: Vec<String>,
+    pub last_judged_at: String,
+}
+
+/// Candidates preferred around in a circle once each pair's preferences are
+/// netted out: the first over the second, ..., the last over the first
+#[derive(Serialize, Apiv2Schema, Debug, PartialEq)]
+pub struct PreferenceCycle {
+    pub talent_ids: Vec<String>,
+    pub talent_texts: Vec<String>,
+    pub component_size: usize,  // Candidates caught up in cycles with these
+}
+
+/// How often two authors judged the same pairs the same way
+#[derive(Serialize, Apiv2Schema, Debug, PartialEq)]
+pub struct AuthorAgreement {
+    pub author_a: String,
+    pub author_b: String,
+    pub shared_pairs: usize,
+    pub agreeing_pairs: usize,
+    pub agreement: f64,
+}
+
+#[derive(Serialize, Apiv2Schema, Debug)]
+pub struct PreferenceAnalysis {
+    pub job_id: String,
+    pub total_preferences: usize,
+    pub active_preferences: usize,  // Neither superseded nor ties
+    pub superseded_preferences: usize,
+    pub ties: usize,
+    pub judged_pairs: usize,
+    pub consistency: f64,  // Share of judged pairs without a contradiction
+    pub contradictions: Vec<PreferenceContradiction>,
+    pub cycles: Vec<PreferenceCycle>,
+    pub authors: Vec<String>,
+    pub agreement: Vec<AuthorAgreement>,
+    pub overall_agreement: Option<f64>,  // Over all shared pairs; None with fewer than two authors
+}
+
+#[derive(Serialize, Apiv2Schema)]
+pub struct ResolvePreferencesResponse {
+    pub superseded: u64,
+    pub contradictions_resolved: usize,
+    pub message: String,
+}
+
+// Evaluation models
+
+#[derive(Deserialize, Apiv2Schema)]
+pub struct CreateEvaluationRequest {
+    pub source: Option<String>,            // job_matches (default) or candidate_score
+    pub label: Option<String>,             // e.g. the grok-service prompt version
+    pub job_ids: Option<Vec<String>>,      // every job with preferences when omitted
+    pub split: Option<String>,             // all (default), train or validation
+    pub validation_fraction: Option<f64>,  // as in the preference export
+    pub seed: Option<String>,
+    pub k: Option<usize>,                  // NDCG cutoff, 10 by default
+}
+
+#[derive(Deserialize, Apiv2Schema)]
+pub struct JobEvaluationQuery {
+    pub source: Option<String>,  // job_matches (default) or candidate_score
+    pub k: Option<usize>,
+}
+
+#[derive(Deserialize, Apiv2Schema)]
+pub struct EvaluationRunQuery {
+    pub source: Option<String>,
+    pub label: Option<String>,
+    pub since: Option<String>,  // RFC 3339, inclusive
+    pub limit: Option<i64>,     // most recent runs, default 100
+}
+
+/// Agreement of one ranking source with one job's preferences
+#[derive(Serialize, Deserialize, Clone, Apiv2Schema, PartialEq, Debug)]
+pub struct JobEvaluation {
+    pub job_id: String,
+    pub job_title: String,
+    pub preferences: usize,         // Preferences whose candidates the source both ranked
+    pub ranked_candidates: usize,   // Candidates in preferences that the source ranked
+    pub pairwise_accuracy: Option<f64>,  // Weighted share of preferences the source agrees with
+    pub kendall_tau: Option<f64>,   // Source scores against preference win rates, tau-b
+    pub ndcg: Option<f64>,          // NDCG@k of the source order, gains from win rates
+    #[serde(default)]
+    pub match_run_id: Option<String>,   // job_matches only: the run that served the matches
+    #[serde(default)]
+    pub model_version: Option<String>,  // job_matches only: that run's model
+}
+
+#[derive(Serialize, Deserialize, Clone, Apiv2Schema, PartialEq, Debug, FromRow)]
+pub struct EvaluationRun {
+    pub id: String,
+    pub source: String,
+    pub label: Option<String>,
+    pub split: String,
+    pub k: i64,
+    pub jobs_evaluated: i64,
+    pub preferences_evaluated: i64,
+    pub pairwise_accuracy: Option<f64>,  // Over all evaluated preferences
+    pub kendall_tau: Option<f64>,        // Mean over jobs
+    pub ndcg: Option<f64>,               // Mean over jobs
+    pub match_run_id: Option<String>,    // job_matches on a single job: the run evaluated
+    pub model_version: Option<String>,   // job_matches: the model every evaluated run used
+    #[serde(skip)]
+    pub job_results: String,  // JSON array of JobEvaluation
+    pub created_at: String,
+}
+
+#[derive(Serialize, Apiv2Schema)]
+pub struct EvaluationRunDetail {
+    pub run: EvaluationRun,
+    pub jobs: Vec<JobEvaluation>,
+}
+
+#[derive(Deserialize, Apiv2Schema)]
+pub struct CreatePreferenceSignalRequest {
+    pub kind: String,  // shortlist, reject or tie
+    pub talent_ids: Vec<String>,
+}
+
+#[derive(Serialize, Apiv2Schema)]
+pub struct PreferenceSignalResponse {
+    pub preferences_created: i32,
+    pub message: String,
 }
 
 #[derive(Serialize, Apiv2Schema)]
 pub struct ReorderResponse {
     pub event_id: String,
     pub preferences_created: i32,
+    pub version: i64,  // Version of the job's order after this reorder
+    pub message: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Apiv2Schema, PartialEq, Debug, FromRow)]
+pub struct JobCandidateOrder {
+    pub job_id: String,
+    pub talent_ids: String,  // JSON array of talent IDs
+    pub version: i64,
+    pub reorder_event_id: Option<String>,
+    pub updated_at: String,
+}
+
+/// Message sent to collaborators on a job's order WebSocket
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CandidateOrderMessage {
+    /// The current order, sent on connect and whenever the client asks
+    Snapshot {
+        job_id: String,
+        version: i64,
+        order: Vec<String>,
+    },
+    /// A reorder was accepted, or candidates came or went
+    OrderChanged {
+        job_id: String,
+        version: i64,
+        order: Vec<String>,
+        moved_talent_id: Option<String>,
+        reorder_event_id: Option<String>,  // None when the order was reconciled
+    },
+}
+
+#[derive(Serialize, Apiv2Schema)]
+pub struct OrderedCandidate {
+    pub position: usize,  // 1-based
+    pub talent: Talent,
+    pub application_id: Option<String>,
+    pub application_status: Option<String>,
+    pub match_score: Option<f64>,
+    pub match_rank: Option<i32>,
+}
+
+#[derive(Serialize, Apiv2Schema)]
+pub struct JobCandidateOrderResponse {
+    pub job_id: String,
+    pub version: i64,                      // 0 until the job is first reordered
+    pub updated_at: Option<String>,
+    pub reorder_event_id: Option<String>,  // reorder behind the order; None after reconciliation
+    pub candidates: Vec<OrderedCandidate>,
+}
+
+// Candidate portal models
+
+#[derive(Deserialize, Apiv2Schema)]
+pub struct PortalLoginRequest {
+    pub email: String,
+}
+
+#[derive(Serialize, Apiv2Schema)]
+pub struct PortalLoginResponse {
     pub message: String,
-}
\ No newline at end of file
+}
+
+#[derive(Deserialize, Apiv2Schema)]
+pub struct PortalSessionRequest {
+    pub token: String, // magic link token
+}
+
+#[derive(Serialize, Apiv2Schema)]
+pub struct PortalSessionResponse {
+    pub session_token: String,
+    pub talent_id: String,
+    pub expires_at: String,
+}
+
+/// A talent's own profile as seen by the candidate (no recruiter-only fields)
+#[derive(Serialize, Apiv2Schema)]
+pub struct CandidateProfile {
+    pub id: String,
+    pub name: String,
+    pub email: String,
+    pub handle: String,
+    pub skills: String,
+    pub avatar: Option<String>,
+    pub title: String,
+    pub location: Option<String>,
+    pub experience: String,
+    pub bio: Option<String>,
+    pub created_at: String,
+    pub resume_experiences: Option<String>,  // JSON array of experiences
+    pub linkedin_url: Option<String>,
+    pub x_url: Option<String>,
+    pub github_url: Option<String>,
+    pub gitlab_url: Option<String>,
+}
+
+impl From<Talent> for CandidateProfile {
+    fn from(talent: Talent) -> Self {
+        Self {
+            id: talent.id,
+            name: talent.name,
+            email: talent.email,
+            handle: talent.handle,
+            skills: talent.skills,
+            avatar: talent.avatar,
+            title: talent.title,
+            location: talent.location,
+            experience: talent.experience,
+            bio: talent.bio,
+            created_at: talent.created_at,
+            resume_experiences: talent.resume_experiences,
+            linkedin_url: talent.linkedin_url,
+            x_url: talent.x_url,
+            github_url: talent.github_url,
+            gitlab_url: talent.gitlab_url,
+        }
+    }
+}
+
+/// Profile fields a candidate may change themselves
+#[derive(Deserialize, Apiv2Schema)]
+pub struct UpdateCandidateProfileRequest {
+    pub name: Option<String>,
+    pub handle: Option<String>,
+    pub avatar: Option<String>,
+    pub title: Option<String>,
+    pub location: Option<String>,
+    pub experience: Option<String>,
+    pub skills: Option<String>,
+    pub bio: Option<String>,
+}
+
+#[derive(Serialize, Apiv2Schema)]
+pub struct CandidateApplication {
+    pub id: String,
+    pub job_id: String,
+    pub job_title: Option<String>,
+    pub company_name: Option<String>,
+    pub has_resume: bool,
+    pub resume_filename: Option<String>,
+    pub cover_letter: Option<String>,
+    pub status: String,
+    pub created_at: String,
+}
+
+#[derive(Deserialize, Apiv2Schema)]
+pub struct PortalResumeUploadRequest {
+    pub resume_data: String,               // Base64 encoded resume
+    pub resume_filename: Option<String>,
+    pub resume_content_type: Option<String>,
+    pub application_id: Option<String>,    // Also attach to this application
+}
+
+// Data subject requests (GDPR/CCPA)
+
+#[derive(Deserialize, Apiv2Schema)]
+pub struct TalentExportQuery {
+    pub format: Option<String>, // json (default) or zip
+}
+
+/// Magic link token metadata; the token hash itself is never exported
+#[derive(Serialize, Deserialize, Clone, Apiv2Schema, PartialEq, Debug, FromRow)]
+pub struct CandidateLoginTokenRecord {
+    pub talent_id: String,
+    pub expires_at: String,
+    pub used_at: Option<String>,
+    pub created_at: String,
+}
+
+/// Everything held about a talent
+#[derive(Serialize, Apiv2Schema)]
+pub struct TalentDataExport {
+    pub exported_at: String,
+    pub talent: Talent,
+    pub applications: Vec<Application>,
+    pub interviews: Vec<Interview>,
+    pub scorecards: Vec<Scorecard>,
+    pub offers: Vec<Offer>,
+    pub job_matches: Vec<JobMatch>,
+    pub pairwise_preferences: Vec<PairwisePreference>,
+    pub reorder_events: Vec<ReorderEvent>,
+    pub login_tokens: Vec<CandidateLoginTokenRecord>,
+    pub match_history: Vec<JobMatch>,  // Matches from every matching run
+    pub emails: Vec<OutboxEmail>,      // Sign-in link bodies redacted
+    pub notification_preferences: NotificationPreferences,
+    pub events: Vec<StoredEvent>,
+    pub webhook_deliveries: Vec<WebhookDelivery>,
+}
+
+#[derive(Deserialize, Apiv2Schema)]
+pub struct EraseTalentRequest {
+    pub reason: Option<String>,
+    pub requested_by: Option<String>,
+}
+
+/// Rows removed or anonymized by an erasure
+#[derive(Serialize, Deserialize, Clone, Apiv2Schema, PartialEq, Debug, Default)]
+pub struct ErasureCounts {
+    pub applications: u64,
+    pub job_matches: u64,
+    pub login_tokens: u64,
+    pub preferences_anonymized: u64,
+    pub webhook_deliveries: u64,
+    pub emails: u64,
+    pub events: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Apiv2Schema, PartialEq, Debug, FromRow)]
+pub struct ErasureTombstone {
+    pub id: String,
+    pub talent_id: String,
+    pub email_hash: String,
+    pub reason: Option<String>,
+    pub requested_by: Option<String>,
+    pub deleted_counts: String, // JSON ErasureCounts
+    pub collection_id: Option<String>,
+    pub remote_status: String,
+    pub erased_at: String,
+}
+
+// Interview models
+
+#[derive(Serialize, Deserialize, Clone, Apiv2Schema, PartialEq, Debug, FromRow)]
+pub struct Interview {
+    pub id: String,
+    pub application_id: String,
+    pub round: i32,
+    pub title: Option<String>,
+    pub interviewers: String, // JSON array
+    pub scheduled_at: String,
+    pub duration_minutes: i32,
+    pub location: Option<String>,
+    pub video_link: Option<String>,
+    pub status: String, // scheduled, completed, cancelled, no_show
+    pub created_at: String,
+}
+
+#[derive(Serialize, Apiv2Schema)]
+pub struct InterviewResponse {
+    pub id: String,
+    pub application_id: String,
+    pub round: i32,
+    pub title: Option<String>,
+    pub interviewers: Vec<String>,
+    pub scheduled_at: String,
+    pub duration_minutes: i32,
+    pub location: Option<String>,
+    pub video_link: Option<String>,
+    pub status: String,
+    pub created_at: String,
+}
+
+impl From<Interview> for InterviewResponse {
+    fn from(interview: Interview) -> Self {
+        InterviewResponse {
+            interviewers: serde_json::from_str(&interview.interviewers).unwrap_or_default(),
+            id: interview.id,
+            application_id: interview.application_id,
+            round: interview.round,
+            title: interview.title,
+            scheduled_at: interview.scheduled_at,
+            duration_minutes: interview.duration_minutes,
+            location: interview.location,
+            video_link: interview.video_link,
+            status: interview.status,
+            created_at: interview.created_at,
+        }
+    }
+}
+
+#[derive(Deserialize, Apiv2Schema)]
+pub struct CreateInterviewRequest {
+    pub round: Option<i32>, // defaults to the application's next round
+    pub title: Option<String>,
+    pub interviewers: Vec<String>,
+    pub scheduled_at: String, // RFC 3339
+    pub duration_minutes: Option<i32>,
+    pub location: Option<String>,
+    pub video_link: Option<String>,
+}
+
+#[derive(Deserialize, Apiv2Schema)]
+pub struct UpdateInterviewRequest {
+    pub round: Option<i32>,
+    pub title: Option<String>,
+    pub interviewers: Option<Vec<String>>,
+    pub scheduled_at: Option<String>,
+    pub duration_minutes: Option<i32>,
+    pub location: Option<String>,
+    pub video_link: Option<String>,
+    pub status: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Apiv2Schema, PartialEq, Debug, FromRow)]
+pub struct Scorecard {
+    pub id: String,
+    pub interview_id: String,
+    pub interviewer: String,
+    pub ratings: String,        // JSON object: competency -> rating (1-4)
+    pub recommendation: String, // strong_no_hire, no_hire, hire, strong_hire
+    pub notes: Option<String>,
+    pub submitted_at: String,
+}
+
+#[derive(Serialize, Apiv2Schema)]
+pub struct ScorecardResponse {
+    pub id: String,
+    pub interview_id: String,
+    pub interviewer: String,
+    pub ratings: BTreeMap<String, i32>,
+    pub recommendation: String,
+    pub notes: Option<String>,
+    pub submitted_at: String,
+}
+
+impl From<Scorecard> for ScorecardResponse {
+    fn from(scorecard: Scorecard) -> Self {
+        ScorecardResponse {
+            ratings: serde_json::from_str(&scorecard.ratings).unwrap_or_default(),
+            id: scorecard.id,
+            interview_id: scorecard.interview_id,
+            interviewer: scorecard.interviewer,
+            recommendation: scorecard.recommendation,
+            notes: scorecard.notes,
+            submitted_at: scorecard.submitted_at,
+        }
+    }
+}
+
+#[derive(Deserialize, Apiv2Schema)]
+pub struct SubmitScorecardRequest {
+    pub interviewer: String,
+    pub ratings: BTreeMap<String, i32>, // competency -> rating (1-4); omit competencies not assessed
+    pub recommendation: String,
+    pub notes: Option<String>,
+}
+
+#[derive(Serialize, Apiv2Schema)]
+pub struct CompetencyFeedback {
+    pub competency: String,
+    pub weight: f64, // points in the candidate scoring system
+    pub average_rating: f64,
+    pub ratings: usize,
+}
+
+#[derive(Serialize, Apiv2Schema, Default)]
+pub struct RecommendationTally {
+    pub strong_no_hire: usize,
+    pub no_hire: usize,
+    pub hire: usize,
+    pub strong_hire: usize,
+}
+
+#[derive(Serialize, Apiv2Schema)]
+pub struct PendingScorecard {
+    pub interview_id: String,
+    pub round: i32,
+    pub interviewer: String,
+}
+
+#[derive(Serialize, Apiv2Schema)]
+pub struct ApplicationFeedback {
+    pub application_id: String,
+    pub interviews: usize,
+    pub competencies: Vec<CompetencyFeedback>,
+    pub weighted_score: Option<f64>, // 0-100 over the assessed competencies
+    pub recommendations: RecommendationTally,
+    pub consensus: Option<String>, // hire, no_hire or split
+    pub pending: Vec<PendingScorecard>, // interviewers of past interviews yet to submit
+    pub scorecards: Vec<ScorecardResponse>,
+}
+
+// Offer models
+
+#[derive(Serialize, Deserialize, Clone, Apiv2Schema, PartialEq, Debug, FromRow)]
+pub struct Offer {
+    pub id: String,
+    pub application_id: String,
+    pub status: String, // draft, pending_approval, extended, accepted, declined, rescinded
+    pub base_salary: i64,
+    pub currency: String,
+    pub bonus: Option<i64>,
+    pub equity: Option<String>,
+    pub start_date: String, // YYYY-MM-DD
+    pub expires_at: Option<String>,
+    pub notes: Option<String>,
+    pub created_by: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Deserialize, Apiv2Schema)]
+pub struct CreateOfferRequest {
+    pub base_salary: i64,
+    pub currency: Option<String>, // defaults to the job's salary currency
+    pub bonus: Option<i64>,
+    pub equity: Option<String>,
+    pub start_date: String,
+    pub expires_at: Option<String>, // RFC 3339
+    pub notes: Option<String>,
+}
+
+#[derive(Deserialize, Apiv2Schema)]
+pub struct UpdateOfferRequest {
+    pub base_salary: Option<i64>,
+    pub currency: Option<String>,
+    pub bonus: Option<i64>,
+    pub equity: Option<String>,
+    pub start_date: Option<String>,
+    pub expires_at: Option<String>,
+    pub notes: Option<String>,
+}
+
+#[derive(Deserialize, Apiv2Schema)]
+pub struct OfferActionRequest {
+    pub comment: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Apiv2Schema, PartialEq, Debug, FromRow)]
+pub struct OfferApproval {
+    pub offer_id: String,
+    pub step: i32,
+    pub approver: String,
+    pub decision: Option<String>, // approved, rejected; null while pending
+    pub comment: Option<String>,
+    pub decided_at: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Apiv2Schema, PartialEq, Debug, FromRow)]
+pub struct OfferEvent {
+    pub id: String,
+    pub offer_id: String,
+    pub action: String,
+    pub actor: String,
+    pub from_status: Option<String>,
+    pub to_status: String,
+    pub comment: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Serialize, Apiv2Schema)]
+pub struct OfferDetail {
+    pub offer: Offer,
+    pub approvals: Vec<OfferApproval>,
+    pub history: Vec<OfferEvent>,
+}
+
+// Webhook models
+
+#[derive(Serialize, Deserialize, Clone, Apiv2Schema, PartialEq, Debug, FromRow)]
+pub struct Webhook {
+    pub id: String,
+    pub url: String,
+    pub secret: String,
+    pub events: String, // JSON array of event types, or ["*"] for all events
+    pub description: Option<String>,
+    pub active: i32,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Serialize, Apiv2Schema)]
+pub struct WebhookResponse {
+    pub id: String,
+    pub url: String,
+    pub events: Vec<String>,
+    pub description: Option<String>,
+    pub active: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>, // only returned when the webhook is created or its secret rotated
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl From<Webhook> for WebhookResponse {
+    fn from(webhook: Webhook) -> Self {
+        WebhookResponse {
+            events: serde_json::from_str(&webhook.events).unwrap_or_default(),
+            id: webhook.id,
+            url: webhook.url,
+            description: webhook.description,
+            active: webhook.active != 0,
+            secret: None,
+            created_at: webhook.created_at,
+            updated_at: webhook.updated_at,
+        }
+    }
+}
+
+#[derive(Deserialize, Apiv2Schema)]
+pub struct CreateWebhookRequest {
+    pub url: String,
+    pub events: Vec<String>, // event types, or "*" for all events
+    pub description: Option<String>,
+    pub secret: Option<String>, // generated when omitted
+}
+
+#[derive(Deserialize, Apiv2Schema)]
+pub struct UpdateWebhookRequest {
+    pub url: Option<String>,
+    pub events: Option<Vec<String>>,
+    pub description: Option<String>,
+    pub active: Option<bool>,
+    pub rotate_secret: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Apiv2Schema, PartialEq, Debug, FromRow)]
+pub struct WebhookDelivery {
+    pub id: String,
+    pub webhook_id: String,
+    pub event_id: String,
+    pub event_type: String,
+    pub payload: String, // JSON event envelope, sent as the request body
+    pub status: String,  // pending, sending (test deliveries in flight), succeeded, failed
+    pub attempts: i32,
+    pub next_attempt_at: Option<String>,
+    pub last_attempt_at: Option<String>,
+    pub response_status: Option<i32>,
+    pub response_body: Option<String>, // truncated
+    pub error: Option<String>,
+    pub redelivery_of: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Serialize, Apiv2Schema)]
+pub struct WebhookDeliveryResponse {
+    pub id: String,
+    pub webhook_id: String,
+    pub event_id: String,
+    pub event_type: String,
+    pub payload: serde_json::Value,
+    pub status: String,  // pending, sending, succeeded or failed
+    pub attempts: i32,
+    pub next_attempt_at: Option<String>,
+    pub last_attempt_at: Option<String>,
+    pub response_status: Option<i32>,
+    pub response_body: Option<String>,
+    pub error: Option<String>,
+    pub redelivery_of: Option<String>,
+    pub created_at: String,
+}
+
+impl From<WebhookDelivery> for WebhookDeliveryResponse {
+    fn from(delivery: WebhookDelivery) -> Self {
+        WebhookDeliveryResponse {
+            payload: serde_json::from_str(&delivery.payload).unwrap_or_default(),
+            id: delivery.id,
+            webhook_id: delivery.webhook_id,
+            event_id: delivery.event_id,
+            event_type: delivery.event_type,
+            status: delivery.status,
+            attempts: delivery.attempts,
+            next_attempt_at: delivery.next_attempt_at,
+            last_attempt_at: delivery.last_attempt_at,
+            response_status: delivery.response_status,
+            response_body: delivery.response_body,
+            error: delivery.error,
+            redelivery_of: delivery.redelivery_of,
+            created_at: delivery.created_at,
+        }
+    }
+}
+
+#[derive(Deserialize, Apiv2Schema)]
+pub struct WebhookDeliveryQuery {
+    pub status: Option<String>, // pending, sending, succeeded or failed
+    pub limit: Option<i64>,
+}
+
+// Event stream models
+
+#[derive(Serialize, Deserialize, Clone, Apiv2Schema, PartialEq, Debug, FromRow)]
+pub struct StoredEvent {
+    pub seq: i64, // event ID on the stream, increasing
+    pub id: String,
+    pub event_type: String,
+    pub job_id: Option<String>,
+    pub talent_id: Option<String>,
+    pub payload: String, // JSON event envelope
+    pub created_at: String,
+}
+
+#[derive(Deserialize, Apiv2Schema)]
+pub struct EventStreamQuery {
+    pub job_id: Option<String>,
+    pub talent_id: Option<String>,
+    pub types: Option<String>,       // comma-separated event types, default all
+    pub last_event_id: Option<i64>,  // for clients that cannot send the Last-Event-ID header
+}
+
+// Email models
+
+#[derive(Serialize, Deserialize, Clone, Apiv2Schema, PartialEq, Debug, FromRow)]
+pub struct OutboxEmail {
+    pub id: String,
+    pub template: String,
+    pub recipient: String,
+    pub subject: String,
+    pub body: String,
+    pub status: String, // pending, sent, failed
+    pub attempts: i32,
+    pub next_attempt_at: Option<String>,
+    pub last_error: Option<String>,
+    pub created_at: String,
+    pub sent_at: Option<String>,
+}
+
+#[derive(Deserialize, Apiv2Schema)]
+pub struct OutboxQuery {
+    pub status: Option<String>, // pending, sent or failed
+    pub recipient: Option<String>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Serialize, Apiv2Schema)]
+pub struct NotificationPreferences {
+    pub email: String,
+    pub preferences: BTreeMap<String, bool>, // template -> enabled, for every optional notification
+}
+
+#[derive(Deserialize, Apiv2Schema)]
+pub struct UpdateNotificationPreferencesRequest {
+    pub preferences: BTreeMap<String, bool>, // template -> enabled; templates left out keep their setting
+}
+
+// Data retention models
+
+#[derive(Deserialize, Apiv2Schema)]
+pub struct LegalHoldRequest {
+    pub legal_hold: bool,
+}
+
+#[derive(Serialize, Apiv2Schema)]
+pub struct RetentionClassReport {
+    pub class: String,
+    pub retention_days: Option<u32>, // None keeps data forever
+    pub cutoff: Option<String>,
+    pub affected: u64,               // rows purged, or that would be purged in a dry run
+}
+
+#[derive(Serialize, Apiv2Schema)]
+pub struct RetentionReport {
+    pub dry_run: bool,
+    pub generated_at: String,
+    pub classes: Vec<RetentionClassReport>,
+}
+
+// Import models
+
+#[derive(Deserialize, Apiv2Schema)]
+pub struct ImportQuery {
+    pub format: Option<String>,       // csv or ndjson; defaults from Content-Type
+    pub map: Option<String>,          // column mapping, e.g. "Full Name:name,E-mail:email"
+    pub dry_run: Option<bool>,
+    pub on_duplicate: Option<String>, // skip (default), update or error
+    pub batch_size: Option<usize>,
+}
+
+#[derive(Serialize, Apiv2Schema)]
+pub struct ImportRowResult {
+    pub line: usize,
+    pub status: String, // created, updated, skipped or failed (what would happen in a dry run)
+    pub id: Option<String>,
+    pub errors: Vec<String>,
+}
+
+#[derive(Serialize, Apiv2Schema)]
+pub struct ImportReport {
+    pub dry_run: bool,
+    pub total_rows: usize,
+    pub created: usize,
+    pub updated: usize,
+    pub skipped: usize,
+    pub failed: usize,
+    pub ignored_columns: Vec<String>,
+    pub rows: Vec<ImportRowResult>,
+}
+
+// Export models
+
+#[derive(Deserialize, Apiv2Schema)]
+pub struct ExportQuery {
+    pub format: Option<String>,  // csv (default), excel, json or ndjson
+    pub columns: Option<String>, // comma-separated; all columns when omitted
+}
+
+#[derive(Deserialize, Apiv2Schema)]
+pub struct PreferenceExportQuery {
+    pub source: Option<String>,            // comma-separated sources; all when omitted
+    pub since: Option<String>,             // RFC 3339; preferences recorded at or after
+    pub until: Option<String>,             // RFC 3339; preferences recorded before
+    pub split: Option<String>,             // train or validation; both when omitted
+    pub validation_fraction: Option<f64>,  // share of jobs held out, 0.1 by default
+    pub seed: Option<String>,              // changes which jobs are held out
+    pub redact: Option<String>,            // names, contact, links or none; all by default
+}
+
+#[derive(Deserialize, Apiv2Schema)]
+pub struct TalentListQuery {
+    pub q: Option<String>,        // matches name, email, handle or title
+    pub skill: Option<String>,
+    pub location: Option<String>,
+    pub verified: Option<bool>,
+    pub min_score: Option<f64>,   // minimum candidate score
+    pub created_after: Option<String>,
+    pub created_before: Option<String>,
+}
+
+/// One application in a job pipeline export, joined with its talent and match
+#[derive(Serialize, Debug, FromRow)]
+pub struct PipelineRow {
+    pub application_id: String,
+    pub application_status: String,
+    pub applied_at: String,
+    pub has_resume: bool,
+    pub talent_id: String,
+    pub name: String,
+    pub email: String,
+    pub handle: String,
+    pub title: String,
+    pub location: Option<String>,
+    pub experience: String,
+    pub skills: String,
+    pub verified: i32,
+    pub candidate_score: Option<f64>,
+    pub match_score: Option<f64>,
+    pub match_rank: Option<i32>,
+    pub match_summary: Option<String>,
+    #[sqlx(skip)]
+    pub recruiter_position: Option<usize>, // position in the latest recruiter reorder
+}
+
+// JSON Resume models (https://jsonresume.org/schema)
+
+#[derive(Serialize, Deserialize, Apiv2Schema, Default, Debug)]
+pub struct JsonResume {
+    #[serde(rename = "$schema", default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<String>,
+    #[serde(default)]
+    pub basics: ResumeBasics,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub work: Vec<ResumeWork>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skills: Vec<ResumeSkill>,
+}
+
+#[derive(Serialize, Deserialize, Apiv2Schema, Default, Debug)]
+pub struct ResumeBasics {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>, // job title
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<ResumeLocation>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub profiles: Vec<ResumeProfile>,
+}
+
+#[derive(Serialize, Deserialize, Apiv2Schema, Default, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ResumeLocation {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub city: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub country_code: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Apiv2Schema, Default, Debug)]
+pub struct ResumeProfile {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub network: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Apiv2Schema, Default, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ResumeWork {
+    #[serde(default, alias = "company", skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>, // company; `company` in pre-1.0 documents
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_date: Option<String>, // YYYY, YYYY-MM or YYYY-MM-DD
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_date: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub highlights: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Apiv2Schema, Default, Debug)]
+pub struct ResumeSkill {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub keywords: Vec<String>,
+}
+
+// Trash models
+
+#[derive(Serialize, Apiv2Schema)]
+pub struct TrashedApplication {
+    pub id: String,
+    pub talent_id: String,
+    pub job_id: String,
+    pub has_resume: bool,
+    pub status: String,
+    pub created_at: String,
+    pub deleted_at: Option<String>,
+}
+
+impl From<Application> for TrashedApplication {
+    fn from(app: Application) -> Self {
+        TrashedApplication {
+            id: app.id,
+            talent_id: app.talent_id,
+            job_id: app.job_id,
+            has_resume: app.resume_data.is_some(),
+            status: app.status,
+            created_at: app.created_at,
+            deleted_at: app.deleted_at,
+        }
+    }
+}
+
+#[derive(Serialize, Apiv2Schema)]
+pub struct TrashListing {
+    pub grace_days: Option<u32>, // days in the trash before the purge; None keeps them forever
+    pub talents: Vec<Talent>,
+    pub jobs: Vec<Job>,
+    pub applications: Vec<TrashedApplication>,
+}
+
+// Audit log models
+
+#[derive(Serialize, Deserialize, Clone, Apiv2Schema, PartialEq, Debug, FromRow)]
+pub struct AuditLogEntry {
+    pub id: String,
+    pub occurred_at: String,
+    pub request_id: Option<String>,
+    pub actor: String,
+    pub method: String,
+    pub route: String,
+    pub path: String,
+    pub target_ids: String, // JSON array of IDs
+    pub status: i64,
+    pub outcome: String,    // success | failure
+    pub error_code: Option<String>,
+    pub ip: Option<String>,
+}
+
+#[derive(Deserialize, Apiv2Schema)]
+pub struct AuditLogQuery {
+    pub actor: Option<String>,
+    pub method: Option<String>,
+    pub route: Option<String>,
+    pub target_id: Option<String>,
+    pub outcome: Option<String>,
+    pub since: Option<String>,  // RFC 3339, inclusive
+    pub until: Option<String>,  // RFC 3339, exclusive
+    pub limit: Option<i64>,     // default 100, max 1000
+    pub offset: Option<i64>,
+    pub format: Option<String>, // json (default) or csv
+}
@@ -0,0 +1,201 @@
+//! Notification emails
+//!
+//! Decides who hears about what. Candidates are emailed when their
+//! application is received, changes status or gets an interview scheduled,
+//! and the configured recruiters are told when a candidate has been scored.
+//! Pipeline notifications follow the published events, so every code path
+//! that publishes an event also notifies.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use chrono::DateTime;
+use log::error;
+use serde_json::Value;
+
+use crate::database::Pool;
+use crate::email::{self, Mailer, Template, Vars};
+use crate::events::EventKind;
+use crate::models::{Application, Interview, Job, NotificationPreferences, Talent};
+
+#[derive(Clone)]
+pub struct Notifier {
+    pool: Pool,
+    mailer: Mailer,
+    recruiter_emails: Arc<[String]>,
+    board_url: String,
+}
+
+/// What a candidate is told when their application moves to `status`;
+/// moving back to pending is not worth an email
+fn status_message(status: &str) -> Option<&'static str> {
+    match status {
+        "reviewed" => Some("The hiring team has reviewed your application and will be in touch about next steps."),
+        "accepted" => Some("Congratulations! Your application has been accepted."),
+        "rejected" => Some(
+            "Thank you for your interest. After careful consideration, the team has decided not to move forward \
+             with your application.",
+        ),
+        "withdrawn" => Some("Your application has been withdrawn as you requested."),
+        _ => None,
+    }
+}
+
+fn str_field<'a>(data: &'a Value, field: &str) -> &'a str {
+    data.get(field).and_then(Value::as_str).unwrap_or_default()
+}
+
+/// Every optional notification with whether `email` receives it
+pub async fn preferences(pool: &Pool, email: &str) -> Result<NotificationPreferences, sqlx::Error> {
+    let stored: BTreeMap<String, bool> = crate::database::get_notification_preferences(pool, email).await?
+        .into_iter()
+        .collect();
+    Ok(NotificationPreferences {
+        email: email.to_lowercase(),
+        preferences: email::optional_templates()
+            .map(|t| (t.as_str().to_string(), stored.get(t.as_str()).copied().unwrap_or(true)))
+            .collect(),
+    })
+}
+
+/// Validate requested preference changes against the optional notifications
+pub fn validate_preferences(requested: &BTreeMap<String, bool>) -> Result<Vec<(String, bool)>, Vec<String>> {
+    let mut errors = Vec::new();
+    let mut valid = Vec::new();
+    for (name, enabled) in requested {
+        match Template::parse(name) {
+            Some(template) if template.is_optional() => valid.push((template.as_str().to_string(), *enabled)),
+            Some(template) => errors.push(format!("{} emails cannot be turned off", template)),
+            None => errors.push(format!(
+                "Unknown notification '{}'; use {}",
+                name,
+                email::optional_templates().map(|t| t.as_str()).collect::<Vec<_>>().join(", ")
+            )),
+        }
+    }
+    if errors.is_empty() { Ok(valid) } else { Err(errors) }
+}
+
+impl Notifier {
+    pub fn new(pool: Pool, mailer: Mailer, recruiter_emails: Vec<String>, board_url: String) -> Self {
+        Notifier { pool, mailer, recruiter_emails: recruiter_emails.into(), board_url }
+    }
+
+    pub fn mailer(&self) -> &Mailer {
+        &self.mailer
+    }
+
+    fn portal_link(&self) -> String {
+        format!("{}/portal", self.board_url)
+    }
+
+    /// Talent and job an event refers to; missing rows are logged and skipped
+    async fn talent_and_job(&self, talent_id: &str, job_id: &str) -> Option<(Talent, Job)> {
+        let talent = crate::database::get_talent_by_id(&self.pool, talent_id.to_string()).await;
+        let job = crate::database::get_job_by_id(&self.pool, job_id.to_string()).await;
+        match (talent, job) {
+            (Ok(Some(talent)), Ok(Some(job))) => Some((talent, job)),
+            (Err(e), _) | (_, Err(e)) => {
+                error!("[notifications] Failed to load talent {} or job {}: {}", talent_id, job_id, e);
+                None
+            }
+            _ => None,
+        }
+    }
+
+    fn candidate_vars(&self, talent: &Talent, job: &Job) -> Vars {
+        Vars::from([
+            ("name", talent.name.clone()),
+            ("job_title", job.title.clone()),
+            ("company_name", job.company_name.clone()),
+            ("portal_link", self.portal_link()),
+        ])
+    }
+
+    /// Send the emails that follow a pipeline event
+    pub async fn on_event(&self, kind: EventKind, data: &Value) {
+        if !self.mailer.is_configured() {
+            return;
+        }
+        let (talent_id, job_id) = (str_field(data, "talent_id"), str_field(data, "job_id"));
+        match kind {
+            EventKind::ApplicationCreated => {
+                if let Some((talent, job)) = self.talent_and_job(talent_id, job_id).await {
+                    let vars = self.candidate_vars(&talent, &job);
+                    self.mailer.send(Template::ApplicationReceived, &talent.email, &vars).await;
+                }
+            }
+            EventKind::ApplicationStatusChanged => {
+                let status = str_field(data, "status");
+                let Some(message) = status_message(status) else {
+                    return;
+                };
+                if let Some((talent, job)) = self.talent_and_job(talent_id, job_id).await {
+                    let mut vars = self.candidate_vars(&talent, &job);
+                    vars.insert("status", status.to_string());
+                    vars.insert("status_message", message.to_string());
+                    self.mailer.send(Template::ApplicationStatusChanged, &talent.email, &vars).await;
+                }
+            }
+            EventKind::TalentScored => {
+                if self.recruiter_emails.is_empty() {
+                    return;
+                }
+                if let Some((talent, job)) = self.talent_and_job(talent_id, job_id).await {
+                    let score = data.get("overall_score").and_then(Value::as_f64).unwrap_or_default();
+                    let vars = Vars::from([
+                        ("name", talent.name.clone()),
+                        ("job_title", job.title.clone()),
+                        ("company_name", job.company_name.clone()),
+                        ("score", format!("{:.0}", score)),
+                        ("recommendation", str_field(data, "recommendation").to_string()),
+                        ("summary", str_field(data, "summary").to_string()),
+                        ("job_link", crate::feed::job_url(&self.board_url, &job)),
+                    ]);
+                    for recruiter in self.recruiter_emails.iter() {
+                        self.mailer.send(Template::ScoringFinished, recruiter, &vars).await;
+                    }
+                }
+            }
+            EventKind::TalentSocialAnalyzed
+            | EventKind::JobMatchesGenerated
+            | EventKind::ReorderCreated
+            | EventKind::JobOrderReconciled => {}
+        }
+    }
+
+    /// Tell the candidate about a newly scheduled interview
+    pub async fn interview_scheduled(&self, interview: &Interview, application: &Application) {
+        if !self.mailer.is_configured() {
+            return;
+        }
+        let Some((talent, job)) = self.talent_and_job(&application.talent_id, &application.job_id).await else {
+            return;
+        };
+        let interviewers: Vec<String> = serde_json::from_str(&interview.interviewers).unwrap_or_default();
+        let scheduled_at = DateTime::parse_from_rfc3339(&interview.scheduled_at)
+            .map(|at| at.format("%A %-d %B %Y, %H:%M (UTC%:z)").to_string())
+            .unwrap_or_else(|_| interview.scheduled_at.clone());
+
+        let mut vars = self.candidate_vars(&talent, &job);
+        vars.extend([
+            ("interview_title", interview.title.clone().unwrap_or_else(|| format!("Round {} interview", interview.round))),
+            ("round", interview.round.to_string()),
+            ("scheduled_at", scheduled_at),
+            ("duration_minutes", interview.duration_minutes.to_string()),
+            ("location", interview.video_link.clone().or(interview.location.clone()).unwrap_or_else(|| "To be confirmed".to_string())),
+            ("interviewers", interviewers.join(", ")),
+        ]);
+        self.mailer.send(Template::InterviewScheduled, &talent.email, &vars).await;
+    }
+
+    /// Email a portal sign-in link
+    pub async fn magic_link(&self, talent: &Talent, link: &str, ttl_minutes: i64) {
+        let vars = Vars::from([
+            ("name", talent.name.clone()),
+            ("link", link.to_string()),
+            ("ttl_minutes", ttl_minutes.to_string()),
+        ]);
+        self.mailer.send(Template::MagicLink, &talent.email, &vars).await;
+    }
+}
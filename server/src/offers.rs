@@ -0,0 +1,179 @@
+//! Offer lifecycle
+//!
+//! Offers move through `draft → pending_approval → extended → accepted |
+//! declined`. Submitting a draft starts the configured approval chain;
+//! approvers sign off in order and the last approval extends the offer to
+//! the candidate (with no approvers configured, submitting extends it
+//! directly). A rejection returns the offer to draft for rework. Any offer
+//! that has not been answered can be rescinded.
+//!
+//! Approvers are identified by the `X-Actor` header, which this server does
+//! not authenticate: put the API behind a proxy that authenticates users
+//! and sets `X-Actor` itself when the approval chain has to be trusted.
+
+use chrono::NaiveDate;
+
+use crate::models::Job;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OfferStatus {
+    Draft,
+    PendingApproval,
+    Extended,
+    Accepted,
+    Declined,
+    Rescinded,
+}
+
+impl OfferStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OfferStatus::Draft => "draft",
+            OfferStatus::PendingApproval => "pending_approval",
+            OfferStatus::Extended => "extended",
+            OfferStatus::Accepted => "accepted",
+            OfferStatus::Declined => "declined",
+            OfferStatus::Rescinded => "rescinded",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "draft" => Some(OfferStatus::Draft),
+            "pending_approval" => Some(OfferStatus::PendingApproval),
+            "extended" => Some(OfferStatus::Extended),
+            "accepted" => Some(OfferStatus::Accepted),
+            "declined" => Some(OfferStatus::Declined),
+            "rescinded" => Some(OfferStatus::Rescinded),
+            _ => None,
+        }
+    }
+
+    /// Whether the offer is still in play; an application has at most one
+    pub fn is_open(&self) -> bool {
+        matches!(self, OfferStatus::Draft | OfferStatus::PendingApproval | OfferStatus::Extended)
+    }
+}
+
+/// Statuses of open offers, for SQL `IN` lists
+pub const OPEN_STATUSES: [OfferStatus; 3] = [OfferStatus::Draft, OfferStatus::PendingApproval, OfferStatus::Extended];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OfferAction {
+    Submit,
+    Approve,
+    Reject,
+    Accept,
+    Decline,
+    Rescind,
+}
+
+impl OfferAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OfferAction::Submit => "submit",
+            OfferAction::Approve => "approve",
+            OfferAction::Reject => "reject",
+            OfferAction::Accept => "accept",
+            OfferAction::Decline => "decline",
+            OfferAction::Rescind => "rescind",
+        }
+    }
+}
+
+/// Status after `action`; `approvals_remaining` counts the approval steps
+/// still open after this action is applied
+pub fn transition(status: OfferStatus, action: OfferAction, approvals_remaining: usize) -> Result<OfferStatus, String> {
+    use OfferAction::*;
+    use OfferStatus::*;
+
+    let next = match (status, action) {
+        (Draft, Submit) | (PendingApproval, Approve) if approvals_remaining > 0 => PendingApproval,
+        (Draft, Submit) | (PendingApproval, Approve) => Extended,
+        (PendingApproval, Reject) => Draft,
+        (Extended, Accept) => Accepted,
+        (Extended, Decline) => Declined,
+        (Draft | PendingApproval | Extended, Rescind) => Rescinded,
+        _ => return Err(format!("Cannot {} an offer that is {}", action.as_str(), status.as_str())),
+    };
+    Ok(next)
+}
+
+/// Check an offer's compensation against the job's posted salary range
+pub fn validate_compensation(job: &Job, base_salary: i64, currency: &str, bonus: Option<i64>) -> Result<(), Vec<String>> {
+    let mut errors = Vec::new();
+
+    if base_salary <= 0 {
+        errors.push("base_salary must be positive".to_string());
+    }
+    if bonus.is_some_and(|b| b < 0) {
+        errors.push("bonus cannot be negative".to_string());
+    }
+    match job.salary_currency.as_deref().map(str::trim).filter(|c| !c.is_empty()) {
+        Some(posted) if !posted.eq_ignore_ascii_case(currency) => {
+            errors.push(format!("currency must match the job's salary currency ({})", posted));
+        }
+        Some(_) => {}
+        None if currency.len() != 3 || !currency.chars().all(|c| c.is_ascii_alphabetic()) => {
+            errors.push("currency must be a three-letter ISO 4217 code".to_string());
+        }
+        None => {}
+    }
+    if let Some(min) = job.salary_min.filter(|min| base_salary < *min) {
+        errors.push(format!("base_salary {} is below the job's salary_min {}", base_salary, min));
+    }
+    if let Some(max) = job.salary_max.filter(|max| base_salary > *max) {
+        errors.push(format!("base_salary {} is above the job's salary_max {}", base_salary, max));
+    }
+
+    if errors.is_empty() { Ok(()) } else { Err(errors) }
+}
+
+pub fn parse_start_date(value: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d").map_err(|_| "start_date must be a YYYY-MM-DD date".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(min: Option<i64>, max: Option<i64>, currency: Option<&str>) -> Job {
+        Job {
+            salary_min: min,
+            salary_max: max,
+            salary_currency: currency.map(str::to_string),
+            ..crate::test_support::job("j1")
+        }
+    }
+
+    #[test]
+    fn test_transitions() {
+        use OfferAction::*;
+        use OfferStatus::*;
+        assert_eq!(transition(Draft, Submit, 2), Ok(PendingApproval));
+        assert_eq!(transition(Draft, Submit, 0), Ok(Extended));
+        assert_eq!(transition(PendingApproval, Approve, 1), Ok(PendingApproval));
+        assert_eq!(transition(PendingApproval, Approve, 0), Ok(Extended));
+        assert_eq!(transition(PendingApproval, Reject, 1), Ok(Draft));
+        assert_eq!(transition(Extended, Accept, 0), Ok(Accepted));
+        assert_eq!(transition(Extended, Rescind, 0), Ok(Rescinded));
+        assert!(transition(Draft, Accept, 0).is_err());
+        assert!(transition(Accepted, Rescind, 0).is_err());
+        assert!(transition(Extended, Approve, 0).is_err());
+    }
+
+    #[test]
+    fn test_validate_compensation() {
+        let posted = job(Some(100_000), Some(150_000), Some("USD"));
+        assert!(validate_compensation(&posted, 120_000, "usd", Some(10_000)).is_ok());
+
+        let Err(errors) = validate_compensation(&posted, 160_000, "EUR", Some(-1)) else {
+            panic!("invalid compensation accepted");
+        };
+        assert_eq!(errors.len(), 3);
+
+        let unposted = job(None, None, None);
+        assert!(validate_compensation(&unposted, 90_000, "GBP", None).is_ok());
+        assert!(validate_compensation(&unposted, 90_000, "pounds", None).is_err());
+    }
+}
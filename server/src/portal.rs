@@ -0,0 +1,121 @@
+//! Candidate self-service portal authentication
+//!
+//! Candidates sign in with a single-use magic link sent to the email address
+//! on their talent profile. Exchanging the link token yields a signed session
+//! token of the form `<talent_id>.<expires_unix>.<hmac_sha256_hex>` that
+//! authorizes the candidate-scoped portal API.
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// How long a magic link stays valid
+pub const MAGIC_LINK_TTL_MINUTES: i64 = 15;
+
+/// Most magic links sent to one email per `MAGIC_LINK_TTL_MINUTES`; further
+/// requests are answered as usual but send nothing
+pub const MAX_MAGIC_LINKS_PER_TTL: i64 = 3;
+
+/// How long a portal session stays valid
+pub const SESSION_TTL_HOURS: i64 = 24;
+
+/// Generate a random, URL-safe token (two UUIDv4s, i.e. 244 random bits, hex encoded)
+pub fn generate_token() -> String {
+    format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
+
+/// Hash a token for storage; only hashes of magic link tokens hit the database
+pub fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionClaims {
+    pub talent_id: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+fn signature(secret: &[u8], payload: &str) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    mac.update(payload.as_bytes());
+    mac
+}
+
+/// Create a signed session token for a talent
+pub fn sign_session(secret: &[u8], talent_id: &str, expires_at: DateTime<Utc>) -> String {
+    let payload = format!("{}.{}", talent_id, expires_at.timestamp());
+    let sig = signature(secret, &payload).finalize().into_bytes();
+    format!("{}.{}", payload, hex::encode(sig))
+}
+
+/// Create a session token valid for `SESSION_TTL_HOURS` from `now`
+pub fn new_session(secret: &[u8], talent_id: &str, now: DateTime<Utc>) -> (String, DateTime<Utc>) {
+    let expires_at = now + Duration::hours(SESSION_TTL_HOURS);
+    (sign_session(secret, talent_id, expires_at), expires_at)
+}
+
+/// Verify a session token's signature and expiry
+pub fn verify_session(secret: &[u8], token: &str, now: DateTime<Utc>) -> Option<SessionClaims> {
+    let (payload, sig_hex) = token.rsplit_once('.')?;
+    let (talent_id, expires) = payload.rsplit_once('.')?;
+    if talent_id.is_empty() {
+        return None;
+    }
+
+    let sig = hex::decode(sig_hex).ok()?;
+    signature(secret, payload).verify_slice(&sig).ok()?;
+
+    let expires_at = Utc.timestamp_opt(expires.parse::<i64>().ok()?, 0).single()?;
+    if expires_at <= now {
+        return None;
+    }
+
+    Some(SessionClaims {
+        talent_id: talent_id.to_string(),
+        expires_at,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &[u8] = b"test-secret";
+
+    #[test]
+    fn test_session_roundtrip() {
+        let now = Utc::now();
+        let (token, expires_at) = new_session(SECRET, "talent-1", now);
+        let claims = verify_session(SECRET, &token, now).unwrap();
+        assert_eq!(claims.talent_id, "talent-1");
+        assert_eq!(claims.expires_at.timestamp(), expires_at.timestamp());
+    }
+
+    #[test]
+    fn test_session_rejects_tampering() {
+        let now = Utc::now();
+        let (token, _) = new_session(SECRET, "talent-1", now);
+        let forged = token.replacen("talent-1", "talent-2", 1);
+        assert!(verify_session(SECRET, &forged, now).is_none());
+        assert!(verify_session(b"other-secret", &token, now).is_none());
+        assert!(verify_session(SECRET, "garbage", now).is_none());
+    }
+
+    #[test]
+    fn test_session_expires() {
+        let now = Utc::now();
+        let token = sign_session(SECRET, "talent-1", now - Duration::seconds(1));
+        assert!(verify_session(SECRET, &token, now).is_none());
+    }
+
+    #[test]
+    fn test_token_hash_is_deterministic() {
+        let token = generate_token();
+        assert_eq!(token.len(), 64);
+        assert_eq!(hash_token(&token), hash_token(&token));
+        assert_ne!(hash_token(&token), token);
+    }
+}
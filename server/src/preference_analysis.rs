@@ -0,0 +1,379 @@
+//! Consistency of a job's pairwise preferences
+//!
+//! Only active preferences count: ties and preferences superseded by a newer
+//! judgement are left out. A contradiction is a pair held both ways. Cycles
+//! are found after netting each pair out (by recency-weighted confidence), so
+//! a pair that was judged both ways points whichever way outweighs the other.
+//! Agreement compares how two authors netted out the pairs they both judged.
+//! Resolving contradictions only settles an author's own reversals, so
+//! disagreements between authors stay on record for agreement to report.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+
+use chrono::{DateTime, Utc};
+
+use crate::models::{AuthorAgreement, PairwisePreference, PreferenceAnalysis, PreferenceContradiction, PreferenceCycle};
+use crate::reorder::recency_weight;
+
+/// Net weights closer to zero than this leave a pair undecided
+const NET_EPSILON: f64 = 1e-9;
+
+fn is_active(preference: &PairwisePreference) -> bool {
+    !preference.tie && preference.superseded_by.is_none()
+}
+
+/// The pair a preference is about, independent of direction
+fn pair_key(preference: &PairwisePreference) -> (&str, &str) {
+    let (winner, loser) = (preference.winner_id.as_str(), preference.loser_id.as_str());
+    if winner < loser { (winner, loser) } else { (loser, winner) }
+}
+
+/// +1 when the preference favours the first of its pair key, -1 otherwise
+fn direction(preference: &PairwisePreference) -> f64 {
+    if preference.winner_id.as_str() == pair_key(preference).0 { 1.0 } else { -1.0 }
+}
+
+pub fn analyze(job_id: &str, preferences: &[PairwisePreference], now: DateTime<Utc>) -> PreferenceAnalysis {
+    let active: Vec<&PairwisePreference> = preferences.iter().filter(|p| is_active(p)).collect();
+    let judged_pairs = active.iter().map(|p| pair_key(p)).collect::<BTreeSet<_>>().len();
+    let contradictions = contradictions(&active);
+    let consistency = if judged_pairs == 0 {
+        1.0
+    } else {
+        1.0 - contradictions.len() as f64 / judged_pairs as f64
+    };
+    let (authors, agreement, overall_agreement) = agreement(&active);
+
+    PreferenceAnalysis {
+        job_id: job_id.to_string(),
+        total_preferences: preferences.len(),
+        active_preferences: active.len(),
+        superseded_preferences: preferences.iter().filter(|p| p.superseded_by.is_some()).count(),
+        ties: preferences.iter().filter(|p| p.tie && p.superseded_by.is_none()).count(),
+        judged_pairs,
+        consistency,
+        cycles: cycles(&active, now),
+        contradictions,
+        authors,
+        agreement,
+        overall_agreement,
+    }
+}
+
+/// Preferences to supersede so every pair an author judged both ways keeps
+/// only their most recent judgement, as (superseded id, superseding id).
+/// Pairs on which different authors disagree are left alone.
+pub fn resolutions(preferences: &[PairwisePreference]) -> Vec<(String, String)> {
+    let mut by_author: BTreeMap<Option<&str>, Vec<&PairwisePreference>> = BTreeMap::new();
+    for preference in preferences.iter().filter(|p| is_active(p)) {
+        by_author.entry(preference.author.as_deref()).or_default().push(preference);
+    }
+    by_author
+        .into_values()
+        .flat_map(|own| contradictions(&own))
+        .flat_map(|contradiction| {
+            let latest = contradiction.preference_ids.last().cloned().unwrap_or_default();
+            contradiction.contradicted_preference_ids.into_iter().map(move |id| (id, latest.clone()))
+        })
+        .collect()
+}
+
+fn contradictions(active: &[&PairwisePreference]) -> Vec<PreferenceContradiction> {
+    let mut pairs: BTreeMap<(&str, &str), Vec<&PairwisePreference>> = BTreeMap::new();
+    for preference in active {
+        pairs.entry(pair_key(preference)).or_default().push(preference);
+    }
+
+    pairs
+        .into_values()
+        .filter_map(|mut judgements| {
+            judgements.sort_by(|a, b| a.created_at.cmp(&b.created_at).then_with(|| a.id.cmp(&b.id)));
+            let latest = *judgements.last()?;
+            let (agreeing, contradicted): (Vec<&PairwisePreference>, Vec<&PairwisePreference>) =
+                judgements.iter().copied().partition(|p| p.winner_id == latest.winner_id);
+            if contradicted.is_empty() {
+                return None;
+            }
+            Some(PreferenceContradiction {
+                winner_id: latest.winner_id.clone(),
+                loser_id: latest.loser_id.clone(),
+                winner_text: latest.winner_text.clone(),
+                loser_text: latest.loser_text.clone(),
+                preference_ids: agreeing.iter().map(|p| p.id.clone()).collect(),
+                contradicted_preference_ids: contradicted.iter().map(|p| p.id.clone()).collect(),
+                authors: judgements.iter().filter_map(|p| p.author.clone()).collect::<BTreeSet<_>>().into_iter().collect(),
+                last_judged_at: latest.created_at.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Candidate -> candidates they are preferred over, once each pair is netted out
+fn net_graph<'a>(active: &[&'a PairwisePreference], now: DateTime<Utc>) -> BTreeMap<&'a str, BTreeSet<&'a str>> {
+    let mut net: BTreeMap<(&str, &str), f64> = BTreeMap::new();
+    for preference in active {
+        *net.entry(pair_key(preference)).or_default() +=
+            direction(preference) * recency_weight(preference.confidence, &preference.created_at, now);
+    }
+
+    let mut graph: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    for ((first, second), weight) in net {
+        graph.entry(first).or_default();
+        graph.entry(second).or_default();
+        if weight > NET_EPSILON {
+            graph.entry(first).or_default().insert(second);
+        } else if weight < -NET_EPSILON {
+            graph.entry(second).or_default().insert(first);
+        }
+    }
+    graph
+}
+
+/// One shortest cycle per group of candidates that prefer each other around
+/// in circles (strongly connected components with more than one member)
+fn cycles(active: &[&PairwisePreference], now: DateTime<Utc>) -> Vec<PreferenceCycle> {
+    let graph = net_graph(active, now);
+    let texts: HashMap<&str, &str> = active
+        .iter()
+        .flat_map(|p| [(p.winner_id.as_str(), p.winner_text.as_str()), (p.loser_id.as_str(), p.loser_text.as_str())])
+        .collect();
+
+    strongly_connected(&graph)
+        .into_iter()
+        .filter(|component| component.len() > 1)
+        .filter_map(|component| {
+            let start = *component.iter().next()?;
+            let talent_ids = shortest_cycle(&graph, &component, start)?;
+            Some(PreferenceCycle {
+                talent_texts: talent_ids.iter().map(|id| texts.get(id).copied().unwrap_or("Unknown").to_string()).collect(),
+                talent_ids: talent_ids.into_iter().map(str::to_string).collect(),
+                component_size: component.len(),
+            })
+        })
+        .collect()
+}
+
+/// Tarjan's algorithm
+fn strongly_connected<'a>(graph: &BTreeMap<&'a str, BTreeSet<&'a str>>) -> Vec<BTreeSet<&'a str>> {
+    struct State<'a> {
+        index: HashMap<&'a str, usize>,
+        low: HashMap<&'a str, usize>,
+        stack: Vec<&'a str>,
+        on_stack: BTreeSet<&'a str>,
+        components: Vec<BTreeSet<&'a str>>,
+    }
+
+    fn visit<'a>(node: &'a str, graph: &BTreeMap<&'a str, BTreeSet<&'a str>>, state: &mut State<'a>) {
+        let index = state.index.len();
+        state.index.insert(node, index);
+        state.low.insert(node, index);
+        state.stack.push(node);
+        state.on_stack.insert(node);
+
+        for &next in graph.get(node).into_iter().flatten() {
+            if !state.index.contains_key(next) {
+                visit(next, graph, state);
+                let low = state.low[node].min(state.low[next]);
+                state.low.insert(node, low);
+            } else if state.on_stack.contains(next) {
+                let low = state.low[node].min(state.index[next]);
+                state.low.insert(node, low);
+            }
+        }
+
+        if state.low[node] == state.index[node] {
+            let mut component = BTreeSet::new();
+            while let Some(member) = state.stack.pop() {
+                state.on_stack.remove(member);
+                component.insert(member);
+                if member == node {
+                    break;
+                }
+            }
+            state.components.push(component);
+        }
+    }
+
+    let mut state = State {
+        index: HashMap::new(),
+        low: HashMap::new(),
+        stack: Vec::new(),
+        on_stack: BTreeSet::new(),
+        components: Vec::new(),
+    };
+    for &node in graph.keys() {
+        if !state.index.contains_key(node) {
+            visit(node, graph, &mut state);
+        }
+    }
+    state.components
+}
+
+/// Breadth-first search from `start` back to itself within one component
+fn shortest_cycle<'a>(
+    graph: &BTreeMap<&'a str, BTreeSet<&'a str>>,
+    component: &BTreeSet<&'a str>,
+    start: &'a str,
+) -> Option<Vec<&'a str>> {
+    let mut previous: HashMap<&str, &str> = HashMap::new();
+    let mut queue = VecDeque::from([start]);
+    while let Some(node) = queue.pop_front() {
+        for &next in graph.get(node).into_iter().flatten().filter(|next| component.contains(*next)) {
+            if next == start {
+                let mut path = vec![node];
+                while let Some(&before) = previous.get(path.last()?) {
+                    path.push(before);
+                }
+                path.reverse();
+                return Some(path);
+            }
+            if next != start && !previous.contains_key(next) {
+                previous.insert(next, node);
+                queue.push_back(next);
+            }
+        }
+    }
+    None
+}
+
+type AgreementReport = (Vec<String>, Vec<AuthorAgreement>, Option<f64>);
+
+/// Pairwise agreement between authors on the pairs both of them judged
+fn agreement(active: &[&PairwisePreference]) -> AgreementReport {
+    let mut by_author: BTreeMap<&str, BTreeMap<(&str, &str), f64>> = BTreeMap::new();
+    for preference in active {
+        if let Some(author) = preference.author.as_deref() {
+            *by_author.entry(author).or_default().entry(pair_key(preference)).or_default() +=
+                direction(preference) * preference.confidence;
+        }
+    }
+
+    let authors: Vec<&str> = by_author.keys().copied().collect();
+    let mut agreement = Vec::new();
+    let (mut shared_total, mut agreeing_total) = (0, 0);
+    for (i, a) in authors.iter().enumerate() {
+        for b in &authors[i + 1..] {
+            let (first, second) = (&by_author[a], &by_author[b]);
+            let shared: Vec<(f64, f64)> = first
+                .iter()
+                .filter_map(|(pair, net)| second.get(pair).map(|other| (*net, *other)))
+                .filter(|(net, other)| net.abs() > NET_EPSILON && other.abs() > NET_EPSILON)
+                .collect();
+            if shared.is_empty() {
+                continue;
+            }
+            let agreeing = shared.iter().filter(|(net, other)| net.signum() == other.signum()).count();
+            shared_total += shared.len();
+            agreeing_total += agreeing;
+            agreement.push(AuthorAgreement {
+                author_a: a.to_string(),
+                author_b: b.to_string(),
+                shared_pairs: shared.len(),
+                agreeing_pairs: agreeing,
+                agreement: agreeing as f64 / shared.len() as f64,
+            });
+        }
+    }
+
+    let overall = (shared_total > 0).then(|| agreeing_total as f64 / shared_total as f64);
+    (authors.into_iter().map(str::to_string).collect(), agreement, overall)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::preference;
+
+    fn pref(id: &str, winner: &str, loser: &str, author: Option<&str>, created_at: &str) -> PairwisePreference {
+        PairwisePreference {
+            id: id.to_string(),
+            winner_text: winner.to_uppercase(),
+            loser_text: loser.to_uppercase(),
+            author: author.map(str::to_string),
+            created_at: created_at.to_string(),
+            ..preference(winner, loser)
+        }
+    }
+
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2024-01-10T00:00:00Z").unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_contradictions_keep_latest() {
+        let prefs = vec![
+            pref("p1", "a", "b", Some("jane"), "2024-01-01T00:00:00Z"),
+            pref("p2", "b", "a", Some("jane"), "2024-01-02T00:00:00Z"),
+            pref("p3", "a", "c", Some("jane"), "2024-01-02T00:00:00Z"),
+        ];
+        let analysis = analyze("job1", &prefs, now());
+        assert_eq!(analysis.judged_pairs, 2);
+        assert_eq!(analysis.contradictions.len(), 1);
+        let contradiction = &analysis.contradictions[0];
+        assert_eq!((contradiction.winner_id.as_str(), contradiction.loser_id.as_str()), ("b", "a"));
+        assert_eq!(contradiction.contradicted_preference_ids, vec!["p1"]);
+        assert_eq!(analysis.consistency, 0.5);
+
+        assert_eq!(resolutions(&prefs), vec![("p1".to_string(), "p2".to_string())]);
+
+        let mut resolved = prefs.clone();
+        resolved[0].superseded_by = Some("p2".to_string());
+        let analysis = analyze("job1", &resolved, now());
+        assert!(analysis.contradictions.is_empty());
+        assert_eq!(analysis.superseded_preferences, 1);
+        assert!(resolutions(&resolved).is_empty());
+    }
+
+    #[test]
+    fn test_cycles() {
+        let prefs = vec![
+            pref("p1", "a", "b", None, "2024-01-01T00:00:00Z"),
+            pref("p2", "b", "c", None, "2024-01-01T00:00:00Z"),
+            pref("p3", "c", "a", None, "2024-01-01T00:00:00Z"),
+            pref("p4", "c", "d", None, "2024-01-01T00:00:00Z"),
+        ];
+        let cycles = analyze("job1", &prefs, now()).cycles;
+        assert_eq!(cycles.len(), 1);
+        assert_eq!(cycles[0].talent_ids, vec!["a", "b", "c"]);
+        assert_eq!(cycles[0].talent_texts, vec!["A", "B", "C"]);
+        assert_eq!(cycles[0].component_size, 3);
+
+        // A pair judged both ways equally is undecided and breaks the cycle
+        let mut balanced = prefs.clone();
+        balanced.push(pref("p5", "a", "c", None, "2024-01-01T00:00:00Z"));
+        assert!(analyze("job1", &balanced, now()).cycles.is_empty());
+    }
+
+    #[test]
+    fn test_agreement() {
+        let prefs = vec![
+            pref("p1", "a", "b", Some("jane"), "2024-01-01T00:00:00Z"),
+            pref("p2", "a", "c", Some("jane"), "2024-01-01T00:00:00Z"),
+            pref("p3", "a", "b", Some("omar"), "2024-01-01T00:00:00Z"),
+            pref("p4", "c", "a", Some("omar"), "2024-01-01T00:00:00Z"),
+            pref("p5", "b", "c", None, "2024-01-01T00:00:00Z"),
+        ];
+        let analysis = analyze("job1", &prefs, now());
+        assert_eq!(analysis.authors, vec!["jane", "omar"]);
+        assert_eq!(analysis.agreement.len(), 1);
+        assert_eq!(analysis.agreement[0].shared_pairs, 2);
+        assert_eq!(analysis.agreement[0].agreeing_pairs, 1);
+        assert_eq!(analysis.overall_agreement, Some(0.5));
+
+        assert_eq!(analyze("job1", &prefs[..2], now()).overall_agreement, None);
+    }
+
+    #[test]
+    fn test_resolutions_keep_disagreement_between_authors() {
+        let prefs = vec![
+            pref("p1", "a", "b", Some("jane"), "2024-01-01T00:00:00Z"),
+            pref("p2", "b", "a", Some("omar"), "2024-01-02T00:00:00Z"),
+            pref("p3", "b", "a", Some("jane"), "2024-01-03T00:00:00Z"),
+            pref("p4", "c", "d", Some("omar"), "2024-01-01T00:00:00Z"),
+            pref("p5", "d", "c", Some("jane"), "2024-01-02T00:00:00Z"),
+        ];
+        assert_eq!(analyze("job1", &prefs, now()).contradictions.len(), 2);
+        // Jane reversed herself on a/b; nobody reversed themselves on c/d
+        assert_eq!(resolutions(&prefs), vec![("p1".to_string(), "p3".to_string())]);
+    }
+}
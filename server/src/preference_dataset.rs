@@ -0,0 +1,422 @@
+//! Pairwise preferences as reward-model training data
+//!
+//! Each preference becomes one chosen/rejected example: the prompt is the
+//! full job posting and both responses are candidate profiles rendered from
+//! the talent and their resume experiences. Examples are split into train
+//! and validation by job, so no job's candidates leak across the split, and
+//! the split of a job only depends on its ID and the seed.
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::grok_client::ExperienceSummary;
+use crate::models::{Job, Talent};
+
+pub const SPLIT_TRAIN: &str = "train";
+pub const SPLIT_VALIDATION: &str = "validation";
+
+/// Share of jobs held out for validation unless the caller picks another
+pub const DEFAULT_VALIDATION_FRACTION: f64 = 0.1;
+
+const REDACTED_NAME: &str = "[NAME]";
+const REDACTED_EMAIL: &str = "[EMAIL]";
+const REDACTED_PHONE: &str = "[PHONE]";
+const REDACTED_LINK: &str = "[LINK]";
+
+/// Fewest digits in a run of text before it is treated as a phone number
+const MIN_PHONE_DIGITS: usize = 7;
+
+/// One line of the export
+#[derive(Serialize, Debug)]
+pub struct PreferenceExample {
+    pub id: String,
+    pub split: &'static str,
+    pub job_id: String,
+    pub prompt: String,
+    pub chosen: String,
+    pub rejected: String,
+    pub chosen_talent_id: Option<String>,  // Left out when names are redacted
+    pub rejected_talent_id: Option<String>,
+    pub source: String,
+    pub confidence: f64,
+    pub weight: f64,
+    pub created_at: String,
+}
+
+/// Which personal data is scrubbed from candidate profiles
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Redaction {
+    /// Candidate names, also where they appear in free text, and talent IDs
+    pub names: bool,
+    /// Email addresses and phone numbers in free text
+    pub contact: bool,
+    /// Profile URLs, handles and links in free text
+    pub links: bool,
+}
+
+impl Redaction {
+    /// Parse a comma-separated list of `names`, `contact` and `links`, or
+    /// `none`; everything is redacted when omitted
+    pub fn parse(spec: Option<&str>) -> Result<Self, String> {
+        let Some(spec) = spec.map(str::trim).filter(|s| !s.is_empty()) else {
+            return Ok(Redaction { names: true, contact: true, links: true });
+        };
+        let mut redaction = Redaction { names: false, contact: false, links: false };
+        for option in spec.split(',').map(str::trim).filter(|o| !o.is_empty()) {
+            match option {
+                "names" => redaction.names = true,
+                "contact" => redaction.contact = true,
+                "links" => redaction.links = true,
+                "none" => {}
+                other => return Err(format!("Unknown redaction '{}'; use names, contact, links or none", other)),
+            }
+        }
+        Ok(redaction)
+    }
+
+    fn apply(&self, text: &str, talent: &Talent) -> String {
+        // Names last, so addresses and handles containing them are caught whole
+        let mut text = text.to_string();
+        if self.contact {
+            text = redact_contact(&text);
+        }
+        if self.links {
+            text = redact_links(&text, &talent.handle);
+        }
+        if self.names {
+            text = redact_name(&text, &talent.name);
+        }
+        text
+    }
+}
+
+/// Train or validation, decided by hashing the job ID with the seed
+pub fn split_for_job(job_id: &str, seed: &str, validation_fraction: f64) -> &'static str {
+    let digest = Sha256::digest(format!("{}:{}", seed, job_id).as_bytes());
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&digest[..8]);
+    let position = u64::from_be_bytes(bytes) as f64 / u64::MAX as f64;
+    if position < validation_fraction {
+        SPLIT_VALIDATION
+    } else {
+        SPLIT_TRAIN
+    }
+}
+
+/// The full job posting, used as the prompt
+pub fn render_job(job: &Job) -> String {
+    let mut lines = vec![format!("{} at {}", job.title, job.company_name)];
+    let location = match &job.location {
+        Some(location) => format!("{} ({})", location, job.location_type),
+        None => job.location_type.clone(),
+    };
+    lines.push(format!("Location: {}, {}", location, job.employment_type));
+    lines.push(format!("Experience level: {}", job.experience_level));
+    lines.push(format!("Skills: {}", job.skills_required));
+    if let (Some(min), Some(max)) = (job.salary_min, job.salary_max) {
+        lines.push(format!("Salary: {}-{} {}", min, max, job.salary_currency.as_deref().unwrap_or("")).trim_end().to_string());
+    }
+    lines.push(String::new());
+    lines.push(job.description.trim().to_string());
+    lines.join("\n")
+}
+
+/// A candidate's profile and work history as plain text
+pub fn render_candidate(talent: &Talent, redaction: &Redaction) -> String {
+    let mut lines = Vec::new();
+    if !redaction.names {
+        lines.push(format!("Name: {}", talent.name));
+    }
+    lines.push(format!("Title: {}", talent.title));
+    lines.push(format!("Experience: {}", talent.experience));
+    if let Some(location) = &talent.location {
+        lines.push(format!("Location: {}", location));
+    }
+    lines.push(format!("Skills: {}", crate::database::decode_skills(talent.skills.clone())));
+    if let Some(bio) = talent.bio.as_deref().map(str::trim).filter(|b| !b.is_empty()) {
+        lines.push(format!("Bio: {}", bio));
+    }
+
+    let experiences: Vec<ExperienceSummary> = talent
+        .resume_experiences
+        .as_deref()
+        .and_then(|json| serde_json::from_str(json).ok())
+        .unwrap_or_default();
+    if !experiences.is_empty() {
+        lines.push("Work history:".to_string());
+        for experience in experiences {
+            let duration = experience.duration.map(|d| format!(" ({})", d)).unwrap_or_default();
+            lines.push(format!("- {} at {}{}: {}", experience.role, experience.company, duration, experience.summary));
+        }
+    }
+
+    if !redaction.links {
+        let links: Vec<&str> = [&talent.linkedin_url, &talent.github_url, &talent.gitlab_url, &talent.x_url]
+            .into_iter()
+            .filter_map(|url| url.as_deref())
+            .collect();
+        if !links.is_empty() {
+            lines.push(format!("Links: {}", links.join(", ")));
+        }
+    }
+
+    redaction.apply(&lines.join("\n"), talent)
+}
+
+/// Replace the full name and each part of it, ignoring case
+fn redact_name(text: &str, name: &str) -> String {
+    let mut parts: Vec<&str> = name.split_whitespace().filter(|p| p.chars().count() > 1).collect();
+    parts.sort_by_key(|p| std::cmp::Reverse(p.len()));
+    let mut text = replace_ignore_case(text, name.trim());
+    for part in parts {
+        text = replace_words_ignore_case(&text, part);
+    }
+    text
+}
+
+fn replace_ignore_case(text: &str, needle: &str) -> String {
+    if needle.is_empty() {
+        return text.to_string();
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        match match_len_ignore_case(rest, needle) {
+            Some(len) => {
+                out.push_str(REDACTED_NAME);
+                rest = &rest[len..];
+            }
+            None => {
+                out.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    out
+}
+
+/// Byte length of the prefix of `text` that equals `needle` char by char,
+/// ignoring case. Comparing chars rather than lowercased strings keeps byte
+/// offsets valid when lowercasing changes a char's length (e.g. 'İ').
+fn match_len_ignore_case(text: &str, needle: &str) -> Option<usize> {
+    let mut chars = text.char_indices();
+    for n in needle.chars() {
+        let (_, c) = chars.next()?;
+        if c != n && !c.to_lowercase().eq(n.to_lowercase()) {
+            return None;
+        }
+    }
+    Some(chars.next().map_or(text.len(), |(i, _)| i))
+}
+
+/// Replace whole words equal to `word`, ignoring case
+fn replace_words_ignore_case(text: &str, word: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut current = String::new();
+    for c in text.chars().chain(std::iter::once('\n')) {
+        if c.is_alphanumeric() || c == '\'' || c == '-' {
+            current.push(c);
+            continue;
+        }
+        if current.to_lowercase() == word.to_lowercase() {
+            out.push_str(REDACTED_NAME);
+        } else {
+            out.push_str(&current);
+        }
+        current.clear();
+        out.push(c);
+    }
+    out.pop();
+    out
+}
+
+/// Replace email addresses and phone numbers
+fn redact_contact(text: &str) -> String {
+    let emails = map_tokens(text, |token| {
+        let core = token.trim_matches(|c: char| !c.is_alphanumeric());
+        match core.split_once('@') {
+            Some((user, domain)) if !user.is_empty() && domain.contains('.') => Some(token.replace(core, REDACTED_EMAIL)),
+            _ => None,
+        }
+    });
+    redact_phones(&emails)
+}
+
+/// Replace runs of digits, spaces and phone punctuation holding enough
+/// digits. ISO dates and date ranges, such as the durations written by the
+/// JSON Resume import, are left alone.
+fn redact_phones(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i].is_ascii_digit() || (chars[i] == '+' && chars.get(i + 1).is_some_and(char::is_ascii_digit)) {
+            let mut end = i;
+            let mut digits = 0;
+            let mut j = i;
+            while j < chars.len() && (chars[j].is_ascii_digit() || matches!(chars[j], '+' | '-' | '.' | '(' | ')' | ' ')) {
+                if chars[j].is_ascii_digit() {
+                    digits += 1;
+                    end = j + 1;
+                }
+                j += 1;
+            }
+            let run: String = chars[i..end].iter().collect();
+            if digits >= MIN_PHONE_DIGITS && !is_dates(&run) {
+                out.push_str(REDACTED_PHONE);
+            } else {
+                out.extend(&chars[i..end]);
+            }
+            i = end;
+        } else {
+            out.push(chars[i]);
+            i += 1;
+        }
+    }
+    out
+}
+
+/// Whether a run is made only of ISO dates (`YYYY`, `YYYY-MM` or
+/// `YYYY-MM-DD`) joined by dashes and spaces, e.g. `2017-09-01 - 2018-06-30`
+fn is_dates(run: &str) -> bool {
+    if !run.chars().all(|c| c.is_ascii_digit() || c == '-' || c == ' ') {
+        return false;
+    }
+    let parts: Vec<&str> = run.split(['-', ' ']).filter(|p| !p.is_empty()).collect();
+    let is_year = |p: &str| p.len() == 4 && p.parse::<u32>().is_ok_and(|y| (1900..=2100).contains(&y));
+    let is_within = |p: &str, max: u32| p.len() == 2 && p.parse::<u32>().is_ok_and(|n| (1..=max).contains(&n));
+
+    let mut i = 0;
+    while i < parts.len() {
+        if !is_year(parts[i]) {
+            return false;
+        }
+        i += 1;
+        if parts.get(i).is_some_and(|p| is_within(p, 12)) {
+            i += 1;
+            if parts.get(i).is_some_and(|p| is_within(p, 31)) {
+                i += 1;
+            }
+        }
+    }
+    !parts.is_empty()
+}
+
+/// Replace URLs and `@` mentions of the candidate's handle
+fn redact_links(text: &str, handle: &str) -> String {
+    let mention = format!("@{}", handle.trim_start_matches('@').to_lowercase());
+    map_tokens(text, |token| {
+        let lower = token.to_lowercase();
+        if lower.contains("http://") || lower.contains("https://") || lower.starts_with("www.") {
+            return Some(REDACTED_LINK.to_string());
+        }
+        if lower.len() != token.len() {
+            return None;
+        }
+        let core = lower.trim_end_matches(|c: char| !c.is_alphanumeric() && c != '_');
+        let start = core.find('@')?;
+        (mention.len() > 1 && core[start..] == mention).then(|| {
+            format!("{}{}{}", &token[..start], REDACTED_LINK, &token[core.len()..])
+        })
+    })
+}
+
+/// Rewrite whitespace-separated tokens, keeping the whitespace as it was
+fn map_tokens(text: &str, replace: impl Fn(&str) -> Option<String>) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut token = String::new();
+    for c in text.chars().chain(std::iter::once('\n')) {
+        if c.is_whitespace() {
+            out.push_str(&replace(&token).unwrap_or_else(|| token.clone()));
+            token.clear();
+            out.push(c);
+        } else {
+            token.push(c);
+        }
+    }
+    out.pop();
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn talent() -> Talent {
+        Talent {
+            email: "ada@example.com".to_string(),
+            handle: "ada".to_string(),
+            skills: "\"rust, sql\"".to_string(),
+            location: Some("London".to_string()),
+            experience: "8 years".to_string(),
+            bio: Some("I'm Ada (ada@example.com, +44 20 7946 0958). See https://ada.dev or @ada. Lovelace built engines.".to_string()),
+            verified: 1,
+            resume_experiences: Some(r#"[{"company":"Analytical Co","role":"Lead","duration":"1843","summary":"Wrote the first program"}]"#.to_string()),
+            linkedin_url: Some("https://linkedin.com/in/ada".to_string()),
+            ..crate::test_support::talent("t1")
+        }
+    }
+
+    #[test]
+    fn test_parse_redaction() {
+        assert_eq!(Redaction::parse(None).unwrap(), Redaction { names: true, contact: true, links: true });
+        assert_eq!(Redaction::parse(Some("none")).unwrap(), Redaction { names: false, contact: false, links: false });
+        assert_eq!(Redaction::parse(Some("contact, links")).unwrap(), Redaction { names: false, contact: true, links: true });
+        assert!(Redaction::parse(Some("everything")).is_err());
+    }
+
+    #[test]
+    fn test_render_candidate_redacted() {
+        let text = render_candidate(&talent(), &Redaction::parse(None).unwrap());
+        for leaked in ["Ada", "Lovelace", "ada@example.com", "7946", "https://", "@ada"] {
+            assert!(!text.contains(leaked), "{} leaked into {}", leaked, text);
+        }
+        assert!(text.contains("Skills: rust, sql"));
+        assert!(text.contains("- Lead at Analytical Co (1843): Wrote the first program"));
+        assert!(text.contains("I'm [NAME] ([EMAIL], [PHONE])."));
+    }
+
+    #[test]
+    fn test_render_candidate_unredacted() {
+        let text = render_candidate(&talent(), &Redaction::parse(Some("none")).unwrap());
+        assert!(text.starts_with("Name: Ada Lovelace\n"));
+        assert!(text.contains("Links: https://linkedin.com/in/ada"));
+        assert!(text.contains("+44 20 7946 0958"));
+    }
+
+    #[test]
+    fn test_redact_name_when_lowercasing_changes_length() {
+        assert_eq!(redact_name("İlker worked with ADA Lovelace", "Ada Lovelace"), "İlker worked with [NAME]");
+        assert_eq!(redact_name("İlker and ÉLODIE DURAND", "Élodie Durand"), "İlker and [NAME]");
+    }
+
+    #[test]
+    fn test_redact_phones_keeps_dates() {
+        assert_eq!(redact_phones("Call +1 (415) 555-0134 today"), "Call [PHONE] today");
+        assert_eq!(redact_phones("555-123-4567"), "[PHONE]");
+        assert_eq!(redact_phones("2025-555-0134"), "[PHONE]");
+        assert_eq!(redact_phones("2017-09-01 - 2018-06-30"), "2017-09-01 - 2018-06-30");
+        assert_eq!(redact_phones("2019-03 - Present"), "2019-03 - Present");
+        assert_eq!(redact_phones("from 2015 - 2018"), "from 2015 - 2018");
+
+        // Durations as the JSON Resume import stores them
+        let imported = Talent {
+            resume_experiences: Some(r#"[{"company":"Initech","role":"Intern","duration":"2017-09-01 - 2018-06-30","summary":""}]"#.to_string()),
+            ..talent()
+        };
+        let text = render_candidate(&imported, &Redaction::parse(None).unwrap());
+        assert!(text.contains("- Intern at Initech (2017-09-01 - 2018-06-30)"), "{}", text);
+    }
+
+    #[test]
+    fn test_split_is_deterministic() {
+        assert_eq!(split_for_job("job1", "seed", 0.5), split_for_job("job1", "seed", 0.5));
+        assert_eq!(split_for_job("job1", "seed", 0.0), SPLIT_TRAIN);
+        assert_eq!(split_for_job("job1", "seed", 1.0), SPLIT_VALIDATION);
+
+        let validation = (0..1000)
+            .filter(|i| split_for_job(&format!("job{}", i), "seed", 0.2) == SPLIT_VALIDATION)
+            .count();
+        assert!((150..250).contains(&validation), "{} of 1000 jobs in validation", validation);
+    }
+}
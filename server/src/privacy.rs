@@ -0,0 +1,199 @@
+//! Data subject requests (GDPR / CCPA)
+//!
+//! Export gathers everything held about a talent across all tables, i.e.
+//! everything erasure removes or anonymizes. Erasure removes the talent and
+//! every row that references them in one transaction, anonymizes the
+//! candidate text in pairwise preferences, deletes their xAI collection
+//! through grok-service and leaves a tombstone for audit. Talents under legal
+//! hold, or with applications on jobs under legal hold, cannot be erased.
+
+use std::io::Write;
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use chrono::Utc;
+use log::{error, info, warn};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+use zip::{result::ZipError, write::SimpleFileOptions, ZipWriter};
+
+use crate::database::Pool;
+use crate::grok_client::GrokClient;
+use crate::models::{ErasureTombstone, Talent, TalentDataExport};
+
+/// Replaces candidate names in pairwise preferences after erasure
+pub const ERASED_PLACEHOLDER: &str = "[erased]";
+
+/// Hash of the normalized email, stored on tombstones instead of the email
+pub fn email_hash(email: &str) -> String {
+    hex::encode(Sha256::digest(email.trim().to_lowercase().as_bytes()))
+}
+
+/// Collect everything held about `talent`
+pub async fn collect_export(pool: &Pool, talent: Talent) -> Result<TalentDataExport, sqlx::Error> {
+    let id = talent.id.clone();
+    Ok(TalentDataExport {
+        exported_at: Utc::now().to_rfc3339(),
+        applications: crate::database::get_applications_by_talent_including_deleted(pool, &id).await?,
+        interviews: crate::database::get_interviews_for_talent(pool, &id).await?,
+        scorecards: crate::database::get_scorecards_for_talent(pool, &id).await?,
+        offers: crate::database::get_offers_for_talent(pool, &id).await?,
+        job_matches: crate::database::get_job_matches_by_talent(pool, &id).await?,
+        pairwise_preferences: crate::database::get_pairwise_preferences_for_talent(pool, &id).await?,
+        reorder_events: crate::database::get_reorder_events_for_talent(pool, &id).await?,
+        login_tokens: crate::database::get_candidate_login_tokens_for_talent(pool, &id).await?,
+        match_history: crate::database::get_match_run_results_for_talent(pool, &id).await?,
+        emails: crate::database::get_outbox_emails_for_recipient(pool, &talent.email).await?
+            .into_iter()
+            .map(crate::email::redacted)
+            .collect(),
+        notification_preferences: crate::notifications::preferences(pool, &talent.email).await?,
+        events: crate::database::get_events_for_talent(pool, &id).await?,
+        webhook_deliveries: crate::database::get_webhook_deliveries_for_talent(pool, &id).await?,
+        talent,
+    })
+}
+
+/// Strip any directory components from an uploaded filename
+fn safe_filename(filename: &str) -> &str {
+    filename
+        .rsplit(['/', '\\'])
+        .next()
+        .filter(|name| !name.is_empty() && *name != "." && *name != "..")
+        .unwrap_or("resume")
+}
+
+/// Package an export as a zip: `data.json` plus each attached resume as a file
+pub fn export_zip(export: &TalentDataExport) -> Result<Vec<u8>, ZipError> {
+    let mut zip = ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default();
+
+    zip.start_file("data.json", options)?;
+    let json = serde_json::to_vec_pretty(export).map_err(std::io::Error::other)?;
+    zip.write_all(&json)?;
+
+    for app in &export.applications {
+        let Some(data) = app.resume_data.as_deref() else {
+            continue;
+        };
+        let Ok(bytes) = STANDARD.decode(data) else {
+            warn!("[privacy] Skipping undecodable resume on application {}", app.id);
+            continue;
+        };
+        let name = safe_filename(app.resume_filename.as_deref().unwrap_or("resume.pdf"));
+        zip.start_file(format!("resumes/{}-{}", app.id, name), options)?;
+        zip.write_all(&bytes)?;
+    }
+
+    Ok(zip.finish()?.into_inner())
+}
+
+/// Erase a talent everywhere and return the resulting tombstone.
+///
+/// Local data is removed first; the remote collection is deleted afterwards
+/// and its outcome recorded on the tombstone (`deleted` or `failed`), so a
+/// grok-service outage never leaves personal data behind locally.
+pub async fn erase(
+    pool: &Pool,
+    grok_url: &str,
+    talent: &Talent,
+    reason: Option<String>,
+    requested_by: Option<String>,
+) -> Result<ErasureTombstone, sqlx::Error> {
+    let collection_id = talent.collection_id.clone().filter(|c| !c.is_empty());
+    let mut tombstone = ErasureTombstone {
+        id: Uuid::new_v4().to_string(),
+        talent_id: talent.id.clone(),
+        email_hash: email_hash(&talent.email),
+        reason,
+        requested_by,
+        deleted_counts: String::new(),
+        collection_id: collection_id.clone(),
+        remote_status: "none".to_string(),
+        erased_at: Utc::now().to_rfc3339(),
+    };
+
+    let counts = crate::database::erase_talent(pool, &talent.id, ERASED_PLACEHOLDER, &tombstone).await?;
+    tombstone.deleted_counts = serde_json::to_string(&counts).unwrap_or_default();
+    info!("[privacy] Erased talent {} ({:?})", talent.id, counts);
+
+    if let Some(collection_id) = collection_id {
+        let remote_status = match GrokClient::new(grok_url).delete_collection(&collection_id).await {
+            Ok(true) => "deleted",
+            Ok(false) => {
+                error!("[privacy] grok-service could not delete collection {}", collection_id);
+                "failed"
+            }
+            Err(e) => {
+                error!("[privacy] Failed to delete collection {}: {}", collection_id, e);
+                "failed"
+            }
+        };
+        if let Some(updated) = crate::database::update_erasure_remote_status(pool, &tombstone.id, remote_status).await? {
+            tombstone = updated;
+        }
+    }
+
+    Ok(tombstone)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Application, NotificationPreferences};
+    use crate::test_support;
+    use std::io::Read;
+
+    #[test]
+    fn test_email_hash_is_normalized() {
+        assert_eq!(email_hash(" Ann@Example.com "), email_hash("ann@example.com"));
+        assert_ne!(email_hash("ann@example.com"), "ann@example.com");
+    }
+
+    #[test]
+    fn test_safe_filename() {
+        assert_eq!(safe_filename("cv.pdf"), "cv.pdf");
+        assert_eq!(safe_filename("../../etc/passwd"), "passwd");
+        assert_eq!(safe_filename("C:\\docs\\cv.pdf"), "cv.pdf");
+        assert_eq!(safe_filename("dir/"), "resume");
+    }
+
+    #[test]
+    fn test_export_zip_contains_data_and_resumes() {
+        let export = TalentDataExport {
+            exported_at: "2026-01-02T00:00:00Z".to_string(),
+            talent: test_support::talent("t1"),
+            applications: vec![
+                Application {
+                    resume_data: Some(STANDARD.encode(b"%PDF-1.4")),
+                    resume_filename: Some("cv.pdf".to_string()),
+                    ..test_support::application("a1", "t1", "j1")
+                },
+                test_support::application("a2", "t1", "j1"),
+            ],
+            interviews: vec![],
+            scorecards: vec![],
+            offers: vec![],
+            job_matches: vec![],
+            pairwise_preferences: vec![],
+            reorder_events: vec![],
+            login_tokens: vec![],
+            match_history: vec![],
+            emails: vec![],
+            notification_preferences: NotificationPreferences { email: "t1@example.com".to_string(), preferences: Default::default() },
+            events: vec![],
+            webhook_deliveries: vec![],
+        };
+
+        let bytes = export_zip(&export).unwrap();
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bytes)).unwrap();
+        assert_eq!(archive.len(), 2);
+
+        let mut resume = Vec::new();
+        archive.by_name("resumes/a1-cv.pdf").unwrap().read_to_end(&mut resume).unwrap();
+        assert_eq!(resume, b"%PDF-1.4");
+
+        let mut json = String::new();
+        archive.by_name("data.json").unwrap().read_to_string(&mut json).unwrap();
+        assert!(json.contains("t1@example.com"));
+    }
+}
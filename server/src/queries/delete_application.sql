@@ -1 +1 @@
-DELETE FROM applications WHERE id = ?
+UPDATE applications SET deleted_at = ? WHERE id = ? AND deleted_at IS NULL
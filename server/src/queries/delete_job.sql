@@ -1 +1 @@
-DELETE FROM jobs WHERE id = ?
+UPDATE jobs SET deleted_at = ? WHERE id = ? AND deleted_at IS NULL
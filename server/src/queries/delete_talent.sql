@@ -1 +1 @@
-DELETE FROM talents WHERE id = ?;
\ No newline at end of file
+UPDATE talents SET deleted_at = ? WHERE id = ? AND deleted_at IS NULL;
\ No newline at end of file
@@ -1 +1 @@
-SELECT * FROM jobs ORDER BY created_at DESC
+SELECT * FROM jobs WHERE deleted_at IS NULL ORDER BY created_at DESC
@@ -1 +0,0 @@
-SELECT * FROM talents ORDER BY created_at DESC;
\ No newline at end of file
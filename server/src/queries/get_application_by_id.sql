@@ -1,3 +1,3 @@
-SELECT id, talent_id, job_id, resume_data, resume_filename, resume_content_type, cover_letter, status, created_at
+SELECT id, talent_id, job_id, resume_data, resume_filename, resume_content_type, cover_letter, status, created_at, deleted_at
 FROM applications
-WHERE id = ?
+WHERE id = ? AND deleted_at IS NULL
@@ -1,4 +1,4 @@
-SELECT id, talent_id, job_id, resume_data, resume_filename, resume_content_type, cover_letter, status, created_at
+SELECT id, talent_id, job_id, resume_data, resume_filename, resume_content_type, cover_letter, status, created_at, deleted_at
 FROM applications
-WHERE job_id = ?
+WHERE job_id = ? AND deleted_at IS NULL
 ORDER BY created_at DESC
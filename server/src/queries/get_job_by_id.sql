@@ -1 +1 @@
-SELECT * FROM jobs WHERE id = ?
+SELECT * FROM jobs WHERE id = ? AND deleted_at IS NULL
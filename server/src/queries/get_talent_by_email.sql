@@ -1 +1 @@
-SELECT * FROM talents WHERE email = ?
+SELECT * FROM talents WHERE email = ? COLLATE NOCASE AND deleted_at IS NULL ORDER BY created_at
\ No newline at end of file
@@ -1 +1 @@
-SELECT * FROM talents WHERE id = ?;
\ No newline at end of file
+SELECT * FROM talents WHERE id = ? AND deleted_at IS NULL;
\ No newline at end of file
@@ -13,5 +13,5 @@ UPDATE jobs SET
     experience_level = CASE WHEN ?12 != '' THEN ?12 ELSE experience_level END,
     status = CASE WHEN ?13 != '' THEN ?13 ELSE status END,
     expires_at = COALESCE(?14, expires_at)
-WHERE id = ?15
+WHERE id = ?15 AND deleted_at IS NULL
 RETURNING *
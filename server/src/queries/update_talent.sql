@@ -9,6 +9,7 @@ SET
     experience = ?,
     skills = ?,
     bio = ?,
-    verified = ?
-WHERE id = ?
+    verified = ?,
+    updated_at = ?
+WHERE id = ? AND deleted_at IS NULL
 RETURNING *;
\ No newline at end of file
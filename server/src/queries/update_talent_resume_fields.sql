@@ -3,6 +3,7 @@ SET resume_experiences = ?,
     linkedin_url = ?,
     x_url = ?,
     github_url = ?,
-    gitlab_url = ?
+    gitlab_url = ?,
+    updated_at = ?
 WHERE id = ?
 RETURNING *;
@@ -1,7 +1,72 @@
 use crate::models::PairwisePreference;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use uuid::Uuid;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+
+/// Preferences from recruiters dragging candidates around
+pub const SOURCE_MANUAL_REORDER: &str = "manual_reorder";
+/// Candidates a recruiter marked as equally good
+pub const SOURCE_TIE: &str = "tie";
+/// Shortlisted candidates over the job's other candidates
+pub const SOURCE_SHORTLIST: &str = "shortlist";
+/// The job's other candidates over rejected ones
+pub const SOURCE_REJECT: &str = "reject";
+/// An application moved forward over applications left behind
+pub const SOURCE_STATUS_CHANGE: &str = "status_change";
+
+/// Every source a preference can come from
+pub const PREFERENCE_SOURCES: [&str; 5] = [
+    SOURCE_MANUAL_REORDER, SOURCE_TIE, SOURCE_SHORTLIST, SOURCE_REJECT, SOURCE_STATUS_CHANGE,
+];
+
+/// Implicit signals say less about a specific pair than a reorder does
+const SHORTLIST_CONFIDENCE: f64 = 0.6;
+const REJECT_CONFIDENCE: f64 = 0.7;
+const STATUS_CHANGE_CONFIDENCE: f64 = 0.5;
+
+/// Confidence of an inversion falls by this factor per position between the
+/// pair, since far-apart pairs were rarely compared directly
+const DISTANCE_DECAY: f64 = 0.85;
+const MIN_INVERSION_CONFIDENCE: f64 = 0.25;
+
+/// A preference counts half as much after this many days
+pub const RECENCY_HALF_LIFE_DAYS: f64 = 30.0;
+
+/// The job and candidates that derived preferences are about
+pub struct PreferenceContext<'a> {
+    pub job_id: &'a str,
+    pub job_text: &'a str,
+    pub talent_names: &'a HashMap<String, String>,  // talent_id -> name
+    pub reorder_event_id: Option<&'a str>,
+    pub author: Option<&'a str>,
+}
+
+impl PreferenceContext<'_> {
+    fn name(&self, talent_id: &str) -> String {
+        self.talent_names.get(talent_id).map_or("Unknown", |s| s.as_str()).to_string()
+    }
+
+    fn preference(&self, winner_id: &str, loser_id: &str, source: &str, confidence: f64, tie: bool) -> PairwisePreference {
+        PairwisePreference {
+            id: Uuid::new_v4().to_string(),
+            winner_id: winner_id.to_string(),
+            loser_id: loser_id.to_string(),
+            job_id: self.job_id.to_string(),
+            job_text: self.job_text.to_string(),
+            winner_text: self.name(winner_id),
+            loser_text: self.name(loser_id),
+            source: source.to_string(),
+            confidence,
+            reorder_event_id: self.reorder_event_id.map(str::to_string),
+            created_at: Utc::now().to_rfc3339(),
+            tie,
+            author: self.author.map(str::to_string),
+            superseded_by: None,
+            superseded_at: None,
+            weight: None,
+        }
+    }
+}
 
 /// Derive pairwise preferences from a reorder event
 ///
@@ -18,11 +83,18 @@ pub fn derive_pairwise_preferences(
     after_order: &[String],
     job_id: &str,
     job_text: &str,
-    talent_names: &std::collections::HashMap<String, String>,  // talent_id -> name
+    talent_names: &HashMap<String, String>,  // talent_id -> name
     reorder_event_id: &str,
     moved_talent_id: Option<&str>,  // If provided, only derive for this candidate
 ) -> Vec<PairwisePreference> {
     let mut preferences = Vec::new();
+    let ctx = PreferenceContext {
+        job_id,
+        job_text,
+        talent_names,
+        reorder_event_id: Some(reorder_event_id),
+        author: None,
+    };
 
     // Handle edge cases
     if before_order.is_empty() || after_order.is_empty() {
@@ -62,63 +134,25 @@ pub fn derive_pairwise_preferences(
             continue;
         }
 
-        let winner_text = talent_names.get(talent_id)
-            .map(|s| s.as_str())
-            .unwrap_or("Unknown");
-
         if new_pos < old_pos {
             // Moved up: talent is now preferred over candidates they passed
             // Look at candidates that were between new_pos and old_pos in BEFORE order
-            for crossed_pos in new_pos..old_pos {
-                let crossed_talent_id = &before_order[crossed_pos];
+            for crossed_talent_id in &before_order[new_pos..old_pos] {
                 if crossed_talent_id == talent_id {
                     continue;
                 }
 
-                let loser_text = talent_names.get(crossed_talent_id)
-                    .map(|s| s.as_str())
-                    .unwrap_or("Unknown");
-
-                preferences.push(PairwisePreference {
-                    id: Uuid::new_v4().to_string(),
-                    winner_id: talent_id.clone(),
-                    loser_id: crossed_talent_id.clone(),
-                    job_id: job_id.to_string(),
-                    job_text: job_text.to_string(),
-                    winner_text: winner_text.to_string(),
-                    loser_text: loser_text.to_string(),
-                    source: "manual_reorder".to_string(),
-                    confidence: 1.0,
-                    reorder_event_id: Some(reorder_event_id.to_string()),
-                    created_at: Utc::now().to_rfc3339(),
-                });
+                preferences.push(ctx.preference(talent_id, crossed_talent_id, SOURCE_MANUAL_REORDER, 1.0, false));
             }
         } else {
             // Moved down: candidates that passed this talent are now preferred
             // Look at candidates that are between old_pos and new_pos in BEFORE order
-            for crossed_pos in (old_pos + 1)..=new_pos {
-                let crossed_talent_id = &before_order[crossed_pos];
+            for crossed_talent_id in &before_order[(old_pos + 1)..=new_pos] {
                 if crossed_talent_id == talent_id {
                     continue;
                 }
 
-                let winner_text_crossed = talent_names.get(crossed_talent_id)
-                    .map(|s| s.as_str())
-                    .unwrap_or("Unknown");
-
-                preferences.push(PairwisePreference {
-                    id: Uuid::new_v4().to_string(),
-                    winner_id: crossed_talent_id.clone(),
-                    loser_id: talent_id.clone(),
-                    job_id: job_id.to_string(),
-                    job_text: job_text.to_string(),
-                    winner_text: winner_text_crossed.to_string(),
-                    loser_text: winner_text.to_string(),
-                    source: "manual_reorder".to_string(),
-                    confidence: 1.0,
-                    reorder_event_id: Some(reorder_event_id.to_string()),
-                    created_at: Utc::now().to_rfc3339(),
-                });
+                preferences.push(ctx.preference(crossed_talent_id, talent_id, SOURCE_MANUAL_REORDER, 1.0, false));
             }
         }
     }
@@ -138,6 +172,168 @@ pub fn derive_pairwise_preferences(
     deduped_preferences
 }
 
+/// Derive preferences from every pair whose relative order the reorder
+/// flipped, so a shuffle of several candidates yields each pair once
+///
+/// The candidate now ahead wins. Adjacent pairs get confidence 1.0, falling
+/// by `DISTANCE_DECAY` per position between the pair in `after_order`.
+pub fn derive_inversion_preferences(
+    before_order: &[String],
+    after_order: &[String],
+    ctx: &PreferenceContext,
+) -> Vec<PairwisePreference> {
+    if before_order.len() != after_order.len() || before_order == after_order {
+        return Vec::new();
+    }
+    let before: HashMap<&String, usize> = before_order.iter().enumerate().map(|(i, id)| (id, i)).collect();
+
+    let mut preferences = Vec::new();
+    for (i, ahead) in after_order.iter().enumerate() {
+        for (distance, behind) in after_order[i + 1..].iter().enumerate().map(|(d, id)| (d + 1, id)) {
+            let (Some(was_ahead), Some(was_behind)) = (before.get(ahead), before.get(behind)) else {
+                continue;
+            };
+            if was_ahead > was_behind {
+                preferences.push(ctx.preference(ahead, behind, SOURCE_MANUAL_REORDER, inversion_confidence(distance), false));
+            }
+        }
+    }
+    preferences
+}
+
+fn inversion_confidence(distance: usize) -> f64 {
+    DISTANCE_DECAY.powi(distance as i32 - 1).max(MIN_INVERSION_CONFIDENCE)
+}
+
+/// Record every pair of `talent_ids` as equally good
+pub fn derive_tie_preferences(talent_ids: &[String], ctx: &PreferenceContext) -> Vec<PairwisePreference> {
+    let mut ids: Vec<&String> = talent_ids.iter().collect::<HashSet<_>>().into_iter().collect();
+    ids.sort();
+    let mut preferences = Vec::new();
+    for (i, first) in ids.iter().enumerate() {
+        for second in &ids[i + 1..] {
+            preferences.push(ctx.preference(first, second, SOURCE_TIE, 1.0, true));
+        }
+    }
+    preferences
+}
+
+/// Shortlisted candidates are preferred over the job's other candidates
+pub fn derive_shortlist_preferences(shortlisted: &[String], others: &[String], ctx: &PreferenceContext) -> Vec<PairwisePreference> {
+    cross_preferences(shortlisted, others, SOURCE_SHORTLIST, SHORTLIST_CONFIDENCE, ctx)
+}
+
+/// The job's other candidates are preferred over rejected ones
+pub fn derive_reject_preferences(rejected: &[String], others: &[String], ctx: &PreferenceContext) -> Vec<PairwisePreference> {
+    cross_preferences(others, rejected, SOURCE_REJECT, REJECT_CONFIDENCE, ctx)
+}
+
+fn cross_preferences(
+    winners: &[String],
+    losers: &[String],
+    source: &str,
+    confidence: f64,
+    ctx: &PreferenceContext,
+) -> Vec<PairwisePreference> {
+    winners
+        .iter()
+        .flat_map(|winner| losers.iter().filter(move |loser| *loser != winner).map(move |loser| (winner, loser)))
+        .map(|(winner, loser)| ctx.preference(winner, loser, source, confidence, false))
+        .collect()
+}
+
+/// How far along the pipeline an application status is; rejected and
+/// withdrawn applications are out of it
+pub fn status_stage(status: &str) -> Option<u8> {
+    match status {
+        "pending" => Some(0),
+        "reviewed" => Some(1),
+        "accepted" => Some(2),
+        _ => None,
+    }
+}
+
+/// Derive preferences from a recruiter moving one application to `status`
+///
+/// A rejection prefers every application still in the pipeline over it (as
+/// a `reject` signal). Moving forward prefers it over applications at an
+/// earlier stage. Moving back says nothing. `others` holds the job's other
+/// applications as (talent_id, status).
+pub fn derive_status_change_preferences(
+    talent_id: &str,
+    previous_status: &str,
+    status: &str,
+    others: &[(String, String)],
+    ctx: &PreferenceContext,
+) -> Vec<PairwisePreference> {
+    let talent = [talent_id.to_string()];
+    if status == "rejected" {
+        let active: Vec<String> = others
+            .iter()
+            .filter(|(_, status)| status_stage(status).is_some())
+            .map(|(id, _)| id.clone())
+            .collect();
+        return derive_reject_preferences(&talent, &active, ctx);
+    }
+
+    let Some(stage) = status_stage(status) else {
+        return Vec::new();
+    };
+    if status_stage(previous_status).is_some_and(|previous| previous >= stage) {
+        return Vec::new();
+    }
+    let behind: Vec<String> = others
+        .iter()
+        .filter(|(_, status)| status_stage(status).is_some_and(|other| other < stage))
+        .map(|(id, _)| id.clone())
+        .collect();
+    cross_preferences(&talent, &behind, SOURCE_STATUS_CHANGE, STATUS_CHANGE_CONFIDENCE, ctx)
+}
+
+/// Confidence of a preference after halving every `RECENCY_HALF_LIFE_DAYS`
+/// since it was recorded; unparseable timestamps count as fresh
+pub fn recency_weight(confidence: f64, created_at: &str, now: DateTime<Utc>) -> f64 {
+    let Ok(created_at) = DateTime::parse_from_rfc3339(created_at) else {
+        return confidence;
+    };
+    let age_days = (now - created_at.with_timezone(&Utc)).num_seconds().max(0) as f64 / 86_400.0;
+    confidence * 0.5_f64.powf(age_days / RECENCY_HALF_LIFE_DAYS)
+}
+
+/// Check that `after_order` rearranges exactly the candidates in
+/// `before_order`, each listed once
+pub fn validate_rearrangement(before_order: &[String], after_order: &[String]) -> Result<(), String> {
+    let before: HashSet<&String> = before_order.iter().collect();
+    if before.len() != before_order.len() {
+        return Err("Order arrays cannot list a candidate twice".to_string());
+    }
+    let after: HashSet<&String> = after_order.iter().collect();
+    if after.len() != after_order.len() || after != before {
+        return Err("After order must contain the same candidates as before order".to_string());
+    }
+    Ok(())
+}
+
+/// Bring a job's stored order in line with its current candidates.
+/// Candidates no longer present are dropped and new ones are appended in
+/// the order given; everyone else keeps their recruiter-chosen position.
+/// Returns None when the order is already up to date.
+pub fn reconcile_order(order: &[String], candidates: &[String]) -> Option<Vec<String>> {
+    let current: HashSet<&String> = candidates.iter().collect();
+    let mut seen = HashSet::new();
+    let mut reconciled: Vec<String> = order
+        .iter()
+        .filter(|id| current.contains(id) && seen.insert(*id))
+        .cloned()
+        .collect();
+    for id in candidates {
+        if seen.insert(id) {
+            reconciled.push(id.clone());
+        }
+    }
+    (reconciled != order).then_some(reconciled)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -325,4 +521,104 @@ mod tests {
 
         assert_eq!(prefs.len(), 0);
     }
+
+    #[test]
+    fn test_validate_rearrangement() {
+        let ids = |ids: &[&str]| ids.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+
+        assert!(validate_rearrangement(&ids(&["t1", "t2", "t3"]), &ids(&["t3", "t1", "t2"])).is_ok());
+        assert!(validate_rearrangement(&ids(&["t1", "t1"]), &ids(&["t1", "t1"])).is_err());
+        assert!(validate_rearrangement(&ids(&["t1", "t2"]), &ids(&["t2", "t2"])).is_err());
+        assert!(validate_rearrangement(&ids(&["t1", "t2"]), &ids(&["t1", "t4"])).is_err());
+    }
+
+    #[test]
+    fn test_reconcile_order() {
+        let ids = |ids: &[&str]| ids.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+
+        // Up to date, whatever order the candidates come in
+        assert_eq!(reconcile_order(&ids(&["t2", "t1"]), &ids(&["t1", "t2"])), None);
+        // New candidates go to the end, departed ones are dropped
+        assert_eq!(
+            reconcile_order(&ids(&["t3", "t1", "t2"]), &ids(&["t4", "t1", "t2"])),
+            Some(ids(&["t1", "t2", "t4"]))
+        );
+        assert_eq!(reconcile_order(&ids(&["t1"]), &[]), Some(vec![]));
+    }
+
+    fn ctx(names: &HashMap<String, String>) -> PreferenceContext<'_> {
+        PreferenceContext { job_id: "job1", job_text: "Software Engineer", talent_names: names, reorder_event_id: Some("event1"), author: None }
+    }
+
+    #[test]
+    fn test_inversions_match_single_move() {
+        let ids = |ids: &[&str]| ids.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+        let names = create_talent_names();
+        let before = ids(&["t1", "t2", "t3", "t4", "t5"]);
+        let after = ids(&["t1", "t5", "t2", "t3", "t4"]);
+
+        let prefs = derive_inversion_preferences(&before, &after, &ctx(&names));
+        let pairs: Vec<(&str, &str, f64)> = prefs.iter().map(|p| (p.winner_id.as_str(), p.loser_id.as_str(), p.confidence)).collect();
+        assert_eq!(pairs.len(), 3);
+        assert_eq!(pairs[0], ("t5", "t2", 1.0));
+        assert_eq!((pairs[1].0, pairs[1].1), ("t5", "t3"));
+        assert!((pairs[1].2 - DISTANCE_DECAY).abs() < 1e-9);
+        assert!(pairs[2].2 < pairs[1].2);
+    }
+
+    #[test]
+    fn test_inversions_of_shuffle_are_not_redundant() {
+        let ids = |ids: &[&str]| ids.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+        let names = create_talent_names();
+        // Full reversal flips every pair exactly once
+        let prefs = derive_inversion_preferences(&ids(&["t1", "t2", "t3", "t4"]), &ids(&["t4", "t3", "t2", "t1"]), &ctx(&names));
+        assert_eq!(prefs.len(), 6);
+        let unique: HashSet<(String, String)> = prefs.iter().map(|p| (p.winner_id.clone(), p.loser_id.clone())).collect();
+        assert_eq!(unique.len(), 6);
+        assert!(prefs.iter().all(|p| !p.tie && p.confidence >= MIN_INVERSION_CONFIDENCE));
+    }
+
+    #[test]
+    fn test_ties_and_signals() {
+        let ids = |ids: &[&str]| ids.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+        let names = create_talent_names();
+
+        let ties = derive_tie_preferences(&ids(&["t3", "t1", "t2", "t1"]), &ctx(&names));
+        assert_eq!(ties.len(), 3);
+        assert!(ties.iter().all(|p| p.tie && p.source == SOURCE_TIE && p.winner_id < p.loser_id));
+
+        let shortlist = derive_shortlist_preferences(&ids(&["t1"]), &ids(&["t2", "t3"]), &ctx(&names));
+        assert!(shortlist.iter().all(|p| p.winner_id == "t1" && p.confidence == SHORTLIST_CONFIDENCE));
+        let reject = derive_reject_preferences(&ids(&["t1"]), &ids(&["t2", "t3"]), &ctx(&names));
+        assert!(reject.iter().all(|p| p.loser_id == "t1" && p.source == SOURCE_REJECT));
+    }
+
+    #[test]
+    fn test_status_change_preferences() {
+        let names = create_talent_names();
+        let others: Vec<(String, String)> = [("t2", "pending"), ("t3", "reviewed"), ("t4", "rejected")]
+            .iter()
+            .map(|(id, status)| (id.to_string(), status.to_string()))
+            .collect();
+
+        let forward = derive_status_change_preferences("t1", "pending", "accepted", &others, &ctx(&names));
+        let losers: Vec<&str> = forward.iter().map(|p| p.loser_id.as_str()).collect();
+        assert_eq!(losers, vec!["t2", "t3"]);
+        assert!(forward.iter().all(|p| p.source == SOURCE_STATUS_CHANGE));
+
+        let rejected = derive_status_change_preferences("t1", "reviewed", "rejected", &others, &ctx(&names));
+        let winners: Vec<&str> = rejected.iter().map(|p| p.winner_id.as_str()).collect();
+        assert_eq!(winners, vec!["t2", "t3"]);
+
+        assert!(derive_status_change_preferences("t1", "accepted", "reviewed", &others, &ctx(&names)).is_empty());
+    }
+
+    #[test]
+    fn test_recency_weight() {
+        let now = Utc::now();
+        let month_ago = (now - chrono::Duration::days(30)).to_rfc3339();
+        assert!((recency_weight(0.8, &month_ago, now) - 0.4).abs() < 1e-6);
+        assert_eq!(recency_weight(0.8, &now.to_rfc3339(), now), 0.8);
+        assert_eq!(recency_weight(0.8, "not a date", now), 0.8);
+    }
 }
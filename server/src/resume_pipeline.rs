@@ -0,0 +1,332 @@
+//! Background resume processing pipeline
+//!
+//! Runs after a resume is submitted: extracts experiences and profile URLs
+//! with Grok, uploads the document to the talent's xAI collection (creating
+//! the collection if needed), analyzes social media and, when a job is given,
+//! scores the candidate against it.
+
+use log::{info, error, warn};
+
+use crate::database::Pool;
+use crate::events::{self, EventKind, Events};
+use crate::grok_client::{GrokClient, TalentInfo, SocialMediaAnalysisRequest, SocialMediaInput, ProfileUrls, CandidateScoringRequest, JobInfoForScoring};
+use crate::models::{Job, Talent};
+
+/// Process a resume for `talent`, optionally scoring against `job`
+pub async fn run(
+    pool: Pool,
+    events: Events,
+    grok_url: String,
+    talent: Talent,
+    job: Option<Job>,
+    pdf_bytes: Vec<u8>,
+    filename: String,
+) {
+    let talent_info = TalentInfo {
+        id: talent.id.clone(),
+        name: talent.name.clone(),
+        email: talent.email.clone(),
+        handle: talent.handle.clone(),
+        skills: talent.skills.clone(),
+        title: talent.title.clone(),
+        location: talent.location.clone(),
+        experience: talent.experience.clone(),
+        bio: talent.bio.clone(),
+    };
+
+    let talent_id = talent.id.clone();
+    let talent_name = talent.name.clone();
+    let talent_title = talent.title.clone();
+    let talent_skills = talent.skills.clone();
+    let collection_id = talent.collection_id.clone();
+    let old_resume_document_id = talent.resume_document_id.clone();
+    let pdf_bytes_for_upload = pdf_bytes.clone();
+    let filename_for_upload = filename.clone();
+
+    info!("======================================================================");
+    info!("GROK ANALYSIS STARTING");
+    info!("======================================================================");
+    info!("Talent ID: {}", talent_id);
+    info!("Grok service URL: {}", grok_url);
+    info!("Resume filename: {}", filename);
+    info!("PDF size: {} bytes", pdf_bytes.len());
+
+    let mut extracted_urls: Option<ProfileUrls> = None;
+    let client = GrokClient::new(&grok_url);
+
+    match client.analyze_resume(&talent_info, &pdf_bytes, &filename).await {
+        Ok(response) => {
+            info!("======================================================================");
+            info!("GROK RESPONSE RECEIVED - Success: {}", response.success);
+            if let Some(ref err) = response.error {
+                error!("Grok error: {}", err);
+            }
+
+            if response.success {
+                if let Some(result) = response.result {
+                    info!("======================================================================");
+                    info!("EXTRACTED INFORMATION FROM RESUME");
+                    info!("======================================================================");
+                    info!("Talent ID: {}", result.talent_id);
+                    info!("WORK EXPERIENCES ({} found):", result.experiences.len());
+                    for (i, exp) in result.experiences.iter().enumerate() {
+                        info!("  {}. {} at {}", i + 1, exp.role, exp.company);
+                        if let Some(ref duration) = exp.duration {
+                            info!("     Duration: {}", duration);
+                        }
+                        info!("     Summary: {}", exp.summary);
+                    }
+                    info!("SOCIAL PROFILE URLS:");
+                    info!("  LinkedIn: {}", result.urls.linkedin.as_deref().unwrap_or("Not found"));
+                    info!("  X/Twitter: {}", result.urls.x.as_deref().unwrap_or("Not found"));
+                    info!("  GitHub: {}", result.urls.github.as_deref().unwrap_or("Not found"));
+                    info!("  GitLab: {}", result.urls.gitlab.as_deref().unwrap_or("Not found"));
+                    info!("======================================================================");
+
+                    extracted_urls = Some(result.urls.clone());
+
+                    // Serialize experiences to JSON
+                    let experiences_json = serde_json::to_string(&result.experiences)
+                        .ok();
+
+                    // Update talent with extracted info
+                    match crate::database::update_talent_resume_fields(
+                        &pool,
+                        talent_id.clone(),
+                        experiences_json,
+                        result.urls.linkedin,
+                        result.urls.x,
+                        result.urls.github,
+                        result.urls.gitlab,
+                    ).await {
+                        Ok(_updated) => {
+                            info!("SUCCESS: Updated talent {} with resume data", talent_id);
+                        },
+                        Err(e) => {
+                            error!("FAILED to update talent {}: {}", talent_id, e);
+                        }
+                    }
+                } else {
+                    info!("No result in response (result is None)");
+                }
+            } else {
+                error!("Grok analysis failed: {:?}", response.error);
+            }
+        }
+        Err(e) => {
+            error!("======================================================================");
+            error!("GROK SERVICE ERROR: {}", e);
+            error!("======================================================================");
+        }
+    }
+
+    // Get or create collection for document upload
+    let final_collection_id = if let Some(coll_id) = collection_id {
+        Some(coll_id)
+    } else {
+        // No collection exists, create one now
+        warn!("======================================================================");
+        warn!("DOCUMENT UPLOAD: No collection_id for talent {}, creating collection now", talent_id);
+        warn!("======================================================================");
+
+        match client.create_collection(&talent_id, &talent_name).await {
+            Ok(response) => {
+                if response.success {
+                    if let Some(collection) = response.collection {
+                        info!("COLLECTION CREATED: {}", collection.collection_id);
+
+                        // Update talent with collection_id
+                        match crate::database::update_talent_collection_id(
+                            &pool,
+                            talent_id.clone(),
+                            collection.collection_id.clone(),
+                        ).await {
+                            Ok(_) => {
+                                info!("SUCCESS: Updated talent {} with collection_id {}", talent_id, collection.collection_id);
+                            },
+                            Err(e) => {
+                                error!("FAILED to update talent {} with collection_id: {}", talent_id, e);
+                            }
+                        }
+
+                        Some(collection.collection_id)
+                    } else {
+                        error!("COLLECTION CREATION: No collection in response");
+                        None
+                    }
+                } else {
+                    error!("COLLECTION CREATION: Failed - {:?}", response.error);
+                    None
+                }
+            }
+            Err(e) => {
+                error!("COLLECTION CREATION ERROR: {}", e);
+                None
+            }
+        }
+    };
+
+    // Upload resume to collection
+    if let Some(coll_id) = final_collection_id {
+        info!("======================================================================");
+        info!("DOCUMENT UPLOAD: Uploading resume to collection");
+        info!("======================================================================");
+        info!("Collection ID: {}", coll_id);
+        info!("Filename: {}", filename_for_upload);
+        if let Some(ref old_doc_id) = old_resume_document_id {
+            info!("Replacing old document: {}", old_doc_id);
+        }
+
+        match client.upload_document(
+            &coll_id,
+            &filename_for_upload,
+            &pdf_bytes_for_upload,
+            old_resume_document_id.as_deref(),
+        ).await {
+            Ok(doc_response) => {
+                if doc_response.success {
+                    if let Some(doc) = doc_response.document {
+                        info!("======================================================================");
+                        info!("DOCUMENT UPLOAD: Success!");
+                        info!("Document ID: {}", doc.document_id);
+                        info!("======================================================================");
+
+                        // Update talent with new document_id
+                        match crate::database::update_talent_resume_document_id(
+                            &pool,
+                            talent_id.clone(),
+                            Some(doc.document_id.clone()),
+                        ).await {
+                            Ok(_) => {
+                                info!("SUCCESS: Updated talent {} with document_id {}", talent_id, doc.document_id);
+                            },
+                            Err(e) => {
+                                error!("FAILED to update talent {} with document_id: {}", talent_id, e);
+                            }
+                        }
+                    }
+                } else {
+                    error!("DOCUMENT UPLOAD: Failed - {:?}", doc_response.error);
+                }
+            }
+            Err(e) => {
+                error!("======================================================================");
+                error!("DOCUMENT UPLOAD ERROR: {}", e);
+                error!("======================================================================");
+            }
+        }
+
+        // Analyze social media if we have URLs
+        if let Some(urls) = extracted_urls {
+            info!("======================================================================");
+            info!("SOCIAL MEDIA ANALYSIS: Starting");
+            info!("======================================================================");
+
+            let social_request = SocialMediaAnalysisRequest {
+                talent_id: talent_id.clone(),
+                collection_id: Some(coll_id.clone()),
+                name: talent_name.clone(),
+                email: Some(talent_info.email.clone()),
+                social_urls: SocialMediaInput {
+                    linkedin: urls.linkedin,
+                    x: urls.x,
+                    github: urls.github,
+                    gitlab: urls.gitlab,
+                    stackoverflow: None,
+                },
+                platforms_to_search: vec!["X".to_string(), "GitHub".to_string(), "LinkedIn".to_string()],
+            };
+
+            match client.analyze_social_media(&social_request).await {
+                Ok(response) => {
+                    if response.success {
+                        info!("SOCIAL MEDIA ANALYSIS: Success!");
+                        if let Some(result) = response.result {
+                            // Update talent with social analysis
+                            let analysis_json = serde_json::to_string(&result).ok();
+
+                            // We need to extract x_handle if present
+                            let x_handle = result.get("x_handle").and_then(|v| v.as_str()).map(|s| s.to_string());
+
+                            match crate::database::update_talent_social_analysis(
+                                &pool,
+                                talent_id.clone(),
+                                analysis_json,
+                                x_handle,
+                            ).await {
+                                Ok(Some(talent)) => {
+                                    info!("SUCCESS: Updated talent {} with social analysis", talent_id);
+                                    events.publish(EventKind::TalentSocialAnalyzed, events::talent_social_analyzed(&talent)).await;
+                                }
+                                Ok(None) => error!("FAILED to update talent {} with social analysis: talent not found", talent_id),
+                                Err(e) => error!("FAILED to update talent {} with social analysis: {}", talent_id, e),
+                            }
+                        }
+                    } else {
+                        error!("SOCIAL MEDIA ANALYSIS: Failed - {:?}", response.error);
+                    }
+                }
+                Err(e) => error!("SOCIAL MEDIA ANALYSIS ERROR: {}", e),
+            }
+        }
+
+        // Score candidate against the job using collection data
+        if let Some(job_for_scoring) = job {
+            info!("======================================================================");
+            info!("CANDIDATE SCORING: Starting");
+            info!("======================================================================");
+
+            let scoring_request = CandidateScoringRequest {
+                talent_id: talent_id.clone(),
+                collection_id: coll_id.clone(),
+                job: JobInfoForScoring {
+                    id: job_for_scoring.id.clone(),
+                    title: job_for_scoring.title.clone(),
+                    description: job_for_scoring.description.clone(),
+                    company_name: job_for_scoring.company_name.clone(),
+                    skills_required: job_for_scoring.skills_required.clone(),
+                    experience_level: job_for_scoring.experience_level.clone(),
+                    location: job_for_scoring.location.clone(),
+                    location_type: job_for_scoring.location_type.clone(),
+                },
+                candidate_name: talent_name.clone(),
+                candidate_title: talent_title.clone(),
+                candidate_skills: talent_skills.clone(),
+            };
+
+            match client.score_candidate(&scoring_request).await {
+                Ok(response) => {
+                    if response.success {
+                        info!("CANDIDATE SCORING: Success!");
+                        if let Some(result) = response.result {
+                            info!("Score: {}", result.overall_score);
+                            info!("Recommendation: {}", result.recommendation);
+                            info!("Summary: {}", result.summary);
+
+                            // Store the scoring result as JSON in the talent record
+                            let scoring_json = serde_json::to_string(&result).ok();
+
+                            match crate::database::update_talent_candidate_score(
+                                &pool,
+                                talent_id.clone(),
+                                result.overall_score,
+                                scoring_json,
+                            ).await {
+                                Ok(_) => {
+                                    info!("SUCCESS: Updated talent {} with candidate score", talent_id);
+                                    events.publish(EventKind::TalentScored, events::talent_scored(&result)).await;
+                                }
+                                Err(e) => error!("FAILED to update talent {} with candidate score: {}", talent_id, e),
+                            }
+                        }
+                    } else {
+                        error!("CANDIDATE SCORING: Failed - {:?}", response.error);
+                    }
+                }
+                Err(e) => error!("CANDIDATE SCORING ERROR: {}", e),
+            }
+        }
+    } else {
+        error!("DOCUMENT UPLOAD: Skipped - no collection available");
+    }
+}
@@ -0,0 +1,182 @@
+//! Data retention policies
+//!
+//! Each data class has an optional retention period; `None` keeps data
+//! forever. A background sweeper purges data that has been inactive for
+//! longer than its period, and the same code path reports what a sweep would
+//! purge. Talents and jobs under legal hold, and everything attached to them,
+//! are exempt.
+//!
+//! Applications are active as of their last status or resume change, talents
+//! as of their last profile edit or application activity. Soft-deleted rows
+//! are purged once they have been in the trash longer than the grace period.
+
+use std::fmt;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use log::{error, info};
+
+use crate::database::Pool;
+use crate::models::{RetentionClassReport, RetentionReport};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataClass {
+    /// Resume files on rejected or withdrawn applications
+    Resumes,
+    /// Social media analysis stored on talents
+    SocialAnalysis,
+    /// Raw reorder events and the preferences derived from them
+    ReorderEvents,
+    /// Rejected applications themselves
+    RejectedApplications,
+    /// Soft-deleted talents, jobs and applications
+    Trash,
+}
+
+impl DataClass {
+    pub const ALL: [DataClass; 5] = [
+        DataClass::Resumes,
+        DataClass::SocialAnalysis,
+        DataClass::ReorderEvents,
+        DataClass::RejectedApplications,
+        DataClass::Trash,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DataClass::Resumes => "resumes",
+            DataClass::SocialAnalysis => "social_analysis",
+            DataClass::ReorderEvents => "reorder_events",
+            DataClass::RejectedApplications => "rejected_applications",
+            DataClass::Trash => "trash",
+        }
+    }
+}
+
+impl fmt::Display for DataClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Retention period in days per data class; `None` keeps data forever
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    pub resumes_days: Option<u32>,
+    pub social_analysis_days: Option<u32>,
+    pub reorder_events_days: Option<u32>,
+    pub rejected_applications_days: Option<u32>,
+    /// Grace period before soft-deleted rows are purged for good
+    pub trash_days: Option<u32>,
+}
+
+impl RetentionPolicy {
+    pub fn days_for(&self, class: DataClass) -> Option<u32> {
+        match class {
+            DataClass::Resumes => self.resumes_days,
+            DataClass::SocialAnalysis => self.social_analysis_days,
+            DataClass::ReorderEvents => self.reorder_events_days,
+            DataClass::RejectedApplications => self.rejected_applications_days,
+            DataClass::Trash => self.trash_days,
+        }
+    }
+
+    /// Whether any data class has a retention period configured
+    pub fn is_enabled(&self) -> bool {
+        DataClass::ALL.iter().any(|c| self.days_for(*c).is_some())
+    }
+
+    /// Data inactive since before the returned timestamp is eligible for purging
+    pub fn cutoff(&self, class: DataClass, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.days_for(class)
+            .map(|days| now - chrono::Duration::days(i64::from(days)))
+    }
+}
+
+/// Purge (or with `dry_run`, only count) everything past its retention period
+pub async fn apply(
+    pool: &Pool,
+    policy: &RetentionPolicy,
+    now: DateTime<Utc>,
+    dry_run: bool,
+) -> Result<RetentionReport, sqlx::Error> {
+    let mut classes = Vec::with_capacity(DataClass::ALL.len());
+    for class in DataClass::ALL {
+        let cutoff = policy.cutoff(class, now);
+        let affected = match cutoff {
+            Some(cutoff) => crate::database::purge_retention_class(pool, class, &cutoff.to_rfc3339(), dry_run).await?,
+            None => 0,
+        };
+        classes.push(RetentionClassReport {
+            class: class.as_str().to_string(),
+            retention_days: policy.days_for(class),
+            cutoff: cutoff.map(|c| c.to_rfc3339()),
+            affected,
+        });
+    }
+
+    Ok(RetentionReport {
+        dry_run,
+        generated_at: now.to_rfc3339(),
+        classes,
+    })
+}
+
+/// Spawn the background task that periodically enforces the retention policy
+pub fn spawn_retention_sweeper(pool: Pool, policy: RetentionPolicy, interval: Duration) {
+    if !policy.is_enabled() {
+        info!("[retention] No retention periods configured; sweeper disabled");
+        return;
+    }
+
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            match apply(&pool, &policy, Utc::now(), false).await {
+                Ok(report) => {
+                    for class in report.classes.iter().filter(|c| c.affected > 0) {
+                        info!("[retention] Purged {} {} older than {:?}", class.affected, class.class, class.cutoff);
+                    }
+                }
+                Err(e) => error!("[retention] Retention sweep failed: {}", e),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_policy_keeps_everything() {
+        let policy = RetentionPolicy::default();
+        assert!(!policy.is_enabled());
+        for class in DataClass::ALL {
+            assert!(policy.cutoff(class, Utc::now()).is_none());
+        }
+    }
+
+    #[test]
+    fn test_cutoff_per_class() {
+        let now = DateTime::parse_from_rfc3339("2026-03-31T12:00:00Z").unwrap().with_timezone(&Utc);
+        let policy = RetentionPolicy {
+            resumes_days: Some(30),
+            reorder_events_days: Some(0),
+            trash_days: Some(7),
+            ..Default::default()
+        };
+        assert!(policy.is_enabled());
+        assert_eq!(
+            policy.cutoff(DataClass::Resumes, now).unwrap().to_rfc3339(),
+            "2026-03-01T12:00:00+00:00"
+        );
+        assert_eq!(policy.cutoff(DataClass::ReorderEvents, now), Some(now));
+        assert_eq!(
+            policy.cutoff(DataClass::Trash, now).unwrap().to_rfc3339(),
+            "2026-03-24T12:00:00+00:00"
+        );
+        assert!(policy.cutoff(DataClass::SocialAnalysis, now).is_none());
+    }
+}
@@ -0,0 +1,243 @@
+//! Interview scorecards
+//!
+//! Interviewers rate a candidate from 1 (poor) to 4 (excellent) on the
+//! competencies of the candidate scoring system (CANDIDATE_SCORING_SYSTEM.md)
+//! and give an overall recommendation. Per-application feedback averages
+//! each competency across scorecards and weighs the averages with the
+//! scoring system's points into a 0-100 score.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+pub const MIN_RATING: i32 = 1;
+pub const MAX_RATING: i32 = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Competency {
+    TechnicalCompetency,
+    ExperienceTrackRecord,
+    ProblemSolving,
+    Communication,
+    CulturalFit,
+}
+
+impl Competency {
+    pub const ALL: [Competency; 5] = [
+        Competency::TechnicalCompetency,
+        Competency::ExperienceTrackRecord,
+        Competency::ProblemSolving,
+        Competency::Communication,
+        Competency::CulturalFit,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Competency::TechnicalCompetency => "technical_competency",
+            Competency::ExperienceTrackRecord => "experience_track_record",
+            Competency::ProblemSolving => "problem_solving",
+            Competency::Communication => "communication",
+            Competency::CulturalFit => "cultural_fit",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Competency::ALL.into_iter().find(|c| c.as_str() == value.trim().to_lowercase())
+    }
+
+    /// Points of the category in the candidate scoring system
+    pub fn weight(&self) -> f64 {
+        match self {
+            Competency::TechnicalCompetency => 40.0,
+            Competency::ExperienceTrackRecord => 25.0,
+            Competency::ProblemSolving => 20.0,
+            Competency::Communication => 10.0,
+            Competency::CulturalFit => 5.0,
+        }
+    }
+}
+
+impl fmt::Display for Competency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Recommendation {
+    StrongNoHire,
+    NoHire,
+    Hire,
+    StrongHire,
+}
+
+impl Recommendation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Recommendation::StrongNoHire => "strong_no_hire",
+            Recommendation::NoHire => "no_hire",
+            Recommendation::Hire => "hire",
+            Recommendation::StrongHire => "strong_hire",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "strong_no_hire" => Some(Recommendation::StrongNoHire),
+            "no_hire" => Some(Recommendation::NoHire),
+            "hire" => Some(Recommendation::Hire),
+            "strong_hire" => Some(Recommendation::StrongHire),
+            _ => None,
+        }
+    }
+
+    pub fn is_hire(&self) -> bool {
+        matches!(self, Recommendation::Hire | Recommendation::StrongHire)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InterviewStatus {
+    Scheduled,
+    Completed,
+    Cancelled,
+    NoShow,
+}
+
+impl InterviewStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            InterviewStatus::Scheduled => "scheduled",
+            InterviewStatus::Completed => "completed",
+            InterviewStatus::Cancelled => "cancelled",
+            InterviewStatus::NoShow => "no_show",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "scheduled" => Some(InterviewStatus::Scheduled),
+            "completed" => Some(InterviewStatus::Completed),
+            "cancelled" => Some(InterviewStatus::Cancelled),
+            "no_show" => Some(InterviewStatus::NoShow),
+            _ => None,
+        }
+    }
+}
+
+/// Validate submitted ratings; competencies that were not assessed are left out
+pub fn validate_ratings(ratings: &BTreeMap<String, i32>) -> Result<BTreeMap<Competency, i32>, Vec<String>> {
+    let mut errors = Vec::new();
+    let mut valid = BTreeMap::new();
+    for (name, rating) in ratings {
+        match Competency::parse(name) {
+            None => errors.push(format!(
+                "Unknown competency '{}'; use {}",
+                name,
+                Competency::ALL.map(|c| c.as_str()).join(", ")
+            )),
+            Some(_) if !(MIN_RATING..=MAX_RATING).contains(rating) => {
+                errors.push(format!("{} must be rated {} to {}", name, MIN_RATING, MAX_RATING))
+            }
+            Some(competency) => {
+                valid.insert(competency, *rating);
+            }
+        }
+    }
+    if valid.is_empty() && errors.is_empty() {
+        errors.push("At least one competency must be rated".to_string());
+    }
+    if errors.is_empty() { Ok(valid) } else { Err(errors) }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompetencyAverage {
+    pub competency: Competency,
+    pub average: f64,
+    pub ratings: usize,
+}
+
+/// Average each competency over the scorecards that rated it
+pub fn competency_averages(scorecards: &[BTreeMap<Competency, i32>]) -> Vec<CompetencyAverage> {
+    Competency::ALL
+        .into_iter()
+        .filter_map(|competency| {
+            let ratings: Vec<i32> = scorecards.iter().filter_map(|s| s.get(&competency).copied()).collect();
+            (!ratings.is_empty()).then(|| CompetencyAverage {
+                competency,
+                average: ratings.iter().sum::<i32>() as f64 / ratings.len() as f64,
+                ratings: ratings.len(),
+            })
+        })
+        .collect()
+}
+
+/// Weighted 0-100 score over the assessed competencies
+pub fn weighted_score(averages: &[CompetencyAverage]) -> Option<f64> {
+    let total_weight: f64 = averages.iter().map(|a| a.competency.weight()).sum();
+    if total_weight == 0.0 {
+        return None;
+    }
+    let span = (MAX_RATING - MIN_RATING) as f64;
+    let points: f64 = averages
+        .iter()
+        .map(|a| a.competency.weight() * (a.average - MIN_RATING as f64) / span)
+        .sum();
+    Some((points / total_weight * 1000.0).round() / 10.0)
+}
+
+/// Overall decision from the recommendations: `hire`, `no_hire` or `split`
+pub fn consensus(recommendations: &[Recommendation]) -> Option<&'static str> {
+    if recommendations.is_empty() {
+        return None;
+    }
+    let hires = recommendations.iter().filter(|r| r.is_hire()).count();
+    let no_hires = recommendations.len() - hires;
+    Some(match hires.cmp(&no_hires) {
+        std::cmp::Ordering::Greater => "hire",
+        std::cmp::Ordering::Less => "no_hire",
+        std::cmp::Ordering::Equal => "split",
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_ratings() {
+        let ratings = BTreeMap::from([("technical_competency".to_string(), 4), ("Communication".to_string(), 2)]);
+        let valid = validate_ratings(&ratings).unwrap_or_else(|e| panic!("{:?}", e));
+        assert_eq!(valid.get(&Competency::Communication), Some(&2));
+
+        let Err(errors) = validate_ratings(&BTreeMap::from([("charisma".to_string(), 3), ("cultural_fit".to_string(), 5)])) else {
+            panic!("invalid ratings accepted");
+        };
+        assert_eq!(errors.len(), 2);
+        assert!(validate_ratings(&BTreeMap::new()).is_err());
+    }
+
+    #[test]
+    fn test_aggregate() {
+        let scorecards = [
+            BTreeMap::from([(Competency::TechnicalCompetency, 4), (Competency::Communication, 2)]),
+            BTreeMap::from([(Competency::TechnicalCompetency, 3)]),
+        ];
+        let averages = competency_averages(&scorecards);
+        assert_eq!(averages.len(), 2);
+        assert_eq!(averages[0].average, 3.5);
+        assert_eq!(averages[0].ratings, 2);
+
+        // technical (40 pts) at 3.5 -> 5/6, communication (10 pts) at 2 -> 1/3
+        assert_eq!(weighted_score(&averages), Some(73.3));
+        assert_eq!(weighted_score(&[]), None);
+    }
+
+    #[test]
+    fn test_consensus() {
+        use Recommendation::*;
+        assert_eq!(consensus(&[]), None);
+        assert_eq!(consensus(&[Hire, StrongHire, NoHire]), Some("hire"));
+        assert_eq!(consensus(&[StrongNoHire, NoHire, Hire]), Some("no_hire"));
+        assert_eq!(consensus(&[Hire, NoHire]), Some("split"));
+    }
+}
@@ -0,0 +1,142 @@
+//! Fixtures shared by the unit tests
+//!
+//! Each fixture is a plain, valid record; tests override the fields they care
+//! about with struct update syntax. Database tests start from
+//! `database::test_pool`.
+
+use chrono::Utc;
+
+use crate::database::{self, Pool};
+use crate::models::{Application, Job, JobMatch, Offer, PairwisePreference, Talent};
+
+pub fn talent(id: &str) -> Talent {
+    Talent {
+        id: id.to_string(),
+        name: "Ada Lovelace".to_string(),
+        email: format!("{}@example.com", id),
+        handle: id.to_string(),
+        skills: "rust".to_string(),
+        avatar: None,
+        title: "Engineer".to_string(),
+        location: None,
+        experience: "5 years".to_string(),
+        bio: None,
+        verified: 0,
+        created_at: "2024-01-01T00:00:00Z".to_string(),
+        resume_experiences: None,
+        linkedin_url: None,
+        x_url: None,
+        github_url: None,
+        gitlab_url: None,
+        collection_id: None,
+        resume_document_id: None,
+        social_analysis: None,
+        x_handle_discovered: None,
+        candidate_score: None,
+        candidate_score_details: None,
+        legal_hold: 0,
+        deleted_at: None,
+    }
+}
+
+pub fn job(id: &str) -> Job {
+    Job {
+        id: id.to_string(),
+        title: "Engineer".to_string(),
+        description: String::new(),
+        company_name: "Acme".to_string(),
+        company_logo: None,
+        location: None,
+        location_type: "remote".to_string(),
+        employment_type: "full-time".to_string(),
+        salary_min: None,
+        salary_max: None,
+        salary_currency: None,
+        skills_required: String::new(),
+        experience_level: "senior".to_string(),
+        status: "published".to_string(),
+        created_at: "2024-01-01T00:00:00Z".to_string(),
+        expires_at: None,
+        published_at: None,
+        closed_at: None,
+        legal_hold: 0,
+        deleted_at: None,
+    }
+}
+
+pub fn application(id: &str, talent_id: &str, job_id: &str) -> Application {
+    Application {
+        id: id.to_string(),
+        talent_id: talent_id.to_string(),
+        job_id: job_id.to_string(),
+        resume_data: None,
+        resume_filename: None,
+        resume_content_type: None,
+        cover_letter: None,
+        status: "pending".to_string(),
+        created_at: "2024-01-01T00:00:00Z".to_string(),
+        deleted_at: None,
+    }
+}
+
+/// An active, strict preference for `winner` over `loser` on job `job1`,
+/// recorded now
+pub fn preference(winner: &str, loser: &str) -> PairwisePreference {
+    PairwisePreference {
+        id: format!("{}>{}", winner, loser),
+        winner_id: winner.to_string(),
+        loser_id: loser.to_string(),
+        job_id: "job1".to_string(),
+        job_text: "Engineer at Acme".to_string(),
+        winner_text: winner.to_string(),
+        loser_text: loser.to_string(),
+        source: "manual_reorder".to_string(),
+        confidence: 1.0,
+        reorder_event_id: None,
+        created_at: Utc::now().to_rfc3339(),
+        tie: false,
+        author: None,
+        superseded_by: None,
+        superseded_at: None,
+        weight: None,
+    }
+}
+
+pub fn job_match(job_id: &str, talent_id: &str, rank: i32, score: f64) -> JobMatch {
+    JobMatch {
+        id: format!("m-{}", talent_id),
+        job_id: job_id.to_string(),
+        talent_id: talent_id.to_string(),
+        score,
+        rank,
+        match_reasons: None,
+        concerns: None,
+        summary: None,
+        created_at: "2024-01-01T00:00:00Z".to_string(),
+    }
+}
+
+/// A draft offer on the application
+pub fn offer(id: &str, application_id: &str) -> Offer {
+    Offer {
+        id: id.to_string(),
+        application_id: application_id.to_string(),
+        status: "draft".to_string(),
+        base_salary: 100_000,
+        currency: "EUR".to_string(),
+        bonus: None,
+        equity: None,
+        start_date: "2024-03-01".to_string(),
+        expires_at: None,
+        notes: None,
+        created_by: "jane".to_string(),
+        created_at: "2024-01-02T00:00:00Z".to_string(),
+        updated_at: "2024-01-02T00:00:00Z".to_string(),
+    }
+}
+
+/// Add talent `talent_id` and their application `id` to job `j1`
+pub async fn insert_applicant(pool: &Pool, id: &str, talent_id: &str) -> Application {
+    database::create_talent(pool, &talent(talent_id)).await.unwrap();
+    database::create_application(pool, &application(id, talent_id, "j1")).await.unwrap()
+}
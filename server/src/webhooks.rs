@@ -0,0 +1,394 @@
+//! Outbound webhooks
+//!
+//! Each published event is recorded as a delivery for every active webhook
+//! subscribed to its type. A background dispatcher POSTs the event envelope
+//! to the endpoint and records the outcome in the delivery log. Failed
+//! attempts are retried with exponential backoff until `MAX_ATTEMPTS`.
+//!
+//! A delivery is `pending` until it succeeds or runs out of attempts, then
+//! `succeeded` or `failed`. Test deliveries, which the caller attempts once
+//! right away, are `sending` while that attempt is in flight.
+//!
+//! Requests are signed with the webhook's secret: `X-Webhook-Signature` is
+//! `sha256=<hex>` where the HMAC-SHA256 covers `<X-Webhook-Timestamp>.<body>`.
+//! Receivers should recompute it and reject stale timestamps.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use futures_util::future::join_all;
+use hmac::{Hmac, Mac};
+use log::{error, info, warn};
+use sha2::Sha256;
+use tokio::sync::Notify;
+use uuid::Uuid;
+
+use crate::database::Pool;
+use crate::events::{Event, EventKind, HISTORY_PRUNE_INTERVAL};
+use crate::models::{Webhook, WebhookDelivery};
+
+type HmacSha256 = Hmac<Sha256>;
+
+pub const SIGNATURE_HEADER: &str = "X-Webhook-Signature";
+pub const TIMESTAMP_HEADER: &str = "X-Webhook-Timestamp";
+pub const EVENT_HEADER: &str = "X-Webhook-Event";
+pub const DELIVERY_HEADER: &str = "X-Webhook-Delivery";
+
+/// Subscribes a webhook to every event type
+pub const ALL_EVENTS: &str = "*";
+
+/// Event type of the deliveries sent by the test endpoint
+pub const TEST_EVENT: &str = "webhook.test";
+
+/// Attempts per delivery before it is marked failed
+pub const MAX_ATTEMPTS: i32 = 8;
+const BASE_RETRY_DELAY_SECS: i64 = 30;
+const MAX_RETRY_DELAY_SECS: i64 = 6 * 60 * 60;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+const POLL_INTERVAL: Duration = Duration::from_secs(15);
+const BATCH_SIZE: i64 = 20;
+/// Characters of the endpoint's response body kept in the delivery log
+const RESPONSE_BODY_LIMIT: usize = 1024;
+
+/// Generate a signing secret for a new webhook
+pub fn generate_secret() -> String {
+    format!("whsec_{}", crate::portal::generate_token())
+}
+
+fn mac(secret: &[u8], timestamp: i64, body: &str) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body.as_bytes());
+    mac
+}
+
+/// The `X-Webhook-Signature` value for a request body sent at `timestamp`
+pub fn sign(secret: &[u8], timestamp: i64, body: &str) -> String {
+    format!("sha256={}", hex::encode(mac(secret, timestamp, body).finalize().into_bytes()))
+}
+
+/// Check a received signature, as a receiver would
+pub fn verify(secret: &[u8], timestamp: i64, body: &str, signature: &str) -> bool {
+    signature
+        .strip_prefix("sha256=")
+        .and_then(|sig| hex::decode(sig).ok())
+        .is_some_and(|sig| mac(secret, timestamp, body).verify_slice(&sig).is_ok())
+}
+
+/// Delay before the next attempt after `attempts` failed ones: 30s, 1m,
+/// 2m, 4m, ... capped at six hours
+pub fn retry_delay(attempts: i32) -> chrono::Duration {
+    let exponent = attempts.clamp(1, 20) as u32 - 1;
+    chrono::Duration::seconds((BASE_RETRY_DELAY_SECS << exponent).min(MAX_RETRY_DELAY_SECS))
+}
+
+/// Whether a webhook with the given event filter receives `event_type`
+pub fn subscribes(events: &[String], event_type: &str) -> bool {
+    events.iter().any(|e| e == ALL_EVENTS || e == event_type)
+}
+
+/// Validate an event filter, returning the normalized event types
+pub fn normalize_events(events: &[String]) -> Result<Vec<String>, Vec<String>> {
+    let mut normalized: Vec<String> = Vec::new();
+    let mut errors = Vec::new();
+    for event in events {
+        let event = event.trim();
+        let name = if event == ALL_EVENTS {
+            ALL_EVENTS
+        } else if let Some(kind) = EventKind::parse(event) {
+            kind.as_str()
+        } else {
+            errors.push(format!(
+                "Unknown event '{}'; use {} or {}",
+                event,
+                EventKind::ALL.map(|k| k.as_str()).join(", "),
+                ALL_EVENTS
+            ));
+            continue;
+        };
+        if !normalized.iter().any(|n| n == name) {
+            normalized.push(name.to_string());
+        }
+    }
+    if normalized.is_empty() && errors.is_empty() {
+        errors.push("At least one event must be subscribed".to_string());
+    }
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    if normalized.iter().any(|n| n == ALL_EVENTS) {
+        normalized = vec![ALL_EVENTS.to_string()];
+    }
+    Ok(normalized)
+}
+
+pub fn validate_url(url: &str) -> Result<(), String> {
+    let parsed = reqwest::Url::parse(url.trim()).map_err(|e| format!("url is not a valid URL: {}", e))?;
+    if !matches!(parsed.scheme(), "http" | "https") || parsed.host_str().is_none() {
+        return Err("url must be an http or https URL".to_string());
+    }
+    Ok(())
+}
+
+/// Timestamp format of `next_attempt_at`, which is compared as text
+fn due_timestamp(at: DateTime<Utc>) -> String {
+    at.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// A new pending delivery of `payload` to `webhook_id`, due now
+pub fn new_delivery(webhook_id: &str, event_id: &str, event_type: &str, payload: String) -> WebhookDelivery {
+    let now = Utc::now();
+    WebhookDelivery {
+        id: Uuid::new_v4().to_string(),
+        webhook_id: webhook_id.to_string(),
+        event_id: event_id.to_string(),
+        event_type: event_type.to_string(),
+        payload,
+        status: "pending".to_string(),
+        attempts: 0,
+        next_attempt_at: Some(due_timestamp(now)),
+        last_attempt_at: None,
+        response_status: None,
+        response_body: None,
+        error: None,
+        redelivery_of: None,
+        created_at: now.to_rfc3339(),
+    }
+}
+
+/// A delivery that is attempted inline by the caller rather than by the
+/// dispatcher. It is stored as `sending` with no next attempt, so the
+/// dispatcher never picks it up concurrently.
+pub fn new_inline_delivery(webhook_id: &str, event_id: &str, event_type: &str, payload: String) -> WebhookDelivery {
+    WebhookDelivery {
+        status: "sending".to_string(),
+        next_attempt_at: None,
+        ..new_delivery(webhook_id, event_id, event_type, payload)
+    }
+}
+
+/// Record a delivery of the event for every active webhook subscribed to
+/// it. Returns the number of deliveries queued.
+pub async fn enqueue(pool: &Pool, event: &Event) -> Result<usize, sqlx::Error> {
+    let webhooks: Vec<Webhook> = crate::database::get_active_webhooks(pool)
+        .await?
+        .into_iter()
+        .filter(|w| {
+            let events: Vec<String> = serde_json::from_str(&w.events).unwrap_or_default();
+            subscribes(&events, &event.event_type)
+        })
+        .collect();
+    if webhooks.is_empty() {
+        return Ok(0);
+    }
+
+    let payload = serde_json::to_string(event).unwrap_or_default();
+    let mut tx = pool.begin().await?;
+    for webhook in &webhooks {
+        let delivery = new_delivery(&webhook.id, &event.id, &event.event_type, payload.clone());
+        crate::database::insert_webhook_delivery(&mut *tx, &delivery).await?;
+    }
+    tx.commit().await?;
+    Ok(webhooks.len())
+}
+
+pub fn http_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .redirect(reqwest::redirect::Policy::none())
+        .user_agent(concat!("talent-server-webhooks/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .expect("HTTP client builds with static configuration")
+}
+
+struct Outcome {
+    status: Option<u16>,
+    body: Option<String>,
+    error: Option<String>,
+}
+
+impl Outcome {
+    fn succeeded(&self) -> bool {
+        self.status.is_some_and(|s| (200..300).contains(&s))
+    }
+}
+
+async fn post(client: &reqwest::Client, webhook: &Webhook, delivery: &WebhookDelivery, timestamp: i64) -> Outcome {
+    let result = client
+        .post(&webhook.url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header(EVENT_HEADER, &delivery.event_type)
+        .header(DELIVERY_HEADER, &delivery.id)
+        .header(TIMESTAMP_HEADER, timestamp.to_string())
+        .header(SIGNATURE_HEADER, sign(webhook.secret.as_bytes(), timestamp, &delivery.payload))
+        .body(delivery.payload.clone())
+        .send()
+        .await;
+
+    match result {
+        Ok(response) => {
+            let status = response.status();
+            let body = response.text().await.ok().map(|b| b.chars().take(RESPONSE_BODY_LIMIT).collect());
+            Outcome {
+                status: Some(status.as_u16()),
+                body,
+                error: (!status.is_success()).then(|| format!("Endpoint responded with {}", status)),
+            }
+        }
+        Err(e) => Outcome { status: None, body: None, error: Some(e.to_string()) },
+    }
+}
+
+/// Make one delivery attempt and record its outcome. With `retry`, a failed
+/// attempt is rescheduled with backoff until `MAX_ATTEMPTS` is reached;
+/// without it the delivery fails straight away.
+pub async fn deliver(
+    pool: &Pool,
+    client: &reqwest::Client,
+    webhook: &Webhook,
+    delivery: WebhookDelivery,
+    retry: bool,
+) -> Result<WebhookDelivery, sqlx::Error> {
+    let now = Utc::now();
+    let outcome = post(client, webhook, &delivery, now.timestamp()).await;
+    let attempts = delivery.attempts + 1;
+
+    let (status, next_attempt_at) = if outcome.succeeded() {
+        ("succeeded", None)
+    } else if retry && attempts < MAX_ATTEMPTS {
+        ("pending", Some(due_timestamp(now + retry_delay(attempts))))
+    } else {
+        ("failed", None)
+    };
+    match status {
+        "succeeded" => info!("[webhooks] Delivered {} {} to {}", delivery.event_type, delivery.id, webhook.url),
+        _ => warn!(
+            "[webhooks] Delivery {} to {} failed (attempt {}): {}",
+            delivery.id,
+            webhook.url,
+            attempts,
+            outcome.error.as_deref().unwrap_or("unknown error")
+        ),
+    }
+
+    crate::database::update_webhook_delivery(pool, &WebhookDelivery {
+        status: status.to_string(),
+        attempts,
+        next_attempt_at,
+        last_attempt_at: Some(now.to_rfc3339()),
+        response_status: outcome.status.map(i32::from),
+        response_body: outcome.body,
+        error: outcome.error,
+        ..delivery
+    }).await
+}
+
+/// Attempt every due delivery, returning how many were attempted
+async fn dispatch_due(pool: &Pool, client: &reqwest::Client) -> Result<usize, sqlx::Error> {
+    let due = crate::database::get_due_webhook_deliveries(pool, &due_timestamp(Utc::now()), BATCH_SIZE).await?;
+
+    let mut webhooks: HashMap<String, Webhook> = HashMap::new();
+    for delivery in &due {
+        if !webhooks.contains_key(&delivery.webhook_id) {
+            if let Some(webhook) = crate::database::get_webhook(pool, &delivery.webhook_id).await? {
+                webhooks.insert(webhook.id.clone(), webhook);
+            }
+        }
+    }
+
+    let attempted = due.len();
+    let results = join_all(due.into_iter().filter_map(|delivery| {
+        let webhook = webhooks.get(&delivery.webhook_id)?;
+        Some(deliver(pool, client, webhook, delivery, true))
+    }))
+    .await;
+    for result in results {
+        result?;
+    }
+    Ok(attempted)
+}
+
+/// Spawn the background task that sends queued deliveries. It runs when
+/// woken by a newly published event and otherwise polls for due retries.
+/// It also prunes the event history every `HISTORY_PRUNE_INTERVAL`.
+pub fn spawn_dispatcher(pool: Pool, wake: Arc<Notify>) {
+    tokio::spawn(async move {
+        let client = http_client();
+        let mut last_pruned: Option<Instant> = None;
+        loop {
+            if last_pruned.is_none_or(|at| at.elapsed() >= HISTORY_PRUNE_INTERVAL) {
+                crate::events::prune_history(&pool).await;
+                last_pruned = Some(Instant::now());
+            }
+            match dispatch_due(&pool, &client).await {
+                // A full batch means more deliveries may be waiting
+                Ok(attempted) if attempted as i64 == BATCH_SIZE => continue,
+                Ok(_) => {}
+                Err(e) => error!("[webhooks] Dispatch failed: {}", e),
+            }
+            tokio::select! {
+                _ = wake.notified() => {}
+                _ = tokio::time::sleep(POLL_INTERVAL) => {}
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signature() {
+        let body = r#"{"id":"e1","type":"application.created"}"#;
+        let signature = sign(b"whsec_test", 1_700_000_000, body);
+        assert!(signature.starts_with("sha256="));
+        assert_eq!(signature.len(), "sha256=".len() + 64);
+        assert!(verify(b"whsec_test", 1_700_000_000, body, &signature));
+        assert!(!verify(b"whsec_test", 1_700_000_001, body, &signature));
+        assert!(!verify(b"other", 1_700_000_000, body, &signature));
+        assert!(!verify(b"whsec_test", 1_700_000_000, &body.replace("e1", "e2"), &signature));
+        assert!(!verify(b"whsec_test", 1_700_000_000, body, "sha256=zz"));
+    }
+
+    #[test]
+    fn test_retry_delay() {
+        assert_eq!(retry_delay(1).num_seconds(), 30);
+        assert_eq!(retry_delay(2).num_seconds(), 60);
+        assert_eq!(retry_delay(4).num_seconds(), 240);
+        assert_eq!(retry_delay(MAX_ATTEMPTS).num_seconds(), 30 << (MAX_ATTEMPTS - 1));
+        assert_eq!(retry_delay(30).num_seconds(), MAX_RETRY_DELAY_SECS);
+    }
+
+    #[test]
+    fn test_event_filters() {
+        let events = |list: &[&str]| list.iter().map(|e| e.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            normalize_events(&events(&[" Application.Created", "talent.scored", "application.created"])),
+            Ok(events(&["application.created", "talent.scored"]))
+        );
+        assert_eq!(normalize_events(&events(&["reorder.created", "*"])), Ok(events(&["*"])));
+        assert!(normalize_events(&events(&["job.deleted"])).is_err());
+        assert!(normalize_events(&[]).is_err());
+
+        assert!(subscribes(&events(&["*"]), "reorder.created"));
+        assert!(subscribes(&events(&["talent.scored"]), "talent.scored"));
+        assert!(!subscribes(&events(&["talent.scored"]), "application.created"));
+    }
+
+    #[test]
+    fn test_inline_delivery_is_never_due() {
+        let queued = new_delivery("w1", "e1", TEST_EVENT, "{}".to_string());
+        assert_eq!(queued.status, "pending");
+        assert!(queued.next_attempt_at.is_some());
+
+        let inline = new_inline_delivery("w1", "e1", TEST_EVENT, "{}".to_string());
+        assert_eq!(inline.status, "sending");
+        assert_eq!(inline.next_attempt_at, None);
+    }
+}
@@ -92,7 +92,7 @@ export interface Job {
 	salary_currency?: string;
 	skills_required: string; // comma-separated from API
 	experience_level: string; // entry, mid, senior, lead
-	status: string; // active, closed, draft
+	status: string; // draft, published, paused, closed, filled
 	created_at: string;
 	expires_at?: string;
 	saved?: boolean; // local UI state
@@ -122,4 +122,22 @@ export interface JobMatch {
 	summary: string;
 	created_at: string;
 	talent?: Talent;
+}
+
+export interface MatchRun {
+	id: string;
+	job_id: string;
+	status: 'running' | 'succeeded' | 'failed' | 'cancelled';
+	top_n: number;
+	candidate_count: number;
+	batch_size: number;
+	batch_count: number;
+	current_batch: number;
+	evaluated: number;
+	total_evaluated: number | null;
+	match_count: number;
+	model_version: string | null;
+	error: string | null;
+	created_at: string;
+	completed_at: string | null;
 }
\ No newline at end of file